@@ -0,0 +1,150 @@
+// Package alg selects the KEM or signature algorithm at runtime, working on encoded keys.
+package alg
+
+// #include "../../libs/opengm_pqc.h"
+import "C"
+
+import (
+	"crypto/rand"
+	"errors"
+	"fmt"
+	"io"
+	"unsafe"
+
+	_ "github.com/opengm-libs/opengm_pqc/goapi"
+)
+
+type Algorithm int32
+
+const (
+	MLKEM512  Algorithm = C.OPENGM_ALG_MLKEM512
+	MLKEM768  Algorithm = C.OPENGM_ALG_MLKEM768
+	MLKEM1024 Algorithm = C.OPENGM_ALG_MLKEM1024
+	MLDSA44   Algorithm = C.OPENGM_ALG_MLDSA44
+	MLDSA65   Algorithm = C.OPENGM_ALG_MLDSA65
+	MLDSA87   Algorithm = C.OPENGM_ALG_MLDSA87
+)
+
+// check converts a status code returned by the C api to an error.
+func check(code C.int32_t) error {
+	if code == C.OPENGM_OK {
+		return nil
+	}
+	return errors.New(C.GoString(C.opengm_strerror(code)))
+}
+
+func ptr(b []byte) *C.uint8_t {
+	return (*C.uint8_t)(unsafe.SliceData(b))
+}
+
+func (alg Algorithm) String() string {
+	name := C.opengm_alg_name(C.int32_t(alg))
+	if name == nil {
+		return fmt.Sprintf("Algorithm(%d)", int32(alg))
+	}
+	return C.GoString(name)
+}
+
+func (alg Algorithm) PublicKeySize() int {
+	return int(C.opengm_alg_public_key_len(C.int32_t(alg)))
+}
+
+func (alg Algorithm) PrivateKeySize() int {
+	return int(C.opengm_alg_private_key_len(C.int32_t(alg)))
+}
+
+func (alg Algorithm) CipherSize() int {
+	return int(C.opengm_alg_ciphertext_len(C.int32_t(alg)))
+}
+
+func (alg Algorithm) SharedKeySize() int {
+	return int(C.opengm_alg_shared_key_len(C.int32_t(alg)))
+}
+
+func (alg Algorithm) SignatureSize() int {
+	return int(C.opengm_alg_signature_len(C.int32_t(alg)))
+}
+
+func readRandom(rnd io.Reader, n int) ([]byte, error) {
+	if rnd == nil {
+		rnd = rand.Reader
+	}
+	b := make([]byte, n)
+	if _, err := io.ReadFull(rnd, b); err != nil {
+		return nil, err
+	}
+	return b, nil
+}
+
+// KemKeyGen returns the encoded encapsulation key and decapsulation key.
+func (alg Algorithm) KemKeyGen(rnd io.Reader) (ek, dk []byte, err error) {
+	d, err := readRandom(rnd, 32)
+	if err != nil {
+		return nil, nil, err
+	}
+	z, err := readRandom(rnd, 32)
+	if err != nil {
+		return nil, nil, err
+	}
+	ek = make([]byte, alg.PublicKeySize())
+	dk = make([]byte, alg.PrivateKeySize())
+	if err := check(C.opengm_kem_keygen(C.int32_t(alg), ptr(d), ptr(z), ptr(ek), C.uintptr_t(len(ek)), ptr(dk), C.uintptr_t(len(dk)))); err != nil {
+		return nil, nil, err
+	}
+	return ek, dk, nil
+}
+
+// Encap returns the shared key and the ciphertext.
+func (alg Algorithm) Encap(ek []byte, rnd io.Reader) (key, c []byte, err error) {
+	m, err := readRandom(rnd, 32)
+	if err != nil {
+		return nil, nil, err
+	}
+	key = make([]byte, 32)
+	c = make([]byte, alg.CipherSize())
+	if err := check(C.opengm_kem_encap(C.int32_t(alg), ptr(key), ptr(c), C.uintptr_t(len(c)), ptr(ek), C.uintptr_t(len(ek)), ptr(m))); err != nil {
+		return nil, nil, err
+	}
+	return key, c, nil
+}
+
+func (alg Algorithm) Decap(dk, c []byte) ([]byte, error) {
+	key := make([]byte, 32)
+	if err := check(C.opengm_kem_decap(C.int32_t(alg), ptr(key), ptr(c), C.uintptr_t(len(c)), ptr(dk), C.uintptr_t(len(dk)))); err != nil {
+		return nil, err
+	}
+	return key, nil
+}
+
+// SigKeyGen returns the encoded public key and private key.
+func (alg Algorithm) SigKeyGen(rnd io.Reader) (pk, sk []byte, err error) {
+	xi, err := readRandom(rnd, 32)
+	if err != nil {
+		return nil, nil, err
+	}
+	pk = make([]byte, alg.PublicKeySize())
+	sk = make([]byte, alg.PrivateKeySize())
+	if err := check(C.opengm_sig_keygen(C.int32_t(alg), ptr(xi), ptr(pk), C.uintptr_t(len(pk)), ptr(sk), C.uintptr_t(len(sk)))); err != nil {
+		return nil, nil, err
+	}
+	return pk, sk, nil
+}
+
+// Sign signs msg with the context ctx, the signature is deterministic if rnd is nil.
+func (alg Algorithm) Sign(sk, msg, ctx []byte, rnd io.Reader) ([]byte, error) {
+	r := make([]byte, 32)
+	if rnd != nil {
+		if _, err := io.ReadFull(rnd, r); err != nil {
+			return nil, err
+		}
+	}
+	sig := make([]byte, alg.SignatureSize())
+	if err := check(C.opengm_sig_sign(C.int32_t(alg), ptr(sig), C.uintptr_t(len(sig)), ptr(sk), C.uintptr_t(len(sk)), ptr(msg), C.uintptr_t(len(msg)), ptr(ctx), C.uintptr_t(len(ctx)), ptr(r))); err != nil {
+		return nil, err
+	}
+	return sig, nil
+}
+
+func (alg Algorithm) Verify(pk, msg, ctx, sig []byte) bool {
+	return C.opengm_sig_verify(C.int32_t(alg), ptr(sig), C.uintptr_t(len(sig)), ptr(pk), C.uintptr_t(len(pk)), ptr(msg), C.uintptr_t(len(msg)), ptr(ctx), C.uintptr_t(len(ctx))) == C.OPENGM_OK
+}
@@ -0,0 +1,48 @@
+package alg
+
+import (
+	"bytes"
+	"crypto/rand"
+	"testing"
+)
+
+func TestKem(t *testing.T) {
+	for _, alg := range []Algorithm{MLKEM512, MLKEM768, MLKEM1024} {
+		ek, dk, err := alg.KemKeyGen(rand.Reader)
+		if err != nil {
+			t.Fatal(alg, err)
+		}
+		key, c, err := alg.Encap(ek, rand.Reader)
+		if err != nil {
+			t.Fatal(alg, err)
+		}
+		key2, err := alg.Decap(dk, c)
+		if err != nil || !bytes.Equal(key, key2) {
+			t.Fatal(alg, err)
+		}
+	}
+	if _, _, err := MLDSA65.KemKeyGen(rand.Reader); err == nil {
+		t.Fatal()
+	}
+}
+
+func TestSig(t *testing.T) {
+	for _, alg := range []Algorithm{MLDSA44, MLDSA65, MLDSA87} {
+		pk, sk, err := alg.SigKeyGen(rand.Reader)
+		if err != nil {
+			t.Fatal(alg, err)
+		}
+		m := make([]byte, 32)
+		rand.Reader.Read(m)
+		sig, err := alg.Sign(sk, m, []byte("ctx"), rand.Reader)
+		if err != nil {
+			t.Fatal(alg, err)
+		}
+		if !alg.Verify(pk, m, []byte("ctx"), sig) || alg.Verify(pk, m, nil, sig) {
+			t.Fatal(alg)
+		}
+	}
+	if _, _, err := MLKEM768.SigKeyGen(rand.Reader); err == nil {
+		t.Fatal()
+	}
+}
@@ -0,0 +1,14 @@
+package mldsa
+
+// #include "../../libs/opengm_pqc.h"
+import "C"
+
+import "errors"
+
+// check converts a status code returned by the C api to an error.
+func check(code C.int32_t) error {
+	if code == C.OPENGM_OK {
+		return nil
+	}
+	return errors.New(C.GoString(C.opengm_strerror(code)))
+}
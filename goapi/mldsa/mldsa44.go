@@ -53,19 +53,27 @@ func Mldsa44KeyGenInternal(xi []byte) (*Mldsa44PrivateKey, error) {
 	if len(xi) != 32 {
 		return nil, fmt.Errorf("seed length want 32, got %d", len(xi))
 	}
-	p := C.mldsa44_generate_key_internal((*C.uint8_t)(unsafe.SliceData(xi)))
+	var p unsafe.Pointer
+	if err := check(C.opengm_mldsa44_generate_key_internal((*C.uint8_t)(unsafe.SliceData(xi)), &p)); err != nil {
+		return nil, err
+	}
 	return newMldsa44PrivateKey(p), nil
 }
 
 func (sk Mldsa44PrivateKey) PublicKey() *Mldsa44PublicKey {
-	return newMldsa44PublicKey(C.mldsa44_public_key(sk.p))
+	var p unsafe.Pointer
+	C.opengm_mldsa44_public_key(sk.p, &p)
+	return newMldsa44PublicKey(p)
 }
 
 func NewMldsa44PublicKey(encodedPk []byte) (*Mldsa44PublicKey, error) {
 	if len(encodedPk) != PublicKeySize44 {
 		return nil, fmt.Errorf("MLDSA44 has public key size %d, but got %d", PublicKeySize44, len(encodedPk))
 	}
-	p := C.mldsa44_import_public_key((*C.uint8_t)(unsafe.SliceData(encodedPk)))
+	var p unsafe.Pointer
+	if err := check(C.opengm_mldsa44_import_public_key((*C.uint8_t)(unsafe.SliceData(encodedPk)), &p)); err != nil {
+		return nil, err
+	}
 	return newMldsa44PublicKey(p), nil
 }
 
@@ -73,7 +81,10 @@ func NewMldsa44PrivateKey(encodedSk []byte) (*Mldsa44PrivateKey, error) {
 	if len(encodedSk) != PrivateKeySize44 {
 		return nil, fmt.Errorf("MLDSA44 has private key size %d, but got %d", PrivateKeySize44, len(encodedSk))
 	}
-	p := C.mldsa44_import_private_key((*C.uint8_t)(unsafe.SliceData(encodedSk)))
+	var p unsafe.Pointer
+	if err := check(C.opengm_mldsa44_import_private_key((*C.uint8_t)(unsafe.SliceData(encodedSk)), &p)); err != nil {
+		return nil, err
+	}
 	return newMldsa44PrivateKey(p), nil
 }
 
@@ -84,30 +95,32 @@ func (sk Mldsa44PrivateKey) Sign(m []byte, rnd io.Reader) ([]byte, error) {
 	}
 	sig := make([]byte, SignatureSize44)
 
-	C.mldsa44_sign_internal((*C.uint8_t)(unsafe.SliceData(sig)), sk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m)), (*C.uint8_t)(unsafe.SliceData(r)))
+	if err := check(C.opengm_mldsa44_sign_internal((*C.uint8_t)(unsafe.SliceData(sig)), sk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m)), (*C.uint8_t)(unsafe.SliceData(r)))); err != nil {
+		return nil, err
+	}
 
 	return sig, nil
 }
 
 func (pk Mldsa44PublicKey) Verify(sig []byte, m []byte) bool {
-	return bool(C.mldsa44_verify_internal((*C.uint8_t)(unsafe.SliceData(sig)), pk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m))))
+	return C.opengm_mldsa44_verify_internal((*C.uint8_t)(unsafe.SliceData(sig)), pk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m))) == C.OPENGM_OK
 }
 func (pk Mldsa44PublicKey) Drop() {
-	C.mldsa44_drop_public_key_handle(pk.p)
+	C.opengm_mldsa44_drop_public_key_handle(pk.p)
 }
 
 func (pk Mldsa44PrivateKey) Drop() {
-	C.mldsa44_drop_private_key_handle(pk.p)
+	C.opengm_mldsa44_drop_private_key_handle(pk.p)
 }
 
 func (sk Mldsa44PrivateKey) Encode() []byte {
 	b := make([]byte, PrivateKeySize44)
-	C.mldsa44_private_key_encode((*C.uint8_t)(unsafe.SliceData(b)), sk.p)
+	C.opengm_mldsa44_private_key_encode((*C.uint8_t)(unsafe.SliceData(b)), sk.p)
 	return b
 }
 
 func (pk Mldsa44PublicKey) Encode() []byte {
 	b := make([]byte, PublicKeySize44)
-	C.mldsa44_public_key_encode((*C.uint8_t)(unsafe.SliceData(b)), pk.p)
+	C.opengm_mldsa44_public_key_encode((*C.uint8_t)(unsafe.SliceData(b)), pk.p)
 	return b
 }
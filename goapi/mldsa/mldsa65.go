@@ -53,19 +53,27 @@ func Mldsa65KeyGenInternal(xi []byte) (*Mldsa65PrivateKey, error) {
 	if len(xi) != 32 {
 		return nil, fmt.Errorf("seed length want 32, got %d", len(xi))
 	}
-	p := C.mldsa65_generate_key_internal((*C.uint8_t)(unsafe.SliceData(xi)))
+	var p unsafe.Pointer
+	if err := check(C.opengm_mldsa65_generate_key_internal((*C.uint8_t)(unsafe.SliceData(xi)), &p)); err != nil {
+		return nil, err
+	}
 	return newMldsa65PrivateKey(p), nil
 }
 
 func (sk Mldsa65PrivateKey) PublicKey() *Mldsa65PublicKey {
-	return newMldsa65PublicKey(C.mldsa65_public_key(sk.p))
+	var p unsafe.Pointer
+	C.opengm_mldsa65_public_key(sk.p, &p)
+	return newMldsa65PublicKey(p)
 }
 
 func NewMldsa65PublicKey(encodedPk []byte) (*Mldsa65PublicKey, error) {
 	if len(encodedPk) != PublicKeySize65 {
 		return nil, fmt.Errorf("MLDSA65 has public key size %d, but got %d", PublicKeySize65, len(encodedPk))
 	}
-	p := C.mldsa65_import_public_key((*C.uint8_t)(unsafe.SliceData(encodedPk)))
+	var p unsafe.Pointer
+	if err := check(C.opengm_mldsa65_import_public_key((*C.uint8_t)(unsafe.SliceData(encodedPk)), &p)); err != nil {
+		return nil, err
+	}
 	return newMldsa65PublicKey(p), nil
 }
 
@@ -73,7 +81,10 @@ func NewMldsa65PrivateKey(encodedSk []byte) (*Mldsa65PrivateKey, error) {
 	if len(encodedSk) != PrivateKeySize65 {
 		return nil, fmt.Errorf("MLDSA65 has private key size %d, but got %d", PrivateKeySize65, len(encodedSk))
 	}
-	p := C.mldsa65_import_private_key((*C.uint8_t)(unsafe.SliceData(encodedSk)))
+	var p unsafe.Pointer
+	if err := check(C.opengm_mldsa65_import_private_key((*C.uint8_t)(unsafe.SliceData(encodedSk)), &p)); err != nil {
+		return nil, err
+	}
 	return newMldsa65PrivateKey(p), nil
 }
 
@@ -83,29 +94,31 @@ func (sk Mldsa65PrivateKey) Sign(m []byte, rnd io.Reader) ([]byte, error) {
 		return nil, err
 	}
 	sig := make([]byte, SignatureSize65)
-	C.mldsa65_sign_internal((*C.uint8_t)(unsafe.SliceData(sig)), sk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m)), (*C.uint8_t)(unsafe.SliceData(r)))
+	if err := check(C.opengm_mldsa65_sign_internal((*C.uint8_t)(unsafe.SliceData(sig)), sk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m)), (*C.uint8_t)(unsafe.SliceData(r)))); err != nil {
+		return nil, err
+	}
 	return sig, nil
 }
 
 func (pk Mldsa65PublicKey) Verify(sig []byte, m []byte) bool {
-	return bool(C.mldsa65_verify_internal((*C.uint8_t)(unsafe.SliceData(sig)), pk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m))))
+	return C.opengm_mldsa65_verify_internal((*C.uint8_t)(unsafe.SliceData(sig)), pk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m))) == C.OPENGM_OK
 }
 func (pk Mldsa65PublicKey) Drop() {
-	C.mldsa65_drop_public_key_handle(pk.p)
+	C.opengm_mldsa65_drop_public_key_handle(pk.p)
 }
 
 func (pk Mldsa65PrivateKey) Drop() {
-	C.mldsa65_drop_private_key_handle(pk.p)
+	C.opengm_mldsa65_drop_private_key_handle(pk.p)
 }
 
 func (sk Mldsa65PrivateKey) Encode() []byte {
 	b := make([]byte, PrivateKeySize65)
-	C.mldsa65_private_key_encode((*C.uint8_t)(unsafe.SliceData(b)), sk.p)
+	C.opengm_mldsa65_private_key_encode((*C.uint8_t)(unsafe.SliceData(b)), sk.p)
 	return b
 }
 
 func (pk Mldsa65PublicKey) Encode() []byte {
 	b := make([]byte, PublicKeySize65)
-	C.mldsa65_public_key_encode((*C.uint8_t)(unsafe.SliceData(b)), pk.p)
+	C.opengm_mldsa65_public_key_encode((*C.uint8_t)(unsafe.SliceData(b)), pk.p)
 	return b
 }
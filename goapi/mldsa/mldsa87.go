@@ -53,19 +53,27 @@ func Mldsa87KeyGenInternal(xi []byte) (*Mldsa87PrivateKey, error) {
 	if len(xi) != 32 {
 		return nil, fmt.Errorf("seed length want 32, got %d", len(xi))
 	}
-	p := C.mldsa87_generate_key_internal((*C.uint8_t)(unsafe.SliceData(xi)))
+	var p unsafe.Pointer
+	if err := check(C.opengm_mldsa87_generate_key_internal((*C.uint8_t)(unsafe.SliceData(xi)), &p)); err != nil {
+		return nil, err
+	}
 	return newMldsa87PrivateKey(p), nil
 }
 
 func (sk Mldsa87PrivateKey) PublicKey() *Mldsa87PublicKey {
-	return newMldsa87PublicKey(C.mldsa87_public_key(sk.p))
+	var p unsafe.Pointer
+	C.opengm_mldsa87_public_key(sk.p, &p)
+	return newMldsa87PublicKey(p)
 }
 
 func NewMldsa87PublicKey(encodedPk []byte) (*Mldsa87PublicKey, error) {
 	if len(encodedPk) != PublicKeySize87 {
 		return nil, fmt.Errorf("MLDSA87 has public key size %d, but got %d", PublicKeySize87, len(encodedPk))
 	}
-	p := C.mldsa87_import_public_key((*C.uint8_t)(unsafe.SliceData(encodedPk)))
+	var p unsafe.Pointer
+	if err := check(C.opengm_mldsa87_import_public_key((*C.uint8_t)(unsafe.SliceData(encodedPk)), &p)); err != nil {
+		return nil, err
+	}
 	return newMldsa87PublicKey(p), nil
 }
 
@@ -73,7 +81,10 @@ func NewMldsa87PrivateKey(encodedSk []byte) (*Mldsa87PrivateKey, error) {
 	if len(encodedSk) != PrivateKeySize87 {
 		return nil, fmt.Errorf("MLDSA87 has private key size %d, but got %d", PrivateKeySize87, len(encodedSk))
 	}
-	p := C.mldsa87_import_private_key((*C.uint8_t)(unsafe.SliceData(encodedSk)))
+	var p unsafe.Pointer
+	if err := check(C.opengm_mldsa87_import_private_key((*C.uint8_t)(unsafe.SliceData(encodedSk)), &p)); err != nil {
+		return nil, err
+	}
 	return newMldsa87PrivateKey(p), nil
 }
 
@@ -84,30 +95,32 @@ func (sk Mldsa87PrivateKey) Sign(m []byte, rnd io.Reader) ([]byte, error) {
 	}
 	sig := make([]byte, SignatureSize87)
 
-	C.mldsa87_sign_internal((*C.uint8_t)(unsafe.SliceData(sig)), sk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m)), (*C.uint8_t)(unsafe.SliceData(r)))
+	if err := check(C.opengm_mldsa87_sign_internal((*C.uint8_t)(unsafe.SliceData(sig)), sk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m)), (*C.uint8_t)(unsafe.SliceData(r)))); err != nil {
+		return nil, err
+	}
 
 	return sig, nil
 }
 
 func (pk Mldsa87PublicKey) Verify(sig []byte, m []byte) bool {
-	return bool(C.mldsa87_verify_internal((*C.uint8_t)(unsafe.SliceData(sig)), pk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m))))
+	return C.opengm_mldsa87_verify_internal((*C.uint8_t)(unsafe.SliceData(sig)), pk.p, (*C.uint8_t)(unsafe.SliceData(m)), C.uintptr_t(len(m))) == C.OPENGM_OK
 }
 func (pk Mldsa87PublicKey) Drop() {
-	C.mldsa87_drop_public_key_handle(pk.p)
+	C.opengm_mldsa87_drop_public_key_handle(pk.p)
 }
 
 func (pk Mldsa87PrivateKey) Drop() {
-	C.mldsa87_drop_private_key_handle(pk.p)
+	C.opengm_mldsa87_drop_private_key_handle(pk.p)
 }
 
 func (sk Mldsa87PrivateKey) Encode() []byte {
 	b := make([]byte, PrivateKeySize87)
-	C.mldsa87_private_key_encode((*C.uint8_t)(unsafe.SliceData(b)), sk.p)
+	C.opengm_mldsa87_private_key_encode((*C.uint8_t)(unsafe.SliceData(b)), sk.p)
 	return b
 }
 
 func (pk Mldsa87PublicKey) Encode() []byte {
 	b := make([]byte, PublicKeySize87)
-	C.mldsa87_public_key_encode((*C.uint8_t)(unsafe.SliceData(b)), pk.p)
+	C.opengm_mldsa87_public_key_encode((*C.uint8_t)(unsafe.SliceData(b)), pk.p)
 	return b
 }
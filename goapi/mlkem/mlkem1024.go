@@ -45,21 +45,33 @@ func NewMlkem1024DecapKey(encodeKey []byte) (*Mlkem1024DecapKey, error) {
 	if len(encodeKey) != DecapKeySize1024 {
 		return nil, fmt.Errorf("encode decap key size wang %d, got %d", DecapKeySize1024, len(encodeKey))
 	}
-	return newMlkem1024DecapKey(C.mlkem1024_decapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)))), nil
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem1024_decapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)), &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem1024DecapKey(p), nil
 }
 
 func NewMlkem1024EncapKey(encodeKey []byte) (*Mlkem1024EncapKey, error) {
 	if len(encodeKey) != EncapKeySize1024 {
 		return nil, fmt.Errorf("encode decap key size wang %d, got %d", EncapKeySize1024, len(encodeKey))
 	}
-	return newMlkem1024EncapKey(C.mlkem1024_encapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)))), nil
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem1024_encapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)), &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem1024EncapKey(p), nil
 }
 
 func Mlkem1024KeyGenInternal(d, z []byte) (*Mlkem1024DecapKey, error) {
 	if len(d) != 32 || len(z) != 32 {
 		return nil, fmt.Errorf("input d/z must have size 32")
 	}
-	return newMlkem1024DecapKey(C.mlkem1024_keygen_internal((*C.uint8_t)(unsafe.SliceData(d)), (*C.uint8_t)(unsafe.SliceData(z)))), nil
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem1024_keygen_internal((*C.uint8_t)(unsafe.SliceData(d)), (*C.uint8_t)(unsafe.SliceData(z)), &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem1024DecapKey(p), nil
 }
 func Mlkem1024KeyGen(rnd io.Reader) (*Mlkem1024DecapKey, error) {
 	d := make([]byte, 32)
@@ -73,13 +85,17 @@ func Mlkem1024KeyGen(rnd io.Reader) (*Mlkem1024DecapKey, error) {
 	}
 	return Mlkem1024KeyGenInternal(d, z)
 }
-func (dk Mlkem1024DecapKey) EncapKey() *Mlkem1024EncapKey {
-	return newMlkem1024EncapKey(C.mlkem1024_encapkey(dk.p))
+func (dk Mlkem1024DecapKey) EncapKey() (*Mlkem1024EncapKey, error) {
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem1024_encapkey(dk.p, &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem1024EncapKey(p), nil
 }
 
 func (dk Mlkem1024DecapKey) Encode() []byte {
 	b := make([]byte, DecapKeySize1024)
-	C.mlkem1024_decapkey_encode((*C.uint8_t)(&b[0]), dk.p)
+	C.opengm_mlkem1024_decapkey_encode((*C.uint8_t)(&b[0]), dk.p)
 	return b
 }
 
@@ -88,13 +104,15 @@ func (dk Mlkem1024DecapKey) Decap(c []byte) ([]byte, error) {
 		return nil, fmt.Errorf("mlkem512 want cipher size %d, got %d", CipherSize512, len(c))
 	}
 	key := make([]byte, 32)
-	C.mlkem1024_decap((*C.uint8_t)(&key[0]), (*C.uint8_t)(&c[0]), dk.p)
+	if err := check(C.opengm_mlkem1024_decap((*C.uint8_t)(&key[0]), (*C.uint8_t)(&c[0]), dk.p)); err != nil {
+		return nil, err
+	}
 	return key, nil
 }
 
 func (ek Mlkem1024EncapKey) Encode() []byte {
 	b := make([]byte, EncapKeySize1024)
-	C.mlkem1024_encapkey_encode((*C.uint8_t)(&b[0]), ek.p)
+	C.opengm_mlkem1024_encapkey_encode((*C.uint8_t)(&b[0]), ek.p)
 	return b
 }
 
@@ -104,13 +122,13 @@ func (ek Mlkem1024EncapKey) Encap() ([]byte, []byte) {
 	m := make([]byte, 32)
 	rand.Read(m)
 
-	C.mlkem1024_encap_internal((*C.uint8_t)(&key[0]), (*C.uint8_t)(&c[0]), ek.p, (*C.uint8_t)(&m[0]))
+	C.opengm_mlkem1024_encap_internal((*C.uint8_t)(&key[0]), (*C.uint8_t)(&c[0]), ek.p, (*C.uint8_t)(&m[0]))
 	return key, c
 }
 func (dk *Mlkem1024DecapKey) Drop() {
-	C.mlkem1024_drop_decapkey_handle(dk.p)
+	C.opengm_mlkem1024_drop_decapkey_handle(dk.p)
 }
 
 func (ek *Mlkem1024EncapKey) Drop() {
-	C.mlkem1024_drop_encapkey_handle(ek.p)
+	C.opengm_mlkem1024_drop_encapkey_handle(ek.p)
 }
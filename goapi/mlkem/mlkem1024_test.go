@@ -10,7 +10,7 @@ import (
 
 func TestMlkem1024(t *testing.T) {
 	dk, _ := mlkem.Mlkem1024KeyGen(rand.Reader)
-	ek := dk.EncapKey()
+	ek, _ := dk.EncapKey()
 	var wg sync.WaitGroup
 
 	for i := 0; i < 10; i++ {
@@ -39,7 +39,7 @@ func BenchmarkMlkem1024KeyGen(b *testing.B) {
 
 func BenchmarkMlkem1024Encap(b *testing.B) {
 	dk, _ := mlkem.Mlkem1024KeyGen(rand.Reader)
-	ek := dk.EncapKey()
+	ek, _ := dk.EncapKey()
 
 	for i := 0; i < b.N; i++ {
 		_, _ = ek.Encap()
@@ -48,7 +48,7 @@ func BenchmarkMlkem1024Encap(b *testing.B) {
 
 func BenchmarkMlkem1024Decap(b *testing.B) {
 	dk, _ := mlkem.Mlkem1024KeyGen(rand.Reader)
-	ek := dk.EncapKey()
+	ek, _ := dk.EncapKey()
 
 	_, c := ek.Encap()
 
@@ -69,7 +69,7 @@ func BenchmarkMlkem1024DkEncode(b *testing.B) {
 
 func BenchmarkMlkem1024EkEncode(b *testing.B) {
 	dk, _ := mlkem.Mlkem1024KeyGen(rand.Reader)
-	ek := dk.EncapKey()
+	ek, _ := dk.EncapKey()
 
 	for i := 0; i < b.N; i++ {
 		_ = ek.Encode()
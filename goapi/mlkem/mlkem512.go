@@ -45,21 +45,33 @@ func NewMlkem512DecapKey(encodeKey []byte) (*Mlkem512DecapKey, error) {
 	if len(encodeKey) != DecapKeySize512 {
 		return nil, fmt.Errorf("encode decap key size wang %d, got %d", DecapKeySize512, len(encodeKey))
 	}
-	return newMlkem512DecapKey(C.mlkem512_decapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)))), nil
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem512_decapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)), &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem512DecapKey(p), nil
 }
 
 func NewMlkem512EncapKey(encodeKey []byte) (*Mlkem512EncapKey, error) {
 	if len(encodeKey) != EncapKeySize512 {
 		return nil, fmt.Errorf("encode decap key size wang %d, got %d", EncapKeySize512, len(encodeKey))
 	}
-	return newMlkem512EncapKey(C.mlkem512_encapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)))), nil
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem512_encapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)), &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem512EncapKey(p), nil
 }
 
 func Mlkem512KeyGenInternal(d, z []byte) (*Mlkem512DecapKey, error) {
 	if len(d) != 32 || len(z) != 32 {
 		return nil, fmt.Errorf("input d/z must have size 32")
 	}
-	return newMlkem512DecapKey(C.mlkem512_keygen_internal((*C.uint8_t)(unsafe.SliceData(d)), (*C.uint8_t)(unsafe.SliceData(z)))), nil
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem512_keygen_internal((*C.uint8_t)(unsafe.SliceData(d)), (*C.uint8_t)(unsafe.SliceData(z)), &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem512DecapKey(p), nil
 }
 func Mlkem512KeyGen(rnd io.Reader) (*Mlkem512DecapKey, error) {
 	d := make([]byte, 32)
@@ -74,13 +86,17 @@ func Mlkem512KeyGen(rnd io.Reader) (*Mlkem512DecapKey, error) {
 	return Mlkem512KeyGenInternal(d, z)
 }
 
-func (dk *Mlkem512DecapKey) EncapKey() *Mlkem512EncapKey {
-	return newMlkem512EncapKey(C.mlkem512_encapkey(dk.p))
+func (dk *Mlkem512DecapKey) EncapKey() (*Mlkem512EncapKey, error) {
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem512_encapkey(dk.p, &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem512EncapKey(p), nil
 }
 
 func (dk *Mlkem512DecapKey) Encode() []byte {
 	b := make([]byte, DecapKeySize512)
-	C.mlkem512_decapkey_encode((*C.uint8_t)(&b[0]), dk.p)
+	C.opengm_mlkem512_decapkey_encode((*C.uint8_t)(&b[0]), dk.p)
 	return b
 }
 
@@ -89,13 +105,15 @@ func (dk *Mlkem512DecapKey) Decap(c []byte) ([]byte, error) {
 		return nil, fmt.Errorf("mlkem512 want cipher size %d, got %d", CipherSize512, len(c))
 	}
 	key := make([]byte, 32)
-	C.mlkem512_decap((*C.uint8_t)(&key[0]), (*C.uint8_t)(&c[0]), dk.p)
+	if err := check(C.opengm_mlkem512_decap((*C.uint8_t)(&key[0]), (*C.uint8_t)(&c[0]), dk.p)); err != nil {
+		return nil, err
+	}
 	return key, nil
 }
 
 func (ek *Mlkem512EncapKey) Encode() []byte {
 	b := make([]byte, EncapKeySize512)
-	C.mlkem512_encapkey_encode((*C.uint8_t)(&b[0]), ek.p)
+	C.opengm_mlkem512_encapkey_encode((*C.uint8_t)(&b[0]), ek.p)
 	return b
 }
 
@@ -105,13 +123,13 @@ func (ek *Mlkem512EncapKey) Encap() ([]byte, []byte) {
 	m := make([]byte, 32)
 	rand.Read(m)
 
-	C.mlkem512_encap_internal((*C.uint8_t)(&key[0]), (*C.uint8_t)(&c[0]), ek.p, (*C.uint8_t)(&m[0]))
+	C.opengm_mlkem512_encap_internal((*C.uint8_t)(&key[0]), (*C.uint8_t)(&c[0]), ek.p, (*C.uint8_t)(&m[0]))
 	return key, c
 }
 func (dk *Mlkem512DecapKey) Drop() {
-	C.mlkem512_drop_decapkey_handle(dk.p)
+	C.opengm_mlkem512_drop_decapkey_handle(dk.p)
 }
 
 func (ek *Mlkem512EncapKey) Drop() {
-	C.mlkem512_drop_encapkey_handle(ek.p)
+	C.opengm_mlkem512_drop_encapkey_handle(ek.p)
 }
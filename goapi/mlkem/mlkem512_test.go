@@ -9,7 +9,7 @@ import (
 
 func TestMlkem512(t *testing.T) {
 	dk, _ := mlkem.Mlkem512KeyGen(rand.Reader)
-	ek := dk.EncapKey()
+	ek, _ := dk.EncapKey()
 	key, c := ek.Encap()
 	key2, _ := dk.Decap(c)
 	for i := 0; i < 32; i++ {
@@ -30,7 +30,7 @@ func BenchmarkMlkem512KeyGen(b *testing.B) {
 
 func BenchmarkMlkem512Encap(b *testing.B) {
 	dk, _ := mlkem.Mlkem512KeyGen(rand.Reader)
-	ek := dk.EncapKey()
+	ek, _ := dk.EncapKey()
 
 	for i := 0; i < b.N; i++ {
 		_, _ = ek.Encap()
@@ -39,7 +39,7 @@ func BenchmarkMlkem512Encap(b *testing.B) {
 
 func BenchmarkMlkem512Decap(b *testing.B) {
 	dk, _ := mlkem.Mlkem512KeyGen(rand.Reader)
-	ek := dk.EncapKey()
+	ek, _ := dk.EncapKey()
 
 	_, c := ek.Encap()
 
@@ -60,7 +60,7 @@ func BenchmarkMlkem512DkEncode(b *testing.B) {
 
 func BenchmarkMlkem512EkEncode(b *testing.B) {
 	dk, _ := mlkem.Mlkem512KeyGen(rand.Reader)
-	ek := dk.EncapKey()
+	ek, _ := dk.EncapKey()
 
 	for i := 0; i < b.N; i++ {
 		_ = ek.Encode()
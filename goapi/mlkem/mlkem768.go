@@ -45,21 +45,33 @@ func NewMlkem768DecapKey(encodeKey []byte) (*Mlkem768DecapKey, error) {
 	if len(encodeKey) != DecapKeySize768 {
 		return nil, fmt.Errorf("encode decap key size wang %d, got %d", DecapKeySize768, len(encodeKey))
 	}
-	return newMlkem768DecapKey(C.mlkem768_decapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)))), nil
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem768_decapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)), &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem768DecapKey(p), nil
 }
 
 func NewMlkem768EncapKey(encodeKey []byte) (*Mlkem768EncapKey, error) {
 	if len(encodeKey) != EncapKeySize768 {
 		return nil, fmt.Errorf("encode decap key size wang %d, got %d", EncapKeySize768, len(encodeKey))
 	}
-	return newMlkem768EncapKey(C.mlkem768_encapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)))), nil
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem768_encapkey_decode((*C.uint8_t)(unsafe.SliceData(encodeKey)), &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem768EncapKey(p), nil
 }
 
 func Mlkem768KeyGenInternal(d, z []byte) (*Mlkem768DecapKey, error) {
 	if len(d) != 32 || len(z) != 32 {
 		return nil, fmt.Errorf("input d/z must have size 32")
 	}
-	return newMlkem768DecapKey(C.mlkem768_keygen_internal((*C.uint8_t)(unsafe.SliceData(d)), (*C.uint8_t)(unsafe.SliceData(z)))), nil
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem768_keygen_internal((*C.uint8_t)(unsafe.SliceData(d)), (*C.uint8_t)(unsafe.SliceData(z)), &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem768DecapKey(p), nil
 }
 func Mlkem768KeyGen(rnd io.Reader) (*Mlkem768DecapKey, error) {
 	d := make([]byte, 32)
@@ -74,13 +86,17 @@ func Mlkem768KeyGen(rnd io.Reader) (*Mlkem768DecapKey, error) {
 	return Mlkem768KeyGenInternal(d, z)
 }
 
-func (dk *Mlkem768DecapKey) EncapKey() *Mlkem768EncapKey {
-	return newMlkem768EncapKey(C.mlkem768_encapkey(dk.p))
+func (dk *Mlkem768DecapKey) EncapKey() (*Mlkem768EncapKey, error) {
+	var p unsafe.Pointer
+	if err := check(C.opengm_mlkem768_encapkey(dk.p, &p)); err != nil {
+		return nil, err
+	}
+	return newMlkem768EncapKey(p), nil
 }
 
 func (dk *Mlkem768DecapKey) Encode() []byte {
 	b := make([]byte, DecapKeySize768)
-	C.mlkem768_decapkey_encode((*C.uint8_t)(unsafe.SliceData(b)), dk.p)
+	C.opengm_mlkem768_decapkey_encode((*C.uint8_t)(unsafe.SliceData(b)), dk.p)
 	return b
 }
 
@@ -89,13 +105,15 @@ func (dk *Mlkem768DecapKey) Decap(c []byte) ([]byte, error) {
 		return nil, fmt.Errorf("mlkem512 want cipher size %d, got %d", CipherSize512, len(c))
 	}
 	key := make([]byte, 32)
-	C.mlkem768_decap((*C.uint8_t)(unsafe.SliceData(key)), (*C.uint8_t)(unsafe.SliceData(c)), dk.p)
+	if err := check(C.opengm_mlkem768_decap((*C.uint8_t)(unsafe.SliceData(key)), (*C.uint8_t)(unsafe.SliceData(c)), dk.p)); err != nil {
+		return nil, err
+	}
 	return key, nil
 }
 
 func (ek *Mlkem768EncapKey) Encode() []byte {
 	b := make([]byte, EncapKeySize768)
-	C.mlkem768_encapkey_encode((*C.uint8_t)(unsafe.SliceData(b)), ek.p)
+	C.opengm_mlkem768_encapkey_encode((*C.uint8_t)(unsafe.SliceData(b)), ek.p)
 	return b
 }
 
@@ -105,14 +123,14 @@ func (ek *Mlkem768EncapKey) Encap() ([]byte, []byte) {
 	m := make([]byte, 32)
 	rand.Read(m)
 
-	C.mlkem768_encap_internal((*C.uint8_t)(unsafe.SliceData(key)), (*C.uint8_t)(unsafe.SliceData(c)), ek.p, (*C.uint8_t)(unsafe.SliceData(m)))
+	C.opengm_mlkem768_encap_internal((*C.uint8_t)(unsafe.SliceData(key)), (*C.uint8_t)(unsafe.SliceData(c)), ek.p, (*C.uint8_t)(unsafe.SliceData(m)))
 	return key, c
 }
 
 func (dk *Mlkem768DecapKey) Drop() {
-	C.mlkem768_drop_decapkey_handle(dk.p)
+	C.opengm_mlkem768_drop_decapkey_handle(dk.p)
 }
 
 func (ek *Mlkem768EncapKey) Drop() {
-	C.mlkem768_drop_encapkey_handle(ek.p)
+	C.opengm_mlkem768_drop_encapkey_handle(ek.p)
 }
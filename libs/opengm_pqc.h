@@ -11,152 +11,1560 @@
 #include <stdbool.h>
 #include <stdint.h>
 #include <stdlib.h>
+#if defined(__GNUC__) || defined(__clang__)
+#define OPENGM_DEPRECATED(note) __attribute__((deprecated(note)))
+#elif defined(_MSC_VER)
+#define OPENGM_DEPRECATED(note) __declspec(deprecated(note))
+#else
+#define OPENGM_DEPRECATED(note)
+#endif
+
+
+/**
+ * ML-KEM-512
+ */
+#define OPENGM_ALG_MLKEM512 1
+
+/**
+ * ML-KEM-768
+ */
+#define OPENGM_ALG_MLKEM768 2
+
+/**
+ * ML-KEM-1024
+ */
+#define OPENGM_ALG_MLKEM1024 3
+
+/**
+ * ML-DSA-44
+ */
+#define OPENGM_ALG_MLDSA44 11
+
+/**
+ * ML-DSA-65
+ */
+#define OPENGM_ALG_MLDSA65 12
+
+/**
+ * ML-DSA-87
+ */
+#define OPENGM_ALG_MLDSA87 13
+
+/**
+ * Success.
+ */
+#define OPENGM_OK 0
+
+/**
+ * A required pointer argument is NULL.
+ */
+#define OPENGM_ERR_NULL -1
+
+/**
+ * The input is not a valid encoding (e.g. an encapsulation key fails the modulus check).
+ */
+#define OPENGM_ERR_DECODE -2
+
+/**
+ * The signature is invalid.
+ */
+#define OPENGM_ERR_VERIFY -3
+
+/**
+ * The handle is not of the expected key type or parameter set, or has been cleared or dropped.
+ */
+#define OPENGM_ERR_HANDLE -4
+
+/**
+ * The caller provided storage is too small or not aligned to OPENGM_STORAGE_ALIGN.
+ */
+#define OPENGM_ERR_STORAGE -5
+
+/**
+ * The algorithm is unknown or not of the expected kind (KEM or signature).
+ */
+#define OPENGM_ERR_ALGORITHM -6
+
+/**
+ * A buffer length does not match the algorithm, or the context string is too long.
+ */
+#define OPENGM_ERR_LENGTH -7
+
+/**
+ * The alignment required by the caller provided storage of the *_inplace functions.
+ */
+#define OPENGM_STORAGE_ALIGN 8
 
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
 
 /**
- * mlkem768_keygen_internal 密钥生成,dk必须指向dk_len的缓冲区
- * d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+ * opengm_alg_name 返回算法名称, 如"ML-KEM-768", 未知算法返回NULL. 返回的字符串是静态的, 调用者不能释放.
+ */
+const char *opengm_alg_name(int32_t alg);
+
+/**
+ * opengm_alg_public_key_len 返回封装密钥或公钥的编码长度, 未知算法返回0.
+ */
+uintptr_t opengm_alg_public_key_len(int32_t alg);
+
+/**
+ * opengm_alg_private_key_len 返回解封装密钥或私钥的编码长度, 未知算法返回0.
+ */
+uintptr_t opengm_alg_private_key_len(int32_t alg);
+
+/**
+ * opengm_alg_ciphertext_len 返回KEM密文长度, 签名算法或未知算法返回0.
+ */
+uintptr_t opengm_alg_ciphertext_len(int32_t alg);
+
+/**
+ * opengm_alg_shared_key_len 返回KEM共享密钥长度, 签名算法或未知算法返回0.
+ */
+uintptr_t opengm_alg_shared_key_len(int32_t alg);
+
+/**
+ * opengm_alg_signature_len 返回签名长度, KEM算法或未知算法返回0.
+ */
+uintptr_t opengm_alg_signature_len(int32_t alg);
+
+/**
+ * opengm_kem_keygen 由32字节的d,z生成密钥对, d,z由调用者使用随机数发生器生成.
+ * ek_len, dk_len必须等于opengm_alg_public_key_len(alg), opengm_alg_private_key_len(alg).
+ *
+ * # Safety
+ * d, z必须为NULL或指向32字节, ek, dk必须指向ek_len, dk_len字节的可写内存, 长度为0时可以为NULL.
+ */
+int32_t opengm_kem_keygen(int32_t alg, const uint8_t *d, const uint8_t *z, uint8_t *ek, uintptr_t ek_len, uint8_t *dk, uintptr_t dk_len);
+
+/**
+ * opengm_kem_encap 封装, key指向32字节, c指向c_len字节, m指向32字节的随机数.
+ *
+ * # Safety
+ * key必须为NULL或指向32字节的可写内存, m必须为NULL或指向32字节, c必须指向c_len字节的可写内存,
+ * ek必须指向ek_len字节, 长度为0时可以为NULL.
+ */
+int32_t opengm_kem_encap(int32_t alg, uint8_t *key, uint8_t *c, uintptr_t c_len, const uint8_t *ek, uintptr_t ek_len, const uint8_t *m);
+
+/**
+ * opengm_kem_decap 解封装, key指向32字节, c指向c_len字节.
+ *
+ * # Safety
+ * key必须为NULL或指向32字节的可写内存, c, dk必须指向c_len, dk_len字节, 长度为0时可以为NULL.
+ */
+int32_t opengm_kem_decap(int32_t alg, uint8_t *key, const uint8_t *c, uintptr_t c_len, const uint8_t *dk, uintptr_t dk_len);
+
+/**
+ * opengm_sig_keygen 由32字节的种子xi生成密钥对.
+ * pk_len, sk_len必须等于opengm_alg_public_key_len(alg), opengm_alg_private_key_len(alg).
+ *
+ * # Safety
+ * xi必须为NULL或指向32字节, pk, sk必须指向pk_len, sk_len字节的可写内存, 长度为0时可以为NULL.
+ */
+int32_t opengm_sig_keygen(int32_t alg, const uint8_t *xi, uint8_t *pk, uintptr_t pk_len, uint8_t *sk, uintptr_t sk_len);
+
+/**
+ * opengm_sig_sign 签名(ML-DSA.Sign), sig_len必须等于opengm_alg_signature_len(alg), ctx不超过255字节.
+ * rnd指向32字节, 全零时为确定性签名. mlen, ctx_len为0时m, ctx可以为NULL.
+ *
+ * # Safety
+ * rnd必须为NULL或指向32字节, sig必须指向sig_len字节的可写内存, sk, m, ctx必须指向sk_len, mlen, ctx_len字节,
+ * 长度为0时可以为NULL.
  */
-void *mlkem1024_keygen_internal(const uint8_t *d, const uint8_t *z);
+int32_t opengm_sig_sign(int32_t alg, uint8_t *sig, uintptr_t sig_len, const uint8_t *sk, uintptr_t sk_len, const uint8_t *m, uintptr_t mlen, const uint8_t *ctx, uintptr_t ctx_len, const uint8_t *rnd);
 
-void *mlkem1024_encapkey(void *dk_handle);
+/**
+ * opengm_sig_verify 验证签名(ML-DSA.Verify), 签名有效时返回OPENGM_OK, 无效时返回OPENGM_ERR_VERIFY.
+ *
+ * # Safety
+ * sig, pk, m, ctx必须指向sig_len, pk_len, mlen, ctx_len字节, 长度为0时可以为NULL.
+ */
+int32_t opengm_sig_verify(int32_t alg, const uint8_t *sig, uintptr_t sig_len, const uint8_t *pk, uintptr_t pk_len, const uint8_t *m, uintptr_t mlen, const uint8_t *ctx, uintptr_t ctx_len);
 
-int32_t mlkem1024_encap_internal(uint8_t *key, uint8_t *c, void *ek_handle, const uint8_t *m);
+/**
+ * opengm_strerror 返回错误码的描述, 返回的字符串是静态的, 调用者不能释放.
+ */
+const char *opengm_strerror(int32_t code);
 
-int32_t mlkem1024_decap(uint8_t *key, const uint8_t *c, void *dk_handle);
+/**
+ * opengm_xwing_keygen_internal 密钥生成, 成功时*dk_handle指向生成的解封装密钥.
+ * seed必须指向32字节, seed由调用者使用随机数发生器生成.
+ *
+ * # Safety
+ * seed必须为NULL或指向32字节, dk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_xwing_keygen_internal(const uint8_t *seed, void **dk_handle);
 
-void mlkem1024_encapkey_encode(uint8_t *ek_encoded, void *ek_handle);
+/**
+ * opengm_xwing_encapkey 由解封装密钥得到封装密钥, 成功时*ek_handle指向封装密钥.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回且未释放的handle, ek_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_xwing_encapkey(const void *dk_handle, void **ek_handle);
 
-void *mlkem1024_encapkey_decode(const uint8_t *ek_encoded);
+/**
+ * opengm_xwing_encap_internal 封装, key指向32字节, c指向cipher_len字节, eseed指向64字节的随机数.
+ *
+ * # Safety
+ * key, c必须为NULL或指向32, cipher_len字节的可写内存, eseed必须为NULL或指向64字节,
+ * ek_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_xwing_encap_internal(uint8_t *key, uint8_t *c, const void *ek_handle, const uint8_t *eseed);
 
-void mlkem1024_decapkey_encode(uint8_t *dk_encoded, void *dk_handle);
+/**
+ * opengm_xwing_decap 解封装, key指向32字节, c指向cipher_len字节.
+ *
+ * # Safety
+ * key必须为NULL或指向32字节的可写内存, c必须为NULL或指向cipher_len字节,
+ * dk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_xwing_decap(uint8_t *key, const uint8_t *c, const void *dk_handle);
 
-void *mlkem1024_decapkey_decode(const uint8_t *dk_encoded);
+/**
+ * opengm_xwing_encapkey_encode 编码封装密钥, ek_encoded指向ek_len字节.
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节的可写内存, ek_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_xwing_encapkey_encode(uint8_t *ek_encoded, const void *ek_handle);
 
-void mlkem1024_drop_encapkey_handle(void *ek_handle);
+/**
+ * opengm_xwing_encapkey_decode 解码封装密钥, ek_encoded指向ek_len字节.
+ * ML-KEM-768封装密钥未通过模数检查时返回OPENGM_ERR_DECODE.
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节, ek_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_xwing_encapkey_decode(const uint8_t *ek_encoded, void **ek_handle);
 
-void mlkem1024_drop_decapkey_handle(void *dk_handle);
+/**
+ * opengm_xwing_decapkey_encode 编码解封装密钥, dk_encoded指向dk_len(32)字节的种子.
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节的可写内存, dk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_xwing_decapkey_encode(uint8_t *dk_encoded, const void *dk_handle);
 
 /**
- *  exports C api
- * mlkem512_keygen_internal 密钥生成,dk必须指向dk_len的缓冲区
- * d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+ * opengm_xwing_decapkey_decode 解码解封装密钥, dk_encoded指向dk_len(32)字节的种子.
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节, dk_handle必须为NULL或指向可写的handle.
  */
-void *mlkem512_keygen_internal(const uint8_t *d, const uint8_t *z);
+int32_t opengm_xwing_decapkey_decode(const uint8_t *dk_encoded, void **dk_handle);
 
-void *mlkem512_encapkey(void *dk_handle);
+/**
+ * opengm_xwing_drop_encapkey_handle 释放封装密钥, ek_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * ek_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_xwing_drop_encapkey_handle(void *ek_handle);
+
+/**
+ * opengm_xwing_drop_decapkey_handle 释放解封装密钥, dk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_xwing_drop_decapkey_handle(void *dk_handle);
 
-int32_t mlkem512_encap_internal(uint8_t *key, uint8_t *c, void *ek_handle, const uint8_t *m);
+/**
+ * opengm_xwing_decapkey_size 返回解封装密钥所需的存储空间字节数.
+ */
+uintptr_t opengm_xwing_decapkey_size(void);
 
-int32_t mlkem512_decap(uint8_t *key, const uint8_t *c, void *dk_handle);
+/**
+ * opengm_xwing_encapkey_size 返回封装密钥所需的存储空间字节数.
+ */
+uintptr_t opengm_xwing_encapkey_size(void);
 
-void mlkem512_encapkey_encode(uint8_t *ek_encoded, void *ek_handle);
+/**
+ * opengm_xwing_keygen_internal_inplace 在storage中生成解封装密钥, 成功时storage可作为dk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_xwing_decapkey_size(), 否则返回OPENGM_ERR_STORAGE.
+ *
+ * # Safety
+ * seed必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_xwing_keygen_internal_inplace(const uint8_t *seed, void *storage, uintptr_t storage_size);
 
-void *mlkem512_encapkey_decode(const uint8_t *ek_encoded);
+/**
+ * opengm_xwing_encapkey_inplace 在storage中由解封装密钥得到封装密钥, 成功时storage可作为ek_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_xwing_encapkey_size().
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_xwing_encapkey_inplace(const void *dk_handle, void *storage, uintptr_t storage_size);
 
-void mlkem512_decapkey_encode(uint8_t *dk_encoded, void *dk_handle);
+/**
+ * opengm_xwing_encapkey_decode_inplace 在storage中解码封装密钥, ek_encoded指向ek_len字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_xwing_encapkey_size().
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_xwing_encapkey_decode_inplace(const uint8_t *ek_encoded, void *storage, uintptr_t storage_size);
 
-void *mlkem512_decapkey_decode(const uint8_t *dk_encoded);
+/**
+ * opengm_xwing_decapkey_decode_inplace 在storage中解码解封装密钥, dk_encoded指向dk_len(32)字节的种子.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_xwing_decapkey_size().
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_xwing_decapkey_decode_inplace(const uint8_t *dk_encoded, void *storage, uintptr_t storage_size);
 
-void mlkem512_drop_encapkey_handle(void *ek_handle);
+/**
+ * opengm_xwing_clear_encapkey 清除storage中的封装密钥, ek_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * ek_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_xwing_clear_encapkey(void *ek_handle);
 
-void mlkem512_drop_decapkey_handle(void *dk_handle);
+/**
+ * opengm_xwing_clear_decapkey 清除storage中的解封装密钥, 密钥被清零, dk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_xwing_clear_decapkey(void *dk_handle);
 
 /**
- * mlkem768_keygen_internal 密钥生成,dk必须指向dk_len的缓冲区
+ * opengm_mlkem1024_keygen_internal 密钥生成, 成功时*dk_handle指向生成的解封装密钥.
  * d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+ *
+ * # Safety
+ * d, z必须为NULL或指向32字节, dk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem1024_keygen_internal(const uint8_t *d, const uint8_t *z, void **dk_handle);
+
+/**
+ * opengm_mlkem1024_encapkey 由解封装密钥得到封装密钥, 成功时*ek_handle指向封装密钥.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回且未释放的handle, ek_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem1024_encapkey(const void *dk_handle, void **ek_handle);
+
+/**
+ * opengm_mlkem1024_encap_internal 封装, key指向32字节, c指向cipher_len字节, m指向32字节的随机数.
+ *
+ * # Safety
+ * key, c必须为NULL或指向32, cipher_len字节的可写内存, m必须为NULL或指向32字节,
+ * ek_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem1024_encap_internal(uint8_t *key, uint8_t *c, const void *ek_handle, const uint8_t *m);
+
+/**
+ * opengm_mlkem1024_decap 解封装, key指向32字节, c指向cipher_len字节.
+ *
+ * # Safety
+ * key必须为NULL或指向32字节的可写内存, c必须为NULL或指向cipher_len字节, dk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem1024_decap(uint8_t *key, const uint8_t *c, const void *dk_handle);
+
+/**
+ * opengm_mlkem1024_encapkey_encode 编码封装密钥, ek_encoded指向ek_len字节.
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节的可写内存, ek_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem1024_encapkey_encode(uint8_t *ek_encoded, const void *ek_handle);
+
+/**
+ * opengm_mlkem1024_encapkey_decode 解码封装密钥, ek_encoded指向ek_len字节.
+ * 封装密钥未通过模数检查时返回OPENGM_ERR_DECODE.
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节, ek_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem1024_encapkey_decode(const uint8_t *ek_encoded, void **ek_handle);
+
+/**
+ * opengm_mlkem1024_decapkey_encode 编码解封装密钥, dk_encoded指向dk_len字节.
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节的可写内存, dk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem1024_decapkey_encode(uint8_t *dk_encoded, const void *dk_handle);
+
+/**
+ * opengm_mlkem1024_decapkey_decode 解码解封装密钥, dk_encoded指向dk_len字节.
+ * 解封装密钥未通过哈希检查或模数检查时返回OPENGM_ERR_DECODE.
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节, dk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem1024_decapkey_decode(const uint8_t *dk_encoded, void **dk_handle);
+
+/**
+ * opengm_mlkem1024_drop_encapkey_handle 释放封装密钥, ek_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * ek_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem1024_drop_encapkey_handle(void *ek_handle);
+
+/**
+ * opengm_mlkem1024_drop_decapkey_handle 释放解封装密钥, dk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem1024_drop_decapkey_handle(void *dk_handle);
+
+/**
+ * opengm_mlkem1024_decapkey_size 返回解封装密钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mlkem1024_decapkey_size(void);
+
+/**
+ * opengm_mlkem1024_encapkey_size 返回封装密钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mlkem1024_encapkey_size(void);
+
+/**
+ * opengm_mlkem1024_keygen_internal_inplace 在storage中生成解封装密钥, 成功时storage可作为dk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem1024_decapkey_size(), 否则返回OPENGM_ERR_STORAGE.
+ *
+ * # Safety
+ * d, z必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem1024_keygen_internal_inplace(const uint8_t *d, const uint8_t *z, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mlkem1024_encapkey_inplace 在storage中由解封装密钥得到封装密钥, 成功时storage可作为ek_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem1024_encapkey_size().
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem1024_encapkey_inplace(const void *dk_handle, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mlkem1024_encapkey_decode_inplace 在storage中解码封装密钥, ek_encoded指向ek_len字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem1024_encapkey_size().
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem1024_encapkey_decode_inplace(const uint8_t *ek_encoded, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mlkem1024_decapkey_decode_inplace 在storage中解码解封装密钥, dk_encoded指向dk_len字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem1024_decapkey_size().
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem1024_decapkey_decode_inplace(const uint8_t *dk_encoded, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mlkem1024_clear_encapkey 清除storage中的封装密钥, ek_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * ek_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem1024_clear_encapkey(void *ek_handle);
+
+/**
+ * opengm_mlkem1024_clear_decapkey 清除storage中的解封装密钥, 密钥被清零, dk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem1024_clear_decapkey(void *dk_handle);
+
+/**
+ * mlkem1024_keygen_internal 密钥生成, d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+ *
+ * # Safety
+ * 同opengm_mlkem1024_keygen_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem1024_keygen_internal") void *mlkem1024_keygen_internal(const uint8_t *d, const uint8_t *z);
+
+/**
+ * # Safety
+ * 同opengm_mlkem1024_encapkey.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem1024_encapkey") void *mlkem1024_encapkey(void *dk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mlkem1024_encap_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem1024_encap_internal") int32_t mlkem1024_encap_internal(uint8_t *key, uint8_t *c, void *ek_handle, const uint8_t *m);
+
+/**
+ * # Safety
+ * 同opengm_mlkem1024_decap.
  */
-void *mlkem768_keygen_internal(const uint8_t *d, const uint8_t *z);
+OPENGM_DEPRECATED("use opengm_mlkem1024_decap") int32_t mlkem1024_decap(uint8_t *key, const uint8_t *c, void *dk_handle);
 
-void *mlkem768_encapkey(void *dk_handle);
+/**
+ * # Safety
+ * 同opengm_mlkem1024_encapkey_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem1024_encapkey_encode") void mlkem1024_encapkey_encode(uint8_t *ek_encoded, void *ek_handle);
+
+/**
+ * # Safety
+ * 同opengm_mlkem1024_encapkey_decode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem1024_encapkey_decode") void *mlkem1024_encapkey_decode(const uint8_t *ek_encoded);
 
-int32_t mlkem768_encap_internal(uint8_t *key, uint8_t *c, void *ek_handle, const uint8_t *m);
+/**
+ * # Safety
+ * 同opengm_mlkem1024_decapkey_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem1024_decapkey_encode") void mlkem1024_decapkey_encode(uint8_t *dk_encoded, void *dk_handle);
 
-int32_t mlkem768_decap(uint8_t *key, const uint8_t *c, void *dk_handle);
+/**
+ * # Safety
+ * 同opengm_mlkem1024_decapkey_decode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem1024_decapkey_decode") void *mlkem1024_decapkey_decode(const uint8_t *dk_encoded);
 
-void mlkem768_encapkey_encode(uint8_t *ek_encoded, void *ek_handle);
+/**
+ * # Safety
+ * 同opengm_mlkem1024_drop_encapkey_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem1024_drop_encapkey_handle") void mlkem1024_drop_encapkey_handle(void *ek_handle);
 
-void *mlkem768_encapkey_decode(const uint8_t *ek_encoded);
+/**
+ * # Safety
+ * 同opengm_mlkem1024_drop_decapkey_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem1024_drop_decapkey_handle") void mlkem1024_drop_decapkey_handle(void *dk_handle);
 
-void mlkem768_decapkey_encode(uint8_t *dk_encoded, void *dk_handle);
+/**
+ * opengm_mlkem512_keygen_internal 密钥生成, 成功时*dk_handle指向生成的解封装密钥.
+ * d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+ *
+ * # Safety
+ * d, z必须为NULL或指向32字节, dk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem512_keygen_internal(const uint8_t *d, const uint8_t *z, void **dk_handle);
 
-void *mlkem768_decapkey_decode(const uint8_t *dk_encoded);
+/**
+ * opengm_mlkem512_encapkey 由解封装密钥得到封装密钥, 成功时*ek_handle指向封装密钥.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回且未释放的handle, ek_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem512_encapkey(const void *dk_handle, void **ek_handle);
 
-void mlkem768_drop_encapkey_handle(void *ek_handle);
+/**
+ * opengm_mlkem512_encap_internal 封装, key指向32字节, c指向cipher_len字节, m指向32字节的随机数.
+ *
+ * # Safety
+ * key, c必须为NULL或指向32, cipher_len字节的可写内存, m必须为NULL或指向32字节,
+ * ek_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem512_encap_internal(uint8_t *key, uint8_t *c, const void *ek_handle, const uint8_t *m);
 
-void mlkem768_drop_decapkey_handle(void *dk_handle);
+/**
+ * opengm_mlkem512_decap 解封装, key指向32字节, c指向cipher_len字节.
+ *
+ * # Safety
+ * key必须为NULL或指向32字节的可写内存, c必须为NULL或指向cipher_len字节, dk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem512_decap(uint8_t *key, const uint8_t *c, const void *dk_handle);
 
 /**
- *  exports C api
+ * opengm_mlkem512_encapkey_encode 编码封装密钥, ek_encoded指向ek_len字节.
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节的可写内存, ek_handle必须为NULL或本库返回且未释放的handle.
  */
-void *mldsa44_generate_key_internal(const uint8_t *xi);
+int32_t opengm_mlkem512_encapkey_encode(uint8_t *ek_encoded, const void *ek_handle);
 
-void *mldsa44_public_key(void *sk_handle);
+/**
+ * opengm_mlkem512_encapkey_decode 解码封装密钥, ek_encoded指向ek_len字节.
+ * 封装密钥未通过模数检查时返回OPENGM_ERR_DECODE.
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节, ek_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem512_encapkey_decode(const uint8_t *ek_encoded, void **ek_handle);
 
-void mldsa44_private_key_encode(uint8_t *sk, void *sk_handle);
+/**
+ * opengm_mlkem512_decapkey_encode 编码解封装密钥, dk_encoded指向dk_len字节.
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节的可写内存, dk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem512_decapkey_encode(uint8_t *dk_encoded, const void *dk_handle);
 
-void mldsa44_public_key_encode(uint8_t *pk, void *pk_handle);
+/**
+ * opengm_mlkem512_decapkey_decode 解码解封装密钥, dk_encoded指向dk_len字节.
+ * 解封装密钥未通过哈希检查或模数检查时返回OPENGM_ERR_DECODE.
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节, dk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem512_decapkey_decode(const uint8_t *dk_encoded, void **dk_handle);
 
-void *mldsa44_import_private_key(const uint8_t *sk);
+/**
+ * opengm_mlkem512_drop_encapkey_handle 释放封装密钥, ek_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * ek_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem512_drop_encapkey_handle(void *ek_handle);
 
-void *mldsa44_import_public_key(const uint8_t *pk);
+/**
+ * opengm_mlkem512_drop_decapkey_handle 释放解封装密钥, dk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem512_drop_decapkey_handle(void *dk_handle);
 
-uint32_t mldsa44_sign_internal(uint8_t *sig, void *sk_handle, const uint8_t *m, uintptr_t mlen, const uint8_t *rnd);
+/**
+ * opengm_mlkem512_decapkey_size 返回解封装密钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mlkem512_decapkey_size(void);
 
-bool mldsa44_verify_internal(const uint8_t *sig, void *pk_handle, const uint8_t *m, uintptr_t mlen);
+/**
+ * opengm_mlkem512_encapkey_size 返回封装密钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mlkem512_encapkey_size(void);
 
-void mldsa44_drop_private_key_handle(void *sk_handle);
+/**
+ * opengm_mlkem512_keygen_internal_inplace 在storage中生成解封装密钥, 成功时storage可作为dk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem512_decapkey_size(), 否则返回OPENGM_ERR_STORAGE.
+ *
+ * # Safety
+ * d, z必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem512_keygen_internal_inplace(const uint8_t *d, const uint8_t *z, void *storage, uintptr_t storage_size);
 
-void mldsa44_drop_public_key_handle(void *pk_handle);
+/**
+ * opengm_mlkem512_encapkey_inplace 在storage中由解封装密钥得到封装密钥, 成功时storage可作为ek_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem512_encapkey_size().
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem512_encapkey_inplace(const void *dk_handle, void *storage, uintptr_t storage_size);
 
 /**
- *  exports C api
+ * opengm_mlkem512_encapkey_decode_inplace 在storage中解码封装密钥, ek_encoded指向ek_len字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem512_encapkey_size().
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节, storage必须为NULL或指向storage_size字节的可写内存.
  */
-void *mldsa65_generate_key_internal(const uint8_t *xi);
+int32_t opengm_mlkem512_encapkey_decode_inplace(const uint8_t *ek_encoded, void *storage, uintptr_t storage_size);
 
-void *mldsa65_public_key(void *sk_handle);
+/**
+ * opengm_mlkem512_decapkey_decode_inplace 在storage中解码解封装密钥, dk_encoded指向dk_len字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem512_decapkey_size().
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem512_decapkey_decode_inplace(const uint8_t *dk_encoded, void *storage, uintptr_t storage_size);
 
-void mldsa65_private_key_encode(uint8_t *sk, void *sk_handle);
+/**
+ * opengm_mlkem512_clear_encapkey 清除storage中的封装密钥, ek_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * ek_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem512_clear_encapkey(void *ek_handle);
 
-void mldsa65_public_key_encode(uint8_t *pk, void *pk_handle);
+/**
+ * opengm_mlkem512_clear_decapkey 清除storage中的解封装密钥, 密钥被清零, dk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem512_clear_decapkey(void *dk_handle);
 
-void *mldsa65_import_private_key(const uint8_t *sk);
+/**
+ * mlkem512_keygen_internal 密钥生成, d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+ *
+ * # Safety
+ * 同opengm_mlkem512_keygen_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem512_keygen_internal") void *mlkem512_keygen_internal(const uint8_t *d, const uint8_t *z);
 
-void *mldsa65_import_public_key(const uint8_t *pk);
+/**
+ * # Safety
+ * 同opengm_mlkem512_encapkey.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem512_encapkey") void *mlkem512_encapkey(void *dk_handle);
 
-uint32_t mldsa65_sign_internal(uint8_t *sig, void *sk_handle, const uint8_t *m, uintptr_t mlen, const uint8_t *rnd);
+/**
+ * # Safety
+ * 同opengm_mlkem512_encap_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem512_encap_internal") int32_t mlkem512_encap_internal(uint8_t *key, uint8_t *c, void *ek_handle, const uint8_t *m);
 
-bool mldsa65_verify_internal(const uint8_t *sig, void *pk_handle, const uint8_t *m, uintptr_t mlen);
+/**
+ * # Safety
+ * 同opengm_mlkem512_decap.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem512_decap") int32_t mlkem512_decap(uint8_t *key, const uint8_t *c, void *dk_handle);
 
-void mldsa65_drop_private_key_handle(void *sk_handle);
+/**
+ * # Safety
+ * 同opengm_mlkem512_encapkey_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem512_encapkey_encode") void mlkem512_encapkey_encode(uint8_t *ek_encoded, void *ek_handle);
 
-void mldsa65_drop_public_key_handle(void *pk_handle);
+/**
+ * # Safety
+ * 同opengm_mlkem512_encapkey_decode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem512_encapkey_decode") void *mlkem512_encapkey_decode(const uint8_t *ek_encoded);
 
 /**
- *  exports C api
+ * # Safety
+ * 同opengm_mlkem512_decapkey_encode.
  */
-void *mldsa87_generate_key_internal(const uint8_t *xi);
+OPENGM_DEPRECATED("use opengm_mlkem512_decapkey_encode") void mlkem512_decapkey_encode(uint8_t *dk_encoded, void *dk_handle);
 
-void *mldsa87_public_key(void *sk_handle);
+/**
+ * # Safety
+ * 同opengm_mlkem512_decapkey_decode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem512_decapkey_decode") void *mlkem512_decapkey_decode(const uint8_t *dk_encoded);
 
-void mldsa87_private_key_encode(uint8_t *sk, void *sk_handle);
+/**
+ * # Safety
+ * 同opengm_mlkem512_drop_encapkey_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem512_drop_encapkey_handle") void mlkem512_drop_encapkey_handle(void *ek_handle);
 
-void mldsa87_public_key_encode(uint8_t *pk, void *pk_handle);
+/**
+ * # Safety
+ * 同opengm_mlkem512_drop_decapkey_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem512_drop_decapkey_handle") void mlkem512_drop_decapkey_handle(void *dk_handle);
 
-void *mldsa87_import_private_key(const uint8_t *sk);
+/**
+ * opengm_mlkem768_keygen_internal 密钥生成, 成功时*dk_handle指向生成的解封装密钥.
+ * d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+ *
+ * # Safety
+ * d, z必须为NULL或指向32字节, dk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem768_keygen_internal(const uint8_t *d, const uint8_t *z, void **dk_handle);
 
-void *mldsa87_import_public_key(const uint8_t *pk);
+/**
+ * opengm_mlkem768_encapkey 由解封装密钥得到封装密钥, 成功时*ek_handle指向封装密钥.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回且未释放的handle, ek_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem768_encapkey(const void *dk_handle, void **ek_handle);
 
-uint32_t mldsa87_sign_internal(uint8_t *sig, void *sk_handle, const uint8_t *m, uintptr_t mlen, const uint8_t *rnd);
+/**
+ * opengm_mlkem768_encap_internal 封装, key指向32字节, c指向cipher_len字节, m指向32字节的随机数.
+ *
+ * # Safety
+ * key, c必须为NULL或指向32, cipher_len字节的可写内存, m必须为NULL或指向32字节,
+ * ek_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem768_encap_internal(uint8_t *key, uint8_t *c, const void *ek_handle, const uint8_t *m);
 
-bool mldsa87_verify_internal(const uint8_t *sig, void *pk_handle, const uint8_t *m, uintptr_t mlen);
+/**
+ * opengm_mlkem768_decap 解封装, key指向32字节, c指向cipher_len字节.
+ *
+ * # Safety
+ * key必须为NULL或指向32字节的可写内存, c必须为NULL或指向cipher_len字节, dk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem768_decap(uint8_t *key, const uint8_t *c, const void *dk_handle);
 
-void mldsa87_drop_private_key_handle(void *sk_handle);
+/**
+ * opengm_mlkem768_encapkey_encode 编码封装密钥, ek_encoded指向ek_len字节.
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节的可写内存, ek_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem768_encapkey_encode(uint8_t *ek_encoded, const void *ek_handle);
 
-void mldsa87_drop_public_key_handle(void *pk_handle);
+/**
+ * opengm_mlkem768_encapkey_decode 解码封装密钥, ek_encoded指向ek_len字节.
+ * 封装密钥未通过模数检查时返回OPENGM_ERR_DECODE.
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节, ek_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem768_encapkey_decode(const uint8_t *ek_encoded, void **ek_handle);
+
+/**
+ * opengm_mlkem768_decapkey_encode 编码解封装密钥, dk_encoded指向dk_len字节.
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节的可写内存, dk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mlkem768_decapkey_encode(uint8_t *dk_encoded, const void *dk_handle);
+
+/**
+ * opengm_mlkem768_decapkey_decode 解码解封装密钥, dk_encoded指向dk_len字节.
+ * 解封装密钥未通过哈希检查或模数检查时返回OPENGM_ERR_DECODE.
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节, dk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mlkem768_decapkey_decode(const uint8_t *dk_encoded, void **dk_handle);
+
+/**
+ * opengm_mlkem768_drop_encapkey_handle 释放封装密钥, ek_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * ek_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem768_drop_encapkey_handle(void *ek_handle);
+
+/**
+ * opengm_mlkem768_drop_decapkey_handle 释放解封装密钥, dk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem768_drop_decapkey_handle(void *dk_handle);
+
+/**
+ * opengm_mlkem768_decapkey_size 返回解封装密钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mlkem768_decapkey_size(void);
+
+/**
+ * opengm_mlkem768_encapkey_size 返回封装密钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mlkem768_encapkey_size(void);
+
+/**
+ * opengm_mlkem768_keygen_internal_inplace 在storage中生成解封装密钥, 成功时storage可作为dk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem768_decapkey_size(), 否则返回OPENGM_ERR_STORAGE.
+ *
+ * # Safety
+ * d, z必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem768_keygen_internal_inplace(const uint8_t *d, const uint8_t *z, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mlkem768_encapkey_inplace 在storage中由解封装密钥得到封装密钥, 成功时storage可作为ek_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem768_encapkey_size().
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem768_encapkey_inplace(const void *dk_handle, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mlkem768_encapkey_decode_inplace 在storage中解码封装密钥, ek_encoded指向ek_len字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem768_encapkey_size().
+ *
+ * # Safety
+ * ek_encoded必须为NULL或指向ek_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem768_encapkey_decode_inplace(const uint8_t *ek_encoded, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mlkem768_decapkey_decode_inplace 在storage中解码解封装密钥, dk_encoded指向dk_len字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem768_decapkey_size().
+ *
+ * # Safety
+ * dk_encoded必须为NULL或指向dk_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mlkem768_decapkey_decode_inplace(const uint8_t *dk_encoded, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mlkem768_clear_encapkey 清除storage中的封装密钥, ek_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * ek_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem768_clear_encapkey(void *ek_handle);
+
+/**
+ * opengm_mlkem768_clear_decapkey 清除storage中的解封装密钥, 密钥被清零, dk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * dk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mlkem768_clear_decapkey(void *dk_handle);
+
+/**
+ * mlkem768_keygen_internal 密钥生成, d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+ *
+ * # Safety
+ * 同opengm_mlkem768_keygen_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_keygen_internal") void *mlkem768_keygen_internal(const uint8_t *d, const uint8_t *z);
+
+/**
+ * # Safety
+ * 同opengm_mlkem768_encapkey.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_encapkey") void *mlkem768_encapkey(void *dk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mlkem768_encap_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_encap_internal") int32_t mlkem768_encap_internal(uint8_t *key, uint8_t *c, void *ek_handle, const uint8_t *m);
+
+/**
+ * # Safety
+ * 同opengm_mlkem768_decap.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_decap") int32_t mlkem768_decap(uint8_t *key, const uint8_t *c, void *dk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mlkem768_encapkey_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_encapkey_encode") void mlkem768_encapkey_encode(uint8_t *ek_encoded, void *ek_handle);
+
+/**
+ * # Safety
+ * 同opengm_mlkem768_encapkey_decode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_encapkey_decode") void *mlkem768_encapkey_decode(const uint8_t *ek_encoded);
+
+/**
+ * # Safety
+ * 同opengm_mlkem768_decapkey_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_decapkey_encode") void mlkem768_decapkey_encode(uint8_t *dk_encoded, void *dk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mlkem768_decapkey_decode.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_decapkey_decode") void *mlkem768_decapkey_decode(const uint8_t *dk_encoded);
+
+/**
+ * # Safety
+ * 同opengm_mlkem768_drop_encapkey_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_drop_encapkey_handle") void mlkem768_drop_encapkey_handle(void *ek_handle);
+
+/**
+ * # Safety
+ * 同opengm_mlkem768_drop_decapkey_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mlkem768_drop_decapkey_handle") void mlkem768_drop_decapkey_handle(void *dk_handle);
+
+/**
+ * opengm_mldsa44_generate_key_internal 由32字节的种子xi生成私钥, 成功时*sk_handle指向私钥.
+ *
+ * # Safety
+ * xi必须为NULL或指向32字节, sk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa44_generate_key_internal(const uint8_t *xi, void **sk_handle);
+
+/**
+ * opengm_mldsa44_public_key 由私钥得到公钥, 成功时*pk_handle指向公钥.
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回且未释放的handle, pk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa44_public_key(const void *sk_handle, void **pk_handle);
+
+/**
+ * opengm_mldsa44_private_key_encode 编码私钥, sk指向sklen字节.
+ *
+ * # Safety
+ * sk必须为NULL或指向sklen字节的可写内存, sk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa44_private_key_encode(uint8_t *sk, const void *sk_handle);
+
+/**
+ * opengm_mldsa44_public_key_encode 编码公钥, pk指向pklen字节.
+ *
+ * # Safety
+ * pk必须为NULL或指向pklen字节的可写内存, pk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa44_public_key_encode(uint8_t *pk, const void *pk_handle);
+
+/**
+ * opengm_mldsa44_import_private_key 导入私钥, sk指向sklen字节, 成功时*sk_handle指向私钥.
+ *
+ * # Safety
+ * sk必须为NULL或指向sklen字节, sk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa44_import_private_key(const uint8_t *sk, void **sk_handle);
+
+/**
+ * opengm_mldsa44_import_public_key 导入公钥, pk指向pklen字节, 成功时*pk_handle指向公钥.
+ *
+ * # Safety
+ * pk必须为NULL或指向pklen字节, pk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa44_import_public_key(const uint8_t *pk, void **pk_handle);
+
+/**
+ * opengm_mldsa44_sign_internal 签名, sig指向siglen字节, rnd指向32字节, mlen为0时m可以为NULL.
+ *
+ * # Safety
+ * sig必须为NULL或指向siglen字节的可写内存, rnd必须为NULL或指向32字节, m必须指向mlen字节,
+ * sk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa44_sign_internal(uint8_t *sig, const void *sk_handle, const uint8_t *m, uintptr_t mlen, const uint8_t *rnd);
+
+/**
+ * opengm_mldsa44_verify_internal 验证签名, 签名有效时返回OPENGM_OK, 无效时返回OPENGM_ERR_VERIFY.
+ *
+ * # Safety
+ * sig必须为NULL或指向siglen字节, m必须指向mlen字节, pk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa44_verify_internal(const uint8_t *sig, const void *pk_handle, const uint8_t *m, uintptr_t mlen);
+
+/**
+ * opengm_mldsa44_drop_private_key_handle 释放私钥, sk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa44_drop_private_key_handle(void *sk_handle);
+
+/**
+ * opengm_mldsa44_drop_public_key_handle 释放公钥, pk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * pk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa44_drop_public_key_handle(void *pk_handle);
+
+/**
+ * opengm_mldsa44_private_key_size 返回私钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mldsa44_private_key_size(void);
+
+/**
+ * opengm_mldsa44_public_key_size 返回公钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mldsa44_public_key_size(void);
+
+/**
+ * opengm_mldsa44_generate_key_internal_inplace 在storage中由32字节的种子xi生成私钥, 成功时storage可作为sk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa44_private_key_size(), 否则返回OPENGM_ERR_STORAGE.
+ *
+ * # Safety
+ * xi必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa44_generate_key_internal_inplace(const uint8_t *xi, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa44_public_key_inplace 在storage中由私钥得到公钥, 成功时storage可作为pk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa44_public_key_size().
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa44_public_key_inplace(const void *sk_handle, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa44_import_private_key_inplace 在storage中导入私钥, sk指向sklen字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa44_private_key_size().
+ *
+ * # Safety
+ * sk必须为NULL或指向sklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa44_import_private_key_inplace(const uint8_t *sk, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa44_import_public_key_inplace 在storage中导入公钥, pk指向pklen字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa44_public_key_size().
+ *
+ * # Safety
+ * pk必须为NULL或指向pklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa44_import_public_key_inplace(const uint8_t *pk, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa44_clear_private_key 清除storage中的私钥, 私钥被清零, sk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa44_clear_private_key(void *sk_handle);
+
+/**
+ * opengm_mldsa44_clear_public_key 清除storage中的公钥, pk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * pk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa44_clear_public_key(void *pk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_generate_key_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_generate_key_internal") void *mldsa44_generate_key_internal(const uint8_t *xi);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_public_key.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_public_key") void *mldsa44_public_key(void *sk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_private_key_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_private_key_encode") void mldsa44_private_key_encode(uint8_t *sk, void *sk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_public_key_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_public_key_encode") void mldsa44_public_key_encode(uint8_t *pk, void *pk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_import_private_key.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_import_private_key") void *mldsa44_import_private_key(const uint8_t *sk);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_import_public_key.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_import_public_key") void *mldsa44_import_public_key(const uint8_t *pk);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_sign_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_sign_internal") uint32_t mldsa44_sign_internal(uint8_t *sig, void *sk_handle, const uint8_t *m, uintptr_t mlen, const uint8_t *rnd);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_verify_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_verify_internal") bool mldsa44_verify_internal(const uint8_t *sig, void *pk_handle, const uint8_t *m, uintptr_t mlen);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_drop_private_key_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_drop_private_key_handle") void mldsa44_drop_private_key_handle(void *sk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa44_drop_public_key_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa44_drop_public_key_handle") void mldsa44_drop_public_key_handle(void *pk_handle);
+
+/**
+ * opengm_mldsa65_generate_key_internal 由32字节的种子xi生成私钥, 成功时*sk_handle指向私钥.
+ *
+ * # Safety
+ * xi必须为NULL或指向32字节, sk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa65_generate_key_internal(const uint8_t *xi, void **sk_handle);
+
+/**
+ * opengm_mldsa65_public_key 由私钥得到公钥, 成功时*pk_handle指向公钥.
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回且未释放的handle, pk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa65_public_key(const void *sk_handle, void **pk_handle);
+
+/**
+ * opengm_mldsa65_private_key_encode 编码私钥, sk指向sklen字节.
+ *
+ * # Safety
+ * sk必须为NULL或指向sklen字节的可写内存, sk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa65_private_key_encode(uint8_t *sk, const void *sk_handle);
+
+/**
+ * opengm_mldsa65_public_key_encode 编码公钥, pk指向pklen字节.
+ *
+ * # Safety
+ * pk必须为NULL或指向pklen字节的可写内存, pk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa65_public_key_encode(uint8_t *pk, const void *pk_handle);
+
+/**
+ * opengm_mldsa65_import_private_key 导入私钥, sk指向sklen字节, 成功时*sk_handle指向私钥.
+ *
+ * # Safety
+ * sk必须为NULL或指向sklen字节, sk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa65_import_private_key(const uint8_t *sk, void **sk_handle);
+
+/**
+ * opengm_mldsa65_import_public_key 导入公钥, pk指向pklen字节, 成功时*pk_handle指向公钥.
+ *
+ * # Safety
+ * pk必须为NULL或指向pklen字节, pk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa65_import_public_key(const uint8_t *pk, void **pk_handle);
+
+/**
+ * opengm_mldsa65_sign_internal 签名, sig指向siglen字节, rnd指向32字节, mlen为0时m可以为NULL.
+ *
+ * # Safety
+ * sig必须为NULL或指向siglen字节的可写内存, rnd必须为NULL或指向32字节, m必须指向mlen字节,
+ * sk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa65_sign_internal(uint8_t *sig, const void *sk_handle, const uint8_t *m, uintptr_t mlen, const uint8_t *rnd);
+
+/**
+ * opengm_mldsa65_verify_internal 验证签名, 签名有效时返回OPENGM_OK, 无效时返回OPENGM_ERR_VERIFY.
+ *
+ * # Safety
+ * sig必须为NULL或指向siglen字节, m必须指向mlen字节, pk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa65_verify_internal(const uint8_t *sig, const void *pk_handle, const uint8_t *m, uintptr_t mlen);
+
+/**
+ * opengm_mldsa65_drop_private_key_handle 释放私钥, sk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa65_drop_private_key_handle(void *sk_handle);
+
+/**
+ * opengm_mldsa65_drop_public_key_handle 释放公钥, pk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * pk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa65_drop_public_key_handle(void *pk_handle);
+
+/**
+ * opengm_mldsa65_private_key_size 返回私钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mldsa65_private_key_size(void);
+
+/**
+ * opengm_mldsa65_public_key_size 返回公钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mldsa65_public_key_size(void);
+
+/**
+ * opengm_mldsa65_generate_key_internal_inplace 在storage中由32字节的种子xi生成私钥, 成功时storage可作为sk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa65_private_key_size(), 否则返回OPENGM_ERR_STORAGE.
+ *
+ * # Safety
+ * xi必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa65_generate_key_internal_inplace(const uint8_t *xi, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa65_public_key_inplace 在storage中由私钥得到公钥, 成功时storage可作为pk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa65_public_key_size().
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa65_public_key_inplace(const void *sk_handle, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa65_import_private_key_inplace 在storage中导入私钥, sk指向sklen字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa65_private_key_size().
+ *
+ * # Safety
+ * sk必须为NULL或指向sklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa65_import_private_key_inplace(const uint8_t *sk, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa65_import_public_key_inplace 在storage中导入公钥, pk指向pklen字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa65_public_key_size().
+ *
+ * # Safety
+ * pk必须为NULL或指向pklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa65_import_public_key_inplace(const uint8_t *pk, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa65_clear_private_key 清除storage中的私钥, 私钥被清零, sk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa65_clear_private_key(void *sk_handle);
+
+/**
+ * opengm_mldsa65_clear_public_key 清除storage中的公钥, pk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * pk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa65_clear_public_key(void *pk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_generate_key_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_generate_key_internal") void *mldsa65_generate_key_internal(const uint8_t *xi);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_public_key.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_public_key") void *mldsa65_public_key(void *sk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_private_key_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_private_key_encode") void mldsa65_private_key_encode(uint8_t *sk, void *sk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_public_key_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_public_key_encode") void mldsa65_public_key_encode(uint8_t *pk, void *pk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_import_private_key.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_import_private_key") void *mldsa65_import_private_key(const uint8_t *sk);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_import_public_key.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_import_public_key") void *mldsa65_import_public_key(const uint8_t *pk);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_sign_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_sign_internal") uint32_t mldsa65_sign_internal(uint8_t *sig, void *sk_handle, const uint8_t *m, uintptr_t mlen, const uint8_t *rnd);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_verify_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_verify_internal") bool mldsa65_verify_internal(const uint8_t *sig, void *pk_handle, const uint8_t *m, uintptr_t mlen);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_drop_private_key_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_drop_private_key_handle") void mldsa65_drop_private_key_handle(void *sk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa65_drop_public_key_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa65_drop_public_key_handle") void mldsa65_drop_public_key_handle(void *pk_handle);
+
+/**
+ * opengm_mldsa87_generate_key_internal 由32字节的种子xi生成私钥, 成功时*sk_handle指向私钥.
+ *
+ * # Safety
+ * xi必须为NULL或指向32字节, sk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa87_generate_key_internal(const uint8_t *xi, void **sk_handle);
+
+/**
+ * opengm_mldsa87_public_key 由私钥得到公钥, 成功时*pk_handle指向公钥.
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回且未释放的handle, pk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa87_public_key(const void *sk_handle, void **pk_handle);
+
+/**
+ * opengm_mldsa87_private_key_encode 编码私钥, sk指向sklen字节.
+ *
+ * # Safety
+ * sk必须为NULL或指向sklen字节的可写内存, sk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa87_private_key_encode(uint8_t *sk, const void *sk_handle);
+
+/**
+ * opengm_mldsa87_public_key_encode 编码公钥, pk指向pklen字节.
+ *
+ * # Safety
+ * pk必须为NULL或指向pklen字节的可写内存, pk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa87_public_key_encode(uint8_t *pk, const void *pk_handle);
+
+/**
+ * opengm_mldsa87_import_private_key 导入私钥, sk指向sklen字节, 成功时*sk_handle指向私钥.
+ *
+ * # Safety
+ * sk必须为NULL或指向sklen字节, sk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa87_import_private_key(const uint8_t *sk, void **sk_handle);
+
+/**
+ * opengm_mldsa87_import_public_key 导入公钥, pk指向pklen字节, 成功时*pk_handle指向公钥.
+ *
+ * # Safety
+ * pk必须为NULL或指向pklen字节, pk_handle必须为NULL或指向可写的handle.
+ */
+int32_t opengm_mldsa87_import_public_key(const uint8_t *pk, void **pk_handle);
+
+/**
+ * opengm_mldsa87_sign_internal 签名, sig指向siglen字节, rnd指向32字节, mlen为0时m可以为NULL.
+ *
+ * # Safety
+ * sig必须为NULL或指向siglen字节的可写内存, rnd必须为NULL或指向32字节, m必须指向mlen字节,
+ * sk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa87_sign_internal(uint8_t *sig, const void *sk_handle, const uint8_t *m, uintptr_t mlen, const uint8_t *rnd);
+
+/**
+ * opengm_mldsa87_verify_internal 验证签名, 签名有效时返回OPENGM_OK, 无效时返回OPENGM_ERR_VERIFY.
+ *
+ * # Safety
+ * sig必须为NULL或指向siglen字节, m必须指向mlen字节, pk_handle必须为NULL或本库返回且未释放的handle.
+ */
+int32_t opengm_mldsa87_verify_internal(const uint8_t *sig, const void *pk_handle, const uint8_t *m, uintptr_t mlen);
+
+/**
+ * opengm_mldsa87_drop_private_key_handle 释放私钥, sk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa87_drop_private_key_handle(void *sk_handle);
+
+/**
+ * opengm_mldsa87_drop_public_key_handle 释放公钥, pk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+ *
+ * # Safety
+ * pk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa87_drop_public_key_handle(void *pk_handle);
+
+/**
+ * opengm_mldsa87_private_key_size 返回私钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mldsa87_private_key_size(void);
+
+/**
+ * opengm_mldsa87_public_key_size 返回公钥所需的存储空间字节数.
+ */
+uintptr_t opengm_mldsa87_public_key_size(void);
+
+/**
+ * opengm_mldsa87_generate_key_internal_inplace 在storage中由32字节的种子xi生成私钥, 成功时storage可作为sk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa87_private_key_size(), 否则返回OPENGM_ERR_STORAGE.
+ *
+ * # Safety
+ * xi必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa87_generate_key_internal_inplace(const uint8_t *xi, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa87_public_key_inplace 在storage中由私钥得到公钥, 成功时storage可作为pk_handle使用.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa87_public_key_size().
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa87_public_key_inplace(const void *sk_handle, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa87_import_private_key_inplace 在storage中导入私钥, sk指向sklen字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa87_private_key_size().
+ *
+ * # Safety
+ * sk必须为NULL或指向sklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa87_import_private_key_inplace(const uint8_t *sk, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa87_import_public_key_inplace 在storage中导入公钥, pk指向pklen字节.
+ * storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa87_public_key_size().
+ *
+ * # Safety
+ * pk必须为NULL或指向pklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+ */
+int32_t opengm_mldsa87_import_public_key_inplace(const uint8_t *pk, void *storage, uintptr_t storage_size);
+
+/**
+ * opengm_mldsa87_clear_private_key 清除storage中的私钥, 私钥被清零, sk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * sk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa87_clear_private_key(void *sk_handle);
+
+/**
+ * opengm_mldsa87_clear_public_key 清除storage中的公钥, pk_handle为NULL时不做任何操作.
+ *
+ * # Safety
+ * pk_handle必须为NULL或本库返回的handle.
+ */
+int32_t opengm_mldsa87_clear_public_key(void *pk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_generate_key_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_generate_key_internal") void *mldsa87_generate_key_internal(const uint8_t *xi);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_public_key.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_public_key") void *mldsa87_public_key(void *sk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_private_key_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_private_key_encode") void mldsa87_private_key_encode(uint8_t *sk, void *sk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_public_key_encode.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_public_key_encode") void mldsa87_public_key_encode(uint8_t *pk, void *pk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_import_private_key.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_import_private_key") void *mldsa87_import_private_key(const uint8_t *sk);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_import_public_key.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_import_public_key") void *mldsa87_import_public_key(const uint8_t *pk);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_sign_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_sign_internal") uint32_t mldsa87_sign_internal(uint8_t *sig, void *sk_handle, const uint8_t *m, uintptr_t mlen, const uint8_t *rnd);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_verify_internal.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_verify_internal") bool mldsa87_verify_internal(const uint8_t *sig, void *pk_handle, const uint8_t *m, uintptr_t mlen);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_drop_private_key_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_drop_private_key_handle") void mldsa87_drop_private_key_handle(void *sk_handle);
+
+/**
+ * # Safety
+ * 同opengm_mldsa87_drop_public_key_handle.
+ */
+OPENGM_DEPRECATED("use opengm_mldsa87_drop_public_key_handle") void mldsa87_drop_public_key_handle(void *pk_handle);
 
 #ifdef __cplusplus
 }  // extern "C"
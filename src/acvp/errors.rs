@@ -0,0 +1,22 @@
+use thiserror;
+
+use crate::{alg, json};
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error(transparent)]
+    Json(#[from] json::Error),
+
+    #[error(transparent)]
+    Alg(#[from] alg::Error),
+
+    #[error("missing field {0}")]
+    MissingField(&'static str),
+
+    #[error("malformed field {0}")]
+    Malformed(&'static str),
+
+    #[error("unsupported algorithm, mode or test group")]
+    Unsupported,
+}
+pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+
+use super::{Error, Result, bool_field, case, hex, hex_array, hex_field, parameter_set, str_field, tests};
+use crate::alg::{self, Algorithm};
+use crate::json::Value;
+use crate::mldsa::PreHash;
+
+// the body with $p the module of the ML-DSA parameter set
+macro_rules! with_parameter_set {
+    ($alg:expr, $p:ident => $body:block) => {
+        match $alg {
+            Algorithm::MlDsa44 => {
+                use crate::mldsa::mldsa44 as $p;
+                $body
+            }
+            Algorithm::MlDsa65 => {
+                use crate::mldsa::mldsa65 as $p;
+                $body
+            }
+            Algorithm::MlDsa87 => {
+                use crate::mldsa::mldsa87 as $p;
+                $body
+            }
+            _ => Err(Error::Unsupported),
+        }
+    };
+}
+
+/// ML-DSA.KeyGen_internal of the seed.
+pub(super) fn key_gen(tg: &Value) -> Result<Vec<Value>> {
+    let alg = parameter_set(tg, Algorithm::is_signature)?;
+    tests(tg)?
+        .iter()
+        .map(|tc| {
+            let (mut pk, mut sk) = (vec![0; alg.public_key_len()], vec![0; alg.private_key_len()]);
+            alg.sig_keygen_internal(&hex_array(tg, tc, "seed")?, &mut pk, &mut sk)?;
+            case(tc, vec![("pk", hex(&pk)), ("sk", hex(&sk))])
+        })
+        .collect()
+}
+
+/// The signatures of the interface of the group, hedged with rnd or deterministic.
+pub(super) fn sig_gen(tg: &Value) -> Result<Vec<Value>> {
+    let alg = parameter_set(tg, Algorithm::is_signature)?;
+    let deterministic = bool_field(tg, "deterministic")?;
+    tests(tg)?
+        .iter()
+        .map(|tc| {
+            let rnd = if deterministic {
+                [0; 32]
+            } else {
+                hex_array(tg, tc, "rnd")?
+            };
+            let sig = sign(alg, &hex_field(tg, tc, "sk")?, &Message::new(tg, tc)?, &rnd)?;
+            case(tc, vec![("signature", hex(&sig))])
+        })
+        .collect()
+}
+
+/// The verifications of the interface of the group.
+pub(super) fn sig_ver(tg: &Value) -> Result<Vec<Value>> {
+    let alg = parameter_set(tg, Algorithm::is_signature)?;
+    tests(tg)?
+        .iter()
+        .map(|tc| {
+            let passed = verify(
+                alg,
+                &hex_field(tg, tc, "pk")?,
+                &Message::new(tg, tc)?,
+                &hex_field(tg, tc, "signature")?,
+            )?;
+            case(tc, vec![("testPassed", passed.into())])
+        })
+        .collect()
+}
+
+// the input of a test case, as the signature interface of its group takes it
+enum Message {
+    Internal(Vec<u8>),
+    // the message representative of the external mu groups
+    Mu([u8; 64]),
+    // the message and the context
+    Pure(Vec<u8>, Vec<u8>),
+    PreHash(Vec<u8>, Vec<u8>, PreHash),
+}
+
+impl Message {
+    fn new(tg: &Value, tc: &Value) -> Result<Self> {
+        // the first revision has only the internal interface, without signatureInterface
+        let interface = match tg.get("signatureInterface") {
+            Some(_) => str_field(tg, "signatureInterface")?,
+            None => "internal",
+        };
+        match interface {
+            "internal" if tg.get("externalMu").and_then(Value::as_bool) == Some(true) => {
+                Ok(Message::Mu(hex_array(tg, tc, "mu")?))
+            }
+            "internal" => Ok(Message::Internal(hex_field(tg, tc, "message")?)),
+            "external" => {
+                let (m, ctx) = (hex_field(tg, tc, "message")?, hex_field(tg, tc, "context")?);
+                match str_field(tg, "preHash")? {
+                    "pure" => Ok(Message::Pure(m, ctx)),
+                    "preHash" => Ok(Message::PreHash(m, ctx, pre_hash(str_field(tc, "hashAlg")?)?)),
+                    _ => Err(Error::Unsupported),
+                }
+            }
+            _ => Err(Error::Unsupported),
+        }
+    }
+}
+
+fn pre_hash(name: &str) -> Result<PreHash> {
+    Ok(match name {
+        "SHA2-224" => PreHash::Sha224,
+        "SHA2-256" => PreHash::Sha256,
+        "SHA2-384" => PreHash::Sha384,
+        "SHA2-512" => PreHash::Sha512,
+        "SHA2-512/224" => PreHash::Sha512_224,
+        "SHA2-512/256" => PreHash::Sha512_256,
+        "SHA3-224" => PreHash::Sha3_224,
+        "SHA3-256" => PreHash::Sha3_256,
+        "SHA3-384" => PreHash::Sha3_384,
+        "SHA3-512" => PreHash::Sha3_512,
+        "SHAKE-128" => PreHash::Shake128,
+        "SHAKE-256" => PreHash::Shake256,
+        _ => return Err(Error::Unsupported),
+    })
+}
+
+fn sign(alg: Algorithm, sk: &[u8], m: &Message, rnd: &[u8; 32]) -> Result<Vec<u8>> {
+    with_parameter_set!(alg, p => {
+        let sk = p::PrivateKey::sk_decode(alg::array_ref(sk)?);
+        let sig = match m {
+            Message::Internal(m) => sk.sign_internal(m, rnd),
+            Message::Mu(mu) => sk.sign_mu(mu, rnd),
+            Message::Pure(m, ctx) => sk.sign(m, ctx, rnd).map_err(|_| alg::Error::ContextTooLong)?,
+            Message::PreHash(m, ctx, ph) => sk.hash_sign(m, ctx, *ph, rnd).map_err(|_| alg::Error::ContextTooLong)?,
+        };
+        Ok(sig.sig_encode().to_vec())
+    })
+}
+
+// false for the signatures of a wrong length or with a malformed hint
+fn verify(alg: Algorithm, pk: &[u8], m: &Message, sig: &[u8]) -> Result<bool> {
+    with_parameter_set!(alg, p => {
+        let pk = p::PublicKey::pk_decode(alg::array_ref(pk)?);
+        let Some(sig) = alg::array_ref(sig).ok().and_then(p::Signature::sig_decode) else {
+            return Ok(false);
+        };
+        Ok(match m {
+            Message::Internal(m) => pk.verify_internal(m, &sig),
+            Message::Mu(mu) => pk.verify_mu(mu, &sig),
+            Message::Pure(m, ctx) => pk.verify(m, ctx, &sig),
+            Message::PreHash(m, ctx, ph) => pk.hash_verify(m, ctx, *ph, &sig),
+        })
+    })
+}
@@ -0,0 +1,65 @@
+use alloc::vec::Vec;
+
+use super::{Error, Result, case, hex, hex_array, hex_field, parameter_set, str_field, tests};
+use crate::alg::{Algorithm, Kem, MlKem512, MlKem768, MlKem1024};
+use crate::json::Value;
+
+/// ML-KEM.KeyGen_internal of the seeds d and z.
+pub(super) fn key_gen(tg: &Value) -> Result<Vec<Value>> {
+    let alg = parameter_set(tg, Algorithm::is_kem)?;
+    tests(tg)?
+        .iter()
+        .map(|tc| {
+            let (mut ek, mut dk) = (vec![0; alg.public_key_len()], vec![0; alg.private_key_len()]);
+            alg.kem_keygen_internal(&hex_array(tg, tc, "d")?, &hex_array(tg, tc, "z")?, &mut ek, &mut dk)?;
+            case(tc, vec![("ek", hex(&ek)), ("dk", hex(&dk))])
+        })
+        .collect()
+}
+
+/// ML-KEM.Encaps_internal, ML-KEM.Decaps and the key checks, by the function of the group.
+pub(super) fn encap_decap(tg: &Value) -> Result<Vec<Value>> {
+    let alg = parameter_set(tg, Algorithm::is_kem)?;
+    let function = str_field(tg, "function")?;
+    tests(tg)?
+        .iter()
+        .map(|tc| match function {
+            "encapsulation" => {
+                let mut c = vec![0; alg.ciphertext_len()];
+                let k = alg.kem_encaps_internal(&hex_field(tg, tc, "ek")?, &hex_array(tg, tc, "m")?, &mut c)?;
+                case(tc, vec![("c", hex(&c)), ("k", hex(&k))])
+            }
+            "decapsulation" => {
+                let k = alg.kem_decaps(&hex_field(tg, tc, "dk")?, &hex_field(tg, tc, "c")?)?;
+                case(tc, vec![("k", hex(&k))])
+            }
+            "encapsulationKeyCheck" => {
+                let passed = key_check(alg, false, &hex_field(tg, tc, "ek")?);
+                case(tc, vec![("testPassed", passed.into())])
+            }
+            "decapsulationKeyCheck" => {
+                let passed = key_check(alg, true, &hex_field(tg, tc, "dk")?);
+                case(tc, vec![("testPassed", passed.into())])
+            }
+            _ => Err(Error::Unsupported),
+        })
+        .collect()
+}
+
+// the input checks of FIPS 203 section 7.2 and 7.3, the length then the modulus check of ek or the
+// hash check of dk, which the decoding of the keys does
+fn key_check(alg: Algorithm, decap: bool, key: &[u8]) -> bool {
+    fn check<K: Kem>(decap: bool, key: &[u8]) -> bool {
+        if decap {
+            K::decode_decap_key(key).is_ok()
+        } else {
+            K::decode_encap_key(key).is_ok()
+        }
+    }
+    match alg {
+        Algorithm::MlKem512 => check::<MlKem512>(decap, key),
+        Algorithm::MlKem768 => check::<MlKem768>(decap, key),
+        Algorithm::MlKem1024 => check::<MlKem1024>(decap, key),
+        _ => false,
+    }
+}
@@ -0,0 +1,428 @@
+//! Responses to the NIST ACVP vector sets of ML-KEM (FIPS 203) and ML-DSA (FIPS 204).
+//!
+//! [`respond`] answers a vector set in the JSON of the ACVP-Server, the `prompt.json` of its
+//! json-files or the `[{"acvVersion": ..}, {vector set}]` message of the protocol, with the response
+//! of the same shape: the `expectedResults.json` when the implementation is correct. The ML-KEM
+//! modes are `keyGen` and `encapDecap` with its four functions, encapsulation, decapsulation and the
+//! encapsulation and decapsulation key checks. The ML-DSA modes are `keyGen`, `sigGen` and `sigVer`
+//! with the internal interface, the external mu included, and the external pure and pre-hash ones.
+//!
+//! The `acvp-responder` binary of the `std` feature answers request files with it. The tests check
+//! the vector sets of `testdata/acvp`, and those of the gen-val/json-files directory of an
+//! ACVP-Server checkout named by the `ACVP_JSON_FILES` environment variable.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::alg::Algorithm;
+use crate::json::Value;
+
+mod errors;
+mod mldsa;
+mod mlkem;
+
+pub use errors::{Error, Result};
+
+/// The response to the prompt of a vector set, or to the array of the protocol message.
+pub fn respond(prompt: &Value) -> Result<Value> {
+    match prompt {
+        Value::Array(a) => a
+            .iter()
+            .map(|v| {
+                if v.get("testGroups").is_some() {
+                    respond_vector_set(v)
+                } else {
+                    Ok(v.clone())
+                }
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array),
+        _ => respond_vector_set(prompt),
+    }
+}
+
+fn respond_vector_set(vs: &Value) -> Result<Value> {
+    let respond_group: fn(&Value) -> Result<Vec<Value>> = match (str_field(vs, "algorithm")?, str_field(vs, "mode")?) {
+        ("ML-KEM", "keyGen") => mlkem::key_gen,
+        ("ML-KEM", "encapDecap") => mlkem::encap_decap,
+        ("ML-DSA", "keyGen") => mldsa::key_gen,
+        ("ML-DSA", "sigGen") => mldsa::sig_gen,
+        ("ML-DSA", "sigVer") => mldsa::sig_ver,
+        _ => return Err(Error::Unsupported),
+    };
+
+    let mut groups = Vec::new();
+    for tg in array_field(vs, "testGroups")? {
+        let tg_id = tg.get("tgId").ok_or(Error::MissingField("tgId"))?.clone();
+        groups.push(object(vec![
+            ("tgId", tg_id),
+            ("tests", Value::Array(respond_group(tg)?)),
+        ]));
+    }
+    let mut response: Vec<_> = ["vsId", "algorithm", "mode", "revision", "isSample"]
+        .into_iter()
+        .filter_map(|name| Some((name, vs.get(name)?.clone())))
+        .collect();
+    response.push(("testGroups", Value::Array(groups)));
+    Ok(object(response))
+}
+
+fn object(fields: Vec<(&str, Value)>) -> Value {
+    Value::Object(fields.into_iter().map(|(name, v)| (name.into(), v)).collect())
+}
+
+fn str_field<'a>(v: &'a Value, name: &'static str) -> Result<&'a str> {
+    v.get(name)
+        .ok_or(Error::MissingField(name))?
+        .as_str()
+        .ok_or(Error::Malformed(name))
+}
+
+fn array_field<'a>(v: &'a Value, name: &'static str) -> Result<&'a [Value]> {
+    v.get(name)
+        .ok_or(Error::MissingField(name))?
+        .as_array()
+        .ok_or(Error::Malformed(name))
+}
+
+fn bool_field(v: &Value, name: &'static str) -> Result<bool> {
+    v.get(name)
+        .ok_or(Error::MissingField(name))?
+        .as_bool()
+        .ok_or(Error::Malformed(name))
+}
+
+/// The parameter set of the test group, of the kind of algorithm.
+fn parameter_set(tg: &Value, kind: fn(Algorithm) -> bool) -> Result<Algorithm> {
+    let name = str_field(tg, "parameterSet")?;
+    Algorithm::ALL
+        .into_iter()
+        .find(|alg| kind(*alg) && alg.name() == name)
+        .ok_or(Error::Unsupported)
+}
+
+// the hex field of the test case, or of its group in the older revisions which share the keys
+fn hex_field(tg: &Value, tc: &Value, name: &'static str) -> Result<Vec<u8>> {
+    let s = tc.get(name).or_else(|| tg.get(name)).ok_or(Error::MissingField(name))?;
+    let s = s.as_str().ok_or(Error::Malformed(name))?.as_bytes();
+    if !s.len().is_multiple_of(2) {
+        return Err(Error::Malformed(name));
+    }
+    let nibble = |c: u8| (c as char).to_digit(16).ok_or(Error::Malformed(name));
+    s.chunks_exact(2)
+        .map(|c| Ok((nibble(c[0])? << 4 | nibble(c[1])?) as u8))
+        .collect()
+}
+
+fn hex_array<const N: usize>(tg: &Value, tc: &Value, name: &'static str) -> Result<[u8; N]> {
+    hex_field(tg, tc, name)?.try_into().map_err(|_| Error::Malformed(name))
+}
+
+// upper case, as the ACVP-Server
+fn hex(b: &[u8]) -> Value {
+    let mut s = String::with_capacity(2 * b.len());
+    for x in b {
+        write!(s, "{x:02X}").unwrap();
+    }
+    Value::String(s)
+}
+
+// the response to a test case, its tcId then the fields
+fn case(tc: &Value, fields: Vec<(&str, Value)>) -> Result<Value> {
+    let tc_id = tc.get("tcId").ok_or(Error::MissingField("tcId"))?.clone();
+    Ok(object([("tcId", tc_id)].into_iter().chain(fields).collect()))
+}
+
+fn tests(tg: &Value) -> Result<&[Value]> {
+    array_field(tg, "tests")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha3::{self, Hash};
+    use alloc::collections::BTreeMap;
+    use std::path::Path;
+    use std::{env, fs};
+
+    fn find<'a>(a: &'a [Value], id_name: &str, id: Option<&Value>) -> &'a Value {
+        a.iter()
+            .find(|v| v.get(id_name) == id)
+            .unwrap_or_else(|| panic!("no {id_name} {id:?}"))
+    }
+
+    // compare the response to the prompt with all the fields of the expected results, and count the
+    // test cases of each mode and parameter set, as "ML-KEM keyGen ML-KEM-512", and again those of
+    // the pre-hash groups, as "ML-DSA sigGen ML-DSA-44 preHash"
+    fn check(prompt: &Value, expected: &Value, counts: &mut BTreeMap<String, usize>) {
+        let response = respond(prompt).unwrap();
+        let name = format!(
+            "{} {}",
+            str_field(prompt, "algorithm").unwrap(),
+            str_field(prompt, "mode").unwrap()
+        );
+        for expected_tg in array_field(expected, "testGroups").unwrap() {
+            let tg_id = expected_tg.get("tgId");
+            let prompt_tg = find(array_field(prompt, "testGroups").unwrap(), "tgId", tg_id);
+            let parameter_set = str_field(prompt_tg, "parameterSet");
+            let response_tg = find(array_field(&response, "testGroups").unwrap(), "tgId", tg_id);
+            for expected_tc in tests(expected_tg).unwrap() {
+                let tc_id = expected_tc.get("tcId");
+                let response_tc = find(tests(response_tg).unwrap(), "tcId", tc_id);
+                for (field, want) in expected_tc.as_object().unwrap() {
+                    let ok = match (want, response_tc.get(field)) {
+                        (Value::String(want), Some(Value::String(got))) => want.eq_ignore_ascii_case(got),
+                        (want, got) => Some(want) == got,
+                    };
+                    assert!(ok, "{name}: tgId {tg_id:?}, tcId {tc_id:?}, {field}");
+                }
+                *counts.entry(format!("{name} {}", parameter_set.unwrap())).or_default() += 1;
+                if prompt_tg.get("preHash").and_then(Value::as_str) == Some("preHash") {
+                    *counts.entry(format!("{name} {} preHash", parameter_set.unwrap())).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    // check the ML-KEM and ML-DSA vector sets of the subdirectories of dir
+    fn check_dir(dir: &Path) -> BTreeMap<String, usize> {
+        let mut paths: Vec<_> = fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()).collect();
+        paths.sort();
+        let mut counts = BTreeMap::new();
+        for path in paths {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            if !name.starts_with("ML-KEM-") && !name.starts_with("ML-DSA-") {
+                continue;
+            }
+            let read = |file| Value::parse(&fs::read_to_string(path.join(file)).unwrap()).unwrap();
+            check(&read("prompt.json"), &read("expectedResults.json"), &mut counts);
+        }
+        counts
+    }
+
+    // the modes of the vector sets of the algorithm
+    fn modes(alg: Algorithm) -> &'static [&'static str] {
+        if alg.is_kem() {
+            &["ML-KEM keyGen", "ML-KEM encapDecap"]
+        } else {
+            &["ML-DSA keyGen", "ML-DSA sigGen", "ML-DSA sigVer"]
+        }
+    }
+
+    #[test]
+    fn test_vendored() {
+        let counts = check_dir(&Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/acvp"));
+        let expected = [
+            ("ML-KEM keyGen ML-KEM-512", 2),
+            ("ML-KEM keyGen ML-KEM-768", 2),
+            ("ML-KEM keyGen ML-KEM-1024", 2),
+            ("ML-KEM encapDecap ML-KEM-512", 8),
+            ("ML-KEM encapDecap ML-KEM-768", 14),
+            ("ML-KEM encapDecap ML-KEM-1024", 8),
+            ("ML-DSA keyGen ML-DSA-44", 3),
+            ("ML-DSA keyGen ML-DSA-65", 2),
+            ("ML-DSA keyGen ML-DSA-87", 2),
+            ("ML-DSA sigGen ML-DSA-44", 8),
+            ("ML-DSA sigGen ML-DSA-44 preHash", 4),
+            ("ML-DSA sigGen ML-DSA-65", 7),
+            ("ML-DSA sigGen ML-DSA-65 preHash", 4),
+            ("ML-DSA sigGen ML-DSA-87", 7),
+            ("ML-DSA sigGen ML-DSA-87 preHash", 4),
+            ("ML-DSA sigVer ML-DSA-44", 24),
+            ("ML-DSA sigVer ML-DSA-44 preHash", 16),
+            ("ML-DSA sigVer ML-DSA-65", 22),
+            ("ML-DSA sigVer ML-DSA-65 preHash", 16),
+            ("ML-DSA sigVer ML-DSA-87", 22),
+            ("ML-DSA sigVer ML-DSA-87 preHash", 16),
+        ];
+        assert_eq!(counts, expected.into_iter().map(|(name, n)| (name.into(), n)).collect());
+    }
+
+    #[test]
+    fn test_acvp_server() {
+        let Some(dir) = env::var_os("ACVP_JSON_FILES") else {
+            return;
+        };
+        let counts = check_dir(Path::new(&dir));
+        for alg in Algorithm::ALL {
+            for mode in modes(alg) {
+                let name = format!("{mode} {}", alg.name());
+                assert!(counts.get(&name).is_some_and(|n| *n > 0), "no test case of {name}");
+            }
+            // the ACVP-Server has HashML-DSA groups in sigGen and sigVer
+            for mode in modes(alg).iter().filter(|mode| mode.starts_with("ML-DSA sig")) {
+                let name = format!("{mode} {} preHash", alg.name());
+                assert!(counts.get(&name).is_some_and(|n| *n > 0), "no test case of {name}");
+            }
+        }
+    }
+
+    fn group(tg_id: u64, fields: Vec<(&str, Value)>, tests: Vec<Vec<(&str, Value)>>) -> Value {
+        let tests = tests
+            .into_iter()
+            .enumerate()
+            .map(|(i, tc)| object([("tcId", (i as u64 + 1).into())].into_iter().chain(tc).collect()));
+        object(
+            [("tgId", tg_id.into())]
+                .into_iter()
+                .chain(fields)
+                .chain([("tests", Value::Array(tests.collect()))])
+                .collect(),
+        )
+    }
+
+    // the field of the first test case of the group tgId of the response to the vector set
+    fn respond_field(algorithm: &str, mode: &str, groups: Vec<Value>, tg_id: u64, name: &'static str) -> Value {
+        let vs = object(vec![
+            ("algorithm", algorithm.into()),
+            ("mode", mode.into()),
+            ("testGroups", Value::Array(groups)),
+        ]);
+        let response = respond(&vs).unwrap();
+        let tg = find(
+            array_field(&response, "testGroups").unwrap(),
+            "tgId",
+            Some(&tg_id.into()),
+        );
+        tests(tg).unwrap()[0].get(name).unwrap().clone()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        // all the parameter sets through all the modes, the responses consistent with each other
+        for alg in [Algorithm::MlKem512, Algorithm::MlKem768, Algorithm::MlKem1024] {
+            let ps = || ("parameterSet", alg.name().into());
+            let key_gen = |name| {
+                let tc = vec![("d", hex(&[1; 32])), ("z", hex(&[2; 32]))];
+                respond_field("ML-KEM", "keyGen", vec![group(1, vec![ps()], vec![tc])], 1, name)
+            };
+            let (ek, dk) = (key_gen("ek"), key_gen("dk"));
+            let encaps = |name| {
+                let tc = vec![("ek", ek.clone()), ("m", hex(&[3; 32]))];
+                let tg = group(1, vec![ps(), ("function", "encapsulation".into())], vec![tc]);
+                respond_field("ML-KEM", "encapDecap", vec![tg], 1, name)
+            };
+            let tc = vec![("dk", dk.clone()), ("c", encaps("c"))];
+            let tg = group(1, vec![ps(), ("function", "decapsulation".into())], vec![tc]);
+            assert_eq!(respond_field("ML-KEM", "encapDecap", vec![tg], 1, "k"), encaps("k"));
+            for (function, key) in [("encapsulationKeyCheck", "ek"), ("decapsulationKeyCheck", "dk")] {
+                let tc = vec![(key, if key == "ek" { ek.clone() } else { dk.clone() })];
+                let tg = group(1, vec![ps(), ("function", function.into())], vec![tc]);
+                assert_eq!(
+                    respond_field("ML-KEM", "encapDecap", vec![tg], 1, "testPassed"),
+                    Value::Bool(true)
+                );
+            }
+        }
+
+        for alg in [Algorithm::MlDsa44, Algorithm::MlDsa65, Algorithm::MlDsa87] {
+            let ps = || ("parameterSet", alg.name().into());
+            let key_gen = |name| {
+                let tg = group(1, vec![ps()], vec![vec![("seed", hex(&[1; 32]))]]);
+                respond_field("ML-DSA", "keyGen", vec![tg], 1, name)
+            };
+            let (pk, sk) = (key_gen("pk"), key_gen("sk"));
+
+            // mu = H(tr || M') of the internal interface
+            let mut mu = [0; 64];
+            let mut h = sha3::new_shake256();
+            h.write(&hex_field(&Value::Null, &object(vec![("sk", sk.clone())]), "sk").unwrap()[64..128]);
+            h.write(b"message");
+            h.read(&mut mu);
+
+            // the groups of the internal, external mu, external pure and pre-hash interfaces
+            let group_of = |tg_id: u64, deterministic: bool, tc: Vec<(&'static str, Value)>| {
+                let (interface, pre_hash, external_mu) = [
+                    ("internal", "pure", false),
+                    ("internal", "pure", true),
+                    ("external", "pure", false),
+                    ("external", "preHash", false),
+                ][tg_id as usize - 1];
+                let (message, context) = (("message", hex(b"message")), ("context", hex(b"context")));
+                let input = match tg_id {
+                    1 => vec![message],
+                    2 => vec![("mu", hex(&mu))],
+                    3 => vec![message, context],
+                    _ => vec![message, context, ("hashAlg", "SHA3-224".into())],
+                };
+                let fields = vec![
+                    ps(),
+                    ("deterministic", deterministic.into()),
+                    ("signatureInterface", interface.into()),
+                    ("preHash", pre_hash.into()),
+                    ("externalMu", external_mu.into()),
+                ];
+                group(tg_id, fields, vec![tc.into_iter().chain(input).collect()])
+            };
+            let signature = |tg_id| {
+                let tc = vec![("sk", sk.clone()), ("rnd", hex(&[4; 32]))];
+                respond_field(
+                    "ML-DSA",
+                    "sigGen",
+                    vec![group_of(tg_id, tg_id != 4, tc)],
+                    tg_id,
+                    "signature",
+                )
+            };
+            let verify = |tg_id, signature| {
+                let tc = vec![("pk", pk.clone()), ("signature", signature)];
+                respond_field(
+                    "ML-DSA",
+                    "sigVer",
+                    vec![group_of(tg_id, false, tc)],
+                    tg_id,
+                    "testPassed",
+                )
+            };
+            assert_eq!(signature(1), signature(2));
+            for tg_id in 1..=4 {
+                assert_eq!(verify(tg_id, signature(tg_id)), Value::Bool(true));
+            }
+            assert_eq!(verify(3, signature(4)), Value::Bool(false));
+            assert_eq!(verify(1, hex(&[0; 8])), Value::Bool(false));
+        }
+    }
+
+    #[test]
+    fn test_respond() {
+        let vs = r#"{"vsId":7,"algorithm":"ML-DSA","mode":"keyGen","revision":"FIPS204","testGroups":[
+            {"tgId":1,"testType":"AFT","parameterSet":"ML-DSA-44","tests":[]}]}"#;
+        let message = Value::parse(&format!(r#"[{{"acvVersion":"1.0"}},{vs}]"#)).unwrap();
+        assert_eq!(
+            format!("{}", respond(&message).unwrap()),
+            r#"[{"acvVersion":"1.0"},{"vsId":7,"algorithm":"ML-DSA","mode":"keyGen","revision":"FIPS204","testGroups":[{"tgId":1,"tests":[]}]}]"#
+        );
+        assert_eq!(
+            respond(&Value::parse(vs).unwrap()).unwrap(),
+            respond(&message).unwrap().as_array().unwrap()[1]
+        );
+
+        for (vs, err) in [
+            (
+                r#"{"algorithm":"ML-DSA","mode":"keyVer","testGroups":[]}"#,
+                Error::Unsupported,
+            ),
+            (
+                r#"{"algorithm":"ML-KEM","mode":"keyGen"}"#,
+                Error::MissingField("testGroups"),
+            ),
+            (
+                r#"{"algorithm":"ML-KEM","mode":"keyGen","testGroups":[{"tgId":1,"parameterSet":"ML-DSA-44","tests":[]}]}"#,
+                Error::Unsupported,
+            ),
+            (
+                r#"{"algorithm":"ML-KEM","mode":"keyGen","testGroups":[{"tgId":1,"parameterSet":"ML-KEM-512",
+                "tests":[{"tcId":1,"d":"0g","z":""}]}]}"#,
+                Error::Malformed("d"),
+            ),
+            (
+                r#"{"algorithm":"ML-DSA","mode":"sigGen","testGroups":[{"tgId":1,"parameterSet":"ML-DSA-44",
+                "deterministic":true,"signatureInterface":"external","preHash":"preHash",
+                "tests":[{"tcId":1,"sk":"","message":"","context":"","hashAlg":"SHA2-1"}]}]}"#,
+                Error::Unsupported,
+            ),
+        ] {
+            assert_eq!(respond(&Value::parse(vs).unwrap()), Err(err));
+        }
+    }
+}
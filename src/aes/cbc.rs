@@ -0,0 +1,91 @@
+//! CBC mode with the PKCS #7 padding of RFC 5652.
+
+use alloc::vec::Vec;
+
+use super::{Aes, BLOCK_SIZE, Error, Result};
+
+/// Pad and encrypt the plaintext, the ciphertext is 1 to 16 bytes longer.
+pub fn encrypt(aes: &Aes, iv: &[u8; 16], plaintext: &[u8]) -> Vec<u8> {
+    let pad = BLOCK_SIZE - plaintext.len() % BLOCK_SIZE;
+    let mut out = Vec::with_capacity(plaintext.len() + pad);
+    out.extend_from_slice(plaintext);
+    out.resize(plaintext.len() + pad, pad as u8);
+    let mut prev = *iv;
+    for block in out.chunks_exact_mut(BLOCK_SIZE) {
+        for (p, c) in prev.iter_mut().zip(block.iter()) {
+            *p ^= c;
+        }
+        aes.encrypt_block(&mut prev);
+        block.copy_from_slice(&prev);
+    }
+    out
+}
+
+/// Decrypt the ciphertext and remove the padding.
+pub fn decrypt(aes: &Aes, iv: &[u8; 16], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(Error::InvalidLength);
+    }
+    let mut out = ciphertext.to_vec();
+    let mut prev = *iv;
+    for block in out.chunks_exact_mut(BLOCK_SIZE) {
+        let c: [u8; 16] = (&*block).try_into().unwrap();
+        let mut p = c;
+        aes.decrypt_block(&mut p);
+        for (b, (p, v)) in block.iter_mut().zip(p.iter().zip(prev)) {
+            *b = p ^ v;
+        }
+        prev = c;
+    }
+    // check the whole last block, without branching on the padding bytes
+    let last = &out[out.len() - BLOCK_SIZE..];
+    let pad = last[BLOCK_SIZE - 1];
+    let mut bad = ((pad as u32).wrapping_sub(1) >> 8) | ((BLOCK_SIZE as u32).wrapping_sub(pad as u32) >> 8);
+    for (i, b) in last.iter().enumerate() {
+        // in the padding when BLOCK_SIZE - i <= pad
+        let in_pad = ((pad as u32).wrapping_sub((BLOCK_SIZE - i) as u32) >> 8) ^ 1;
+        bad |= in_pad & (((b ^ pad) as u32).wrapping_add(0xff) >> 8);
+    }
+    if bad & 1 != 0 {
+        return Err(Error::Padding);
+    }
+    out.truncate(out.len() - pad as usize);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_cbc() {
+        // SP 800-38A F.2.5, the padding block is appended
+        let aes = Aes::new(&hex!("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4")).unwrap();
+        let iv = hex!("000102030405060708090a0b0c0d0e0f");
+        let plaintext = hex!("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51");
+        let ciphertext = encrypt(&aes, &iv, &plaintext);
+        assert_eq!(ciphertext.len(), 48);
+        assert_eq!(ciphertext[..32], hex!("f58c4c04d6e5f1ba779eabfb5f7bfbd69cfc4e967edb808d679f777bc6702c7d"));
+        assert_eq!(decrypt(&aes, &iv, &ciphertext), Ok(plaintext.to_vec()));
+
+        for len in 0..40 {
+            let plaintext = vec![len as u8; len];
+            let ciphertext = encrypt(&aes, &iv, &plaintext);
+            assert_eq!(ciphertext.len(), (len / 16 + 1) * 16);
+            assert_eq!(decrypt(&aes, &iv, &ciphertext), Ok(plaintext));
+        }
+
+        // the padding is checked
+        for last in [[0u8; 16], [17; 16], core::array::from_fn(|i| if i == 15 { 2 } else { 3 })] {
+            let mut block = last;
+            for (b, v) in block.iter_mut().zip(iv) {
+                *b ^= v;
+            }
+            aes.encrypt_block(&mut block);
+            assert_eq!(decrypt(&aes, &iv, &block), Err(Error::Padding));
+        }
+        assert_eq!(decrypt(&aes, &iv, &[]), Err(Error::InvalidLength));
+        assert_eq!(decrypt(&aes, &iv, &ciphertext[1..]), Err(Error::InvalidLength));
+    }
+}
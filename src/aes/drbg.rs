@@ -0,0 +1,326 @@
+//! CTR_DRBG with AES-256 and no derivation function (SP 800-90A rev. 1, section 10.2.1), the random
+//! bit generator of the NIST PQC KAT programs.
+//!
+//! [`CtrDrbg::new`] with a 48 bytes seed and no personalization string is `randombytes_init` of
+//! PQCgenKAT, and each [`TryRngCore::try_fill_bytes`] is one `randombytes` call, a single generate
+//! request of at most 65536 bytes. The request fails when a reseed is required without an entropy
+//! source, [`TryRngCore::unwrap_mut`] turns the DRBG into a [`rand::CryptoRng`] which panics then.
+//! The key generations and encapsulations draw their seeds in the requests of the KAT programs,
+//! 64 bytes d || z then 32 bytes m for ML-KEM and 32 bytes xi for ML-DSA, so that they regenerate
+//! the .rsp records from the DRBG.
+
+use rand::{TryCryptoRng, TryRngCore};
+
+use super::{Aes, BLOCK_SIZE, Error, Result};
+
+/// The length of the entropy input, the key and V.
+///
+/// cbindgen:ignore
+pub const SEED_LEN: usize = 48;
+
+// the generate requests between the reseeds, 2^48 in SP 800-90A
+const RESEED_INTERVAL: u64 = 1 << 48;
+
+// the bytes of one generate request, 2^19 bits in SP 800-90A
+const MAX_REQUEST_LEN: usize = 1 << 16;
+
+#[derive(Clone)]
+pub struct CtrDrbg {
+    aes: Aes,
+    v: [u8; BLOCK_SIZE],
+    reseed_counter: u64,
+    entropy: Option<fn(&mut [u8; SEED_LEN])>,
+    prediction_resistance: bool,
+}
+
+// data xor'ed with the zero padded input of at most SEED_LEN bytes
+fn xor_padded(data: &[u8; SEED_LEN], input: &[u8]) -> Result<[u8; SEED_LEN]> {
+    if input.len() > SEED_LEN {
+        return Err(Error::InvalidLength);
+    }
+    let mut out = *data;
+    for (o, x) in out.iter_mut().zip(input) {
+        *o ^= x;
+    }
+    Ok(out)
+}
+
+impl CtrDrbg {
+    /// Instantiate with the entropy input and a personalization string of at most 48 bytes.
+    pub fn new(entropy: &[u8; SEED_LEN], personalization: &[u8]) -> Result<Self> {
+        let seed_material = xor_padded(entropy, personalization)?;
+        let mut drbg = CtrDrbg {
+            aes: Aes::new(&[0; 32])?,
+            v: [0; BLOCK_SIZE],
+            reseed_counter: 1,
+            entropy: None,
+            prediction_resistance: false,
+        };
+        drbg.update(&seed_material);
+        Ok(drbg)
+    }
+
+    /// Set the source of the entropy input of the automatic reseeds, before every request with
+    /// prediction resistance, otherwise when the reseed interval is reached.
+    pub fn set_entropy_source(&mut self, entropy: fn(&mut [u8; SEED_LEN]), prediction_resistance: bool) {
+        self.entropy = Some(entropy);
+        self.prediction_resistance = prediction_resistance;
+    }
+
+    /// Reseed with the entropy input and additional input of at most 48 bytes.
+    pub fn reseed(&mut self, entropy: &[u8; SEED_LEN], additional_input: &[u8]) -> Result<()> {
+        let seed_material = xor_padded(entropy, additional_input)?;
+        self.update(&seed_material);
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    /// Fill out of at most 65536 bytes with one generate request, the additional input is at most
+    /// 48 bytes.
+    ///
+    /// Fails with [`Error::ReseedRequired`] when the reseed interval is reached without an entropy
+    /// source.
+    pub fn generate(&mut self, out: &mut [u8], additional_input: &[u8]) -> Result<()> {
+        if out.len() > MAX_REQUEST_LEN {
+            return Err(Error::InvalidLength);
+        }
+        let mut additional = xor_padded(&[0; SEED_LEN], additional_input)?;
+        let mut has_additional = !additional_input.is_empty();
+        if self.prediction_resistance || self.reseed_counter > RESEED_INTERVAL {
+            let source = self.entropy.ok_or(Error::ReseedRequired)?;
+            let mut entropy = [0; SEED_LEN];
+            source(&mut entropy);
+            self.reseed(&entropy, additional_input)?;
+            entropy.fill(0);
+            additional = [0; SEED_LEN];
+            has_additional = false;
+        }
+
+        if has_additional {
+            self.update(&additional);
+        }
+        for chunk in out.chunks_mut(BLOCK_SIZE) {
+            let block = self.next_block();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.update(&additional);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    fn next_block(&mut self) -> [u8; BLOCK_SIZE] {
+        self.v = (u128::from_be_bytes(self.v).wrapping_add(1)).to_be_bytes();
+        let mut block = self.v;
+        self.aes.encrypt_block(&mut block);
+        block
+    }
+
+    // CTR_DRBG_Update, the new key and V are the next 48 bytes of the keystream xor provided_data
+    fn update(&mut self, provided_data: &[u8; SEED_LEN]) {
+        let mut temp = [0; SEED_LEN];
+        for chunk in temp.chunks_exact_mut(BLOCK_SIZE) {
+            chunk.copy_from_slice(&self.next_block());
+        }
+        for (t, p) in temp.iter_mut().zip(provided_data) {
+            *t ^= p;
+        }
+        self.aes = Aes::new(&temp[..32]).unwrap();
+        self.v.copy_from_slice(&temp[32..]);
+        temp.fill(0);
+    }
+}
+
+impl TryRngCore for CtrDrbg {
+    type Error = Error;
+
+    fn try_next_u32(&mut self) -> Result<u32> {
+        let mut b = [0; 4];
+        self.try_fill_bytes(&mut b)?;
+        Ok(u32::from_le_bytes(b))
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64> {
+        let mut b = [0; 8];
+        self.try_fill_bytes(&mut b)?;
+        Ok(u64::from_le_bytes(b))
+    }
+
+    /// One generate request without additional input, fails with [`Error::InvalidLength`] above
+    /// 65536 bytes and with [`Error::ReseedRequired`] when a reseed is required and there is no
+    /// entropy source.
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+        self.generate(dest, &[])
+    }
+}
+
+impl TryCryptoRng for CtrDrbg {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    #[cfg(feature = "alloc")]
+    use crate::alg::{Kem, MlDsa44, MlDsa65, MlDsa87, MlKem512, MlKem768, MlKem1024, SignatureScheme, Signer};
+    use hex_literal::hex;
+
+    #[cfg(feature = "alloc")]
+    // the field of the first record of a .rsp file
+    fn field(rsp: &str, name: &str) -> Vec<u8> {
+        let line = rsp.lines().find_map(|line| line.strip_prefix(name)?.strip_prefix(" = ")).unwrap();
+        (0..line.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&line[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[cfg(feature = "alloc")]
+    // the DRBG of the PQCgenKAT programs, which draws the seeds of the records
+    fn kat_drbg() -> CtrDrbg {
+        CtrDrbg::new(&core::array::from_fn(|i| i as u8), &[]).unwrap()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mlkem_kat() {
+        // the first records of kat_MLKEM_*.rsp, from keygen then encaps on the DRBG of the seed
+        fn check<K: Kem>(rsp: &str) {
+            let mut seed = [0; SEED_LEN];
+            kat_drbg().try_fill_bytes(&mut seed).unwrap();
+            assert_eq!(seed[..], field(rsp, "seed"));
+
+            let mut drbg = CtrDrbg::new(&seed, &[]).unwrap();
+            let mut rng = drbg.unwrap_mut();
+            let alg = K::ALGORITHM;
+            let dk = K::keygen(&mut rng);
+            let ek = K::encapsulation_key(&dk);
+            let (mut pk, mut sk) = (vec![0; alg.public_key_len()], vec![0; alg.private_key_len()]);
+            K::encode_encap_key(&ek, &mut pk).unwrap();
+            K::encode_decap_key(&dk, &mut sk).unwrap();
+            assert_eq!(pk, field(rsp, "pk"), "{}", alg.name());
+            assert_eq!(sk, field(rsp, "sk"), "{}", alg.name());
+
+            let mut ct = vec![0; alg.ciphertext_len()];
+            let ss = K::encaps(&ek, &mut rng, &mut ct).unwrap();
+            assert_eq!(ct, field(rsp, "ct"), "{}", alg.name());
+            assert_eq!(ss[..], field(rsp, "ss"), "{}", alg.name());
+            assert_eq!(K::decaps(&dk, &ct), Ok(ss));
+        }
+        check::<MlKem512>(include_str!("../../testdata/kat/kat_MLKEM_512.rsp"));
+        check::<MlKem768>(include_str!("../../testdata/kat/kat_MLKEM_768.rsp"));
+        check::<MlKem1024>(include_str!("../../testdata/kat/kat_MLKEM_1024.rsp"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mldsa_kat() {
+        // the first records of kat_MLDSA_*.rsp, the message of 33 bytes follows the seed, the
+        // signature is hedged with the 32 bytes drawn after the key generation, and sm = sig || msg
+        fn check<S: SignatureScheme>(rsp: &str) {
+            let mut master = kat_drbg();
+            let (mut seed, mut msg) = ([0; SEED_LEN], [0; 33]);
+            master.try_fill_bytes(&mut seed).unwrap();
+            master.try_fill_bytes(&mut msg).unwrap();
+            assert_eq!(seed[..], field(rsp, "seed"));
+            assert_eq!(msg[..], field(rsp, "msg"));
+
+            let mut drbg = CtrDrbg::new(&seed, &[]).unwrap();
+            let alg = S::ALGORITHM;
+            let sk = S::keygen(&mut drbg.unwrap_mut());
+            let (mut pk_encoded, mut sk_encoded) = (vec![0; alg.public_key_len()], vec![0; alg.private_key_len()]);
+            S::encode_public_key(&S::public_key(&sk), &mut pk_encoded).unwrap();
+            S::encode_private_key(&sk, &mut sk_encoded).unwrap();
+            assert_eq!(pk_encoded, field(rsp, "pk"), "{}", alg.name());
+            assert_eq!(sk_encoded, field(rsp, "sk"), "{}", alg.name());
+
+            let mut rnd = [0; 32];
+            drbg.try_fill_bytes(&mut rnd).unwrap();
+            let mut sm = vec![0; alg.signature_len()];
+            sk.sign(&msg, &[], &rnd, &mut sm).unwrap();
+            sm.extend_from_slice(&msg);
+            assert_eq!(sm, field(rsp, "sm"), "{}", alg.name());
+        }
+        check::<MlDsa44>(include_str!("../../testdata/kat/kat_MLDSA_44.rsp"));
+        check::<MlDsa65>(include_str!("../../testdata/kat/kat_MLDSA_65.rsp"));
+        check::<MlDsa87>(include_str!("../../testdata/kat/kat_MLDSA_87.rsp"));
+    }
+
+    #[test]
+    fn test_pqc_kat_seeds() {
+        // the seeds of the first two records of every NIST PQC .rsp file
+        let entropy: [u8; 48] = core::array::from_fn(|i| i as u8);
+        let mut drbg = CtrDrbg::new(&entropy, &[]).unwrap();
+        let mut seed = [0; 48];
+        drbg.try_fill_bytes(&mut seed).unwrap();
+        assert_eq!(
+            seed,
+            hex!(
+                "061550234d158c5ec95595fe04ef7a25767f2e24cc2bc479d09d86dc9abcfde7"
+                "056a8c266f9ef97ed08541dbd2e1ffa1"
+            )
+        );
+        drbg.try_fill_bytes(&mut seed).unwrap();
+        assert_eq!(
+            seed,
+            hex!(
+                "d81c4d8d734fcbfbeade3d3f8a039faa2a2c9957e835ad55b22e75bf57bb556a"
+                "c81adde6aeeb4a5a875c3bfcadfa958f"
+            )
+        );
+
+        // a request is a single generate, whatever its length
+        let mut drbg = CtrDrbg::new(&entropy, &[]).unwrap();
+        let mut a = [0; 5];
+        let mut b = [0; 37];
+        drbg.try_fill_bytes(&mut a).unwrap();
+        drbg.try_fill_bytes(&mut b).unwrap();
+        assert_eq!(a, hex!("061550234d"));
+        assert_eq!(b, hex!("7bada89bf0e1852e7998951ea7268f7f573c52a713871f895bab3c59cd75068f80971c93d0"));
+    }
+
+    #[test]
+    fn test_reseed() {
+        let entropy: [u8; 48] = core::array::from_fn(|i| i as u8);
+        let mut drbg = CtrDrbg::new(&entropy, b"personalization string").unwrap();
+        let mut out = [0; 40];
+        drbg.generate(&mut out, &[]).unwrap();
+        assert_eq!(out, hex!("1d293f6d63fbcb5cabcb2fb8f36d594f82fa9b1409e6949fd94c3a26108a534742c6b221d6300b43"));
+
+        let entropy: [u8; 48] = core::array::from_fn(|i| 48 + i as u8);
+        drbg.reseed(&entropy, b"reseed additional").unwrap();
+        drbg.generate(&mut out, b"additional input").unwrap();
+        assert_eq!(out, hex!("6e6f36b4847f884f3b6ea172e7d36db0dece821363f91feef323fd4650e672a47f4bb3393080fb6f"));
+
+        assert_eq!(CtrDrbg::new(&entropy, &[0; 49]).err(), Some(Error::InvalidLength));
+        assert_eq!(drbg.generate(&mut out, &[0; 49]), Err(Error::InvalidLength));
+        assert_eq!(drbg.reseed(&entropy, &[0; 49]), Err(Error::InvalidLength));
+
+        // at most 2^19 bits per request
+        let mut out = [0; MAX_REQUEST_LEN + 1];
+        assert_eq!(drbg.generate(&mut out, &[]), Err(Error::InvalidLength));
+        assert_eq!(drbg.try_fill_bytes(&mut out), Err(Error::InvalidLength));
+        assert!(drbg.generate(&mut out[..MAX_REQUEST_LEN], &[]).is_ok());
+    }
+
+    #[test]
+    fn test_prediction_resistance() {
+        // every request reseeds with fresh entropy input and the additional input
+        let entropy: [u8; 48] = core::array::from_fn(|i| i as u8);
+        let mut drbg = CtrDrbg::new(&entropy, &[]).unwrap();
+        drbg.set_entropy_source(|e| e.fill(0x5a), true);
+        let mut out = [0; 32];
+        drbg.generate(&mut out, b"additional input").unwrap();
+        assert_eq!(out, hex!("30f4aa02c5cdde4b70fe3ad31c301f62b10690fe8b87607f84f9d1674c1aaec5"));
+        drbg.try_fill_bytes(&mut out).unwrap();
+        assert_eq!(out, hex!("49dbdc76edb80fa6608c0bb8a77a96c65b67067c9d67c5da7ef0f833a29acf45"));
+
+        // the reseed interval without an entropy source
+        let mut drbg = CtrDrbg::new(&entropy, &[]).unwrap();
+        drbg.reseed_counter = RESEED_INTERVAL + 1;
+        assert_eq!(drbg.generate(&mut out, &[]), Err(Error::ReseedRequired));
+        assert_eq!(drbg.try_fill_bytes(&mut out), Err(Error::ReseedRequired));
+        drbg.set_entropy_source(|e| e.fill(0x5a), false);
+        assert!(drbg.generate(&mut out, &[]).is_ok());
+        assert_eq!(drbg.reseed_counter, 2);
+    }
+}
@@ -0,0 +1,23 @@
+use thiserror;
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("invalid key length")]
+    InvalidKeyLength,
+
+    #[error("invalid length")]
+    InvalidLength,
+
+    #[error("key unwrap integrity check failed")]
+    Unwrap,
+
+    #[error("invalid padding")]
+    Padding,
+
+    #[error("authentication tag mismatch")]
+    Tag,
+
+    #[error("DRBG reseed required")]
+    ReseedRequired,
+}
+pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,157 @@
+//! AES-GCM (SP 800-38D) with 96 bits nonces and 128 bits tags, in place.
+//!
+//! GHASH multiplies bit by bit with masks instead of tables, so the timing does not depend on the
+//! hash key or the data.
+
+use super::{Aes, BLOCK_SIZE, Error, Result};
+
+pub(crate) const NONCE_SIZE: usize = 12;
+pub(crate) const TAG_SIZE: usize = 16;
+
+// x^128 + x^7 + x^2 + x + 1 in the reflected bit order of GCM.
+const R: u128 = 0xe1 << 120;
+
+fn gf_mul(x: u128, y: u128) -> u128 {
+    let (mut z, mut v) = (0, y);
+    for i in (0..128).rev() {
+        z ^= v & 0u128.wrapping_sub((x >> i) & 1);
+        v = (v >> 1) ^ (R & 0u128.wrapping_sub(v & 1));
+    }
+    z
+}
+
+struct GHash {
+    h: u128,
+    y: u128,
+}
+
+impl GHash {
+    fn new(aes: &Aes) -> Self {
+        let mut h = [0; BLOCK_SIZE];
+        aes.encrypt_block(&mut h);
+        GHash { h: u128::from_be_bytes(h), y: 0 }
+    }
+
+    // The data is zero padded to a multiple of the block size.
+    fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(BLOCK_SIZE) {
+            let mut block = [0; BLOCK_SIZE];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.y = gf_mul(self.y ^ u128::from_be_bytes(block), self.h);
+        }
+    }
+
+    fn finish(mut self, aad_len: usize, text_len: usize) -> u128 {
+        let lens = ((aad_len as u128 * 8) << 64) | (text_len as u128 * 8);
+        self.y = gf_mul(self.y ^ lens, self.h);
+        self.y
+    }
+}
+
+// The first counter block of the nonce, encrypted to mask the tag.
+fn tag_mask(aes: &Aes, nonce: &[u8; NONCE_SIZE]) -> [u8; BLOCK_SIZE] {
+    let mut j = [0; BLOCK_SIZE];
+    j[..NONCE_SIZE].copy_from_slice(nonce);
+    j[15] = 1;
+    aes.encrypt_block(&mut j);
+    j
+}
+
+// The CTR mode from the second counter block.
+fn ctr(aes: &Aes, nonce: &[u8; NONCE_SIZE], data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(BLOCK_SIZE).enumerate() {
+        let mut block = [0; BLOCK_SIZE];
+        block[..NONCE_SIZE].copy_from_slice(nonce);
+        block[NONCE_SIZE..].copy_from_slice(&(i as u32).wrapping_add(2).to_be_bytes());
+        aes.encrypt_block(&mut block);
+        for (d, k) in chunk.iter_mut().zip(block) {
+            *d ^= k;
+        }
+    }
+}
+
+fn tag(aes: &Aes, nonce: &[u8; NONCE_SIZE], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_SIZE] {
+    let tag_mask = tag_mask(aes, nonce);
+    let mut g = GHash::new(aes);
+    g.update(aad);
+    g.update(ciphertext);
+    let s = g.finish(aad.len(), ciphertext.len()).to_be_bytes();
+    core::array::from_fn(|i| s[i] ^ tag_mask[i])
+}
+
+/// Encrypt buf in place and return the tag.
+pub fn seal(aes: &Aes, nonce: &[u8; NONCE_SIZE], aad: &[u8], buf: &mut [u8]) -> [u8; TAG_SIZE] {
+    ctr(aes, nonce, buf);
+    tag(aes, nonce, aad, buf)
+}
+
+/// Decrypt buf in place if the tag matches, buf is zeroed otherwise.
+pub fn open(aes: &Aes, nonce: &[u8; NONCE_SIZE], aad: &[u8], buf: &mut [u8], tag: &[u8; TAG_SIZE]) -> Result<()> {
+    let expected = self::tag(aes, nonce, aad, buf);
+    if expected.iter().zip(tag).fold(0, |d, (a, b)| d | (a ^ b)) != 0 {
+        buf.fill(0);
+        return Err(Error::Tag);
+    }
+    ctr(aes, nonce, buf);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_gcm() {
+        // The test cases 2, 4 and 16 of the GCM specification
+        let aes = Aes::new(&[0; 16]).unwrap();
+        let mut buf = [0; 16];
+        let t = seal(&aes, &[0; 12], &[], &mut buf);
+        assert_eq!(buf, hex!("0388dace60b6a392f328c2b971b2fe78"));
+        assert_eq!(t, hex!("ab6e47d42cec13bdf53a67b21257bddf"));
+
+        let key = hex!("feffe9928665731c6d6a8f9467308308");
+        let nonce = hex!("cafebabefacedbaddecaf888");
+        let aad = hex!("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext = hex!(
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72"
+            "1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39"
+        );
+        for (key, ciphertext, t) in [
+            (
+                &key[..],
+                hex!(
+                    "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e"
+                    "21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091"
+                ),
+                hex!("5bc94fbc3221a5db94fae95ae7121a47"),
+            ),
+            (
+                &hex!("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308")[..],
+                hex!(
+                    "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa"
+                    "8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662"
+                ),
+                hex!("76fc6ece0f4e1768cddf8853bb2d551b"),
+            ),
+        ] {
+            let aes = Aes::new(key).unwrap();
+            let mut buf = plaintext;
+            assert_eq!(seal(&aes, &nonce, &aad, &mut buf), t);
+            assert_eq!(buf, ciphertext);
+            assert_eq!(open(&aes, &nonce, &aad, &mut buf, &t), Ok(()));
+            assert_eq!(buf, plaintext);
+
+            let mut buf = ciphertext;
+            assert_eq!(open(&aes, &nonce, &aad[1..], &mut buf, &t), Err(Error::Tag));
+            assert_eq!(buf, [0; 60]);
+            let mut buf = ciphertext;
+            buf[59] ^= 1;
+            assert_eq!(open(&aes, &nonce, &aad, &mut buf, &t), Err(Error::Tag));
+        }
+
+        // only the additional data
+        let aes = Aes::new(&key).unwrap();
+        assert_eq!(seal(&aes, &nonce, &aad, &mut []), hex!("346434fd51d5cd0c5887ec63e39b907a"));
+    }
+}
@@ -0,0 +1,105 @@
+//! AES key wrap (RFC 3394) with the default initial value.
+
+use super::{Aes, Error, Result};
+
+const IV: [u8; 8] = [0xa6; 8];
+
+/// Wrap key into out of key.len() + 8 bytes, key is at least 16 bytes and a multiple of 8.
+pub fn wrap(kek: &Aes, key: &[u8], out: &mut [u8]) -> Result<()> {
+    let n = key.len() / 8;
+    if !key.len().is_multiple_of(8) || n < 2 || out.len() != key.len() + 8 {
+        return Err(Error::InvalidLength);
+    }
+    let (a, r) = out.split_at_mut(8);
+    a.copy_from_slice(&IV);
+    r.copy_from_slice(key);
+    let mut b = [0u8; 16];
+    for j in 0..6 {
+        for (i, r) in r.chunks_exact_mut(8).enumerate() {
+            b[..8].copy_from_slice(a);
+            b[8..].copy_from_slice(r);
+            kek.encrypt_block(&mut b);
+            let t = (n * j + i + 1) as u64;
+            for (a, (b, t)) in a.iter_mut().zip(b.iter().zip(t.to_be_bytes())) {
+                *a = b ^ t;
+            }
+            r.copy_from_slice(&b[8..]);
+        }
+    }
+    Ok(())
+}
+
+/// Unwrap wrapped into out of wrapped.len() - 8 bytes, out is zeroed if the integrity check fails.
+pub fn unwrap(kek: &Aes, wrapped: &[u8], out: &mut [u8]) -> Result<()> {
+    let n = out.len() / 8;
+    if !wrapped.len().is_multiple_of(8) || n < 2 || wrapped.len() != out.len() + 8 {
+        return Err(Error::InvalidLength);
+    }
+    let mut a = [0u8; 8];
+    a.copy_from_slice(&wrapped[..8]);
+    out.copy_from_slice(&wrapped[8..]);
+    let mut b = [0u8; 16];
+    for j in (0..6).rev() {
+        for (i, r) in out.chunks_exact_mut(8).enumerate().rev() {
+            let t = (n * j + i + 1) as u64;
+            for (b, (a, t)) in b.iter_mut().zip(a.iter().zip(t.to_be_bytes())) {
+                *b = a ^ t;
+            }
+            b[8..].copy_from_slice(r);
+            kek.decrypt_block(&mut b);
+            a.copy_from_slice(&b[..8]);
+            r.copy_from_slice(&b[8..]);
+        }
+    }
+    if a.iter().zip(IV).fold(0, |d, (a, b)| d | (a ^ b)) != 0 {
+        out.fill(0);
+        return Err(Error::Unwrap);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_keywrap() {
+        // RFC 3394 section 4
+        for (kek, key, wrapped) in [
+            (
+                &hex!("000102030405060708090a0b0c0d0e0f")[..],
+                &hex!("00112233445566778899aabbccddeeff")[..],
+                &hex!("1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5")[..],
+            ),
+            (
+                &hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"),
+                &hex!("00112233445566778899aabbccddeeff"),
+                &hex!("64e8c3f9ce0f5ba263e9777905818a2a93c8191e7d6e8ae7"),
+            ),
+            (
+                &hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"),
+                &hex!("00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f"),
+                &hex!("28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21"),
+            ),
+        ] {
+            let kek = Aes::new(kek).unwrap();
+            let mut out = [0u8; 40];
+            wrap(&kek, key, &mut out[..wrapped.len()]).unwrap();
+            assert_eq!(&out[..wrapped.len()], wrapped);
+            let mut unwrapped = [0u8; 32];
+            unwrap(&kek, wrapped, &mut unwrapped[..key.len()]).unwrap();
+            assert_eq!(&unwrapped[..key.len()], key);
+
+            let mut tampered = [0u8; 40];
+            tampered[..wrapped.len()].copy_from_slice(wrapped);
+            tampered[wrapped.len() - 1] ^= 1;
+            assert_eq!(unwrap(&kek, &tampered[..wrapped.len()], &mut unwrapped[..key.len()]), Err(Error::Unwrap));
+            assert!(unwrapped.iter().all(|b| *b == 0));
+        }
+        let kek = Aes::new(&[0; 16]).unwrap();
+        assert_eq!(wrap(&kek, &[0; 8], &mut [0; 16]), Err(Error::InvalidLength));
+        assert_eq!(wrap(&kek, &[0; 20], &mut [0; 28]), Err(Error::InvalidLength));
+        assert_eq!(unwrap(&kek, &[0; 24], &mut [0; 24]), Err(Error::InvalidLength));
+    }
+}
@@ -0,0 +1,235 @@
+//! AES block cipher (FIPS 197) with the AES key wrap (RFC 3394), CBC mode (SP 800-38A), GCM
+//! (SP 800-38D) and CTR_DRBG (SP 800-90A).
+//!
+//! The S-box is computed as the inversion in GF(2^8) on the 16 bytes of the state at once, without
+//! table lookups, so the timing does not depend on the key or the data. It is slower than a table
+//! implementation and meant for wrapping keys and encrypting messages of moderate size.
+
+mod errors;
+pub mod drbg;
+pub mod gcm;
+pub mod keywrap;
+#[cfg(feature = "alloc")]
+pub mod cbc;
+
+pub use errors::{Error, Result};
+
+pub(crate) const BLOCK_SIZE: usize = 16;
+
+// 0x01 in every byte of the state
+const LO: u128 = u128::MAX / 0xff;
+
+// multiply every byte by x in GF(2^8)
+#[inline(always)]
+fn xtime(a: u128) -> u128 {
+    ((a & (LO * 0x7f)) << 1) ^ (((a >> 7) & LO) * 0x1b)
+}
+
+#[inline(always)]
+fn gmul(mut a: u128, b: u128) -> u128 {
+    let mut p = 0;
+    for i in 0..8 {
+        p ^= a & (((b >> i) & LO) * 0xff);
+        a = xtime(a);
+    }
+    p
+}
+
+// x^254, the inverse of every byte, 0 is mapped to 0
+fn inv(x: u128) -> u128 {
+    let sq = |a| gmul(a, a);
+    let x2 = sq(x);
+    let x3 = gmul(x2, x);
+    let x12 = sq(sq(x3));
+    let x15 = gmul(x12, x3);
+    let x240 = sq(sq(sq(sq(x15))));
+    gmul(gmul(x240, x12), x2)
+}
+
+// rotate every byte left by n
+#[inline(always)]
+fn rotl8(x: u128, n: u32) -> u128 {
+    let m = LO * ((0xffu8 << n) as u128);
+    ((x << n) & m) | ((x >> (8 - n)) & !m)
+}
+
+fn sub_bytes(s: &mut [u8; 16]) {
+    let b = inv(u128::from_le_bytes(*s));
+    *s = (b ^ rotl8(b, 1) ^ rotl8(b, 2) ^ rotl8(b, 3) ^ rotl8(b, 4) ^ (LO * 0x63)).to_le_bytes();
+}
+
+fn inv_sub_bytes(s: &mut [u8; 16]) {
+    let x = u128::from_le_bytes(*s);
+    *s = inv(rotl8(x, 1) ^ rotl8(x, 3) ^ rotl8(x, 6) ^ (LO * 0x05)).to_le_bytes();
+}
+
+// the state is column major, byte r + 4c is row r of column c
+fn shift_rows(s: &mut [u8; 16]) {
+    let t = *s;
+    for i in 0..16 {
+        s[i] = t[(i + 4 * (i % 4)) % 16];
+    }
+}
+
+fn inv_shift_rows(s: &mut [u8; 16]) {
+    let t = *s;
+    for i in 0..16 {
+        s[(i + 4 * (i % 4)) % 16] = t[i];
+    }
+}
+
+#[inline(always)]
+fn xt(x: u8) -> u8 {
+    (x << 1) ^ ((x >> 7) * 0x1b)
+}
+
+fn mix_columns(s: &mut [u8; 16]) {
+    for c in s.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = [c[0], c[1], c[2], c[3]];
+        let t = a0 ^ a1 ^ a2 ^ a3;
+        c[0] ^= t ^ xt(a0 ^ a1);
+        c[1] ^= t ^ xt(a1 ^ a2);
+        c[2] ^= t ^ xt(a2 ^ a3);
+        c[3] ^= t ^ xt(a3 ^ a0);
+    }
+}
+
+fn inv_mix_columns(s: &mut [u8; 16]) {
+    // InvMixColumns is MixColumns after multiplying by {04}x^2 + {05}
+    for c in s.chunks_exact_mut(4) {
+        let u = xt(xt(c[0] ^ c[2]));
+        let v = xt(xt(c[1] ^ c[3]));
+        c[0] ^= u;
+        c[1] ^= v;
+        c[2] ^= u;
+        c[3] ^= v;
+    }
+    mix_columns(s);
+}
+
+fn add_round_key(s: &mut [u8; 16], k: &[u8; 16]) {
+    for (a, b) in s.iter_mut().zip(k) {
+        *a ^= b;
+    }
+}
+
+/// An expanded AES-128, AES-192 or AES-256 key.
+#[derive(Clone)]
+pub struct Aes {
+    round_keys: [[u8; 16]; 15],
+    rounds: usize,
+}
+
+impl Aes {
+    /// The key is 16, 24 or 32 bytes.
+    pub fn new(key: &[u8]) -> Result<Self> {
+        let nk = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(Error::InvalidKeyLength),
+        };
+        let rounds = nk + 6;
+        let mut w = [[0u8; 4]; 60];
+        for (i, k) in key.chunks_exact(4).enumerate() {
+            w[i].copy_from_slice(k);
+        }
+        let mut rcon = 1u8;
+        for i in nk..4 * (rounds + 1) {
+            let mut t = w[i - 1];
+            if i % nk == 0 || (nk == 8 && i % nk == 4) {
+                if i % nk == 0 {
+                    t.rotate_left(1);
+                }
+                let mut s = [0; 16];
+                s[..4].copy_from_slice(&t);
+                sub_bytes(&mut s);
+                t.copy_from_slice(&s[..4]);
+                if i % nk == 0 {
+                    t[0] ^= rcon;
+                    rcon = xt(rcon);
+                }
+            }
+            for j in 0..4 {
+                w[i][j] = w[i - nk][j] ^ t[j];
+            }
+        }
+        let mut round_keys = [[0u8; 16]; 15];
+        for (k, w) in round_keys.iter_mut().zip(w.chunks_exact(4)) {
+            for (k, w) in k.chunks_exact_mut(4).zip(w) {
+                k.copy_from_slice(w);
+            }
+        }
+        Ok(Aes { round_keys, rounds })
+    }
+
+    /// The key length in bytes.
+    pub fn key_len(&self) -> usize {
+        (self.rounds - 6) * 4
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        add_round_key(block, &self.round_keys[0]);
+        for k in &self.round_keys[1..self.rounds] {
+            sub_bytes(block);
+            shift_rows(block);
+            mix_columns(block);
+            add_round_key(block, k);
+        }
+        sub_bytes(block);
+        shift_rows(block);
+        add_round_key(block, &self.round_keys[self.rounds]);
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8; 16]) {
+        add_round_key(block, &self.round_keys[self.rounds]);
+        for k in self.round_keys[1..self.rounds].iter().rev() {
+            inv_shift_rows(block);
+            inv_sub_bytes(block);
+            add_round_key(block, k);
+            inv_mix_columns(block);
+        }
+        inv_shift_rows(block);
+        inv_sub_bytes(block);
+        add_round_key(block, &self.round_keys[0]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_sbox() {
+        let mut s: [u8; 16] = core::array::from_fn(|i| i as u8);
+        sub_bytes(&mut s);
+        assert_eq!(s, hex!("637c777bf26b6fc53001672bfed7ab76"));
+        inv_sub_bytes(&mut s);
+        assert_eq!(s, core::array::from_fn(|i| i as u8));
+        let mut s = [0xff; 16];
+        sub_bytes(&mut s);
+        assert_eq!(s, [0x16; 16]);
+    }
+
+    #[test]
+    fn test_aes() {
+        // FIPS 197 appendix C
+        let plaintext = hex!("00112233445566778899aabbccddeeff");
+        for (key, ciphertext) in [
+            (&hex!("000102030405060708090a0b0c0d0e0f")[..], hex!("69c4e0d86a7b0430d8cdb78070b4c55a")),
+            (&hex!("000102030405060708090a0b0c0d0e0f1011121314151617"), hex!("dda97ca4864cdfe06eaf70a0ec0d7191")),
+            (
+                &hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"),
+                hex!("8ea2b7ca516745bfeafc49904b496089"),
+            ),
+        ] {
+            let aes = Aes::new(key).unwrap();
+            assert_eq!(aes.key_len(), key.len());
+            let mut block = plaintext;
+            aes.encrypt_block(&mut block);
+            assert_eq!(block, ciphertext);
+            aes.decrypt_block(&mut block);
+            assert_eq!(block, plaintext);
+        }
+        assert!(matches!(Aes::new(&[0; 20]), Err(Error::InvalidKeyLength)));
+    }
+}
@@ -0,0 +1,20 @@
+use thiserror;
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("unsupported algorithm")]
+    UnsupportedAlgorithm,
+
+    #[error("invalid length")]
+    InvalidLength,
+
+    #[error("invalid encoding")]
+    Decode,
+
+    #[error("context string longer than 255 bytes")]
+    ContextTooLong,
+
+    #[error("signature verification failed")]
+    Verify,
+}
+pub type Result<T> = core::result::Result<T, Error>;
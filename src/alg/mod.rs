@@ -0,0 +1,774 @@
+//! Runtime dispatch of the KEM and signature algorithms.
+//!
+//! The parameter sets implement [`Kem`] (ML-KEM) or [`SignatureScheme`] (ML-DSA),
+//! and [`Algorithm`] selects one of them at runtime, working on encoded keys.
+
+use core::ffi::{CStr, c_char};
+
+use rand::CryptoRng;
+
+use crate::capi;
+
+mod errors;
+pub use errors::{Error, Result};
+
+pub use crate::mldsa::mldsa44::MlDsa44;
+pub use crate::mldsa::mldsa65::MlDsa65;
+pub use crate::mldsa::mldsa87::MlDsa87;
+pub use crate::mlkem::mlkem512::MlKem512;
+pub use crate::mlkem::mlkem768::MlKem768;
+pub use crate::mlkem::mlkem1024::MlKem1024;
+
+/// The algorithm identifiers, the values are the OPENGM_ALG_* constants of the C api.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum Algorithm {
+    MlKem512 = 1,
+    MlKem768 = 2,
+    MlKem1024 = 3,
+    MlDsa44 = 11,
+    MlDsa65 = 12,
+    MlDsa87 = 13,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 6] = [
+        Algorithm::MlKem512,
+        Algorithm::MlKem768,
+        Algorithm::MlKem1024,
+        Algorithm::MlDsa44,
+        Algorithm::MlDsa65,
+        Algorithm::MlDsa87,
+    ];
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| *alg as i32 == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        // the names are ASCII
+        self.c_name().to_str().unwrap()
+    }
+
+    // The NUL-terminated name, the table of both name and opengm_alg_name.
+    fn c_name(self) -> &'static CStr {
+        match self {
+            Algorithm::MlKem512 => c"ML-KEM-512",
+            Algorithm::MlKem768 => c"ML-KEM-768",
+            Algorithm::MlKem1024 => c"ML-KEM-1024",
+            Algorithm::MlDsa44 => c"ML-DSA-44",
+            Algorithm::MlDsa65 => c"ML-DSA-65",
+            Algorithm::MlDsa87 => c"ML-DSA-87",
+        }
+    }
+
+    pub fn is_kem(self) -> bool {
+        matches!(self, Algorithm::MlKem512 | Algorithm::MlKem768 | Algorithm::MlKem1024)
+    }
+
+    pub fn is_signature(self) -> bool {
+        !self.is_kem()
+    }
+
+    /// The encoded encapsulation key or public key length.
+    pub fn public_key_len(self) -> usize {
+        use crate::mldsa::{mldsa44, mldsa65, mldsa87};
+        use crate::mlkem::{mlkem512, mlkem768, mlkem1024};
+        match self {
+            Algorithm::MlKem512 => mlkem512::ek_len,
+            Algorithm::MlKem768 => mlkem768::ek_len,
+            Algorithm::MlKem1024 => mlkem1024::ek_len,
+            Algorithm::MlDsa44 => mldsa44::pklen,
+            Algorithm::MlDsa65 => mldsa65::pklen,
+            Algorithm::MlDsa87 => mldsa87::pklen,
+        }
+    }
+
+    /// The encoded decapsulation key or private key length.
+    pub fn private_key_len(self) -> usize {
+        use crate::mldsa::{mldsa44, mldsa65, mldsa87};
+        use crate::mlkem::{mlkem512, mlkem768, mlkem1024};
+        match self {
+            Algorithm::MlKem512 => mlkem512::dk_len,
+            Algorithm::MlKem768 => mlkem768::dk_len,
+            Algorithm::MlKem1024 => mlkem1024::dk_len,
+            Algorithm::MlDsa44 => mldsa44::sklen,
+            Algorithm::MlDsa65 => mldsa65::sklen,
+            Algorithm::MlDsa87 => mldsa87::sklen,
+        }
+    }
+
+    /// The ciphertext length of a KEM, 0 for signatures.
+    pub fn ciphertext_len(self) -> usize {
+        use crate::mlkem::{mlkem512, mlkem768, mlkem1024};
+        match self {
+            Algorithm::MlKem512 => mlkem512::cipher_len,
+            Algorithm::MlKem768 => mlkem768::cipher_len,
+            Algorithm::MlKem1024 => mlkem1024::cipher_len,
+            _ => 0,
+        }
+    }
+
+    /// The shared key length of a KEM, 0 for signatures.
+    pub fn shared_key_len(self) -> usize {
+        if self.is_kem() { 32 } else { 0 }
+    }
+
+    /// The signature length, 0 for KEMs.
+    pub fn signature_len(self) -> usize {
+        use crate::mldsa::{mldsa44, mldsa65, mldsa87};
+        match self {
+            Algorithm::MlDsa44 => mldsa44::siglen,
+            Algorithm::MlDsa65 => mldsa65::siglen,
+            Algorithm::MlDsa87 => mldsa87::siglen,
+            _ => 0,
+        }
+    }
+
+    /// Generate a KEM key pair from the seeds d and z, and encode it to ek and dk.
+    pub fn kem_keygen_internal(self, d: &[u8; 32], z: &[u8; 32], ek: &mut [u8], dk: &mut [u8]) -> Result<()> {
+        match self {
+            Algorithm::MlKem512 => kem_keygen_::<MlKem512>(d, z, ek, dk),
+            Algorithm::MlKem768 => kem_keygen_::<MlKem768>(d, z, ek, dk),
+            Algorithm::MlKem1024 => kem_keygen_::<MlKem1024>(d, z, ek, dk),
+            _ => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Encapsulate to the encoded ek with the randomness m, write the ciphertext to c and return the shared key.
+    pub fn kem_encaps_internal(self, ek: &[u8], m: &[u8; 32], c: &mut [u8]) -> Result<[u8; 32]> {
+        match self {
+            Algorithm::MlKem512 => kem_encaps_::<MlKem512>(ek, m, c),
+            Algorithm::MlKem768 => kem_encaps_::<MlKem768>(ek, m, c),
+            Algorithm::MlKem1024 => kem_encaps_::<MlKem1024>(ek, m, c),
+            _ => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Decapsulate c with the encoded dk.
+    pub fn kem_decaps(self, dk: &[u8], c: &[u8]) -> Result<[u8; 32]> {
+        match self {
+            Algorithm::MlKem512 => kem_decaps_::<MlKem512>(dk, c),
+            Algorithm::MlKem768 => kem_decaps_::<MlKem768>(dk, c),
+            Algorithm::MlKem1024 => kem_decaps_::<MlKem1024>(dk, c),
+            _ => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Generate a signature key pair from the seed xi, and encode it to pk and sk.
+    pub fn sig_keygen_internal(self, xi: &[u8; 32], pk: &mut [u8], sk: &mut [u8]) -> Result<()> {
+        match self {
+            Algorithm::MlDsa44 => sig_keygen_::<MlDsa44>(xi, pk, sk),
+            Algorithm::MlDsa65 => sig_keygen_::<MlDsa65>(xi, pk, sk),
+            Algorithm::MlDsa87 => sig_keygen_::<MlDsa87>(xi, pk, sk),
+            _ => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Sign m with the context ctx by the encoded sk, rnd is all zero for the deterministic variant.
+    pub fn sig_sign(self, sk: &[u8], m: &[u8], ctx: &[u8], rnd: &[u8; 32], sig: &mut [u8]) -> Result<()> {
+        match self {
+            Algorithm::MlDsa44 => sig_sign_::<MlDsa44>(sk, m, ctx, rnd, sig),
+            Algorithm::MlDsa65 => sig_sign_::<MlDsa65>(sk, m, ctx, rnd, sig),
+            Algorithm::MlDsa87 => sig_sign_::<MlDsa87>(sk, m, ctx, rnd, sig),
+            _ => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Verify the signature of m with the context ctx by the encoded pk.
+    pub fn sig_verify(self, pk: &[u8], m: &[u8], ctx: &[u8], sig: &[u8]) -> Result<()> {
+        match self {
+            Algorithm::MlDsa44 => sig_verify_::<MlDsa44>(pk, m, ctx, sig),
+            Algorithm::MlDsa65 => sig_verify_::<MlDsa65>(pk, m, ctx, sig),
+            Algorithm::MlDsa87 => sig_verify_::<MlDsa87>(pk, m, ctx, sig),
+            _ => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+}
+
+impl TryFrom<i32> for Algorithm {
+    type Error = Error;
+
+    fn try_from(id: i32) -> Result<Self> {
+        Self::from_id(id).ok_or(Error::UnsupportedAlgorithm)
+    }
+}
+
+/// A KEM parameter set, implemented by MlKem512, MlKem768 and MlKem1024.
+pub trait Kem {
+    const ALGORITHM: Algorithm;
+    type EncapKey: Encapsulator;
+    type DecapKey: Decapsulator;
+
+    fn keygen_internal(d: &[u8; 32], z: &[u8; 32]) -> Self::DecapKey;
+
+    fn keygen(rng: &mut dyn CryptoRng) -> Self::DecapKey {
+        let (d, z) = random_dz(rng);
+        Self::keygen_internal(&d, &z)
+    }
+
+    fn encapsulation_key(dk: &Self::DecapKey) -> Self::EncapKey;
+
+    /// Write the ciphertext to c of ALGORITHM.ciphertext_len() bytes, and return the shared key.
+    fn encaps_internal(ek: &Self::EncapKey, m: &[u8; 32], c: &mut [u8]) -> Result<[u8; 32]>;
+
+    fn encaps(ek: &Self::EncapKey, rng: &mut dyn CryptoRng, c: &mut [u8]) -> Result<[u8; 32]> {
+        Self::encaps_internal(ek, &random_bytes(rng), c)
+    }
+
+    fn decaps(dk: &Self::DecapKey, c: &[u8]) -> Result<[u8; 32]>;
+
+    fn encode_encap_key(ek: &Self::EncapKey, b: &mut [u8]) -> Result<()>;
+
+    fn decode_encap_key(b: &[u8]) -> Result<Self::EncapKey>;
+
+    fn encode_decap_key(dk: &Self::DecapKey, b: &mut [u8]) -> Result<()>;
+
+    fn decode_decap_key(b: &[u8]) -> Result<Self::DecapKey>;
+}
+
+/// A public key which encapsulates shared keys, implemented by the ML-KEM encapsulation keys.
+pub trait Encapsulator {
+    fn algorithm(&self) -> Algorithm;
+
+    /// Write the ciphertext to c of algorithm().ciphertext_len() bytes, and return the shared key.
+    fn encaps_internal(&self, m: &[u8; 32], c: &mut [u8]) -> Result<[u8; 32]>;
+
+    fn encaps(&self, rng: &mut dyn CryptoRng, c: &mut [u8]) -> Result<[u8; 32]> {
+        self.encaps_internal(&random_bytes(rng), c)
+    }
+}
+
+/// A private key which decapsulates shared keys, implemented by the ML-KEM decapsulation keys.
+pub trait Decapsulator {
+    fn algorithm(&self) -> Algorithm;
+
+    fn decaps(&self, c: &[u8]) -> Result<[u8; 32]>;
+}
+
+/// A private key which signs messages, implemented by the ML-DSA private keys.
+pub trait Signer {
+    fn algorithm(&self) -> Algorithm;
+
+    /// Sign m with the context ctx, write the signature to sig of algorithm().signature_len() bytes.
+    fn sign(&self, m: &[u8], ctx: &[u8], rnd: &[u8; 32], sig: &mut [u8]) -> Result<()>;
+}
+
+/// A public key which verifies signatures, implemented by the ML-DSA public keys.
+pub trait Verifier {
+    fn algorithm(&self) -> Algorithm;
+
+    fn verify(&self, m: &[u8], ctx: &[u8], sig: &[u8]) -> Result<()>;
+}
+
+/// A signature parameter set, implemented by MlDsa44, MlDsa65 and MlDsa87.
+pub trait SignatureScheme {
+    const ALGORITHM: Algorithm;
+    type PrivateKey: Signer;
+    type PublicKey: Verifier;
+
+    fn keygen_internal(xi: &[u8; 32]) -> Self::PrivateKey;
+
+    fn keygen(rng: &mut dyn CryptoRng) -> Self::PrivateKey {
+        Self::keygen_internal(&random_bytes(rng))
+    }
+
+    fn public_key(sk: &Self::PrivateKey) -> Self::PublicKey;
+
+    fn encode_public_key(pk: &Self::PublicKey, b: &mut [u8]) -> Result<()>;
+
+    fn decode_public_key(b: &[u8]) -> Result<Self::PublicKey>;
+
+    fn encode_private_key(sk: &Self::PrivateKey, b: &mut [u8]) -> Result<()>;
+
+    fn decode_private_key(b: &[u8]) -> Result<Self::PrivateKey>;
+}
+
+// Convert b to an array, the length must be exactly N.
+#[inline]
+pub(crate) fn array_ref<const N: usize>(b: &[u8]) -> Result<&[u8; N]> {
+    b.try_into().map_err(|_| Error::InvalidLength)
+}
+
+#[inline]
+pub(crate) fn array_mut<const N: usize>(b: &mut [u8]) -> Result<&mut [u8; N]> {
+    b.try_into().map_err(|_| Error::InvalidLength)
+}
+
+fn kem_keygen_<K: Kem>(d: &[u8; 32], z: &[u8; 32], ek: &mut [u8], dk: &mut [u8]) -> Result<()> {
+    if ek.len() != K::ALGORITHM.public_key_len() || dk.len() != K::ALGORITHM.private_key_len() {
+        return Err(Error::InvalidLength);
+    }
+    let decap_key = K::keygen_internal(d, z);
+    K::encode_encap_key(&K::encapsulation_key(&decap_key), ek)?;
+    K::encode_decap_key(&decap_key, dk)
+}
+
+fn kem_encaps_<K: Kem>(ek: &[u8], m: &[u8; 32], c: &mut [u8]) -> Result<[u8; 32]> {
+    if c.len() != K::ALGORITHM.ciphertext_len() {
+        return Err(Error::InvalidLength);
+    }
+    K::encaps_internal(&K::decode_encap_key(ek)?, m, c)
+}
+
+fn kem_decaps_<K: Kem>(dk: &[u8], c: &[u8]) -> Result<[u8; 32]> {
+    if c.len() != K::ALGORITHM.ciphertext_len() {
+        return Err(Error::InvalidLength);
+    }
+    K::decaps(&K::decode_decap_key(dk)?, c)
+}
+
+fn sig_keygen_<S: SignatureScheme>(xi: &[u8; 32], pk: &mut [u8], sk: &mut [u8]) -> Result<()> {
+    if pk.len() != S::ALGORITHM.public_key_len() || sk.len() != S::ALGORITHM.private_key_len() {
+        return Err(Error::InvalidLength);
+    }
+    let private_key = S::keygen_internal(xi);
+    S::encode_public_key(&S::public_key(&private_key), pk)?;
+    S::encode_private_key(&private_key, sk)
+}
+
+fn sig_sign_<S: SignatureScheme>(sk: &[u8], m: &[u8], ctx: &[u8], rnd: &[u8; 32], sig: &mut [u8]) -> Result<()> {
+    S::decode_private_key(sk)?.sign(m, ctx, rnd, sig)
+}
+
+fn sig_verify_<S: SignatureScheme>(pk: &[u8], m: &[u8], ctx: &[u8], sig: &[u8]) -> Result<()> {
+    S::decode_public_key(pk)?.verify(m, ctx, sig)
+}
+
+/////////////////////////////////////////////////////////////////////
+//  exports C api
+/////////////////////////////////////////////////////////////////////
+
+/// ML-KEM-512
+pub const OPENGM_ALG_MLKEM512: i32 = 1;
+/// ML-KEM-768
+pub const OPENGM_ALG_MLKEM768: i32 = 2;
+/// ML-KEM-1024
+pub const OPENGM_ALG_MLKEM1024: i32 = 3;
+/// ML-DSA-44
+pub const OPENGM_ALG_MLDSA44: i32 = 11;
+/// ML-DSA-65
+pub const OPENGM_ALG_MLDSA65: i32 = 12;
+/// ML-DSA-87
+pub const OPENGM_ALG_MLDSA87: i32 = 13;
+
+fn status_code(e: Error) -> i32 {
+    match e {
+        Error::UnsupportedAlgorithm => capi::OPENGM_ERR_ALGORITHM,
+        Error::InvalidLength | Error::ContextTooLong => capi::OPENGM_ERR_LENGTH,
+        Error::Decode => capi::OPENGM_ERR_DECODE,
+        Error::Verify => capi::OPENGM_ERR_VERIFY,
+    }
+}
+
+// N random bytes in a single request of rng, as the NIST KAT programs draw the seeds, so that the
+// CTR_DRBG of aes::drbg reproduces their records.
+pub(crate) fn random_bytes<const N: usize>(rng: &mut dyn CryptoRng) -> [u8; N] {
+    let mut b = [0; N];
+    rng.fill_bytes(&mut b);
+    b
+}
+
+// the seeds d and z of the ML-KEM key generation, drawn together
+pub(crate) fn random_dz(rng: &mut dyn CryptoRng) -> ([u8; 32], [u8; 32]) {
+    let dz: [u8; 64] = random_bytes(rng);
+    (dz[..32].try_into().unwrap(), dz[32..].try_into().unwrap())
+}
+
+fn algorithm(alg: i32) -> capi::Result<Algorithm> {
+    Algorithm::from_id(alg).ok_or(capi::OPENGM_ERR_ALGORITHM)
+}
+
+// Query a length of alg, 0 for an unknown algorithm.
+fn len_of(alg: i32, f: impl FnOnce(Algorithm) -> usize) -> usize {
+    Algorithm::from_id(alg).map_or(0, f)
+}
+
+/// opengm_alg_name 返回算法名称, 如"ML-KEM-768", 未知算法返回NULL. 返回的字符串是静态的, 调用者不能释放.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_alg_name(alg: i32) -> *const c_char {
+    Algorithm::try_from(alg).map_or(core::ptr::null(), |alg| alg.c_name().as_ptr())
+}
+
+/// opengm_alg_public_key_len 返回封装密钥或公钥的编码长度, 未知算法返回0.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_alg_public_key_len(alg: i32) -> usize {
+    len_of(alg, Algorithm::public_key_len)
+}
+
+/// opengm_alg_private_key_len 返回解封装密钥或私钥的编码长度, 未知算法返回0.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_alg_private_key_len(alg: i32) -> usize {
+    len_of(alg, Algorithm::private_key_len)
+}
+
+/// opengm_alg_ciphertext_len 返回KEM密文长度, 签名算法或未知算法返回0.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_alg_ciphertext_len(alg: i32) -> usize {
+    len_of(alg, Algorithm::ciphertext_len)
+}
+
+/// opengm_alg_shared_key_len 返回KEM共享密钥长度, 签名算法或未知算法返回0.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_alg_shared_key_len(alg: i32) -> usize {
+    len_of(alg, Algorithm::shared_key_len)
+}
+
+/// opengm_alg_signature_len 返回签名长度, KEM算法或未知算法返回0.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_alg_signature_len(alg: i32) -> usize {
+    len_of(alg, Algorithm::signature_len)
+}
+
+/// opengm_kem_keygen 由32字节的d,z生成密钥对, d,z由调用者使用随机数发生器生成.
+/// ek_len, dk_len必须等于opengm_alg_public_key_len(alg), opengm_alg_private_key_len(alg).
+///
+/// # Safety
+/// d, z必须为NULL或指向32字节, ek, dk必须指向ek_len, dk_len字节的可写内存, 长度为0时可以为NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_kem_keygen(
+    alg: i32,
+    d: *const u8,
+    z: *const u8,
+    ek: *mut u8,
+    ek_len: usize,
+    dk: *mut u8,
+    dk_len: usize,
+) -> i32 {
+    capi::status(|| {
+        let alg = algorithm(alg)?;
+        let d = unsafe { capi::array_ref::<32>(d) }?;
+        let z = unsafe { capi::array_ref::<32>(z) }?;
+        let ek = unsafe { capi::slice_mut(ek, ek_len) }?;
+        let dk = unsafe { capi::slice_mut(dk, dk_len) }?;
+        alg.kem_keygen_internal(d, z, ek, dk).map_err(status_code)
+    })
+}
+
+/// opengm_kem_encap 封装, key指向32字节, c指向c_len字节, m指向32字节的随机数.
+///
+/// # Safety
+/// key必须为NULL或指向32字节的可写内存, m必须为NULL或指向32字节, c必须指向c_len字节的可写内存,
+/// ek必须指向ek_len字节, 长度为0时可以为NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_kem_encap(
+    alg: i32,
+    key: *mut u8,
+    c: *mut u8,
+    c_len: usize,
+    ek: *const u8,
+    ek_len: usize,
+    m: *const u8,
+) -> i32 {
+    capi::status(|| {
+        let alg = algorithm(alg)?;
+        let key = unsafe { capi::array_mut::<32>(key) }?;
+        let c = unsafe { capi::slice_mut(c, c_len) }?;
+        let ek = unsafe { capi::slice_ref(ek, ek_len) }?;
+        let m = unsafe { capi::array_ref::<32>(m) }?;
+        *key = alg.kem_encaps_internal(ek, m, c).map_err(status_code)?;
+        Ok(())
+    })
+}
+
+/// opengm_kem_decap 解封装, key指向32字节, c指向c_len字节.
+///
+/// # Safety
+/// key必须为NULL或指向32字节的可写内存, c, dk必须指向c_len, dk_len字节, 长度为0时可以为NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_kem_decap(
+    alg: i32,
+    key: *mut u8,
+    c: *const u8,
+    c_len: usize,
+    dk: *const u8,
+    dk_len: usize,
+) -> i32 {
+    capi::status(|| {
+        let alg = algorithm(alg)?;
+        let key = unsafe { capi::array_mut::<32>(key) }?;
+        let c = unsafe { capi::slice_ref(c, c_len) }?;
+        let dk = unsafe { capi::slice_ref(dk, dk_len) }?;
+        *key = alg.kem_decaps(dk, c).map_err(status_code)?;
+        Ok(())
+    })
+}
+
+/// opengm_sig_keygen 由32字节的种子xi生成密钥对.
+/// pk_len, sk_len必须等于opengm_alg_public_key_len(alg), opengm_alg_private_key_len(alg).
+///
+/// # Safety
+/// xi必须为NULL或指向32字节, pk, sk必须指向pk_len, sk_len字节的可写内存, 长度为0时可以为NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_sig_keygen(
+    alg: i32,
+    xi: *const u8,
+    pk: *mut u8,
+    pk_len: usize,
+    sk: *mut u8,
+    sk_len: usize,
+) -> i32 {
+    capi::status(|| {
+        let alg = algorithm(alg)?;
+        let xi = unsafe { capi::array_ref::<32>(xi) }?;
+        let pk = unsafe { capi::slice_mut(pk, pk_len) }?;
+        let sk = unsafe { capi::slice_mut(sk, sk_len) }?;
+        alg.sig_keygen_internal(xi, pk, sk).map_err(status_code)
+    })
+}
+
+/// opengm_sig_sign 签名(ML-DSA.Sign), sig_len必须等于opengm_alg_signature_len(alg), ctx不超过255字节.
+/// rnd指向32字节, 全零时为确定性签名. mlen, ctx_len为0时m, ctx可以为NULL.
+///
+/// # Safety
+/// rnd必须为NULL或指向32字节, sig必须指向sig_len字节的可写内存, sk, m, ctx必须指向sk_len, mlen, ctx_len字节,
+/// 长度为0时可以为NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_sig_sign(
+    alg: i32,
+    sig: *mut u8,
+    sig_len: usize,
+    sk: *const u8,
+    sk_len: usize,
+    m: *const u8,
+    mlen: usize,
+    ctx: *const u8,
+    ctx_len: usize,
+    rnd: *const u8,
+) -> i32 {
+    capi::status(|| {
+        let alg = algorithm(alg)?;
+        let sig = unsafe { capi::slice_mut(sig, sig_len) }?;
+        let sk = unsafe { capi::slice_ref(sk, sk_len) }?;
+        let m = unsafe { capi::slice_ref(m, mlen) }?;
+        let ctx = unsafe { capi::slice_ref(ctx, ctx_len) }?;
+        let rnd = unsafe { capi::array_ref::<32>(rnd) }?;
+        alg.sig_sign(sk, m, ctx, rnd, sig).map_err(status_code)
+    })
+}
+
+/// opengm_sig_verify 验证签名(ML-DSA.Verify), 签名有效时返回OPENGM_OK, 无效时返回OPENGM_ERR_VERIFY.
+///
+/// # Safety
+/// sig, pk, m, ctx必须指向sig_len, pk_len, mlen, ctx_len字节, 长度为0时可以为NULL.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_sig_verify(
+    alg: i32,
+    sig: *const u8,
+    sig_len: usize,
+    pk: *const u8,
+    pk_len: usize,
+    m: *const u8,
+    mlen: usize,
+    ctx: *const u8,
+    ctx_len: usize,
+) -> i32 {
+    capi::status(|| {
+        let alg = algorithm(alg)?;
+        let sig = unsafe { capi::slice_ref(sig, sig_len) }?;
+        let pk = unsafe { capi::slice_ref(pk, pk_len) }?;
+        let m = unsafe { capi::slice_ref(m, mlen) }?;
+        let ctx = unsafe { capi::slice_ref(ctx, ctx_len) }?;
+        alg.sig_verify(pk, m, ctx, sig).map_err(status_code)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ffi::CStr;
+    use core::ptr::null;
+
+    use rand::Rng;
+
+    use super::*;
+    use crate::capi::{OPENGM_ERR_ALGORITHM, OPENGM_ERR_DECODE, OPENGM_ERR_LENGTH, OPENGM_ERR_VERIFY, OPENGM_OK};
+
+    #[test]
+    fn test_algorithm() {
+        for alg in Algorithm::ALL {
+            assert_eq!(Algorithm::from_id(alg as i32), Some(alg));
+            assert_eq!(Algorithm::try_from(alg as i32), Ok(alg));
+            let name = unsafe { CStr::from_ptr(opengm_alg_name(alg as i32)) };
+            assert_eq!(name.to_str(), Ok(alg.name()));
+            assert!(alg.public_key_len() > 0 && alg.private_key_len() > 0);
+            assert_eq!(alg.is_kem(), alg.ciphertext_len() > 0);
+            assert_eq!(alg.is_signature(), alg.signature_len() > 0);
+        }
+        assert_eq!(Algorithm::MlKem768.name(), "ML-KEM-768");
+        assert_eq!(Algorithm::from_id(0), None);
+        assert_eq!(Algorithm::try_from(4), Err(Error::UnsupportedAlgorithm));
+        assert!(opengm_alg_name(0).is_null());
+        assert_eq!(opengm_alg_public_key_len(0), 0);
+        assert_eq!(opengm_alg_ciphertext_len(OPENGM_ALG_MLKEM768), 1088);
+        assert_eq!(opengm_alg_signature_len(OPENGM_ALG_MLDSA65), 3309);
+        assert_eq!(opengm_alg_signature_len(OPENGM_ALG_MLKEM768), 0);
+    }
+
+    fn kem_roundtrip<K: Kem>() {
+        let mut rng = rand::rng();
+        let dk = K::keygen(&mut rng);
+        let ek = K::encapsulation_key(&dk);
+        let mut c = vec![0u8; K::ALGORITHM.ciphertext_len()];
+        let key = K::encaps(&ek, &mut rng, &mut c).unwrap();
+        assert_eq!(K::decaps(&dk, &c), Ok(key));
+        assert_eq!((ek.algorithm(), dk.algorithm()), (K::ALGORITHM, K::ALGORITHM));
+        let key = Encapsulator::encaps(&ek, &mut rng, &mut c).unwrap();
+        assert_eq!(Decapsulator::decaps(&dk, &c), Ok(key));
+
+        let mut ek_encoded = vec![0u8; K::ALGORITHM.public_key_len()];
+        let mut dk_encoded = vec![0u8; K::ALGORITHM.private_key_len()];
+        K::encode_encap_key(&ek, &mut ek_encoded).unwrap();
+        K::encode_decap_key(&dk, &mut dk_encoded).unwrap();
+        let dk2 = K::decode_decap_key(&dk_encoded).unwrap();
+        assert_eq!(K::decaps(&dk2, &c), Ok(key));
+        assert!(K::decode_encap_key(&ek_encoded).is_ok());
+
+        assert!(matches!(K::decode_encap_key(&ek_encoded[1..]), Err(Error::InvalidLength)));
+        assert_eq!(K::decaps(&dk, &c[1..]), Err(Error::InvalidLength));
+    }
+
+    fn sig_roundtrip<S: SignatureScheme>() {
+        let mut rng = rand::rng();
+        let sk = S::keygen(&mut rng);
+        let pk = S::public_key(&sk);
+        assert_eq!(sk.algorithm(), S::ALGORITHM);
+        assert_eq!(pk.algorithm(), S::ALGORITHM);
+
+        let m: [u8; 32] = rng.random();
+        let mut sig = vec![0u8; S::ALGORITHM.signature_len()];
+        sk.sign(&m, b"ctx", &rng.random(), &mut sig).unwrap();
+        assert_eq!(pk.verify(&m, b"ctx", &sig), Ok(()));
+        assert_eq!(pk.verify(&m, b"", &sig), Err(Error::Verify));
+        assert_eq!(pk.verify(&m, b"ctx", &sig[1..]), Err(Error::InvalidLength));
+        assert_eq!(sk.sign(&m, &[0; 256], &[0; 32], &mut sig), Err(Error::ContextTooLong));
+        assert_eq!(sk.sign(&m, b"", &[0; 32], &mut sig[1..]), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    fn test_traits() {
+        kem_roundtrip::<MlKem512>();
+        kem_roundtrip::<MlKem768>();
+        kem_roundtrip::<MlKem1024>();
+        sig_roundtrip::<MlDsa44>();
+        sig_roundtrip::<MlDsa65>();
+        sig_roundtrip::<MlDsa87>();
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let mut rng = rand::rng();
+        for alg in Algorithm::ALL {
+            let mut pk = vec![0u8; alg.public_key_len()];
+            let mut sk = vec![0u8; alg.private_key_len()];
+            if alg.is_kem() {
+                alg.kem_keygen_internal(&rng.random(), &rng.random(), &mut pk, &mut sk).unwrap();
+                let mut c = vec![0u8; alg.ciphertext_len()];
+                let key = alg.kem_encaps_internal(&pk, &rng.random(), &mut c).unwrap();
+                assert_eq!(alg.kem_decaps(&sk, &c), Ok(key));
+                assert_eq!(
+                    alg.sig_keygen_internal(&rng.random(), &mut pk, &mut sk),
+                    Err(Error::UnsupportedAlgorithm)
+                );
+            } else {
+                alg.sig_keygen_internal(&rng.random(), &mut pk, &mut sk).unwrap();
+                let m: [u8; 32] = rng.random();
+                let mut sig = vec![0u8; alg.signature_len()];
+                alg.sig_sign(&sk, &m, b"", &[0; 32], &mut sig).unwrap();
+                assert_eq!(alg.sig_verify(&pk, &m, b"", &sig), Ok(()));
+
+                // deterministic
+                let mut sig2 = vec![0u8; alg.signature_len()];
+                alg.sig_sign(&sk, &m, b"", &[0; 32], &mut sig2).unwrap();
+                assert_eq!(sig, sig2);
+                assert_eq!(alg.sig_verify(&pk, &m[1..], b"", &sig), Err(Error::Verify));
+                assert_eq!(alg.kem_decaps(&sk, &sig), Err(Error::UnsupportedAlgorithm));
+            }
+            assert_eq!(
+                alg.kem_keygen_internal(&[0; 32], &[0; 32], &mut pk[1..], &mut sk),
+                Err(if alg.is_kem() { Error::InvalidLength } else { Error::UnsupportedAlgorithm })
+            );
+        }
+    }
+
+    #[test]
+    fn test_capi() {
+        let mut rng = rand::rng();
+        let d: [u8; 32] = rng.random();
+        let z: [u8; 32] = rng.random();
+        let m: [u8; 32] = rng.random();
+
+        let alg = OPENGM_ALG_MLKEM768;
+        let mut ek = vec![0u8; opengm_alg_public_key_len(alg)];
+        let mut dk = vec![0u8; opengm_alg_private_key_len(alg)];
+        let mut c = vec![0u8; opengm_alg_ciphertext_len(alg)];
+        let (mut key, mut key2) = ([0u8; 32], [0u8; 32]);
+        let kem_keygen = |alg, ek: &mut [u8], dk: &mut [u8]| {
+            unsafe {
+                opengm_kem_keygen(alg, d.as_ptr(), z.as_ptr(), ek.as_mut_ptr(), ek.len(), dk.as_mut_ptr(), dk.len())
+            }
+        };
+        let kem_encap = |key: &mut [u8; 32], c: &mut [u8], ek: &[u8]| unsafe {
+            opengm_kem_encap(alg, key.as_mut_ptr(), c.as_mut_ptr(), c.len(), ek.as_ptr(), ek.len(), m.as_ptr())
+        };
+        assert_eq!(kem_keygen(alg, &mut ek, &mut dk), OPENGM_OK);
+        assert_eq!(kem_encap(&mut key, &mut c, &ek), OPENGM_OK);
+        assert_eq!(
+            unsafe { opengm_kem_decap(alg, key2.as_mut_ptr(), c.as_ptr(), c.len(), dk.as_ptr(), dk.len()) },
+            OPENGM_OK
+        );
+        assert_eq!(key, key2);
+        assert_eq!(kem_keygen(OPENGM_ALG_MLDSA65, &mut ek, &mut dk), OPENGM_ERR_ALGORITHM);
+        assert_eq!(kem_keygen(0, &mut ek, &mut dk), OPENGM_ERR_ALGORITHM);
+        assert_eq!(kem_keygen(OPENGM_ALG_MLKEM512, &mut ek, &mut dk), OPENGM_ERR_LENGTH);
+        ek[..2].fill(0xff);
+        assert_eq!(kem_encap(&mut key, &mut c, &ek), OPENGM_ERR_DECODE);
+
+        let alg = OPENGM_ALG_MLDSA65;
+        let mut pk = vec![0u8; opengm_alg_public_key_len(alg)];
+        let mut sk = vec![0u8; opengm_alg_private_key_len(alg)];
+        let mut sig = vec![0u8; opengm_alg_signature_len(alg)];
+        let ctx = b"context";
+        assert_eq!(
+            unsafe { opengm_sig_keygen(alg, d.as_ptr(), pk.as_mut_ptr(), pk.len(), sk.as_mut_ptr(), sk.len()) },
+            OPENGM_OK
+        );
+        assert_eq!(
+            unsafe {
+                opengm_sig_sign(
+                    alg,
+                    sig.as_mut_ptr(),
+                    sig.len(),
+                    sk.as_ptr(),
+                    sk.len(),
+                    m.as_ptr(),
+                    m.len(),
+                    ctx.as_ptr(),
+                    ctx.len(),
+                    z.as_ptr(),
+                )
+            },
+            OPENGM_OK
+        );
+        let verify = |alg, m: &[u8], ctx: &[u8]| {
+            let ctx_ptr = if ctx.is_empty() { null() } else { ctx.as_ptr() };
+            unsafe {
+                opengm_sig_verify(
+                    alg,
+                    sig.as_ptr(),
+                    sig.len(),
+                    pk.as_ptr(),
+                    pk.len(),
+                    m.as_ptr(),
+                    m.len(),
+                    ctx_ptr,
+                    ctx.len(),
+                )
+            }
+        };
+        assert_eq!(verify(alg, &m, ctx), OPENGM_OK);
+        assert_eq!(verify(alg, &m, b""), OPENGM_ERR_VERIFY);
+        assert_eq!(verify(alg, &m[1..], ctx), OPENGM_ERR_VERIFY);
+        assert_eq!(verify(OPENGM_ALG_MLDSA44, &m, ctx), OPENGM_ERR_LENGTH);
+        assert_eq!(verify(OPENGM_ALG_MLKEM768, &m, ctx), OPENGM_ERR_ALGORITHM);
+    }
+}
@@ -0,0 +1,51 @@
+//! Answers an ACVP vector set of ML-KEM or ML-DSA offline, with the `*_internal` functions.
+//!
+//! `acvp-responder [REQUEST [RESPONSE]]` reads the request JSON from the file REQUEST and writes
+//! the response JSON to the file RESPONSE, the standard input and output when they are `-` or
+//! omitted. The request is the vector set downloaded from the ACVP server,
+//! `[{"acvVersion": ..}, {vector set}]`, or the `prompt.json` of the ACVP-Server json-files.
+
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use std::{env, fs};
+
+use opengm_pqc::acvp;
+use opengm_pqc::json::Value;
+
+const USAGE: &str = "usage: acvp-responder [REQUEST [RESPONSE]]";
+
+fn run(request: Option<&str>, response: Option<&str>) -> Result<(), String> {
+    let input = match request {
+        None | Some("-") => {
+            let mut s = String::new();
+            io::stdin()
+                .read_to_string(&mut s)
+                .map_err(|e| format!("reading the standard input: {e}"))?;
+            s
+        }
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("reading {path}: {e}"))?,
+    };
+    let request = Value::parse(&input).map_err(|e| format!("request: {e}"))?;
+    let output = format!("{}\n", acvp::respond(&request).map_err(|e| format!("request: {e}"))?);
+    match response {
+        None | Some("-") => io::stdout()
+            .write_all(output.as_bytes())
+            .map_err(|e| format!("writing the standard output: {e}")),
+        Some(path) => fs::write(path, output).map_err(|e| format!("writing {path}: {e}")),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() > 2 || args.iter().any(|a| a == "-h" || a == "--help") {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    }
+    match run(args.first().map(String::as_str), args.get(1).map(String::as_str)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("acvp-responder: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
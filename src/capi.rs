@@ -0,0 +1,435 @@
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, collections::BTreeSet};
+#[cfg(feature = "alloc")]
+use core::cell::UnsafeCell;
+use core::ffi::{c_char, c_void};
+#[cfg(feature = "alloc")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/////////////////////////////////////////////////////////////////////
+//  status codes returned by the C api
+/////////////////////////////////////////////////////////////////////
+
+/// Success.
+pub const OPENGM_OK: i32 = 0;
+/// A required pointer argument is NULL.
+pub const OPENGM_ERR_NULL: i32 = -1;
+/// The input is not a valid encoding (e.g. an encapsulation key fails the modulus check).
+pub const OPENGM_ERR_DECODE: i32 = -2;
+/// The signature is invalid.
+pub const OPENGM_ERR_VERIFY: i32 = -3;
+/// The handle is not of the expected key type or parameter set, or has been cleared or dropped.
+pub const OPENGM_ERR_HANDLE: i32 = -4;
+/// The caller provided storage is too small or not aligned to OPENGM_STORAGE_ALIGN.
+pub const OPENGM_ERR_STORAGE: i32 = -5;
+/// The algorithm is unknown or not of the expected kind (KEM or signature).
+pub const OPENGM_ERR_ALGORITHM: i32 = -6;
+/// A buffer length does not match the algorithm, or the context string is too long.
+pub const OPENGM_ERR_LENGTH: i32 = -7;
+
+/// The alignment required by the caller provided storage of the *_inplace functions.
+pub const OPENGM_STORAGE_ALIGN: usize = 8;
+
+/// opengm_strerror 返回错误码的描述, 返回的字符串是静态的, 调用者不能释放.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_strerror(code: i32) -> *const c_char {
+    let s = match code {
+        OPENGM_OK => c"success",
+        OPENGM_ERR_NULL => c"null pointer argument",
+        OPENGM_ERR_DECODE => c"invalid encoding",
+        OPENGM_ERR_VERIFY => c"signature verification failed",
+        OPENGM_ERR_HANDLE => c"invalid handle",
+        OPENGM_ERR_STORAGE => c"storage too small or misaligned",
+        OPENGM_ERR_ALGORITHM => c"unsupported algorithm",
+        OPENGM_ERR_LENGTH => c"invalid length",
+        _ => c"unknown error",
+    };
+    s.as_ptr()
+}
+
+pub(crate) type Result<T> = core::result::Result<T, i32>;
+
+// Run f and convert its result to a status code.
+#[inline]
+pub(crate) fn status(f: impl FnOnce() -> Result<()>) -> i32 {
+    match f() {
+        Ok(()) => OPENGM_OK,
+        Err(code) => code,
+    }
+}
+
+// SAFETY: p must be NULL or point to N readable bytes.
+#[inline]
+pub(crate) unsafe fn array_ref<'a, const N: usize>(p: *const u8) -> Result<&'a [u8; N]> {
+    if p.is_null() {
+        return Err(OPENGM_ERR_NULL);
+    }
+    Ok(unsafe { &*(p as *const [u8; N]) })
+}
+
+// SAFETY: p must be NULL or point to N writable bytes.
+#[inline]
+pub(crate) unsafe fn array_mut<'a, const N: usize>(p: *mut u8) -> Result<&'a mut [u8; N]> {
+    if p.is_null() {
+        return Err(OPENGM_ERR_NULL);
+    }
+    Ok(unsafe { &mut *(p as *mut [u8; N]) })
+}
+
+// SAFETY: p must point to len readable bytes, p can be NULL if len = 0.
+#[inline]
+pub(crate) unsafe fn slice_ref<'a>(p: *const u8, len: usize) -> Result<&'a [u8]> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if p.is_null() {
+        return Err(OPENGM_ERR_NULL);
+    }
+    Ok(unsafe { core::slice::from_raw_parts(p, len) })
+}
+
+// SAFETY: p must point to len writable bytes, p can be NULL if len = 0.
+#[inline]
+pub(crate) unsafe fn slice_mut<'a>(p: *mut u8, len: usize) -> Result<&'a mut [u8]> {
+    if len == 0 {
+        return Ok(&mut []);
+    }
+    if p.is_null() {
+        return Err(OPENGM_ERR_NULL);
+    }
+    Ok(unsafe { core::slice::from_raw_parts_mut(p, len) })
+}
+
+// SAFETY: out must be NULL or point to a writable handle.
+#[inline]
+pub(crate) unsafe fn out_handle<'a>(out: *mut *mut c_void) -> Result<&'a mut *mut c_void> {
+    if out.is_null() {
+        return Err(OPENGM_ERR_NULL);
+    }
+    Ok(unsafe { &mut *out })
+}
+
+/////////////////////////////////////////////////////////////////////
+//  handles
+/////////////////////////////////////////////////////////////////////
+
+// A handle points to a Header followed by the value, the header is checked
+// by every export so that passing a wrong handle returns OPENGM_ERR_HANDLE.
+const MAGIC: u32 = 0x4f50_474d; // "OPGM"
+const POISON: u32 = 0xdead_dead;
+
+// Handle kinds, combined with the parameter set to form a tag.
+pub(crate) const MLKEM_DECAPKEY: u32 = 1;
+pub(crate) const MLKEM_ENCAPKEY: u32 = 2;
+pub(crate) const MLDSA_PRIVATE_KEY: u32 = 3;
+pub(crate) const MLDSA_PUBLIC_KEY: u32 = 4;
+pub(crate) const XWING_DECAPKEY: u32 = 5;
+pub(crate) const XWING_ENCAPKEY: u32 = 6;
+
+// tag = kind || parameter set, e.g., tag(MLKEM_DECAPKEY, 768).
+pub(crate) const fn tag(kind: u32, param: u32) -> u32 {
+    (kind << 16) | param
+}
+
+// Types that can be passed to C as handles.
+pub(crate) trait Handle {
+    const TAG: u32;
+}
+
+// A handle is either allocated by into_handle (boxed) and freed by drop_handle,
+// or initialized in the caller's storage by init_handle and cleared by clear_handle.
+const BOXED: u32 = 1;
+
+#[repr(C)]
+struct Header {
+    magic: u32,
+    tag: u32,
+    flags: u32,
+}
+
+#[repr(C)]
+struct Tagged<T> {
+    header: Header,
+    value: T,
+}
+
+impl<T: Handle> Tagged<T> {
+    fn new(value: T, flags: u32) -> Self {
+        Tagged {
+            header: Header {
+                magic: MAGIC,
+                tag: T::TAG,
+                flags,
+            },
+            value,
+        }
+    }
+}
+
+// The addresses of the boxed handles not dropped yet, so that dropping a handle twice, or a
+// pointer that is not a boxed handle, returns OPENGM_ERR_HANDLE before the memory is read.
+// A spin lock, the crate has no std.
+#[cfg(feature = "alloc")]
+struct Registry {
+    locked: AtomicBool,
+    live: UnsafeCell<BTreeSet<usize>>,
+}
+
+// SAFETY: live is only accessed with the lock held.
+#[cfg(feature = "alloc")]
+unsafe impl Sync for Registry {}
+
+#[cfg(feature = "alloc")]
+impl Registry {
+    fn with<R>(&self, f: impl FnOnce(&mut BTreeSet<usize>) -> R) -> R {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        let r = f(unsafe { &mut *self.live.get() });
+        self.locked.store(false, Ordering::Release);
+        r
+    }
+}
+
+#[cfg(feature = "alloc")]
+static LIVE: Registry = Registry {
+    locked: AtomicBool::new(false),
+    live: UnsafeCell::new(BTreeSet::new()),
+};
+
+#[cfg(feature = "alloc")]
+#[inline]
+pub(crate) fn into_handle<T: Handle>(v: T) -> *mut c_void {
+    let h = Box::into_raw(Box::new(Tagged::new(v, BOXED))) as *mut c_void;
+    LIVE.with(|live| live.insert(h as usize));
+    h
+}
+
+// The size of the caller provided storage for a handle of T.
+pub(crate) const fn storage_size<T: Handle>() -> usize {
+    core::mem::size_of::<Tagged<T>>()
+}
+
+// Initialize a handle of f() in the caller provided storage, the storage
+// is left untouched on error.
+// SAFETY: storage must be NULL or point to size writable bytes.
+pub(crate) unsafe fn init_handle<T: Handle>(
+    storage: *mut c_void,
+    size: usize,
+    f: impl FnOnce() -> Result<T>,
+) -> Result<()> {
+    if storage.is_null() {
+        return Err(OPENGM_ERR_NULL);
+    }
+    // all key types are aligned to at most OPENGM_STORAGE_ALIGN, checking both keeps it sound anyway.
+    let addr = storage as usize;
+    if size < storage_size::<T>()
+        || !addr.is_multiple_of(OPENGM_STORAGE_ALIGN)
+        || !addr.is_multiple_of(core::mem::align_of::<Tagged<T>>())
+    {
+        return Err(OPENGM_ERR_STORAGE);
+    }
+    let v = f()?;
+    unsafe { core::ptr::write(storage as *mut Tagged<T>, Tagged::new(v, 0)) };
+    Ok(())
+}
+
+// SAFETY: h must be NULL or point to at least a readable Header.
+#[inline]
+unsafe fn check_handle<'a, T: Handle>(h: *const c_void) -> Result<&'a Header> {
+    if h.is_null() {
+        return Err(OPENGM_ERR_NULL);
+    }
+    let header = unsafe { &*(h as *const Header) };
+    if header.magic != MAGIC || header.tag != T::TAG {
+        return Err(OPENGM_ERR_HANDLE);
+    }
+    Ok(header)
+}
+
+// SAFETY: h must be NULL or a handle returned by into_handle or init_handle.
+#[inline]
+pub(crate) unsafe fn handle_ref<'a, T: Handle>(h: *const c_void) -> Result<&'a T> {
+    unsafe { check_handle::<T>(h) }?;
+    Ok(unsafe { &(*(h as *const Tagged<T>)).value })
+}
+
+// Drop the value of the handle, then zero its storage (the key material is
+// erased) and poison the header.
+// SAFETY: h must be a live handle of T.
+unsafe fn erase_handle<T: Handle>(h: *mut c_void) {
+    let p = h as *mut Tagged<T>;
+    unsafe {
+        core::ptr::drop_in_place(p);
+        // volatile, so the writes to memory about to be released are not optimized out.
+        let bytes = h as *mut u8;
+        for i in 0..storage_size::<T>() {
+            core::ptr::write_volatile(bytes.add(i), 0);
+        }
+        core::ptr::write_volatile(&mut (*p).header.magic, POISON);
+    }
+}
+
+// Free a handle allocated by into_handle, the key material is erased before
+// the allocation is freed, dropping a NULL handle is a no-op, like free(NULL).
+// A handle that is not in the registry, dropped already or initialized by
+// init_handle, returns OPENGM_ERR_HANDLE without being read. A dropped handle
+// must not be used otherwise, its address may be the one of a later handle.
+// SAFETY: h must be NULL, a handle, or a pointer to at least a readable Header.
+#[cfg(feature = "alloc")]
+#[inline]
+pub(crate) unsafe fn drop_handle<T: Handle>(h: *mut c_void) -> Result<()> {
+    if h.is_null() {
+        return Ok(());
+    }
+    // removed under the lock, so two concurrent drops of a handle free it once.
+    LIVE.with(|live| {
+        if !live.contains(&(h as usize)) {
+            return Err(OPENGM_ERR_HANDLE);
+        }
+        unsafe { check_handle::<T>(h) }?;
+        live.remove(&(h as usize));
+        Ok(())
+    })?;
+    unsafe {
+        erase_handle::<T>(h);
+        // the value is already dropped, only the allocation is freed.
+        drop(Box::from_raw(h as *mut core::mem::MaybeUninit<Tagged<T>>));
+    }
+    Ok(())
+}
+
+// Clear a handle initialized by init_handle, the storage is zeroed (the
+// key material is erased) and poisoned, clearing a NULL handle is a no-op.
+// The storage stays the caller's, so clearing it twice returns OPENGM_ERR_HANDLE.
+// SAFETY: h must be NULL or a handle returned by into_handle or init_handle.
+pub(crate) unsafe fn clear_handle<T: Handle>(h: *mut c_void) -> Result<()> {
+    if h.is_null() {
+        return Ok(());
+    }
+    if unsafe { check_handle::<T>(h) }?.flags & BOXED != 0 {
+        return Err(OPENGM_ERR_HANDLE);
+    }
+    unsafe { erase_handle::<T>(h) };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ffi::CStr;
+
+    use super::*;
+
+    #[test]
+    fn test_strerror() {
+        for code in [
+            OPENGM_OK,
+            OPENGM_ERR_NULL,
+            OPENGM_ERR_DECODE,
+            OPENGM_ERR_VERIFY,
+            OPENGM_ERR_HANDLE,
+            OPENGM_ERR_STORAGE,
+            OPENGM_ERR_ALGORITHM,
+            OPENGM_ERR_LENGTH,
+            1,
+        ] {
+            let s = unsafe { CStr::from_ptr(opengm_strerror(code)) };
+            assert!(!s.is_empty());
+        }
+        assert_eq!(unsafe { CStr::from_ptr(opengm_strerror(OPENGM_OK)) }, c"success");
+    }
+
+    struct A(u64);
+    impl Handle for A {
+        const TAG: u32 = tag(1, 1);
+    }
+    struct B(u64);
+    impl Handle for B {
+        const TAG: u32 = tag(1, 2);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_handle() {
+        let h = into_handle(A(7));
+        assert_eq!(unsafe { handle_ref::<A>(h) }.map(|a| a.0), Ok(7));
+        assert!(matches!(unsafe { handle_ref::<B>(h) }, Err(OPENGM_ERR_HANDLE)));
+        assert!(matches!(
+            unsafe { handle_ref::<A>(core::ptr::null()) },
+            Err(OPENGM_ERR_NULL)
+        ));
+        assert_eq!(unsafe { drop_handle::<B>(h) }, Err(OPENGM_ERR_HANDLE));
+        assert_eq!(unsafe { drop_handle::<A>(h) }, Ok(()));
+
+        // a buffer that is not a handle
+        let not_handle = [0u64; 4];
+        let p = not_handle.as_ptr() as *const c_void;
+        assert!(matches!(unsafe { handle_ref::<A>(p) }, Err(OPENGM_ERR_HANDLE)));
+    }
+
+    // the value of a dropped handle is dropped exactly once, before its storage is erased
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_drop_handle() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+        struct C([u8; 32]);
+        impl Handle for C {
+            const TAG: u32 = tag(1, 3);
+        }
+        impl Drop for C {
+            fn drop(&mut self) {
+                assert_eq!(self.0, [0x5a; 32]);
+                DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let h = into_handle(C([0x5a; 32]));
+        assert_eq!(unsafe { drop_handle::<C>(h) }, Ok(()));
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+        // a stale handle is not freed twice
+        assert_eq!(unsafe { drop_handle::<C>(h) }, Err(OPENGM_ERR_HANDLE));
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+        assert_eq!(unsafe { drop_handle::<C>(core::ptr::null_mut()) }, Ok(()));
+    }
+
+    #[test]
+    fn test_inplace_handle() {
+        let mut storage = [0u64; 4];
+        let h = storage.as_mut_ptr() as *mut c_void;
+        let size = size_of_val(&storage);
+        assert_eq!(storage_size::<A>(), 24);
+
+        assert_eq!(unsafe { init_handle(h, storage_size::<A>() - 1, || Ok(A(7))) }, Err(OPENGM_ERR_STORAGE));
+        assert_eq!(
+            unsafe { init_handle(h.wrapping_byte_add(4), size - 4, || Ok(A(7))) },
+            Err(OPENGM_ERR_STORAGE)
+        );
+        assert_eq!(unsafe { init_handle::<A>(h, size, || Err(OPENGM_ERR_DECODE)) }, Err(OPENGM_ERR_DECODE));
+        assert!(matches!(unsafe { handle_ref::<A>(h) }, Err(OPENGM_ERR_HANDLE)));
+
+        assert_eq!(unsafe { init_handle(h, size, || Ok(A(7))) }, Ok(()));
+        assert_eq!(unsafe { handle_ref::<A>(h) }.map(|a| a.0), Ok(7));
+        assert!(matches!(unsafe { handle_ref::<B>(h) }, Err(OPENGM_ERR_HANDLE)));
+        #[cfg(feature = "alloc")]
+        assert_eq!(unsafe { drop_handle::<A>(h) }, Err(OPENGM_ERR_HANDLE));
+
+        assert_eq!(unsafe { clear_handle::<B>(h) }, Err(OPENGM_ERR_HANDLE));
+        assert_eq!(unsafe { clear_handle::<A>(h) }, Ok(()));
+        assert!(matches!(unsafe { handle_ref::<A>(h) }, Err(OPENGM_ERR_HANDLE)));
+        assert_eq!(unsafe { clear_handle::<A>(h) }, Err(OPENGM_ERR_HANDLE));
+        assert_eq!(storage[1..], [0; 3]);
+
+        #[cfg(feature = "alloc")]
+        {
+            let h = into_handle(A(7));
+            assert_eq!(unsafe { clear_handle::<A>(h) }, Err(OPENGM_ERR_HANDLE));
+            assert_eq!(unsafe { drop_handle::<A>(h) }, Ok(()));
+        }
+    }
+}
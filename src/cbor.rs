@@ -0,0 +1,378 @@
+//! A minimal CBOR (RFC 8949) encoder and decoder for COSE, without allocation.
+//!
+//! [`Encoder`] writes the preferred (shortest) heads into a caller buffer, maps and arrays are
+//! written as a head followed by their items. [`Decoder`] reads items in place, indefinite
+//! lengths are not supported.
+
+use thiserror;
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("malformed CBOR")]
+    Malformed,
+
+    #[error("unexpected CBOR type")]
+    UnexpectedType,
+
+    #[error("unsupported CBOR encoding")]
+    Unsupported,
+
+    #[error("CBOR nesting too deep")]
+    Depth,
+
+    #[error("output buffer too small")]
+    BufferTooSmall,
+}
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// The major types.
+pub(crate) const UNSIGNED: u8 = 0;
+pub(crate) const NEGATIVE: u8 = 1;
+pub(crate) const BYTES: u8 = 2;
+pub(crate) const TEXT: u8 = 3;
+pub(crate) const ARRAY: u8 = 4;
+pub(crate) const MAP: u8 = 5;
+pub(crate) const TAG: u8 = 6;
+pub(crate) const SIMPLE: u8 = 7;
+
+const FALSE: u8 = 20;
+const TRUE: u8 = 21;
+const NULL: u8 = 22;
+
+const MAX_DEPTH: usize = 64;
+
+pub struct Encoder<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Encoder<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Encoder { buf, len: 0 }
+    }
+
+    /// The length written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Append encoded CBOR.
+    pub fn raw(&mut self, b: &[u8]) -> Result<()> {
+        let end = self.len + b.len();
+        self.buf.get_mut(self.len..end).ok_or(Error::BufferTooSmall)?.copy_from_slice(b);
+        self.len = end;
+        Ok(())
+    }
+
+    /// The head of the major type with the argument in the shortest form.
+    pub(crate) fn head(&mut self, major: u8, arg: u64) -> Result<()> {
+        let m = major << 5;
+        match arg {
+            0..24 => self.raw(&[m | arg as u8]),
+            24..0x100 => self.raw(&[m | 24, arg as u8]),
+            0x100..0x10000 => {
+                self.raw(&[m | 25])?;
+                self.raw(&(arg as u16).to_be_bytes())
+            }
+            0x10000..0x1_0000_0000 => {
+                self.raw(&[m | 26])?;
+                self.raw(&(arg as u32).to_be_bytes())
+            }
+            _ => {
+                self.raw(&[m | 27])?;
+                self.raw(&arg.to_be_bytes())
+            }
+        }
+    }
+
+    pub fn uint(&mut self, n: u64) -> Result<()> {
+        self.head(UNSIGNED, n)
+    }
+
+    pub fn int(&mut self, n: i64) -> Result<()> {
+        if n < 0 { self.head(NEGATIVE, !n as u64) } else { self.head(UNSIGNED, n as u64) }
+    }
+
+    pub fn bytes(&mut self, b: &[u8]) -> Result<()> {
+        self.head(BYTES, b.len() as u64)?;
+        self.raw(b)
+    }
+
+    pub fn text(&mut self, s: &str) -> Result<()> {
+        self.head(TEXT, s.len() as u64)?;
+        self.raw(s.as_bytes())
+    }
+
+    /// The head of an array of n items.
+    pub fn array(&mut self, n: usize) -> Result<()> {
+        self.head(ARRAY, n as u64)
+    }
+
+    /// The head of a map of n pairs.
+    pub fn map(&mut self, n: usize) -> Result<()> {
+        self.head(MAP, n as u64)
+    }
+
+    pub fn tag(&mut self, tag: u64) -> Result<()> {
+        self.head(TAG, tag)
+    }
+
+    pub fn bool(&mut self, b: bool) -> Result<()> {
+        self.head(SIMPLE, if b { TRUE } else { FALSE } as u64)
+    }
+
+    pub fn null(&mut self) -> Result<()> {
+        self.head(SIMPLE, NULL as u64)
+    }
+
+    /// The encoded length.
+    pub fn finish(self) -> usize {
+        self.len
+    }
+}
+
+#[derive(Clone)]
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Decoder { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.data.len()
+    }
+
+    /// Check that all the data is read.
+    pub fn finish(&self) -> Result<()> {
+        if !self.is_empty() {
+            return Err(Error::Malformed);
+        }
+        Ok(())
+    }
+
+    /// The major type of the next item.
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).map(|b| b >> 5)
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let b = self.data.get(self.pos..self.pos.checked_add(n).ok_or(Error::Malformed)?).ok_or(Error::Malformed)?;
+        self.pos += n;
+        Ok(b)
+    }
+
+    /// Read a head, returns the major type and the argument.
+    pub(crate) fn head(&mut self) -> Result<(u8, u64)> {
+        let b = self.take(1)?[0];
+        let arg = match b & 0x1f {
+            ai @ 0..24 => ai as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            31 => return Err(Error::Unsupported),
+            _ => return Err(Error::Malformed),
+        };
+        Ok((b >> 5, arg))
+    }
+
+    fn expect(&mut self, major: u8) -> Result<u64> {
+        if self.peek() != Some(major) {
+            return Err(if self.is_empty() { Error::Malformed } else { Error::UnexpectedType });
+        }
+        Ok(self.head()?.1)
+    }
+
+    pub fn uint(&mut self) -> Result<u64> {
+        self.expect(UNSIGNED)
+    }
+
+    /// An unsigned or negative integer in the range of i64.
+    pub fn int(&mut self) -> Result<i64> {
+        let major = self.peek();
+        let n = if major == Some(NEGATIVE) { self.expect(NEGATIVE)? } else { self.expect(UNSIGNED)? };
+        let n = i64::try_from(n).map_err(|_| Error::Unsupported)?;
+        Ok(if major == Some(NEGATIVE) { !n } else { n })
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8]> {
+        let n = self.expect(BYTES)?;
+        self.take(usize::try_from(n).map_err(|_| Error::Malformed)?)
+    }
+
+    pub fn text(&mut self) -> Result<&'a str> {
+        let n = self.expect(TEXT)?;
+        let b = self.take(usize::try_from(n).map_err(|_| Error::Malformed)?)?;
+        core::str::from_utf8(b).map_err(|_| Error::Malformed)
+    }
+
+    /// The number of items of an array.
+    pub fn array(&mut self) -> Result<u64> {
+        self.expect(ARRAY)
+    }
+
+    /// The number of pairs of a map.
+    pub fn map(&mut self) -> Result<u64> {
+        self.expect(MAP)
+    }
+
+    pub fn tag(&mut self) -> Result<u64> {
+        self.expect(TAG)
+    }
+
+    /// Read the tag if the next item is tagged with it.
+    pub fn optional_tag(&mut self, tag: u64) -> Result<bool> {
+        if self.peek() == Some(TAG) && self.clone().tag()? == tag {
+            self.tag()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    pub fn bool(&mut self) -> Result<bool> {
+        match self.data.get(self.pos) {
+            Some(0xf4) => self.head().map(|_| false),
+            Some(0xf5) => self.head().map(|_| true),
+            _ => Err(Error::UnexpectedType),
+        }
+    }
+
+    /// Read a null, returns false and reads nothing if the next item is not null.
+    pub fn null(&mut self) -> Result<bool> {
+        if self.data.get(self.pos) == Some(&0xf6) {
+            self.pos += 1;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Skip the next item, returns its encoding.
+    pub fn skip(&mut self) -> Result<&'a [u8]> {
+        let start = self.pos;
+        self.skip_(0)?;
+        Ok(&self.data[start..self.pos])
+    }
+
+    fn skip_(&mut self, depth: usize) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(Error::Depth);
+        }
+        let (major, arg) = self.head()?;
+        match major {
+            BYTES | TEXT => {
+                self.take(usize::try_from(arg).map_err(|_| Error::Malformed)?)?;
+            }
+            ARRAY | MAP => {
+                let items = if major == MAP { arg.checked_mul(2).ok_or(Error::Malformed)? } else { arg };
+                for _ in 0..items {
+                    self.skip_(depth + 1)?;
+                }
+            }
+            TAG => self.skip_(depth + 1)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_encode() {
+        // RFC 8949 appendix A
+        let mut buf = [0; 32];
+        let mut check = |f: &dyn Fn(&mut Encoder) -> Result<()>, expected: &[u8]| {
+            let mut e = Encoder::new(&mut buf);
+            f(&mut e).unwrap();
+            let n = e.finish();
+            assert_eq!(&buf[..n], expected);
+        };
+        check(&|e| e.uint(0), &hex!("00"));
+        check(&|e| e.uint(23), &hex!("17"));
+        check(&|e| e.uint(24), &hex!("1818"));
+        check(&|e| e.uint(1000), &hex!("1903e8"));
+        check(&|e| e.uint(1000000), &hex!("1a000f4240"));
+        check(&|e| e.uint(1000000000000), &hex!("1b000000e8d4a51000"));
+        check(&|e| e.uint(u64::MAX), &hex!("1bffffffffffffffff"));
+        check(&|e| e.int(-1), &hex!("20"));
+        check(&|e| e.int(-100), &hex!("3863"));
+        check(&|e| e.int(-1000), &hex!("3903e7"));
+        check(&|e| e.int(i64::MIN), &hex!("3b7fffffffffffffff"));
+        check(&|e| e.bytes(&hex!("01020304")), &hex!("4401020304"));
+        check(&|e| e.text("ü"), &hex!("62c3bc"));
+        check(&|e| e.text("IETF"), &hex!("6449455446"));
+        check(&|e| e.tag(1).and_then(|_| e.uint(1363896240)), &hex!("c11a514b67b0"));
+        check(&|e| e.bool(false).and_then(|_| e.bool(true)).and_then(|_| e.null()), &hex!("f4f5f6"));
+        check(
+            &|e| {
+                e.array(3)?;
+                e.uint(1)?;
+                e.array(2)?;
+                e.uint(2)?;
+                e.uint(3)?;
+                e.array(2)?;
+                e.uint(4)?;
+                e.uint(5)
+            },
+            &hex!("8301820203820405"),
+        );
+        check(&|e| e.map(2).and_then(|_| e.raw(&hex!("01020304"))), &hex!("a201020304"));
+
+        let mut small = [0; 4];
+        let mut e = Encoder::new(&mut small);
+        assert_eq!(e.bytes(&hex!("01020304")), Err(Error::BufferTooSmall));
+    }
+
+    #[test]
+    fn test_decode() {
+        let data = hex!("8301820203a2616101616282f5f6c11a514b67b0");
+        let mut d = Decoder::new(&data);
+        assert_eq!(d.array(), Ok(3));
+        assert_eq!(d.uint(), Ok(1));
+        assert_eq!(d.skip(), Ok(&hex!("820203")[..]));
+        assert_eq!(d.map(), Ok(2));
+        assert_eq!(d.text(), Ok("a"));
+        assert_eq!(d.int(), Ok(1));
+        assert_eq!(d.text(), Ok("b"));
+        assert_eq!(d.array(), Ok(2));
+        assert_eq!(d.bool(), Ok(true));
+        assert_eq!(d.null(), Ok(true));
+        assert_eq!(d.optional_tag(2), Ok(false));
+        assert_eq!(d.optional_tag(1), Ok(true));
+        assert_eq!(d.uint(), Ok(1363896240));
+        assert_eq!(d.finish(), Ok(()));
+
+        assert_eq!(Decoder::new(&hex!("3903e7")).int(), Ok(-1000));
+        assert_eq!(Decoder::new(&hex!("3b7fffffffffffffff")).int(), Ok(i64::MIN));
+        assert_eq!(Decoder::new(&hex!("3b8000000000000000")).int(), Err(Error::Unsupported));
+        assert_eq!(Decoder::new(&hex!("1b000000e8d4a51000")).uint(), Ok(1000000000000));
+        assert_eq!(Decoder::new(&hex!("4401020304")).bytes(), Ok(&hex!("01020304")[..]));
+        assert_eq!(Decoder::new(&hex!("62c3bc")).text(), Ok("ü"));
+        // a float is skipped
+        assert_eq!(Decoder::new(&hex!("fb3ff199999999999a")).skip(), Ok(&hex!("fb3ff199999999999a")[..]));
+
+        assert_eq!(Decoder::new(&hex!("4401020304")).text(), Err(Error::UnexpectedType));
+        assert_eq!(Decoder::new(&hex!("44010203")).bytes(), Err(Error::Malformed));
+        assert_eq!(Decoder::new(&hex!("62c3")).text(), Err(Error::Malformed));
+        assert_eq!(Decoder::new(&hex!("62fffe")).text(), Err(Error::Malformed));
+        assert_eq!(Decoder::new(&hex!("1c")).uint(), Err(Error::Malformed));
+        assert_eq!(Decoder::new(&hex!("5f42010243030405ff")).bytes(), Err(Error::Unsupported));
+        assert_eq!(Decoder::new(&hex!("9f01ff")).skip(), Err(Error::Unsupported));
+        assert_eq!(Decoder::new(&hex!("5bffffffffffffffff")).skip(), Err(Error::Malformed));
+        assert_eq!(Decoder::new(&hex!("bbffffffffffffffff")).skip(), Err(Error::Malformed));
+        assert_eq!(Decoder::new(&[]).uint(), Err(Error::Malformed));
+        assert_eq!(Decoder::new(&[0x81; 100]).skip(), Err(Error::Depth));
+        assert_eq!(Decoder::new(&hex!("0000")).finish(), Err(Error::Malformed));
+    }
+}
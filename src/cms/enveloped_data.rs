@@ -0,0 +1,498 @@
+use alloc::vec::Vec;
+
+use rand::{CryptoRng, Rng};
+
+use crate::aes::{Aes, cbc, keywrap};
+use crate::alg::{Algorithm, Decapsulator, Encapsulator};
+use crate::hkdf;
+use crate::pkcs::{self, der, der::Reader, der::Writer};
+use crate::sha3;
+
+use super::errors::{Error, Result};
+use super::{SignerIdentifier, content_info_der, decode_content_info, oid};
+
+/// The rid of a KEMRecipientInfo, the same CHOICE as the sid of a SignerInfo.
+pub type RecipientIdentifier<'a> = SignerIdentifier<'a>;
+
+/// The key derivation of the key-encryption key from the KEM shared secret.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum KdfAlgorithm {
+    /// KMAC256 (SP 800-185) with the shared secret as the key and an empty customization string.
+    #[default]
+    Kmac256,
+    /// HKDF-SHA256 (RFC 8619) with the shared secret as the IKM and an empty salt.
+    HkdfSha256,
+}
+
+impl KdfAlgorithm {
+    pub const ALL: [KdfAlgorithm; 2] = [Self::Kmac256, Self::HkdfSha256];
+
+    pub fn oid(self) -> &'static [u8] {
+        match self {
+            Self::Kmac256 => oid::KMAC256,
+            Self::HkdfSha256 => oid::HKDF_SHA256,
+        }
+    }
+
+    pub fn from_oid(oid: &[u8]) -> Result<Self> {
+        Self::ALL.into_iter().find(|k| k.oid() == oid).ok_or(Error::Pkcs(pkcs::Error::UnsupportedAlgorithm))
+    }
+
+    /// Derive out.len() bytes from the shared secret and the DER CMSORIforKEMOtherInfo.
+    pub fn derive(self, shared_secret: &[u8], info: &[u8], out: &mut [u8]) -> Result<()> {
+        match self {
+            Self::Kmac256 => sha3::kmac256(shared_secret, info, b"", out),
+            Self::HkdfSha256 => hkdf::expand_sha256(&hkdf::extract_sha256(b"", shared_secret), info, out)?,
+        }
+        Ok(())
+    }
+}
+
+/// The AES key wrap of the content-encryption key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum KeyWrapAlgorithm {
+    Aes128,
+    Aes192,
+    #[default]
+    Aes256,
+}
+
+impl KeyWrapAlgorithm {
+    pub const ALL: [KeyWrapAlgorithm; 3] = [Self::Aes128, Self::Aes192, Self::Aes256];
+
+    pub fn oid(self) -> &'static [u8] {
+        match self {
+            Self::Aes128 => oid::AES128_WRAP,
+            Self::Aes192 => oid::AES192_WRAP,
+            Self::Aes256 => oid::AES256_WRAP,
+        }
+    }
+
+    pub fn from_oid(oid: &[u8]) -> Result<Self> {
+        Self::ALL.into_iter().find(|w| w.oid() == oid).ok_or(Error::Pkcs(pkcs::Error::UnsupportedAlgorithm))
+    }
+
+    /// The kekLength.
+    pub fn key_len(self) -> usize {
+        match self {
+            Self::Aes128 => 16,
+            Self::Aes192 => 24,
+            Self::Aes256 => 32,
+        }
+    }
+}
+
+/// The content encryption, AES-CBC with the IV as the parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ContentEncryptionAlgorithm {
+    Aes128Cbc,
+    Aes192Cbc,
+    #[default]
+    Aes256Cbc,
+}
+
+impl ContentEncryptionAlgorithm {
+    pub const ALL: [ContentEncryptionAlgorithm; 3] = [Self::Aes128Cbc, Self::Aes192Cbc, Self::Aes256Cbc];
+
+    pub fn oid(self) -> &'static [u8] {
+        match self {
+            Self::Aes128Cbc => oid::AES128_CBC,
+            Self::Aes192Cbc => oid::AES192_CBC,
+            Self::Aes256Cbc => oid::AES256_CBC,
+        }
+    }
+
+    pub fn from_oid(oid: &[u8]) -> Result<Self> {
+        Self::ALL.into_iter().find(|c| c.oid() == oid).ok_or(Error::Pkcs(pkcs::Error::UnsupportedAlgorithm))
+    }
+
+    pub fn key_len(self) -> usize {
+        match self {
+            Self::Aes128Cbc => 16,
+            Self::Aes192Cbc => 24,
+            Self::Aes256Cbc => 32,
+        }
+    }
+}
+
+// An AlgorithmIdentifier without parameters.
+fn read_oid_algorithm<'a>(r: &mut Reader<'a>) -> Result<&'a [u8]> {
+    let mut seq = r.read_sequence()?;
+    let id = seq.read_oid()?;
+    seq.finish()?;
+    Ok(id)
+}
+
+fn write_oid_algorithm(w: &mut Writer, id: &[u8]) {
+    w.write_sequence(|w| {
+        w.write_oid(id);
+    });
+}
+
+// The DER CMSORIforKEMOtherInfo, the info of the KDF.
+fn other_info_der(wrap: KeyWrapAlgorithm, ukm: Option<&[u8]>) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.write_sequence(|w| {
+        write_oid_algorithm(w, wrap.oid());
+        w.write_u64(wrap.key_len() as u64);
+        if let Some(ukm) = ukm {
+            w.write_constructed(der::context_constructed(0), |w| {
+                w.write_octet_string(ukm);
+            });
+        }
+    });
+    w.finish()
+}
+
+/// A parsed KEMRecipientInfo (RFC 9629), the fields are borrowed from the DER.
+#[derive(Clone, Debug)]
+pub struct KemRecipientInfo<'a> {
+    pub rid: RecipientIdentifier<'a>,
+    pub kem: Algorithm,
+    /// The KEM ciphertext.
+    pub kem_ct: &'a [u8],
+    pub kdf: KdfAlgorithm,
+    /// The user keying material.
+    pub ukm: Option<&'a [u8]>,
+    pub wrap: KeyWrapAlgorithm,
+    /// The wrapped content-encryption key.
+    pub encrypted_key: &'a [u8],
+}
+
+impl<'a> KemRecipientInfo<'a> {
+    // the oriValue of an OtherRecipientInfo with id-ori-kem
+    fn read(r: &mut Reader<'a>) -> Result<Self> {
+        let mut seq = r.read_sequence()?;
+        if seq.read_u64()? != 0 {
+            return Err(Error::Malformed);
+        }
+        let rid = RecipientIdentifier::read(&mut seq)?;
+        let kem = pkcs::read_algorithm_identifier(&mut seq)?;
+        if !kem.is_kem() {
+            return Err(Error::Pkcs(pkcs::Error::UnsupportedAlgorithm));
+        }
+        let kem_ct = seq.read_octet_string()?;
+        let kdf = KdfAlgorithm::from_oid(read_oid_algorithm(&mut seq)?)?;
+        let kek_length = seq.read_u64()?;
+        let ukm = match seq.read_optional(der::context_constructed(0))? {
+            Some(explicit) => {
+                let mut r = Reader::new(explicit);
+                let ukm = r.read_octet_string()?;
+                r.finish()?;
+                Some(ukm)
+            }
+            None => None,
+        };
+        let wrap = KeyWrapAlgorithm::from_oid(read_oid_algorithm(&mut seq)?)?;
+        let encrypted_key = seq.read_octet_string()?;
+        seq.finish()?;
+        if kek_length != wrap.key_len() as u64 {
+            return Err(Error::Malformed);
+        }
+        Ok(KemRecipientInfo { rid, kem, kem_ct, kdf, ukm, wrap, encrypted_key })
+    }
+
+    /// Decapsulate with the private key and unwrap the content-encryption key.
+    pub fn decrypt_key(&self, key: &dyn Decapsulator) -> Result<Vec<u8>> {
+        if key.algorithm() != self.kem {
+            return Err(Error::NoRecipient);
+        }
+        let ss = key.decaps(self.kem_ct)?;
+        let mut kek = [0u8; 32];
+        let kek = &mut kek[..self.wrap.key_len()];
+        self.kdf.derive(&ss, &other_info_der(self.wrap, self.ukm), kek)?;
+        let mut cek = vec![0; self.encrypted_key.len().saturating_sub(8)];
+        keywrap::unwrap(&Aes::new(kek)?, self.encrypted_key, &mut cek)?;
+        Ok(cek)
+    }
+}
+
+/// A parsed EnvelopedData in a ContentInfo, only the KEM recipients are kept.
+#[derive(Clone, Debug)]
+pub struct EnvelopedData<'a> {
+    pub content_type: &'a [u8],
+    pub content_encryption: ContentEncryptionAlgorithm,
+    pub iv: &'a [u8; 16],
+    /// None for detached encrypted content.
+    pub encrypted_content: Option<&'a [u8]>,
+    pub recipient_infos: Vec<KemRecipientInfo<'a>>,
+}
+
+impl<'a> EnvelopedData<'a> {
+    /// Parse a DER ContentInfo with an EnvelopedData.
+    pub fn from_der(der: &'a [u8]) -> Result<Self> {
+        let (content_type, content) = decode_content_info(der)?;
+        if content_type != oid::ENVELOPED_DATA {
+            return Err(Error::ContentType);
+        }
+        let mut r = Reader::new(content);
+        let mut seq = r.read_sequence()?;
+        r.finish()?;
+        if seq.read_u64()? > 4 {
+            return Err(Error::Malformed);
+        }
+        seq.read_optional(der::context_constructed(0))?;
+
+        let mut recipient_infos = Vec::new();
+        let mut set = seq.read_constructed(der::SET)?;
+        while !set.is_empty() {
+            let (tag, ori, _) = set.read_any()?;
+            if tag != der::context_constructed(4) {
+                continue;
+            }
+            let mut ori = Reader::new(ori);
+            if ori.read_oid()? == oid::ORI_KEM {
+                recipient_infos.push(KemRecipientInfo::read(&mut ori)?);
+                ori.finish()?;
+            }
+        }
+
+        let mut eci = seq.read_sequence()?;
+        let content_type = eci.read_oid()?;
+        let mut alg = eci.read_sequence()?;
+        let content_encryption = ContentEncryptionAlgorithm::from_oid(alg.read_oid()?)?;
+        let iv = alg.read_octet_string()?.try_into().map_err(|_| Error::Malformed)?;
+        alg.finish()?;
+        let encrypted_content = eci.read_optional(der::context(0))?;
+        eci.finish()?;
+        seq.read_optional(der::context_constructed(1))?;
+        seq.finish()?;
+        Ok(EnvelopedData { content_type, content_encryption, iv, encrypted_content, recipient_infos })
+    }
+
+    /// Decrypt the content with the private key, by the first recipient of the key algorithm which unwraps
+    /// a key of the content encryption length.
+    pub fn decrypt(&self, key: &dyn Decapsulator) -> Result<Vec<u8>> {
+        let encrypted_content = self.encrypted_content.ok_or(Error::NoContent)?;
+        let mut result = Err(Error::NoRecipient);
+        for info in self.recipient_infos.iter().filter(|info| info.kem == key.algorithm()) {
+            match info.decrypt_key(key) {
+                Ok(cek) if cek.len() == self.content_encryption.key_len() => {
+                    return Ok(cbc::decrypt(&Aes::new(&cek)?, self.iv, encrypted_content)?);
+                }
+                Ok(_) => result = Err(Error::Malformed),
+                Err(e) => result = Err(e),
+            }
+        }
+        result
+    }
+}
+
+/// Builds an EnvelopedData in a ContentInfo with a KEMRecipientInfo for every recipient.
+#[derive(Clone)]
+pub struct EnvelopedDataBuilder<'a> {
+    content_type: &'a [u8],
+    content: &'a [u8],
+    content_encryption: ContentEncryptionAlgorithm,
+    kdf: KdfAlgorithm,
+    wrap: KeyWrapAlgorithm,
+    ukm: Option<&'a [u8]>,
+    recipients: Vec<(&'a dyn Encapsulator, RecipientIdentifier<'a>)>,
+}
+
+impl<'a> EnvelopedDataBuilder<'a> {
+    /// Encrypt id-data content with AES-256-CBC, wrapping the key with AES-256 under a KMAC256 derived key.
+    pub fn new(content: &'a [u8]) -> Self {
+        EnvelopedDataBuilder {
+            content_type: oid::DATA,
+            content,
+            content_encryption: ContentEncryptionAlgorithm::default(),
+            kdf: KdfAlgorithm::default(),
+            wrap: KeyWrapAlgorithm::default(),
+            ukm: None,
+            recipients: Vec::new(),
+        }
+    }
+
+    pub fn content_type(mut self, oid: &'a [u8]) -> Self {
+        self.content_type = oid;
+        self
+    }
+
+    pub fn content_encryption(mut self, content_encryption: ContentEncryptionAlgorithm) -> Self {
+        self.content_encryption = content_encryption;
+        self
+    }
+
+    pub fn kdf(mut self, kdf: KdfAlgorithm) -> Self {
+        self.kdf = kdf;
+        self
+    }
+
+    pub fn wrap(mut self, wrap: KeyWrapAlgorithm) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// The user keying material, an input of the KDF of every recipient.
+    pub fn ukm(mut self, ukm: &'a [u8]) -> Self {
+        self.ukm = Some(ukm);
+        self
+    }
+
+    pub fn recipient(mut self, key: &'a dyn Encapsulator, rid: RecipientIdentifier<'a>) -> Self {
+        self.recipients.push((key, rid));
+        self
+    }
+
+    // The RecipientInfo, an ori [4] with an OtherRecipientInfo of id-ori-kem.
+    fn recipient_info(&self, key: &dyn Encapsulator, rid: &RecipientIdentifier, cek: &[u8], rng: &mut dyn CryptoRng) -> Result<Vec<u8>> {
+        let alg = key.algorithm();
+        let mut kem_ct = vec![0; alg.ciphertext_len()];
+        let ss = key.encaps(rng, &mut kem_ct)?;
+        let mut kek = [0u8; 32];
+        let kek = &mut kek[..self.wrap.key_len()];
+        self.kdf.derive(&ss, &other_info_der(self.wrap, self.ukm), kek)?;
+        let mut encrypted_key = vec![0; cek.len() + 8];
+        keywrap::wrap(&Aes::new(kek)?, cek, &mut encrypted_key)?;
+
+        let mut w = Writer::new();
+        w.write_constructed(der::context_constructed(4), |w| {
+            w.write_oid(oid::ORI_KEM);
+            w.write_sequence(|w| {
+                w.write_u64(0);
+                rid.write(w);
+                pkcs::write_algorithm_identifier(w, pkcs::algorithm_oid(alg));
+                w.write_octet_string(&kem_ct);
+                write_oid_algorithm(w, self.kdf.oid());
+                w.write_u64(self.wrap.key_len() as u64);
+                if let Some(ukm) = self.ukm {
+                    w.write_constructed(der::context_constructed(0), |w| {
+                        w.write_octet_string(ukm);
+                    });
+                }
+                write_oid_algorithm(w, self.wrap.oid());
+                w.write_octet_string(&encrypted_key);
+            });
+        });
+        Ok(w.finish())
+    }
+
+    /// Encrypt the content to every recipient, returns the DER ContentInfo.
+    pub fn encrypt(&self, rng: &mut dyn CryptoRng) -> Result<Vec<u8>> {
+        let mut cek = [0u8; 32];
+        let cek = &mut cek[..self.content_encryption.key_len()];
+        rng.fill(&mut cek[..]);
+        let iv: [u8; 16] = rng.random();
+        let encrypted_content = cbc::encrypt(&Aes::new(cek)?, &iv, self.content);
+        let mut recipient_infos = Vec::with_capacity(self.recipients.len());
+        for (key, rid) in &self.recipients {
+            recipient_infos.push(self.recipient_info(*key, rid, cek, rng)?);
+        }
+
+        let mut w = Writer::new();
+        w.write_sequence(|w| {
+            // version 3 with an ori recipient
+            w.write_u64(3);
+            w.write_set_of(der::SET, &mut recipient_infos);
+            w.write_sequence(|w| {
+                w.write_oid(self.content_type);
+                w.write_sequence(|w| {
+                    w.write_oid(self.content_encryption.oid()).write_octet_string(&iv);
+                });
+                w.write(der::context(0), &encrypted_content);
+            });
+        });
+        Ok(content_info_der(oid::ENVELOPED_DATA, &w.finish()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes;
+    use crate::mlkem::{mlkem512, mlkem768, mlkem1024};
+    use hex_literal::hex;
+
+    #[test]
+    fn test_enveloped_data() {
+        let mut rng = rand::rng();
+        let dk512 = mlkem512::DecapKey::from_seed(&[1; 64]);
+        let dk768 = mlkem768::DecapKey::from_seed(&[2; 64]);
+        let dk1024 = mlkem1024::DecapKey::from_seed(&[3; 64]);
+        let (ek512, ek768, ek1024) = (dk512.encapsulation_key(), dk768.encapsulation_key(), dk1024.encapsulation_key());
+        let content: Vec<u8> = (0..100).collect();
+
+        let algs = ContentEncryptionAlgorithm::ALL.into_iter().zip(KeyWrapAlgorithm::ALL);
+        for ((content_encryption, wrap), kdf) in algs.zip(KdfAlgorithm::ALL.into_iter().cycle()) {
+            let der = EnvelopedDataBuilder::new(&content)
+                .content_encryption(content_encryption)
+                .kdf(kdf)
+                .wrap(wrap)
+                .recipient(&ek512, RecipientIdentifier::SubjectKeyIdentifier(&[1; 20]))
+                .recipient(&ek768, RecipientIdentifier::SubjectKeyIdentifier(&[2; 20]))
+                .recipient(&ek1024, RecipientIdentifier::SubjectKeyIdentifier(&[3; 20]))
+                .encrypt(&mut rng)
+                .unwrap();
+            let ed = EnvelopedData::from_der(&der).unwrap();
+            assert_eq!(ed.content_type, oid::DATA);
+            assert_eq!(ed.content_encryption, content_encryption);
+            assert_eq!(ed.recipient_infos.len(), 3);
+            for info in &ed.recipient_infos {
+                assert_eq!(info.wrap, wrap);
+                assert_eq!(info.kdf, kdf);
+                assert_eq!(info.kem_ct.len(), info.kem.ciphertext_len());
+                assert_eq!(info.ukm, None);
+            }
+            assert_eq!(ed.decrypt(&dk512), Ok(content.clone()));
+            assert_eq!(ed.decrypt(&dk768), Ok(content.clone()));
+            assert_eq!(ed.decrypt(&dk1024), Ok(content.clone()));
+        }
+    }
+
+    #[test]
+    fn test_kdf() {
+        // RFC 5869 A.3, an empty salt and info
+        let mut okm = [0; 42];
+        KdfAlgorithm::HkdfSha256.derive(&[0x0b; 22], b"", &mut okm).unwrap();
+        assert_eq!(okm, hex!("8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"));
+        assert_eq!(KdfAlgorithm::from_oid(oid::HKDF_SHA256), Ok(KdfAlgorithm::HkdfSha256));
+        assert_eq!(KdfAlgorithm::from_oid(oid::SHA3_256), Err(Error::Pkcs(pkcs::Error::UnsupportedAlgorithm)));
+    }
+
+    #[test]
+    fn test_recipients() {
+        let mut rng = rand::rng();
+        let dk = mlkem768::DecapKey::from_seed(&[1; 64]);
+        let ek = dk.encapsulation_key();
+        let der = EnvelopedDataBuilder::new(b"secret")
+            .content_type(oid::SIGNED_DATA)
+            .ukm(b"user keying material")
+            .recipient(&ek, RecipientIdentifier::IssuerAndSerialNumber { issuer: &[0x30, 0], serial: &[0x80] })
+            .encrypt(&mut rng)
+            .unwrap();
+        let ed = EnvelopedData::from_der(&der).unwrap();
+        assert_eq!(ed.content_type, oid::SIGNED_DATA);
+        let info = &ed.recipient_infos[0];
+        assert_eq!(info.rid, RecipientIdentifier::IssuerAndSerialNumber { issuer: &[0x30, 0], serial: &[0x80] });
+        assert_eq!(info.ukm, Some(&b"user keying material"[..]));
+        assert_eq!(ed.decrypt(&dk), Ok(b"secret".to_vec()));
+        assert_eq!(info.decrypt_key(&dk).map(|cek| cek.len()), Ok(32));
+
+        // another key of the same parameter set fails the key unwrap
+        let other = mlkem768::DecapKey::from_seed(&[2; 64]);
+        assert_eq!(ed.decrypt(&other), Err(Error::Aes(aes::Error::Unwrap)));
+        assert_eq!(ed.decrypt(&mlkem512::DecapKey::from_seed(&[1; 64])), Err(Error::NoRecipient));
+
+        // a recipient with a key of another length is skipped
+        let der128 = EnvelopedDataBuilder::new(b"secret")
+            .content_encryption(ContentEncryptionAlgorithm::Aes128Cbc)
+            .recipient(&ek, RecipientIdentifier::SubjectKeyIdentifier(&[1; 20]))
+            .encrypt(&mut rng)
+            .unwrap();
+        let ed128 = EnvelopedData::from_der(&der128).unwrap();
+        let mut mixed = ed.clone();
+        mixed.recipient_infos.insert(0, ed128.recipient_infos[0].clone());
+        assert_eq!(mixed.decrypt(&dk), Ok(b"secret".to_vec()));
+        mixed.recipient_infos.truncate(1);
+        assert_eq!(mixed.decrypt(&dk), Err(Error::Malformed));
+
+        // a tampered ciphertext fails the padding check or decrypts to garbage
+        let mut tampered = der.clone();
+        let n = tampered.len();
+        tampered[n - 16] ^= 1;
+        let ed = EnvelopedData::from_der(&tampered).unwrap();
+        assert_ne!(ed.decrypt(&dk), Ok(b"secret".to_vec()));
+        assert!(EnvelopedData::from_der(&der[..n - 1]).is_err());
+    }
+}
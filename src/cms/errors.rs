@@ -0,0 +1,46 @@
+use thiserror;
+
+use crate::{aes, alg, hkdf, pkcs, x509};
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error(transparent)]
+    Pkcs(#[from] pkcs::Error),
+
+    #[error(transparent)]
+    Alg(#[from] alg::Error),
+
+    #[error(transparent)]
+    X509(#[from] x509::Error),
+
+    #[error(transparent)]
+    Aes(#[from] aes::Error),
+
+    #[error(transparent)]
+    Hkdf(#[from] hkdf::Error),
+
+    #[error("malformed CMS structure")]
+    Malformed,
+
+    #[error("unsupported content type")]
+    ContentType,
+
+    #[error("unsupported digest algorithm")]
+    UnsupportedDigest,
+
+    #[error("detached content is missing")]
+    NoContent,
+
+    #[error("message digest does not match")]
+    MessageDigest,
+
+    #[error("signature verification failed")]
+    Signature,
+
+    #[error("no signer matches the key")]
+    NoSigner,
+
+    #[error("no recipient matches the key")]
+    NoRecipient,
+}
+pub type Result<T> = core::result::Result<T, Error>;
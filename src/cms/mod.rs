@@ -0,0 +1,218 @@
+//! Cryptographic Message Syntax (RFC 5652) with ML-DSA signatures (RFC 9882) and ML-KEM recipients
+//! (RFC 9629).
+//!
+//! [`SignedDataBuilder`] creates a SignedData with attached or detached content, with or without
+//! signed attributes, and [`SignedData`] parses and verifies one against an ML-DSA public key or a
+//! certificate. The signatures use the pure ML-DSA algorithm identifiers with an empty context.
+//!
+//! [`EnvelopedDataBuilder`] encrypts content with AES-CBC under a random content-encryption key,
+//! which is AES key wrapped to every recipient in a KEMRecipientInfo: the key-encryption key is
+//! derived from the ML-KEM shared secret by the KDF. [`EnvelopedData`] decrypts it with a
+//! decapsulation key.
+
+use alloc::vec::Vec;
+
+use crate::pkcs::der::{self, Reader, Writer};
+use crate::sha2;
+use crate::sha3::{self, Hash};
+
+mod enveloped_data;
+mod errors;
+mod signed_data;
+
+pub use enveloped_data::{
+    ContentEncryptionAlgorithm, EnvelopedData, EnvelopedDataBuilder, KdfAlgorithm, KemRecipientInfo, KeyWrapAlgorithm,
+    RecipientIdentifier,
+};
+pub use errors::{Error, Result};
+pub use signed_data::{SignedData, SignedDataBuilder, SignerIdentifier, SignerInfo};
+
+/// The DER contents of the CMS OIDs.
+pub mod oid {
+    /// 1.2.840.113549.1.7.1
+    pub const DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x01];
+    /// 1.2.840.113549.1.7.2
+    pub const SIGNED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x02];
+    /// 1.2.840.113549.1.7.3
+    pub const ENVELOPED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x03];
+    /// 1.2.840.113549.1.9.3
+    pub const CONTENT_TYPE: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x03];
+    /// 1.2.840.113549.1.9.4
+    pub const MESSAGE_DIGEST: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x04];
+    /// 1.2.840.113549.1.9.5
+    pub const SIGNING_TIME: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x05];
+    /// 1.2.840.113549.1.9.16.3.28
+    pub const HKDF_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x10, 0x03, 0x1c];
+    /// 1.2.840.113549.1.9.16.13.3
+    pub const ORI_KEM: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x10, 0x0d, 0x03];
+    /// 2.16.840.1.101.3.4.1.2
+    pub const AES128_CBC: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x02];
+    /// 2.16.840.1.101.3.4.1.5
+    pub const AES128_WRAP: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x05];
+    /// 2.16.840.1.101.3.4.1.22
+    pub const AES192_CBC: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x16];
+    /// 2.16.840.1.101.3.4.1.25
+    pub const AES192_WRAP: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x19];
+    /// 2.16.840.1.101.3.4.1.42
+    pub const AES256_CBC: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2a];
+    /// 2.16.840.1.101.3.4.1.45
+    pub const AES256_WRAP: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2d];
+    /// 2.16.840.1.101.3.4.2.3
+    pub const SHA512: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03];
+    /// 2.16.840.1.101.3.4.2.8
+    pub const SHA3_256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x08];
+    /// 2.16.840.1.101.3.4.2.9
+    pub const SHA3_384: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x09];
+    /// 2.16.840.1.101.3.4.2.10
+    pub const SHA3_512: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0a];
+    /// 2.16.840.1.101.3.4.2.12
+    pub const SHAKE256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0c];
+    /// 2.16.840.1.101.3.4.2.22
+    pub const KMAC256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x16];
+}
+
+/// The message digest algorithms of the signed attributes.
+///
+/// RFC 9882 requires SHA-512, the default, and asks for a digest at least as strong as the ML-DSA
+/// parameter set, SHA3-512 and SHAKE256 (with 512 bits of output, RFC 8702) suit all of them too.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DigestAlgorithm {
+    #[default]
+    Sha512,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+    Shake256,
+}
+
+impl DigestAlgorithm {
+    pub const ALL: [DigestAlgorithm; 5] =
+        [Self::Sha512, Self::Sha3_256, Self::Sha3_384, Self::Sha3_512, Self::Shake256];
+
+    pub fn oid(self) -> &'static [u8] {
+        match self {
+            Self::Sha512 => oid::SHA512,
+            Self::Sha3_256 => oid::SHA3_256,
+            Self::Sha3_384 => oid::SHA3_384,
+            Self::Sha3_512 => oid::SHA3_512,
+            Self::Shake256 => oid::SHAKE256,
+        }
+    }
+
+    pub fn from_oid(oid: &[u8]) -> Result<Self> {
+        Self::ALL.into_iter().find(|d| d.oid() == oid).ok_or(Error::UnsupportedDigest)
+    }
+
+    pub fn output_len(self) -> usize {
+        match self {
+            Self::Sha3_256 => 32,
+            Self::Sha3_384 => 48,
+            Self::Sha512 | Self::Sha3_512 | Self::Shake256 => 64,
+        }
+    }
+
+    /// The digest of m, in the first output_len() bytes.
+    pub fn digest(self, m: &[u8]) -> [u8; 64] {
+        let mut out = [0; 64];
+        match self {
+            Self::Sha512 => digest_into(sha2::new512(), m, &mut out),
+            Self::Sha3_256 => digest_into(sha3::new256(), m, &mut out),
+            Self::Sha3_384 => digest_into(sha3::new384(), m, &mut out),
+            Self::Sha3_512 => digest_into(sha3::new512(), m, &mut out),
+            Self::Shake256 => {
+                let mut h = sha3::new_shake256();
+                h.write(m);
+                h.read(&mut out);
+            }
+        }
+        out
+    }
+
+    // The parameters must be absent, NULL is tolerated.
+    pub(crate) fn read(r: &mut Reader) -> Result<Self> {
+        let mut seq = r.read_sequence()?;
+        let d = Self::from_oid(seq.read_oid()?)?;
+        if !seq.is_empty() && !seq.read(der::NULL)?.is_empty() {
+            return Err(Error::Malformed);
+        }
+        seq.finish()?;
+        Ok(d)
+    }
+
+    pub(crate) fn write(self, w: &mut Writer) {
+        w.write_sequence(|w| {
+            w.write_oid(self.oid());
+        });
+    }
+}
+
+fn digest_into<const N: usize>(mut h: impl Hash<N>, m: &[u8], out: &mut [u8]) {
+    h.write(m);
+    h.sum_into(&mut out[..N]);
+}
+
+/// Decode a ContentInfo, returns the content type and the content TLV.
+pub fn decode_content_info(der: &[u8]) -> Result<(&[u8], &[u8])> {
+    let mut r = Reader::new(der);
+    let mut ci = r.read_sequence()?;
+    r.finish()?;
+    let content_type = ci.read_oid()?;
+    let mut content = ci.read_constructed(der::context_constructed(0))?;
+    ci.finish()?;
+    let (_, _, tlv) = content.read_any()?;
+    content.finish()?;
+    Ok((content_type, tlv))
+}
+
+pub(crate) fn content_info_der(content_type: &[u8], content: &[u8]) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.write_sequence(|w| {
+        w.write_oid(content_type);
+        w.write(der::context_constructed(0), content);
+    });
+    w.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest() {
+        for d in DigestAlgorithm::ALL {
+            assert_eq!(DigestAlgorithm::from_oid(d.oid()), Ok(d));
+            let mut w = Writer::new();
+            d.write(&mut w);
+            let der = w.finish();
+            assert_eq!(DigestAlgorithm::read(&mut Reader::new(&der)), Ok(d));
+            assert!(d.digest(b"abc")[d.output_len()..].iter().all(|b| *b == 0));
+        }
+        assert_eq!(DigestAlgorithm::from_oid(crate::pkcs::oid::ID_ML_DSA_44), Err(Error::UnsupportedDigest));
+        assert_eq!(
+            DigestAlgorithm::Sha3_256.digest(b"abc")[..32],
+            [
+                0x3a, 0x98, 0x5d, 0xa7, 0x4f, 0xe2, 0x25, 0xb2, 0x04, 0x5c, 0x17, 0x2d, 0x6b, 0xd3, 0x90, 0xbd,
+                0x85, 0x5f, 0x08, 0x6e, 0x3e, 0x9d, 0x52, 0x5b, 0x46, 0xbf, 0xe2, 0x45, 0x11, 0x43, 0x15, 0x32
+            ]
+        );
+        assert_eq!(
+            DigestAlgorithm::Sha512.digest(b"abc"),
+            [
+                0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba, 0xcc, 0x41, 0x73, 0x49, 0xae, 0x20, 0x41, 0x31,
+                0x12, 0xe6, 0xfa, 0x4e, 0x89, 0xa9, 0x7e, 0xa2, 0x0a, 0x9e, 0xee, 0xe6, 0x4b, 0x55, 0xd3, 0x9a,
+                0x21, 0x92, 0x99, 0x2a, 0x27, 0x4f, 0xc1, 0xa8, 0x36, 0xba, 0x3c, 0x23, 0xa3, 0xfe, 0xeb, 0xbd,
+                0x45, 0x4d, 0x44, 0x23, 0x64, 0x3c, 0xe8, 0x0e, 0x2a, 0x9a, 0xc9, 0x4f, 0xa5, 0x4c, 0xa4, 0x9f
+            ]
+        );
+        // the NULL parameters are tolerated
+        let der = [0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0a, 0x05, 0x00];
+        assert_eq!(DigestAlgorithm::read(&mut Reader::new(&der)), Ok(DigestAlgorithm::Sha3_512));
+    }
+
+    #[test]
+    fn test_content_info() {
+        let der = content_info_der(oid::DATA, &[0x04, 0x01, 0x2a]);
+        assert_eq!(decode_content_info(&der), Ok((oid::DATA, &[0x04, 0x01, 0x2a][..])));
+        assert!(decode_content_info(&der[..der.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,550 @@
+use alloc::vec::Vec;
+
+use crate::alg::{self, Algorithm, Signer, Verifier};
+use crate::pkcs::{self, der, der::Reader, der::Writer};
+use crate::x509::{self, Certificate};
+
+use super::errors::{Error, Result};
+use super::{DigestAlgorithm, content_info_der, decode_content_info, oid};
+
+/// The sid of a SignerInfo.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignerIdentifier<'a> {
+    /// The DER Name of the issuer and the serial number without the leading zero.
+    IssuerAndSerialNumber { issuer: &'a [u8], serial: &'a [u8] },
+    SubjectKeyIdentifier(&'a [u8]),
+}
+
+impl<'a> SignerIdentifier<'a> {
+    /// The issuerAndSerialNumber of the signer certificate.
+    pub fn from_certificate(cert: &Certificate<'a>) -> Self {
+        Self::IssuerAndSerialNumber { issuer: cert.issuer, serial: cert.serial }
+    }
+
+    pub fn matches(&self, cert: &Certificate) -> bool {
+        match *self {
+            Self::IssuerAndSerialNumber { issuer, serial } => issuer == cert.issuer && serial == cert.serial,
+            Self::SubjectKeyIdentifier(ski) => cert.subject_key_id == Some(ski),
+        }
+    }
+
+    pub(super) fn read(r: &mut Reader<'a>) -> Result<Self> {
+        if r.peek_tag() == Some(der::context(0)) {
+            return Ok(Self::SubjectKeyIdentifier(r.read(der::context(0))?));
+        }
+        let mut seq = r.read_sequence()?;
+        let (tag, _, issuer) = seq.read_any()?;
+        if tag != der::SEQUENCE {
+            return Err(Error::Malformed);
+        }
+        let serial = seq.read_uint()?;
+        seq.finish()?;
+        Ok(Self::IssuerAndSerialNumber { issuer, serial })
+    }
+
+    pub(super) fn write(&self, w: &mut Writer) {
+        match *self {
+            Self::IssuerAndSerialNumber { issuer, serial } => {
+                w.write_sequence(|w| {
+                    w.write_raw(issuer).write_uint(serial);
+                });
+            }
+            Self::SubjectKeyIdentifier(ski) => {
+                w.write(der::context(0), ski);
+            }
+        }
+    }
+}
+
+/// A parsed SignerInfo, the fields are borrowed from the DER.
+#[derive(Clone, Debug)]
+pub struct SignerInfo<'a> {
+    pub sid: SignerIdentifier<'a>,
+    pub digest_algorithm: DigestAlgorithm,
+    /// The content of the signedAttrs, without the [0] header.
+    pub signed_attributes: Option<&'a [u8]>,
+    /// The content-type attribute.
+    pub content_type: Option<&'a [u8]>,
+    /// The message-digest attribute.
+    pub message_digest: Option<&'a [u8]>,
+    /// The signing-time attribute in seconds since the Unix epoch.
+    pub signing_time: Option<i64>,
+    pub signature_algorithm: Algorithm,
+    pub signature: &'a [u8],
+}
+
+// The single value of an attribute.
+fn attribute_value<'a>(values: &mut Reader<'a>, tag: u8) -> Result<&'a [u8]> {
+    let v = values.read(tag)?;
+    if !values.is_empty() {
+        return Err(Error::Malformed);
+    }
+    Ok(v)
+}
+
+impl<'a> SignerInfo<'a> {
+    fn read(r: &mut Reader<'a>) -> Result<Self> {
+        let mut seq = r.read_sequence()?;
+        let version = seq.read_u64()?;
+        let sid = SignerIdentifier::read(&mut seq)?;
+        // version 1 goes with issuerAndSerialNumber, 3 with subjectKeyIdentifier
+        if version != if matches!(sid, SignerIdentifier::SubjectKeyIdentifier(_)) { 3 } else { 1 } {
+            return Err(Error::Malformed);
+        }
+        let digest_algorithm = DigestAlgorithm::read(&mut seq)?;
+        let mut info = SignerInfo {
+            sid,
+            digest_algorithm,
+            signed_attributes: seq.read_optional(der::context_constructed(0))?,
+            content_type: None,
+            message_digest: None,
+            signing_time: None,
+            signature_algorithm: pkcs::read_algorithm_identifier(&mut seq)?,
+            signature: &[],
+        };
+        if !info.signature_algorithm.is_signature() {
+            return Err(Error::Pkcs(pkcs::Error::UnsupportedAlgorithm));
+        }
+        info.signature = seq.read_octet_string()?;
+        seq.read_optional(der::context_constructed(1))?;
+        seq.finish()?;
+        if let Some(attributes) = info.signed_attributes {
+            info.read_attributes(attributes)?;
+        }
+        Ok(info)
+    }
+
+    // content-type and message-digest are required, each attribute appears once.
+    fn read_attributes(&mut self, attributes: &'a [u8]) -> Result<()> {
+        let mut r = Reader::new(attributes);
+        let mut signing_time = false;
+        while !r.is_empty() {
+            let mut attribute = r.read_sequence()?;
+            let id = attribute.read_oid()?;
+            let mut values = attribute.read_constructed(der::SET)?;
+            attribute.finish()?;
+            match id {
+                oid::CONTENT_TYPE if self.content_type.is_none() => {
+                    self.content_type = Some(attribute_value(&mut values, der::OBJECT_IDENTIFIER)?);
+                }
+                oid::MESSAGE_DIGEST if self.message_digest.is_none() => {
+                    self.message_digest = Some(attribute_value(&mut values, der::OCTET_STRING)?);
+                }
+                oid::SIGNING_TIME if !signing_time => {
+                    signing_time = true;
+                    self.signing_time = Some(x509::read_time(&mut values)?);
+                    values.finish()?;
+                }
+                oid::CONTENT_TYPE | oid::MESSAGE_DIGEST | oid::SIGNING_TIME => return Err(Error::Malformed),
+                _ => {}
+            }
+        }
+        if self.content_type.is_none() || self.message_digest.is_none() {
+            return Err(Error::Malformed);
+        }
+        Ok(())
+    }
+
+    /// Verify the signature over the content of the content type with the public key.
+    pub fn verify(&self, content_type: &[u8], content: &[u8], key: &dyn Verifier) -> Result<()> {
+        self.check(content_type, content, key.algorithm(), |m, sig| key.verify(m, b"", sig))
+    }
+
+    fn check(
+        &self,
+        content_type: &[u8],
+        content: &[u8],
+        alg: Algorithm,
+        verify: impl Fn(&[u8], &[u8]) -> alg::Result<()>,
+    ) -> Result<()> {
+        if alg != self.signature_algorithm {
+            return Err(Error::Signature);
+        }
+        let Some(attributes) = self.signed_attributes else {
+            // without signed attributes the content itself is signed, which must be id-data
+            if content_type != oid::DATA {
+                return Err(Error::ContentType);
+            }
+            return verify(content, self.signature).map_err(|_| Error::Signature);
+        };
+        if self.content_type != Some(content_type) {
+            return Err(Error::ContentType);
+        }
+        let digest = self.digest_algorithm.digest(content);
+        if self.message_digest != Some(&digest[..self.digest_algorithm.output_len()]) {
+            return Err(Error::MessageDigest);
+        }
+        // the signature is over the DER of the SET OF, not the [0] IMPLICIT encoding
+        let mut w = Writer::new();
+        w.write(der::SET, attributes);
+        verify(&w.finish(), self.signature).map_err(|_| Error::Signature)
+    }
+}
+
+/// A parsed SignedData in a ContentInfo.
+#[derive(Clone, Debug)]
+pub struct SignedData<'a> {
+    /// The eContentType.
+    pub content_type: &'a [u8],
+    /// The eContent, None for detached content.
+    pub content: Option<&'a [u8]>,
+    /// The DER of the certificates, the other certificate choices are skipped.
+    pub certificates: Vec<&'a [u8]>,
+    pub signer_infos: Vec<SignerInfo<'a>>,
+}
+
+impl<'a> SignedData<'a> {
+    /// Parse a DER ContentInfo with a SignedData.
+    pub fn from_der(der: &'a [u8]) -> Result<Self> {
+        let (content_type, content) = decode_content_info(der)?;
+        if content_type != oid::SIGNED_DATA {
+            return Err(Error::ContentType);
+        }
+        let mut r = Reader::new(content);
+        let mut seq = r.read_sequence()?;
+        if !matches!(seq.read_u64()?, 1 | 3 | 4 | 5) {
+            return Err(Error::Malformed);
+        }
+        seq.read(der::SET)?;
+
+        let mut encap = seq.read_sequence()?;
+        let content_type = encap.read_oid()?;
+        let content = match encap.read_optional(der::context_constructed(0))? {
+            Some(explicit) => {
+                let mut r = Reader::new(explicit);
+                let content = r.read_octet_string()?;
+                r.finish()?;
+                Some(content)
+            }
+            None => None,
+        };
+        encap.finish()?;
+
+        let mut certificates = Vec::new();
+        if let Some(set) = seq.read_optional(der::context_constructed(0))? {
+            let mut r = Reader::new(set);
+            while !r.is_empty() {
+                let (tag, _, cert) = r.read_any()?;
+                if tag == der::SEQUENCE {
+                    certificates.push(cert);
+                }
+            }
+        }
+        seq.read_optional(der::context_constructed(1))?;
+
+        let mut signer_infos = Vec::new();
+        let mut r = seq.read_constructed(der::SET)?;
+        while !r.is_empty() {
+            signer_infos.push(SignerInfo::read(&mut r)?);
+        }
+        seq.finish()?;
+        r.finish()?;
+        Ok(SignedData { content_type, content, certificates, signer_infos })
+    }
+
+    fn content<'b>(&'b self, detached: Option<&'b [u8]>) -> Result<&'b [u8]> {
+        detached.or(self.content).ok_or(Error::NoContent)
+    }
+
+    /// Verify with the public key, returns the first SignerInfo of the key algorithm which verifies.
+    /// detached is the content when it is not attached.
+    pub fn verify(&self, detached: Option<&[u8]>, key: &dyn Verifier) -> Result<&SignerInfo<'a>> {
+        let content = self.content(detached)?;
+        let mut result = Err(Error::NoSigner);
+        for info in self.signer_infos.iter().filter(|info| info.signature_algorithm == key.algorithm()) {
+            match info.verify(self.content_type, content, key) {
+                Ok(()) => return Ok(info),
+                Err(e) => result = Err(e),
+            }
+        }
+        result
+    }
+
+    /// Verify the SignerInfo whose sid matches the certificate with its public key.
+    pub fn verify_certificate(&self, detached: Option<&[u8]>, cert: &Certificate) -> Result<&SignerInfo<'a>> {
+        let content = self.content(detached)?;
+        let info = self.signer_infos.iter().find(|info| info.sid.matches(cert)).ok_or(Error::NoSigner)?;
+        let alg = cert.public_key_algorithm;
+        info.check(self.content_type, content, alg, |m, sig| alg.sig_verify(cert.public_key, m, b"", sig))?;
+        Ok(info)
+    }
+}
+
+/// Builds a SignedData in a ContentInfo, every signer signs with the same digest algorithm.
+#[derive(Clone)]
+pub struct SignedDataBuilder<'a> {
+    content_type: &'a [u8],
+    content: &'a [u8],
+    detached: bool,
+    signed_attributes: bool,
+    signing_time: Option<i64>,
+    digest_algorithm: DigestAlgorithm,
+    certificates: Vec<&'a [u8]>,
+    signers: Vec<(&'a dyn Signer, SignerIdentifier<'a>)>,
+}
+
+impl<'a> SignedDataBuilder<'a> {
+    /// Sign id-data content, attached and with signed attributes.
+    pub fn new(content: &'a [u8]) -> Self {
+        SignedDataBuilder {
+            content_type: oid::DATA,
+            content,
+            detached: false,
+            signed_attributes: true,
+            signing_time: None,
+            digest_algorithm: DigestAlgorithm::default(),
+            certificates: Vec::new(),
+            signers: Vec::new(),
+        }
+    }
+
+    /// The eContentType OID, other than id-data requires the signed attributes.
+    pub fn content_type(mut self, oid: &'a [u8]) -> Self {
+        self.content_type = oid;
+        self
+    }
+
+    /// Leave the content out of the SignedData.
+    pub fn detached(mut self, detached: bool) -> Self {
+        self.detached = detached;
+        self
+    }
+
+    /// Without the signed attributes the content is signed directly, OpenSSL 3.5 does not verify that
+    /// with ML-DSA.
+    pub fn signed_attributes(mut self, signed_attributes: bool) -> Self {
+        self.signed_attributes = signed_attributes;
+        self
+    }
+
+    /// Add the signing-time attribute, in seconds since the Unix epoch.
+    pub fn signing_time(mut self, time: i64) -> Self {
+        self.signing_time = Some(time);
+        self
+    }
+
+    pub fn digest_algorithm(mut self, digest_algorithm: DigestAlgorithm) -> Self {
+        self.digest_algorithm = digest_algorithm;
+        self
+    }
+
+    /// Include a DER certificate, e.g. of a signer.
+    pub fn certificate(mut self, der: &'a [u8]) -> Self {
+        self.certificates.push(der);
+        self
+    }
+
+    pub fn signer(mut self, key: &'a dyn Signer, sid: SignerIdentifier<'a>) -> Self {
+        self.signers.push((key, sid));
+        self
+    }
+
+    // The sorted DER of the signed attributes, without the SET header.
+    fn signed_attributes_der(&self, digest: &[u8]) -> Vec<u8> {
+        let attribute = |id: &[u8], f: &dyn Fn(&mut Writer)| {
+            let mut w = Writer::new();
+            w.write_sequence(|w| {
+                w.write_oid(id).write_constructed(der::SET, f);
+            });
+            w.finish()
+        };
+        let mut attributes = vec![
+            attribute(oid::CONTENT_TYPE, &|w| {
+                w.write_oid(self.content_type);
+            }),
+            attribute(oid::MESSAGE_DIGEST, &|w| {
+                w.write_octet_string(digest);
+            }),
+        ];
+        if let Some(t) = self.signing_time {
+            attributes.push(attribute(oid::SIGNING_TIME, &|w| x509::write_time(w, t)));
+        }
+        attributes.sort();
+        attributes.concat()
+    }
+
+    fn signer_info(&self, key: &dyn Signer, sid: &SignerIdentifier, digest: &[u8], rnd: &[u8; 32]) -> Result<Vec<u8>> {
+        let alg = key.algorithm();
+        let attributes = self.signed_attributes.then(|| self.signed_attributes_der(digest));
+        let mut signature = vec![0; alg.signature_len()];
+        match &attributes {
+            Some(attributes) => {
+                let mut w = Writer::new();
+                w.write(der::SET, attributes);
+                key.sign(&w.finish(), b"", rnd, &mut signature)?;
+            }
+            None => key.sign(self.content, b"", rnd, &mut signature)?,
+        }
+
+        let mut w = Writer::new();
+        w.write_sequence(|w| {
+            w.write_u64(if matches!(sid, SignerIdentifier::SubjectKeyIdentifier(_)) { 3 } else { 1 });
+            sid.write(w);
+            self.digest_algorithm.write(w);
+            if let Some(attributes) = &attributes {
+                w.write(der::context_constructed(0), attributes);
+            }
+            pkcs::write_algorithm_identifier(w, pkcs::algorithm_oid(alg));
+            w.write_octet_string(&signature);
+        });
+        Ok(w.finish())
+    }
+
+    /// Sign the content by every signer, returns the DER ContentInfo.
+    /// rnd is all zero for the deterministic variant of ML-DSA.
+    pub fn sign(&self, rnd: &[u8; 32]) -> Result<Vec<u8>> {
+        if !self.signed_attributes && self.content_type != oid::DATA {
+            return Err(Error::ContentType);
+        }
+        let digest = self.digest_algorithm.digest(self.content);
+        let digest = &digest[..self.digest_algorithm.output_len()];
+        let mut signer_infos = Vec::with_capacity(self.signers.len());
+        for (key, sid) in &self.signers {
+            signer_infos.push(self.signer_info(*key, sid, digest, rnd)?);
+        }
+        let ski = self.signers.iter().any(|(_, sid)| matches!(sid, SignerIdentifier::SubjectKeyIdentifier(_)));
+        let mut certificates: Vec<Vec<u8>> = self.certificates.iter().map(|c| c.to_vec()).collect();
+
+        let mut w = Writer::new();
+        w.write_sequence(|w| {
+            w.write_u64(if ski || self.content_type != oid::DATA { 3 } else { 1 });
+            w.write_constructed(der::SET, |w| {
+                if !self.signers.is_empty() {
+                    self.digest_algorithm.write(w);
+                }
+            });
+            w.write_sequence(|w| {
+                w.write_oid(self.content_type);
+                if !self.detached {
+                    w.write_constructed(der::context_constructed(0), |w| {
+                        w.write_octet_string(self.content);
+                    });
+                }
+            });
+            if !certificates.is_empty() {
+                w.write_set_of(der::context_constructed(0), &mut certificates);
+            }
+            w.write_set_of(der::SET, &mut signer_infos);
+        });
+        Ok(content_info_der(oid::SIGNED_DATA, &w.finish()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mldsa::{mldsa44, mldsa65, mldsa87};
+    use crate::x509::{CertificateBuilder, Name, unix_time};
+
+    fn certificate(key: &mldsa65::PrivateKey) -> Vec<u8> {
+        let not_before = unix_time(2025, 1, 1, 0, 0, 0).unwrap();
+        let not_after = unix_time(2035, 1, 1, 0, 0, 0).unwrap();
+        CertificateBuilder::new(&[0x42], &Name::new().common_name("signer"), &key.public_key().to_public_key_der(), not_before, not_after)
+            .unwrap()
+            .sign(key, &[0; 32])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_signed_data() {
+        let key = mldsa65::PrivateKey::from_seed(&[1; 32]);
+        let pk = key.public_key();
+        let cert_der = certificate(&key);
+        let cert = Certificate::from_der(&cert_der).unwrap();
+        let content = b"package contents";
+        let signing_time = unix_time(2025, 6, 30, 12, 0, 0).unwrap();
+
+        for digest_algorithm in DigestAlgorithm::ALL {
+            for detached in [false, true] {
+                for signed_attributes in [true, false] {
+                    let der = SignedDataBuilder::new(content)
+                        .detached(detached)
+                        .signed_attributes(signed_attributes)
+                        .signing_time(signing_time)
+                        .digest_algorithm(digest_algorithm)
+                        .certificate(&cert_der)
+                        .signer(&key, SignerIdentifier::from_certificate(&cert))
+                        .sign(&[0; 32])
+                        .unwrap();
+                    let sd = SignedData::from_der(&der).unwrap();
+                    assert_eq!(sd.content_type, oid::DATA);
+                    assert_eq!(sd.content, (!detached).then_some(&content[..]));
+                    assert_eq!(sd.certificates, [&cert_der[..]]);
+                    assert_eq!(sd.signer_infos.len(), 1);
+                    let info = &sd.signer_infos[0];
+                    assert_eq!(info.digest_algorithm, digest_algorithm);
+                    assert_eq!(info.signature_algorithm, Algorithm::MlDsa65);
+                    assert_eq!(info.signing_time, signed_attributes.then_some(signing_time));
+                    assert!(info.sid.matches(&cert));
+
+                    let detached_content = detached.then_some(&content[..]);
+                    sd.verify(detached_content, &pk).unwrap();
+                    sd.verify_certificate(detached_content, &cert).unwrap();
+                    if detached {
+                        assert_eq!(sd.verify(None, &pk).err(), Some(Error::NoContent));
+                    }
+                    let err = if signed_attributes { Error::MessageDigest } else { Error::Signature };
+                    assert_eq!(sd.verify(Some(b"other contents"), &pk).err(), Some(err));
+                }
+            }
+        }
+    }
+
+    // SignedData produced by OpenSSL 3.5, see testdata/cms/README.md
+    #[test]
+    fn test_openssl() {
+        let content = include_bytes!("../../testdata/cms/content.txt");
+        let cert_der = include_bytes!("../../testdata/cms/signer.der");
+        let cert = Certificate::from_der(cert_der).unwrap();
+
+        let sd = SignedData::from_der(include_bytes!("../../testdata/cms/signed_data.der")).unwrap();
+        assert_eq!(sd.content_type, oid::DATA);
+        assert_eq!(sd.content, Some(&content[..]));
+        assert_eq!(sd.certificates, [&cert_der[..]]);
+        let info = sd.verify_certificate(None, &cert).unwrap();
+        assert_eq!(info.digest_algorithm, DigestAlgorithm::Sha512);
+        assert_eq!(info.signature_algorithm, Algorithm::MlDsa65);
+        assert!(info.sid.matches(&cert));
+
+        let sd = SignedData::from_der(include_bytes!("../../testdata/cms/detached.der")).unwrap();
+        assert_eq!(sd.content, None);
+        sd.verify_certificate(Some(content), &cert).unwrap();
+        assert_eq!(sd.verify_certificate(Some(b"other contents"), &cert).err(), Some(Error::MessageDigest));
+    }
+
+    #[test]
+    fn test_signers() {
+        let key44 = mldsa44::PrivateKey::from_seed(&[1; 32]);
+        let key87 = mldsa87::PrivateKey::from_seed(&[2; 32]);
+        let other = mldsa87::PrivateKey::from_seed(&[3; 32]);
+        let content_type = crate::pkcs::oid::ID_ML_DSA_44;
+        let der = SignedDataBuilder::new(b"content")
+            .content_type(content_type)
+            .signer(&key44, SignerIdentifier::SubjectKeyIdentifier(&[1; 20]))
+            .signer(&key87, SignerIdentifier::SubjectKeyIdentifier(&[2; 20]))
+            .sign(&[7; 32])
+            .unwrap();
+        let sd = SignedData::from_der(&der).unwrap();
+        assert_eq!(sd.content_type, content_type);
+        assert_eq!(sd.signer_infos.len(), 2);
+        assert_eq!(sd.verify(None, &key44.public_key()).unwrap().sid, SignerIdentifier::SubjectKeyIdentifier(&[1; 20]));
+        assert_eq!(sd.verify(None, &key87.public_key()).unwrap().sid, SignerIdentifier::SubjectKeyIdentifier(&[2; 20]));
+        assert_eq!(sd.verify(None, &other.public_key()).err(), Some(Error::Signature));
+        assert_eq!(sd.verify(None, &mldsa65::PrivateKey::from_seed(&[1; 32]).public_key()).err(), Some(Error::NoSigner));
+
+        // the content type other than id-data is only signed through the signed attributes
+        let builder = SignedDataBuilder::new(b"content").content_type(content_type).signed_attributes(false);
+        assert_eq!(builder.sign(&[0; 32]).err(), Some(Error::ContentType));
+
+        // a tampered signature
+        let info = sd.signer_infos.iter().find(|info| info.signature_algorithm == Algorithm::MlDsa87).unwrap();
+        let offset = info.signature.as_ptr() as usize - der.as_ptr() as usize;
+        let mut der = der.clone();
+        der[offset] ^= 1;
+        let sd = SignedData::from_der(&der).unwrap();
+        assert_eq!(sd.verify(None, &key87.public_key()).err(), Some(Error::Signature));
+        sd.verify(None, &key44.public_key()).unwrap();
+        assert!(SignedData::from_der(&der[..der.len() - 1]).is_err());
+    }
+}
+
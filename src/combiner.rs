@@ -0,0 +1,62 @@
+//! The KEM combiner of X-Wing and of the composite ML-KEM drafts,
+//! H(ss_M || ss_T || ct_T || pk_T || label), over any [`Hash`] so that the deployments bound to
+//! the national algorithms can use SM3 instead of SHA3-256.
+//!
+//! ss_M is the ML-KEM shared secret, ss_T, ct_T and pk_T are the shared secret, ciphertext and
+//! public key of the traditional KEM, and label separates the hybrid schemes.
+
+use crate::sha3::{self, Hash};
+use crate::sm3;
+
+/// H(ss_m || ss_t || ct_t || pk_t || label), h is reset first.
+pub fn combine<const N: usize>(
+    h: &mut impl Hash<N>,
+    ss_m: &[u8],
+    ss_t: &[u8],
+    ct_t: &[u8],
+    pk_t: &[u8],
+    label: &[u8],
+) -> [u8; N] {
+    h.reset();
+    for x in [ss_m, ss_t, ct_t, pk_t, label] {
+        h.write(x);
+    }
+    h.sum()
+}
+
+/// The combiner with SHA3-256, the one of X-Wing.
+pub fn combine_sha3_256(ss_m: &[u8], ss_t: &[u8], ct_t: &[u8], pk_t: &[u8], label: &[u8]) -> [u8; 32] {
+    combine(&mut sha3::new256(), ss_m, ss_t, ct_t, pk_t, label)
+}
+
+/// The combiner with SM3.
+pub fn combine_sm3(ss_m: &[u8], ss_t: &[u8], ct_t: &[u8], pk_t: &[u8], label: &[u8]) -> [u8; 32] {
+    combine(&mut sm3::new(), ss_m, ss_t, ct_t, pk_t, label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha2;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_combine() {
+        let (ss_m, ss_t, ct_t, pk_t) = ([1u8; 32], [2u8; 32], [3u8; 32], [4u8; 32]);
+        let label = b"\\.//^\\";
+        assert_eq!(
+            combine_sm3(&ss_m, &ss_t, &ct_t, &pk_t, label),
+            hex!("608f2d0f3b0af177624bd9f92ea381adce2b4dfb733dc4b50d4f757924b47c99")
+        );
+        assert_eq!(
+            combine(&mut sha2::new256(), &ss_m, &ss_t, &ct_t, &pk_t, label),
+            hex!("141b76433deb85e9f7bc1fcaf68b475177cfa4b55cca631b78146837af064fc3")
+        );
+
+        let mut h = sha3::new256();
+        for x in [&ss_m[..], &ss_t, &ct_t, &pk_t, label] {
+            h.write(x);
+        }
+        assert_eq!(combine_sha3_256(&ss_m, &ss_t, &ct_t, &pk_t, label), h.sum());
+    }
+}
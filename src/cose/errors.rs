@@ -0,0 +1,43 @@
+use thiserror;
+
+use crate::{alg, cbor};
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error(transparent)]
+    Cbor(#[from] cbor::Error),
+
+    #[error(transparent)]
+    Alg(#[from] alg::Error),
+
+    #[error("malformed COSE structure")]
+    Malformed,
+
+    #[error("unsupported key type")]
+    UnsupportedKeyType,
+
+    #[error("unsupported algorithm")]
+    UnsupportedAlgorithm,
+
+    #[error("algorithm does not match the key")]
+    AlgorithmMismatch,
+
+    #[error("invalid key length")]
+    InvalidLength,
+
+    #[error("public and private key mismatch")]
+    KeyMismatch,
+
+    #[error("no private key in the COSE_Key")]
+    NoPrivateKey,
+
+    #[error("unsupported critical header parameter")]
+    UnsupportedCritical,
+
+    #[error("detached payload is missing")]
+    NoPayload,
+
+    #[error("signature verification failed")]
+    Signature,
+}
+pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,161 @@
+use crate::alg::Algorithm;
+use crate::cbor::{Decoder, Encoder};
+
+use super::{Error, Result, algorithm_from_id, algorithm_id, read_label};
+
+// The labels of the AKP key parameters.
+const KTY: i64 = 1;
+const KID: i64 = 2;
+const ALG: i64 = 3;
+const PUB: i64 = -1;
+const PRIV: i64 = -2;
+
+const KTY_AKP: i64 = 7;
+
+/// An ML-DSA COSE_Key, the private key is the seed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Key<'a> {
+    pub alg: Algorithm,
+    pub public: &'a [u8],
+    pub seed: Option<&'a [u8; 32]>,
+    pub kid: Option<&'a [u8]>,
+}
+
+impl<'a> Key<'a> {
+    /// The public key must be of the algorithm, the seed is not checked against it.
+    pub fn new(alg: Algorithm, public: &'a [u8], seed: Option<&'a [u8; 32]>) -> Result<Self> {
+        algorithm_id(alg)?;
+        if public.len() != alg.public_key_len() {
+            return Err(Error::InvalidLength);
+        }
+        Ok(Key { alg, public, seed, kid: None })
+    }
+
+    pub fn with_kid(mut self, kid: &'a [u8]) -> Self {
+        self.kid = Some(kid);
+        self
+    }
+
+    /// The key without the seed.
+    pub fn to_public(&self) -> Self {
+        Key { seed: None, ..*self }
+    }
+
+    /// Decode the CBOR map, unknown parameters are ignored.
+    pub fn decode(data: &'a [u8]) -> Result<Self> {
+        let mut d = Decoder::new(data);
+        let (mut kty, mut alg, mut public, mut seed, mut kid) = (None, None, None, None, None);
+        for _ in 0..d.map()? {
+            let label = read_label(&mut d)?;
+            let found = match label {
+                Some(KTY) => kty.replace(d.int()?).is_some(),
+                Some(KID) => kid.replace(d.bytes()?).is_some(),
+                Some(ALG) => alg.replace(d.int()?).is_some(),
+                Some(PUB) => public.replace(d.bytes()?).is_some(),
+                Some(PRIV) => seed.replace(d.bytes()?).is_some(),
+                _ => {
+                    d.skip()?;
+                    false
+                }
+            };
+            if found {
+                return Err(Error::Malformed);
+            }
+        }
+        d.finish()?;
+        if kty.ok_or(Error::Malformed)? != KTY_AKP {
+            return Err(Error::UnsupportedKeyType);
+        }
+        let alg = algorithm_from_id(alg.ok_or(Error::Malformed)?)?;
+        let seed = seed.map(|s| s.try_into().map_err(|_| Error::InvalidLength)).transpose()?;
+        let key = Key::new(alg, public.ok_or(Error::Malformed)?, seed)?;
+        Ok(Key { kid, ..key })
+    }
+
+    /// Encode the CBOR map in the deterministic order kty, kid, alg, pub, priv, returns the length.
+    pub fn encode(&self, out: &mut [u8]) -> Result<usize> {
+        let mut e = Encoder::new(out);
+        e.map(3 + self.kid.is_some() as usize + self.seed.is_some() as usize)?;
+        e.int(KTY)?;
+        e.int(KTY_AKP)?;
+        if let Some(kid) = self.kid {
+            e.int(KID)?;
+            e.bytes(kid)?;
+        }
+        e.int(ALG)?;
+        e.int(algorithm_id(self.alg)?)?;
+        e.int(PUB)?;
+        e.bytes(self.public)?;
+        if let Some(seed) = self.seed {
+            e.int(PRIV)?;
+            e.bytes(seed)?;
+        }
+        Ok(e.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbor;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_key() {
+        let public = [7; 1312];
+        let key = Key::new(Algorithm::MlDsa44, &public, Some(&[1; 32])).unwrap().with_kid(b"k1");
+        let mut buf = [0; 1400];
+        let n = key.encode(&mut buf).unwrap();
+        assert_eq!(buf[..14], hex!("a5 0107 02426b31 03382f 20 590520"));
+        assert_eq!(buf[n - 35..n - 32], hex!("21 5820"));
+        assert_eq!(Key::decode(&buf[..n]), Ok(key));
+        let n = key.to_public().encode(&mut buf).unwrap();
+        assert_eq!(buf[0], 0xa4);
+        assert_eq!(Key::decode(&buf[..n]), Ok(key.to_public()));
+        assert_eq!(key.encode(&mut buf[..1000]), Err(Error::Cbor(cbor::Error::BufferTooSmall)));
+        assert_eq!(Key::new(Algorithm::MlDsa65, &public, None), Err(Error::InvalidLength));
+        assert_eq!(Key::new(Algorithm::MlKem512, &[0; 800], None), Err(Error::UnsupportedAlgorithm));
+
+        // unknown parameters, int and text labels, are ignored
+        let mut e = Encoder::new(&mut buf);
+        e.map(6).unwrap();
+        for (label, value) in [(ALG, -48), (KTY, 7), (4, 1)] {
+            e.int(label).unwrap();
+            e.int(value).unwrap();
+        }
+        e.text("x").unwrap();
+        e.array(0).unwrap();
+        e.int(PUB).unwrap();
+        e.bytes(&public).unwrap();
+        e.int(-3).unwrap();
+        e.bool(true).unwrap();
+        let n = e.finish();
+        assert_eq!(Key::decode(&buf[..n]).unwrap().public, &public[..]);
+
+        let encode = |pairs: &[(i64, &[u8])], buf: &mut [u8]| {
+            let mut e = Encoder::new(buf);
+            e.map(pairs.len()).unwrap();
+            for (label, value) in pairs {
+                e.int(*label).unwrap();
+                e.raw(value).unwrap();
+            }
+            e.finish()
+        };
+        let mut pub_cbor = [0; 1315];
+        Encoder::new(&mut pub_cbor).bytes(&public).unwrap();
+        for (pairs, err) in [
+            (&[(KTY, &[0x01][..]), (ALG, &[0x38, 0x2f]), (PUB, &pub_cbor)][..], Error::UnsupportedKeyType),
+            (&[(KTY, &[0x07]), (ALG, &[0x26]), (PUB, &pub_cbor)], Error::UnsupportedAlgorithm),
+            (&[(KTY, &[0x07]), (ALG, &[0x38, 0x30]), (PUB, &pub_cbor)], Error::InvalidLength),
+            (&[(KTY, &[0x07]), (ALG, &[0x38, 0x2f]), (PUB, &pub_cbor), (PRIV, &[0x41, 0x00])], Error::InvalidLength),
+            (&[(KTY, &[0x07]), (ALG, &[0x38, 0x2f])], Error::Malformed),
+            (&[(KTY, &[0x07]), (ALG, &[0x38, 0x2f]), (PUB, &pub_cbor), (KTY, &[0x07])], Error::Malformed),
+            (&[(KTY, &[0x07]), (ALG, &[0x63, 0x41, 0x4b, 0x50]), (PUB, &pub_cbor)], Error::Cbor(cbor::Error::UnexpectedType)),
+        ] {
+            let n = encode(pairs, &mut buf);
+            assert_eq!(Key::decode(&buf[..n]), Err(err));
+        }
+        assert_eq!(Key::decode(&hex!("80")), Err(Error::Cbor(cbor::Error::UnexpectedType)));
+        assert_eq!(Key::decode(&hex!("a0a0")), Err(Error::Cbor(cbor::Error::Malformed)));
+    }
+}
@@ -0,0 +1,71 @@
+//! COSE (RFC 9052) with ML-DSA, after the IETF COSE/JOSE ML-DSA draft, without allocation.
+//!
+//! [`Key`] is the `AKP` (Algorithm Key Pair, kty 7) COSE_Key: the algorithm is ML-DSA-44 (-48),
+//! ML-DSA-65 (-49) or ML-DSA-87 (-50), `pub` (-1) is the encoded public key and `priv` (-2) the
+//! 32 bytes seed. The parameter modules wrap it for their key types, as they do for JOSE.
+//!
+//! [`Sign1Builder`] creates a COSE_Sign1 with an attached or detached payload and [`Sign1`] parses
+//! and verifies one. The signatures use the pure ML-DSA algorithm with an empty context over the
+//! Sig_structure. Both work on caller buffers.
+
+use crate::alg::Algorithm;
+use crate::cbor::{self, Decoder};
+
+mod errors;
+mod key;
+mod sign1;
+
+pub use errors::{Error, Result};
+pub use key::Key;
+pub use sign1::{Sign1, Sign1Builder, sig_structure};
+
+/// The labels of the common header parameters.
+pub(crate) mod header {
+    pub(crate) const ALG: i64 = 1;
+    pub(crate) const CRIT: i64 = 2;
+    pub(crate) const KID: i64 = 4;
+}
+
+/// The COSE algorithm identifier.
+pub fn algorithm_id(alg: Algorithm) -> Result<i64> {
+    match alg {
+        Algorithm::MlDsa44 => Ok(-48),
+        Algorithm::MlDsa65 => Ok(-49),
+        Algorithm::MlDsa87 => Ok(-50),
+        _ => Err(Error::UnsupportedAlgorithm),
+    }
+}
+
+/// The signature algorithm of the COSE identifier.
+pub fn algorithm_from_id(id: i64) -> Result<Algorithm> {
+    Algorithm::ALL
+        .into_iter()
+        .find(|alg| algorithm_id(*alg) == Ok(id))
+        .ok_or(Error::UnsupportedAlgorithm)
+}
+
+// A map label is an int or a text string, None for a text string.
+fn read_label(d: &mut Decoder) -> Result<Option<i64>> {
+    if d.peek() == Some(cbor::TEXT) {
+        d.text()?;
+        return Ok(None);
+    }
+    Ok(Some(d.int()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algorithm_id() {
+        for (alg, id) in [(Algorithm::MlDsa44, -48), (Algorithm::MlDsa65, -49), (Algorithm::MlDsa87, -50)] {
+            assert_eq!(algorithm_id(alg), Ok(id));
+            assert_eq!(algorithm_from_id(id), Ok(alg));
+        }
+        assert_eq!(algorithm_id(Algorithm::MlKem768), Err(Error::UnsupportedAlgorithm));
+        for id in [-8, -7, -51, 0, 48] {
+            assert_eq!(algorithm_from_id(id), Err(Error::UnsupportedAlgorithm));
+        }
+    }
+}
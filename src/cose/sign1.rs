@@ -0,0 +1,289 @@
+use crate::alg::{Algorithm, Signer, Verifier};
+use crate::cbor::{Decoder, Encoder};
+use crate::mldsa::mldsa87;
+
+use super::{Error, Result, algorithm_from_id, algorithm_id, header, read_label};
+
+const TAG_SIGN1: u64 = 18;
+
+/// Encode the Sig_structure of a COSE_Sign1 into out, returns the length.
+/// 39 bytes more than the protected header, the external data and the payload are enough.
+pub fn sig_structure(protected: &[u8], external_aad: &[u8], payload: &[u8], out: &mut [u8]) -> Result<usize> {
+    let mut e = Encoder::new(out);
+    e.array(4)?;
+    e.text("Signature1")?;
+    e.bytes(protected)?;
+    e.bytes(external_aad)?;
+    e.bytes(payload)?;
+    Ok(e.finish())
+}
+
+// Read a header map into alg and kid, critical parameters are not understood.
+fn read_header<'a>(d: &mut Decoder<'a>, alg: &mut Option<i64>, kid: &mut Option<&'a [u8]>) -> Result<()> {
+    for _ in 0..d.map()? {
+        let found = match read_label(d)? {
+            Some(header::ALG) => alg.replace(d.int()?).is_some(),
+            Some(header::CRIT) => return Err(Error::UnsupportedCritical),
+            Some(header::KID) => kid.replace(d.bytes()?).is_some(),
+            _ => {
+                d.skip()?;
+                false
+            }
+        };
+        if found {
+            return Err(Error::Malformed);
+        }
+    }
+    Ok(())
+}
+
+/// A parsed COSE_Sign1, tagged or not.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sign1<'a> {
+    /// The encoded protected header.
+    pub protected: &'a [u8],
+    /// The algorithm of the protected header.
+    pub alg: Algorithm,
+    /// The kid of the protected or the unprotected header.
+    pub kid: Option<&'a [u8]>,
+    /// None if the payload is detached.
+    pub payload: Option<&'a [u8]>,
+    pub signature: &'a [u8],
+}
+
+impl<'a> Sign1<'a> {
+    pub fn decode(data: &'a [u8]) -> Result<Self> {
+        let mut d = Decoder::new(data);
+        d.optional_tag(TAG_SIGN1)?;
+        if d.array()? != 4 {
+            return Err(Error::Malformed);
+        }
+        let protected = d.bytes()?;
+        let (mut alg, mut kid, mut unprotected_alg) = (None, None, None);
+        if !protected.is_empty() {
+            let mut p = Decoder::new(protected);
+            read_header(&mut p, &mut alg, &mut kid)?;
+            p.finish()?;
+        }
+        let protected_kid = kid.take();
+        read_header(&mut d, &mut unprotected_alg, &mut kid)?;
+        // the algorithm must be protected, and a parameter must not be in both headers
+        if unprotected_alg.is_some() || (protected_kid.is_some() && kid.is_some()) {
+            return Err(Error::Malformed);
+        }
+        let payload = if d.null()? { None } else { Some(d.bytes()?) };
+        let signature = d.bytes()?;
+        d.finish()?;
+        Ok(Sign1 {
+            protected,
+            alg: algorithm_from_id(alg.ok_or(Error::Malformed)?)?,
+            kid: protected_kid.or(kid),
+            payload,
+            signature,
+        })
+    }
+
+    /// Verify the signature, with the detached payload if the payload is not attached.
+    /// buf holds the Sig_structure, see [`sig_structure`] for its length.
+    pub fn verify(&self, key: &dyn Verifier, external_aad: &[u8], detached: Option<&[u8]>, buf: &mut [u8]) -> Result<()> {
+        if self.alg != key.algorithm() {
+            return Err(Error::AlgorithmMismatch);
+        }
+        let payload = match (self.payload, detached) {
+            (Some(payload), None) | (None, Some(payload)) => payload,
+            (None, None) => return Err(Error::NoPayload),
+            (Some(_), Some(_)) => return Err(Error::Malformed),
+        };
+        let n = sig_structure(self.protected, external_aad, payload, buf)?;
+        key.verify(&buf[..n], b"", self.signature).map_err(|_| Error::Signature)
+    }
+}
+
+/// Creates a COSE_Sign1, the algorithm is in the protected header and the kid in the unprotected one.
+pub struct Sign1Builder<'a> {
+    payload: &'a [u8],
+    external_aad: &'a [u8],
+    kid: Option<&'a [u8]>,
+    detached: bool,
+    tagged: bool,
+}
+
+impl<'a> Sign1Builder<'a> {
+    pub fn new(payload: &'a [u8]) -> Self {
+        Sign1Builder { payload, external_aad: b"", kid: None, detached: false, tagged: true }
+    }
+
+    pub fn external_aad(mut self, external_aad: &'a [u8]) -> Self {
+        self.external_aad = external_aad;
+        self
+    }
+
+    pub fn kid(mut self, kid: &'a [u8]) -> Self {
+        self.kid = Some(kid);
+        self
+    }
+
+    /// Leave the payload out, it is nil in the COSE_Sign1.
+    pub fn detached(mut self, detached: bool) -> Self {
+        self.detached = detached;
+        self
+    }
+
+    /// Prefix the COSE_Sign1 with its CBOR tag 18, the default.
+    pub fn tagged(mut self, tagged: bool) -> Self {
+        self.tagged = tagged;
+        self
+    }
+
+    /// An upper bound of the output length of sign, which also holds the Sig_structure.
+    pub fn max_len(&self, alg: Algorithm) -> usize {
+        48 + self.kid.map_or(0, |kid| kid.len()) + self.external_aad.len() + self.payload.len() + alg.signature_len()
+    }
+
+    /// Sign into out, returns the length. rnd is all zero for the deterministic variant of ML-DSA.
+    pub fn sign(&self, key: &dyn Signer, rnd: &[u8; 32], out: &mut [u8]) -> Result<usize> {
+        let alg = key.algorithm();
+        let mut protected = [0; 8];
+        let mut e = Encoder::new(&mut protected);
+        e.map(1)?;
+        e.int(header::ALG)?;
+        e.int(algorithm_id(alg)?)?;
+        let n = e.finish();
+        let protected = &protected[..n];
+
+        let n = sig_structure(protected, self.external_aad, self.payload, out)?;
+        let mut signature = [0; mldsa87::siglen];
+        let signature = &mut signature[..alg.signature_len()];
+        key.sign(&out[..n], b"", rnd, signature)?;
+
+        let mut e = Encoder::new(out);
+        if self.tagged {
+            e.tag(TAG_SIGN1)?;
+        }
+        e.array(4)?;
+        e.bytes(protected)?;
+        e.map(self.kid.is_some() as usize)?;
+        if let Some(kid) = self.kid {
+            e.int(header::KID)?;
+            e.bytes(kid)?;
+        }
+        if self.detached {
+            e.null()?;
+        } else {
+            e.bytes(self.payload)?;
+        }
+        e.bytes(signature)?;
+        Ok(e.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbor;
+    use crate::mldsa::{mldsa44, mldsa65};
+    use hex_literal::hex;
+    use rand::Rng;
+
+    #[test]
+    fn test_sig_structure() {
+        let mut buf = [0; 32];
+        let n = sig_structure(&hex!("a101382f"), b"", b"hi", &mut buf).unwrap();
+        assert_eq!(buf[..n], hex!("84 6a5369676e617475726531 44a101382f 40 426869"));
+        assert_eq!(sig_structure(&hex!("a101382f"), b"", &[0; 32], &mut buf), Err(Error::Cbor(cbor::Error::BufferTooSmall)));
+    }
+
+    #[test]
+    fn test_sign1() {
+        let mut rng = rand::rng();
+        let sk = mldsa65::PrivateKey::from_seed(&rng.random());
+        let pk = sk.public_key();
+        let mut buf = vec![0; 8192];
+        let mut scratch = vec![0; 8192];
+
+        let builder = Sign1Builder::new(b"attestation").kid(b"device-1").external_aad(b"aad");
+        let n = builder.sign(&sk, &rng.random(), &mut buf).unwrap();
+        assert!(n <= builder.max_len(Algorithm::MlDsa65));
+        assert_eq!(buf[..9], hex!("d2 84 44a1013830 a1 04"));
+        let sign1 = Sign1::decode(&buf[..n]).unwrap();
+        assert_eq!(sign1.alg, Algorithm::MlDsa65);
+        assert_eq!(sign1.kid, Some(&b"device-1"[..]));
+        assert_eq!(sign1.payload, Some(&b"attestation"[..]));
+        assert_eq!(sign1.verify(&pk, b"aad", None, &mut scratch), Ok(()));
+        assert_eq!(sign1.verify(&pk, b"", None, &mut scratch), Err(Error::Signature));
+        assert_eq!(sign1.verify(&pk, b"aad", Some(b"attestation"), &mut scratch), Err(Error::Malformed));
+        assert_eq!(sign1.verify(&pk, b"aad", None, &mut scratch[..32]), Err(Error::Cbor(cbor::Error::BufferTooSmall)));
+
+        // the signature is over the Sig_structure, with an empty context
+        let m = sig_structure(sign1.protected, b"aad", b"attestation", &mut scratch).unwrap();
+        let sig = mldsa65::Signature::sig_decode(sign1.signature.try_into().unwrap()).unwrap();
+        assert!(pk.verify(&scratch[..m], b"", &sig));
+
+        // detached and untagged, deterministic
+        let builder = Sign1Builder::new(b"attestation").detached(true).tagged(false);
+        let n = builder.sign(&sk, &[0; 32], &mut buf).unwrap();
+        let mut buf2 = vec![0; 8192];
+        assert_eq!(builder.sign(&sk, &[0; 32], &mut buf2), Ok(n));
+        assert_eq!(buf[..n], buf2[..n]);
+        assert_eq!(buf[..8], hex!("84 44a1013830 a0 f6"));
+        let sign1 = Sign1::decode(&buf[..n]).unwrap();
+        assert_eq!((sign1.kid, sign1.payload), (None, None));
+        assert_eq!(sign1.verify(&pk, b"", None, &mut scratch), Err(Error::NoPayload));
+        assert_eq!(sign1.verify(&pk, b"", Some(b"attestation"), &mut scratch), Ok(()));
+        assert_eq!(sign1.verify(&pk, b"", Some(b"attestatioN"), &mut scratch), Err(Error::Signature));
+
+        let other = mldsa44::PrivateKey::from_seed(&rng.random());
+        assert_eq!(sign1.verify(&other.public_key(), b"", Some(b"attestation"), &mut scratch), Err(Error::AlgorithmMismatch));
+        assert_eq!(
+            Sign1Builder::new(b"").sign(&sk, &[0; 32], &mut buf[..3000]),
+            Err(Error::Cbor(cbor::Error::BufferTooSmall))
+        );
+    }
+
+    #[test]
+    fn test_sign1_invalid() {
+        let sig = [0; 2420];
+        let encode = |protected: &[u8], unprotected: &[u8], buf: &mut [u8]| {
+            let mut e = Encoder::new(buf);
+            e.tag(TAG_SIGN1).unwrap();
+            e.array(4).unwrap();
+            e.bytes(protected).unwrap();
+            e.raw(unprotected).unwrap();
+            e.bytes(b"payload").unwrap();
+            e.bytes(&sig).unwrap();
+            e.finish()
+        };
+        let mut buf = [0; 2500];
+        let n = encode(&hex!("a101382f"), &hex!("a1044131"), &mut buf);
+        let sign1 = Sign1::decode(&buf[..n]).unwrap();
+        assert_eq!((sign1.alg, sign1.kid), (Algorithm::MlDsa44, Some(&b"1"[..])));
+        let pk = mldsa44::PrivateKey::from_seed(&[0; 32]).public_key();
+        assert_eq!(sign1.verify(&pk, b"", None, &mut [0; 100]), Err(Error::Signature));
+        // the kid in the protected header, other parameters are ignored
+        let n = encode(&hex!("a3 01382f 0441 32 03 6474657874"), &hex!("a1 6178 f5"), &mut buf);
+        assert_eq!(Sign1::decode(&buf[..n]).unwrap().kid, Some(&b"2"[..]));
+
+        for (protected, unprotected, err) in [
+            (&hex!("")[..], &hex!("a1 01382f")[..], Error::Malformed),
+            (&hex!("a0"), &hex!("a0"), Error::Malformed),
+            (&hex!("a101382f"), &hex!("a1 01382f"), Error::Malformed),
+            (&hex!("a2 01382f 0441 32"), &hex!("a1 044131"), Error::Malformed),
+            (&hex!("a2 01382f 01382f"), &hex!("a0"), Error::Malformed),
+            (&hex!("a2 01382f 028101"), &hex!("a0"), Error::UnsupportedCritical),
+            (&hex!("a101382f"), &hex!("a1 028101"), Error::UnsupportedCritical),
+            (&hex!("a10126"), &hex!("a0"), Error::UnsupportedAlgorithm),
+            (&hex!("a101382f00"), &hex!("a0"), Error::Cbor(cbor::Error::Malformed)),
+            (&hex!("a101382f"), &hex!("80"), Error::Cbor(cbor::Error::UnexpectedType)),
+        ] {
+            let n = encode(protected, unprotected, &mut buf);
+            assert_eq!(Sign1::decode(&buf[..n]), Err(err), "{protected:02x?} {unprotected:02x?}");
+        }
+        // a COSE_Sign1 has 4 items and no trailing data
+        assert_eq!(Sign1::decode(&hex!("d2 83 44a101382f a0 40")), Err(Error::Malformed));
+        let n = encode(&hex!("a101382f"), &hex!("a0"), &mut buf);
+        assert_eq!(Sign1::decode(&buf[..n + 1]), Err(Error::Cbor(cbor::Error::Malformed)));
+        // another tag
+        buf[0] = 0xd1;
+        assert_eq!(Sign1::decode(&buf[..n]), Err(Error::Cbor(cbor::Error::UnexpectedType)));
+    }
+}
@@ -0,0 +1,160 @@
+//! HKDF (RFC 5869) over any [`Hash`], with [`Hmac`] as the PRF.
+
+use thiserror;
+
+use crate::hmac::Hmac;
+use crate::sha2;
+use crate::sha3::{self, Hash};
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("output longer than 255 hash lengths")]
+    OutputTooLong,
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// HKDF-Extract, the pseudorandom key HMAC(salt, ikm). An empty salt is the same HMAC key as the N
+/// zero bytes of RFC 5869.
+pub fn extract<H: Hash<N> + Clone, const N: usize>(h: &H, salt: &[u8], ikm: &[u8]) -> [u8; N] {
+    extract_parts(h, salt, &[ikm])
+}
+
+/// HKDF-Expand of the pseudorandom key into out, at most 255 * N bytes.
+pub fn expand<H: Hash<N> + Clone, const N: usize>(h: &H, prk: &[u8], info: &[u8], out: &mut [u8]) -> Result<()> {
+    if out.len() > 255 * N {
+        return Err(Error::OutputTooLong);
+    }
+    expand_parts(h, prk, &[info], out);
+    Ok(())
+}
+
+/// HKDF-Extract then HKDF-Expand.
+pub fn hkdf<H: Hash<N> + Clone, const N: usize>(
+    h: &H,
+    salt: &[u8],
+    ikm: &[u8],
+    info: &[u8],
+    out: &mut [u8],
+) -> Result<()> {
+    expand(h, &extract(h, salt, ikm), info, out)
+}
+
+// extract with ikm the concatenation of the parts
+pub(crate) fn extract_parts<H: Hash<N> + Clone, const N: usize>(h: &H, salt: &[u8], ikm: &[&[u8]]) -> [u8; N] {
+    let mut mac = Hmac::new(h.clone(), salt);
+    for d in ikm {
+        mac.write(d);
+    }
+    mac.sum()
+}
+
+// expand with info the concatenation of the parts, out is at most 255 * N bytes.
+pub(crate) fn expand_parts<H: Hash<N> + Clone, const N: usize>(h: &H, prk: &[u8], info: &[&[u8]], out: &mut [u8]) {
+    let mac = Hmac::new(h.clone(), prk);
+    let mut t = [0; N];
+    for (i, chunk) in out.chunks_mut(N).enumerate() {
+        let mut mac = mac.clone();
+        if i > 0 {
+            mac.write(&t);
+        }
+        for d in info {
+            mac.write(d);
+        }
+        mac.write(&[i as u8 + 1]);
+        t = mac.sum();
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+}
+
+pub fn extract_sha256(salt: &[u8], ikm: &[u8]) -> [u8; 32] {
+    extract(&sha2::new256(), salt, ikm)
+}
+
+pub fn expand_sha256(prk: &[u8], info: &[u8], out: &mut [u8]) -> Result<()> {
+    expand(&sha2::new256(), prk, info, out)
+}
+
+pub fn extract_sha3_256(salt: &[u8], ikm: &[u8]) -> [u8; 32] {
+    extract(&sha3::new256(), salt, ikm)
+}
+
+pub fn expand_sha3_256(prk: &[u8], info: &[u8], out: &mut [u8]) -> Result<()> {
+    expand(&sha3::new256(), prk, info, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sm3;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_hkdf_sha256() {
+        // RFC 5869 A.1, A.2 and A.3
+        let prk = extract_sha256(&hex!("000102030405060708090a0b0c"), &[0x0b; 22]);
+        assert_eq!(prk, hex!("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"));
+        let mut okm = [0; 42];
+        expand_sha256(&prk, &hex!("f0f1f2f3f4f5f6f7f8f9"), &mut okm).unwrap();
+        assert_eq!(
+            okm,
+            hex!("3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865")
+        );
+
+        let ikm: [u8; 80] = core::array::from_fn(|i| i as u8);
+        let salt: [u8; 80] = core::array::from_fn(|i| 0x60 + i as u8);
+        let info: [u8; 80] = core::array::from_fn(|i| 0xb0 + i as u8);
+        let mut okm = [0; 82];
+        hkdf(&sha2::new256(), &salt, &ikm, &info, &mut okm).unwrap();
+        assert_eq!(
+            okm,
+            hex!(
+                "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c"
+                "59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71"
+                "cc30c58179ec3e87c14c01d5c1f3434f1d87"
+            )
+        );
+
+        let mut okm = [0; 42];
+        hkdf(&sha2::new256(), &[], &[0x0b; 22], &[], &mut okm).unwrap();
+        assert_eq!(
+            okm,
+            hex!("8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8")
+        );
+
+        assert!(expand_sha256(&prk, &[], &mut [0; 255 * 32]).is_ok());
+        assert_eq!(expand_sha256(&prk, &[], &mut [0; 255 * 32 + 1]), Err(Error::OutputTooLong));
+    }
+
+    #[test]
+    fn test_hkdf_sha3_256() {
+        // the inputs of RFC 5869 A.1 and A.3 with SHA3-256
+        let prk = extract_sha3_256(&hex!("000102030405060708090a0b0c"), &[0x0b; 22]);
+        assert_eq!(prk, hex!("7d4194836f7a113a44677abc825640ade07af1c1d69a9a4b109b280a8fe54ef0"));
+        let mut okm = [0; 42];
+        expand_sha3_256(&prk, &hex!("f0f1f2f3f4f5f6f7f8f9"), &mut okm).unwrap();
+        assert_eq!(
+            okm,
+            hex!("0c5160501d65021deaf2c14f5abce04c5bd2635abceeba61c2edb6e8ed72674900557728f2c9f2c4c179")
+        );
+
+        let prk = extract_sha3_256(&[], &[0x0b; 22]);
+        assert_eq!(prk, hex!("b899e6e4b88a35f9f5d618f48b424c313f9704012763eb6295414d673365928a"));
+        expand_sha3_256(&prk, &[], &mut okm).unwrap();
+        assert_eq!(
+            okm,
+            hex!("bc1342cdd75c05e8b0c3ae609ce4410684d197232875073499b30cdfe2de2853c1c1bed63d725e885e78")
+        );
+    }
+
+    #[test]
+    fn test_hkdf_sm3() {
+        let mut okm = [0; 42];
+        hkdf(&sm3::new(), &hex!("000102030405060708090a0b0c"), &[0x0b; 22], &hex!("f0f1f2f3f4f5f6f7f8f9"), &mut okm)
+            .unwrap();
+        assert_eq!(
+            okm,
+            hex!("c69fe91b7aaee2dd5718d72dcaee0cce93f1b8e41f792da51261b6a517e68b36ed2c595572b01dfa359b")
+        );
+    }
+}
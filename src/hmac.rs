@@ -0,0 +1,244 @@
+//! HMAC (RFC 2104, FIPS 198-1) over any [`Hash`], itself a [`Hash`] so that HKDF and the KEM
+//! combiners can take it like any other hash function.
+
+use crate::sha2;
+use crate::sha3::{self, Digest, Hash};
+use crate::sm3;
+
+#[derive(Clone)]
+pub struct Hmac<H, const N: usize> {
+    // the inner hash with the key absorbed, to reset to
+    ipad: H,
+    inner: H,
+    outer: H,
+}
+
+impl<H: Hash<N> + Clone, const N: usize> Hmac<H, N> {
+    /// HMAC with the hash function h, reset first, and the key.
+    ///
+    /// A key longer than the block is hashed first. A hash function with a digest longer than its
+    /// block is keyed with the whole digest of such a key.
+    pub fn new(h: H, key: &[u8]) -> Self {
+        let mut h = h;
+        h.reset();
+        let b = h.block_size();
+
+        let mut hashed = [0; N];
+        let key = if key.len() > b {
+            h.write(key);
+            h.sum_into(&mut hashed);
+            h.reset();
+            &hashed[..]
+        } else {
+            key
+        };
+        let (mut ipad, mut outer) = (h.clone(), h);
+        write_padded(&mut ipad, key, b, 0x36);
+        write_padded(&mut outer, key, b, 0x5c);
+        hashed.fill(0);
+        Hmac { inner: ipad.clone(), ipad, outer }
+    }
+}
+
+// write the key zero padded to b bytes xor pad, a block at a time of any block size
+fn write_padded<H: Hash<N>, const N: usize>(h: &mut H, key: &[u8], b: usize, pad: u8) {
+    let mut buf = [0; 64];
+    let n = key.len().max(b);
+    for i in (0..n).step_by(buf.len()) {
+        let chunk = &mut buf[..(n - i).min(64)];
+        for (j, x) in chunk.iter_mut().enumerate() {
+            *x = key.get(i + j).copied().unwrap_or(0) ^ pad;
+        }
+        h.write(chunk);
+    }
+    buf.fill(0);
+}
+
+impl<H: Hash<N> + Clone, const N: usize> Hash<N> for Hmac<H, N> {
+    /// Start a new message with the same key.
+    fn reset(&mut self) {
+        self.inner = self.ipad.clone();
+    }
+
+    fn write(&mut self, data: &[u8]) {
+        self.inner.write(data);
+    }
+
+    fn sum_into(&self, digest: &mut [u8]) {
+        let mut outer = self.outer.clone();
+        outer.write(&self.inner.sum());
+        outer.sum_into(digest);
+    }
+
+    fn block_size(&self) -> usize {
+        self.outer.block_size()
+    }
+
+    fn size(&self) -> usize {
+        N
+    }
+}
+
+pub fn new_sha256(key: &[u8]) -> Hmac<sha2::Sha256<32>, 32> {
+    Hmac::new(sha2::new256(), key)
+}
+
+pub fn new_sha384(key: &[u8]) -> Hmac<sha2::Sha512<48>, 48> {
+    Hmac::new(sha2::new384(), key)
+}
+
+pub fn new_sha512(key: &[u8]) -> Hmac<sha2::Sha512<64>, 64> {
+    Hmac::new(sha2::new512(), key)
+}
+
+pub fn new_sha3_256(key: &[u8]) -> Hmac<Digest<32>, 32> {
+    Hmac::new(sha3::new256(), key)
+}
+
+pub fn new_sha3_384(key: &[u8]) -> Hmac<Digest<48>, 48> {
+    Hmac::new(sha3::new384(), key)
+}
+
+pub fn new_sha3_512(key: &[u8]) -> Hmac<Digest<64>, 64> {
+    Hmac::new(sha3::new512(), key)
+}
+
+pub fn new_sm3(key: &[u8]) -> Hmac<sm3::Sm3, 32> {
+    Hmac::new(sm3::new(), key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_hmac_sha2() {
+        // RFC 4231 test cases 1 and 6
+        let mut h = new_sha256(&[0x0b; 20]);
+        h.write(b"Hi There");
+        assert_eq!(h.sum(), hex!("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"));
+        h.write(b"discarded");
+        h.reset();
+        h.write(b"Hi ");
+        h.write(b"There");
+        assert_eq!(h.sum(), hex!("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"));
+
+        let mut h = new_sha512(&[0x0b; 20]);
+        h.write(b"Hi There");
+        assert_eq!(
+            h.sum(),
+            hex!(
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde"
+                "daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"
+            )
+        );
+
+        let mut h = new_sha256(&[0xaa; 131]);
+        h.write(b"Test Using Larger Than Block-Size Key - Hash Key First");
+        assert_eq!(h.sum(), hex!("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"));
+    }
+
+    #[test]
+    fn test_hmac_sha3() {
+        // the HMAC-SHA3 samples of NIST, keys shorter, equal to and longer than the block
+        let key: [u8; 168] = core::array::from_fn(|i| i as u8);
+        for (key, data, expect) in [
+            (
+                &key[..32],
+                &b"Sample message for keylen<blocklen"[..],
+                hex!("4fe8e202c4f058e8dddc23d8c34e467343e23555e24fc2f025d598f558f67205"),
+            ),
+            (
+                &key[..136],
+                b"Sample message for keylen=blocklen",
+                hex!("68b94e2e538a9be4103bebb5aa016d47961d4d1aa906061313b557f8af2c3faa"),
+            ),
+            (
+                &key[..168],
+                b"Sample message for keylen>blocklen",
+                hex!("9bcf2c238e235c3ce88404e813bd2f3a97185ac6f238c63d6229a00b07974258"),
+            ),
+        ] {
+            let mut h = new_sha3_256(key);
+            h.write(data);
+            assert_eq!(h.sum(), expect);
+        }
+
+        let mut h = new_sha3_512(&key[..64]);
+        h.write(b"Sample message for keylen<blocklen");
+        assert_eq!(
+            h.sum(),
+            hex!(
+                "4efd629d6c71bf86162658f29943b1c308ce27cdfa6db0d9c3ce81763f9cbce5"
+                "f7ebe9868031db1a8f8eb7b6b95e5c5e3f657a8996c86a2f6527e307f0213196"
+            )
+        );
+    }
+
+    // SHA-256 with a block of 200 bytes, larger than any block of the crate
+    #[derive(Clone)]
+    struct WideBlock(sha2::Sha256<32>);
+
+    impl Hash<32> for WideBlock {
+        fn reset(&mut self) {
+            self.0.reset();
+        }
+
+        fn write(&mut self, data: &[u8]) {
+            self.0.write(data);
+        }
+
+        fn sum_into(&self, digest: &mut [u8]) {
+            self.0.sum_into(digest);
+        }
+
+        fn block_size(&self) -> usize {
+            200
+        }
+
+        fn size(&self) -> usize {
+            32
+        }
+    }
+
+    #[test]
+    fn test_hmac_block_size() {
+        // H(K ^ opad || H(K ^ ipad || m)) with K padded to the 200 bytes block
+        let key = [0x0b; 20];
+        let hmac = |key: &[u8; 200]| {
+            let mut inner = sha2::new256();
+            inner.write(&key.map(|x| x ^ 0x36));
+            inner.write(b"Hi There");
+            let mut outer = sha2::new256();
+            outer.write(&key.map(|x| x ^ 0x5c));
+            outer.write(&inner.sum());
+            outer.sum()
+        };
+        let mut padded = [0; 200];
+        padded[..20].copy_from_slice(&key);
+
+        // usable as a trait object
+        let mut h = Hmac::new(WideBlock(sha2::new256()), &key);
+        let d: &mut dyn Hash<32> = &mut h;
+        d.write(b"Hi There");
+        assert_eq!(d.sum(), hmac(&padded));
+        assert_eq!(d.block_size(), 200);
+
+        // a key longer than the block is hashed first
+        let mut h = Hmac::new(WideBlock(sha2::new256()), &[0xaa; 201]);
+        h.write(b"Hi There");
+        let mut hashed = sha2::new256();
+        hashed.write(&[0xaa; 201]);
+        let mut padded = [0; 200];
+        padded[..32].copy_from_slice(&hashed.sum());
+        assert_eq!(h.sum(), hmac(&padded));
+    }
+
+    #[test]
+    fn test_hmac_sm3() {
+        let mut h = new_sm3(&[0x0b; 20]);
+        h.write(b"Hi There");
+        assert_eq!(h.sum(), hex!("51b00d1fb49832bfb01c3ce27848e59f871d9ba938dc563b338ca964755cce70"));
+    }
+}
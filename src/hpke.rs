@@ -0,0 +1,853 @@
+//! HPKE (RFC 9180) base and PSK modes with the post-quantum KEMs, without allocation.
+//!
+//! The KEMs are ML-KEM-512 (0x0040), ML-KEM-768 (0x0041) and ML-KEM-1024 (0x0042) of
+//! draft-ietf-hpke-pq and X-Wing (0x647a). The KEM shared secret is used as is, enc is the KEM
+//! ciphertext, the public key is the encoded encapsulation key and the private key is the seed,
+//! 64 bytes d || z for ML-KEM and 32 bytes for X-Wing. DHKEM(X25519, HKDF-SHA256) (0x0020) of
+//! RFC 9180 is there for the hybrid deployments and the test vectors, its private key is the
+//! X25519 private key and the encapsulation randomness is the ikm of DeriveKeyPair. The KDF is
+//! HKDF-SHA256 (0x0001) and the AEAD is AES-128-GCM (0x0001), AES-256-GCM (0x0002) or export-only
+//! (0xffff).
+//!
+//! The key schedule, the nonces and the secret export follow RFC 9180. The sender calls
+//! [`Suite::setup_base_s`] or [`Suite::setup_psk_s`] and sends enc, the recipient calls
+//! [`Suite::setup_base_r`] or [`Suite::setup_psk_r`]. The ciphertexts are the plaintexts followed
+//! by the 16 bytes tag.
+
+use rand::CryptoRng;
+use thiserror;
+
+use crate::aes::{self, Aes, gcm};
+use crate::hkdf;
+use crate::mlkem::{mlkem512, mlkem768, mlkem1024};
+use crate::sha2;
+use crate::x25519::{x25519, x25519_base};
+use crate::xwing;
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("invalid length")]
+    InvalidLength,
+
+    #[error("invalid public key")]
+    InvalidPublicKey,
+
+    #[error("inconsistent PSK inputs")]
+    InconsistentPsk,
+
+    #[error("decryption failed")]
+    Open,
+
+    #[error("message limit reached")]
+    MessageLimitReached,
+
+    #[error("export-only AEAD")]
+    ExportOnly,
+
+    #[error("output buffer too small")]
+    BufferTooSmall,
+}
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kem {
+    MlKem512 = 0x0040,
+    MlKem768 = 0x0041,
+    MlKem1024 = 0x0042,
+    XWing = 0x647a,
+    X25519Sha256 = 0x0020,
+}
+
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kdf {
+    HkdfSha256 = 0x0001,
+}
+
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aead {
+    Aes128Gcm = 0x0001,
+    Aes256Gcm = 0x0002,
+    ExportOnly = 0xffff,
+}
+
+/// cbindgen:ignore
+impl Kem {
+    pub const ALL: [Kem; 5] = [Kem::MlKem512, Kem::MlKem768, Kem::MlKem1024, Kem::XWing, Kem::X25519Sha256];
+
+    pub fn id(self) -> u16 {
+        self as u16
+    }
+
+    pub fn from_id(id: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|kem| kem.id() == id)
+    }
+
+    /// Npk, the length of the encoded public key.
+    pub fn public_key_len(self) -> usize {
+        match self {
+            Kem::MlKem512 => mlkem512::ek_len,
+            Kem::MlKem768 => mlkem768::ek_len,
+            Kem::MlKem1024 => mlkem1024::ek_len,
+            Kem::XWing => xwing::ek_len,
+            Kem::X25519Sha256 => 32,
+        }
+    }
+
+    /// Nsk, the length of the private key seed.
+    pub fn private_key_len(self) -> usize {
+        match self {
+            Kem::XWing => xwing::dk_len,
+            Kem::X25519Sha256 => 32,
+            _ => 64,
+        }
+    }
+
+    /// Nenc, the length of the KEM ciphertext.
+    pub fn enc_len(self) -> usize {
+        match self {
+            Kem::MlKem512 => mlkem512::cipher_len,
+            Kem::MlKem768 => mlkem768::cipher_len,
+            Kem::MlKem1024 => mlkem1024::cipher_len,
+            Kem::XWing => xwing::cipher_len,
+            Kem::X25519Sha256 => 32,
+        }
+    }
+
+    /// The length of the randomness of the derandomized encapsulation.
+    pub fn encap_rand_len(self) -> usize {
+        match self {
+            Kem::XWing => 64,
+            _ => 32,
+        }
+    }
+
+    /// Write the public key of the private key seed, returns its length.
+    pub fn public_key(self, sk: &[u8], pk: &mut [u8]) -> Result<usize> {
+        let len = self.public_key_len();
+        let pk = pk.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        match self {
+            Kem::MlKem512 => mlkem512::DecapKey::from_seed(fixed(sk)?)
+                .encapsulation_key_ref()
+                .byte_encode_inplace(pk.try_into().unwrap()),
+            Kem::MlKem768 => mlkem768::DecapKey::from_seed(fixed(sk)?)
+                .encapsulation_key_ref()
+                .byte_encode_inplace(pk.try_into().unwrap()),
+            Kem::MlKem1024 => mlkem1024::DecapKey::from_seed(fixed(sk)?)
+                .encapsulation_key_ref()
+                .byte_encode_inplace(pk.try_into().unwrap()),
+            Kem::XWing => xwing::DecapKey::from_seed(fixed(sk)?)
+                .encapsulation_key_ref()
+                .byte_encode_inplace(pk.try_into().unwrap()),
+            Kem::X25519Sha256 => pk.copy_from_slice(&x25519_base(fixed(sk)?)),
+        }
+        Ok(len)
+    }
+
+    // Encapsulate to pk with the randomness, writes enc.
+    fn encap(self, pk: &[u8], rand: &[u8], enc: &mut [u8]) -> Result<[u8; 32]> {
+        let enc = enc.get_mut(..self.enc_len()).ok_or(Error::BufferTooSmall)?;
+        let invalid = |_| Error::InvalidPublicKey;
+        let ss = match self {
+            Kem::MlKem512 => {
+                let ek = mlkem512::EncapKey::byte_decode(fixed(pk)?).map_err(invalid)?;
+                let (ss, c) = ek.encaps_internal(fixed(rand)?);
+                enc.copy_from_slice(&c);
+                ss
+            }
+            Kem::MlKem768 => {
+                let ek = mlkem768::EncapKey::byte_decode(fixed(pk)?).map_err(invalid)?;
+                let (ss, c) = ek.encaps_internal(fixed(rand)?);
+                enc.copy_from_slice(&c);
+                ss
+            }
+            Kem::MlKem1024 => {
+                let ek = mlkem1024::EncapKey::byte_decode(fixed(pk)?).map_err(invalid)?;
+                let (ss, c) = ek.encaps_internal(fixed(rand)?);
+                enc.copy_from_slice(&c);
+                ss
+            }
+            Kem::XWing => {
+                let (ss, c) = xwing::EncapKey::byte_decode(fixed(pk)?).map_err(invalid)?.encaps_internal(fixed(rand)?);
+                enc.copy_from_slice(&c);
+                ss
+            }
+            Kem::X25519Sha256 => {
+                let (sk_e, pk_e) = self.derive_key_pair(fixed::<32>(rand)?);
+                let ss = self.extract_and_expand(&x25519(&sk_e, fixed(pk)?), &pk_e, fixed(pk)?)?;
+                enc.copy_from_slice(&pk_e);
+                ss
+            }
+        };
+        Ok(ss)
+    }
+
+    fn decap(self, enc: &[u8], sk: &[u8]) -> Result<[u8; 32]> {
+        Ok(match self {
+            Kem::MlKem512 => mlkem512::DecapKey::from_seed(fixed(sk)?).decaps(fixed(enc)?),
+            Kem::MlKem768 => mlkem768::DecapKey::from_seed(fixed(sk)?).decaps(fixed(enc)?),
+            Kem::MlKem1024 => mlkem1024::DecapKey::from_seed(fixed(sk)?).decaps(fixed(enc)?),
+            Kem::XWing => xwing::DecapKey::from_seed(fixed(sk)?).decaps(fixed(enc)?),
+            Kem::X25519Sha256 => {
+                let sk = fixed(sk)?;
+                self.extract_and_expand(&x25519(sk, fixed(enc)?), fixed(enc)?, &x25519_base(sk))?
+            }
+        })
+    }
+
+    fn suite_id(self) -> [u8; 5] {
+        let mut id = *b"KEM\0\0";
+        id[3..].copy_from_slice(&self.id().to_be_bytes());
+        id
+    }
+
+    // DeriveKeyPair of DHKEM(X25519), x25519 clamps the private key.
+    fn derive_key_pair(self, ikm: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+        let suite_id = self.suite_id();
+        let prk = labeled_extract(&suite_id, &[], b"dkp_prk", ikm);
+        let mut sk = [0; 32];
+        labeled_expand(&suite_id, &prk, b"sk", &[], &mut sk);
+        (sk, x25519_base(&sk))
+    }
+
+    // ExtractAndExpand of DHKEM, the all-zero shared secret of a small order point is rejected.
+    fn extract_and_expand(self, dh: &[u8; 32], pk_e: &[u8; 32], pk_r: &[u8; 32]) -> Result<[u8; 32]> {
+        if dh.iter().fold(0, |acc, b| acc | b) == 0 {
+            return Err(Error::InvalidPublicKey);
+        }
+        let suite_id = self.suite_id();
+        let mut kem_context = [0; 64];
+        kem_context[..32].copy_from_slice(pk_e);
+        kem_context[32..].copy_from_slice(pk_r);
+        let prk = labeled_extract(&suite_id, &[], b"eae_prk", dh);
+        let mut ss = [0; 32];
+        labeled_expand(&suite_id, &prk, b"shared_secret", &kem_context, &mut ss);
+        Ok(ss)
+    }
+}
+
+// A fixed length input.
+fn fixed<const N: usize>(b: &[u8]) -> Result<&[u8; N]> {
+    b.try_into().map_err(|_| Error::InvalidLength)
+}
+
+/// cbindgen:ignore
+impl Kdf {
+    pub const ALL: [Kdf; 1] = [Kdf::HkdfSha256];
+
+    pub fn id(self) -> u16 {
+        self as u16
+    }
+
+    pub fn from_id(id: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|kdf| kdf.id() == id)
+    }
+}
+
+/// cbindgen:ignore
+impl Aead {
+    pub const ALL: [Aead; 3] = [Aead::Aes128Gcm, Aead::Aes256Gcm, Aead::ExportOnly];
+
+    pub fn id(self) -> u16 {
+        self as u16
+    }
+
+    pub fn from_id(id: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|aead| aead.id() == id)
+    }
+
+    /// Nk, 0 for export-only.
+    pub fn key_len(self) -> usize {
+        match self {
+            Aead::Aes128Gcm => 16,
+            Aead::Aes256Gcm => 32,
+            Aead::ExportOnly => 0,
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////
+//  HKDF-SHA256 (RFC 5869)
+/////////////////////////////////////////////////////////////////////
+
+const NH: usize = 32;
+
+fn extract(salt: &[u8], ikm: &[&[u8]]) -> [u8; NH] {
+    hkdf::extract_parts(&sha2::new256(), salt, ikm)
+}
+
+// out is at most 255 * NH bytes.
+fn expand(prk: &[u8; NH], info: &[&[u8]], out: &mut [u8]) {
+    hkdf::expand_parts(&sha2::new256(), prk, info, out);
+}
+
+fn labeled_extract(suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> [u8; NH] {
+    extract(salt, &[b"HPKE-v1", suite_id, label, ikm])
+}
+
+fn labeled_expand(suite_id: &[u8], prk: &[u8; NH], label: &[u8], info: &[u8], out: &mut [u8]) {
+    let len = (out.len() as u16).to_be_bytes();
+    expand(prk, &[&len, b"HPKE-v1", suite_id, label, info], out);
+}
+
+/////////////////////////////////////////////////////////////////////
+//  key schedule
+/////////////////////////////////////////////////////////////////////
+
+const MODE_BASE: u8 = 0x00;
+const MODE_PSK: u8 = 0x01;
+
+/// An HPKE cipher suite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Suite {
+    pub kem: Kem,
+    pub kdf: Kdf,
+    pub aead: Aead,
+}
+
+impl Suite {
+    pub fn new(kem: Kem, kdf: Kdf, aead: Aead) -> Self {
+        Suite { kem, kdf, aead }
+    }
+
+    fn suite_id(&self) -> [u8; 10] {
+        let mut id = *b"HPKE\0\0\0\0\0\0";
+        id[4..6].copy_from_slice(&self.kem.id().to_be_bytes());
+        id[6..8].copy_from_slice(&self.kdf.id().to_be_bytes());
+        id[8..].copy_from_slice(&self.aead.id().to_be_bytes());
+        id
+    }
+
+    fn labeled_extract(&self, salt: &[u8], label: &[u8], ikm: &[u8]) -> [u8; NH] {
+        labeled_extract(&self.suite_id(), salt, label, ikm)
+    }
+
+    fn labeled_expand(&self, prk: &[u8; NH], label: &[u8], info: &[u8], out: &mut [u8]) {
+        labeled_expand(&self.suite_id(), prk, label, info, out)
+    }
+
+    fn key_schedule(
+        &self,
+        mode: u8,
+        shared_secret: &[u8; 32],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<Context> {
+        if psk.is_empty() != psk_id.is_empty() || psk.is_empty() != (mode == MODE_BASE) {
+            return Err(Error::InconsistentPsk);
+        }
+        let mut context = [0; 1 + 2 * NH];
+        context[0] = mode;
+        context[1..1 + NH].copy_from_slice(&self.labeled_extract(&[], b"psk_id_hash", psk_id));
+        context[1 + NH..].copy_from_slice(&self.labeled_extract(&[], b"info_hash", info));
+        let mut secret = self.labeled_extract(shared_secret, b"secret", psk);
+
+        let mut key = [0; 32];
+        let key = &mut key[..self.aead.key_len()];
+        let mut base_nonce = [0; gcm::NONCE_SIZE];
+        let mut exporter_secret = [0; NH];
+        let aes = if self.aead == Aead::ExportOnly {
+            None
+        } else {
+            self.labeled_expand(&secret, b"key", &context, key);
+            self.labeled_expand(&secret, b"base_nonce", &context, &mut base_nonce);
+            Some(Aes::new(key).unwrap())
+        };
+        self.labeled_expand(&secret, b"exp", &context, &mut exporter_secret);
+        key.fill(0);
+        secret.fill(0);
+        Ok(Context { suite: *self, aes, base_nonce, seq: 0, exporter_secret })
+    }
+
+    /// The derandomized sender setup, psk is (psk, psk_id) in the PSK mode and rand is
+    /// [`Kem::encap_rand_len`] bytes. Writes enc and returns its length.
+    pub fn setup_s_internal(
+        &self,
+        pk_r: &[u8],
+        info: &[u8],
+        psk: Option<(&[u8], &[u8])>,
+        rand: &[u8],
+        enc: &mut [u8],
+    ) -> Result<(usize, SenderContext)> {
+        let (mode, (psk, psk_id)) = match psk {
+            Some(psk) => (MODE_PSK, psk),
+            None => (MODE_BASE, (&[][..], &[][..])),
+        };
+        let ss = self.kem.encap(pk_r, rand, enc)?;
+        let ctx = self.key_schedule(mode, &ss, info, psk, psk_id)?;
+        Ok((self.kem.enc_len(), SenderContext(ctx)))
+    }
+
+    fn setup_s(
+        &self,
+        pk_r: &[u8],
+        info: &[u8],
+        psk: Option<(&[u8], &[u8])>,
+        rng: &mut dyn CryptoRng,
+        enc: &mut [u8],
+    ) -> Result<(usize, SenderContext)> {
+        let mut rand = [0; 64];
+        let rand = &mut rand[..self.kem.encap_rand_len()];
+        rng.fill_bytes(rand);
+        self.setup_s_internal(pk_r, info, psk, rand, enc)
+    }
+
+    /// SetupBaseS, writes enc and returns its length with the context.
+    pub fn setup_base_s(
+        &self,
+        pk_r: &[u8],
+        info: &[u8],
+        rng: &mut dyn CryptoRng,
+        enc: &mut [u8],
+    ) -> Result<(usize, SenderContext)> {
+        self.setup_s(pk_r, info, None, rng, enc)
+    }
+
+    /// SetupPSKS, psk and psk_id must not be empty.
+    pub fn setup_psk_s(
+        &self,
+        pk_r: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        rng: &mut dyn CryptoRng,
+        enc: &mut [u8],
+    ) -> Result<(usize, SenderContext)> {
+        self.setup_s(pk_r, info, Some((psk, psk_id)), rng, enc)
+    }
+
+    /// SetupBaseR, sk_r is the private key seed.
+    pub fn setup_base_r(&self, enc: &[u8], sk_r: &[u8], info: &[u8]) -> Result<RecipientContext> {
+        let ss = self.kem.decap(enc, sk_r)?;
+        Ok(RecipientContext(self.key_schedule(MODE_BASE, &ss, info, &[], &[])?))
+    }
+
+    /// SetupPSKR, psk and psk_id must not be empty.
+    pub fn setup_psk_r(
+        &self,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<RecipientContext> {
+        if psk.is_empty() {
+            return Err(Error::InconsistentPsk);
+        }
+        let ss = self.kem.decap(enc, sk_r)?;
+        Ok(RecipientContext(self.key_schedule(MODE_PSK, &ss, info, psk, psk_id)?))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////
+//  encryption contexts
+/////////////////////////////////////////////////////////////////////
+
+#[derive(Clone)]
+struct Context {
+    suite: Suite,
+    aes: Option<Aes>,
+    base_nonce: [u8; gcm::NONCE_SIZE],
+    seq: u64,
+    exporter_secret: [u8; NH],
+}
+
+impl Context {
+    fn nonce(&self) -> Result<[u8; gcm::NONCE_SIZE]> {
+        if self.seq == u64::MAX {
+            return Err(Error::MessageLimitReached);
+        }
+        let mut nonce = self.base_nonce;
+        for (n, s) in nonce[4..].iter_mut().zip(self.seq.to_be_bytes()) {
+            *n ^= s;
+        }
+        Ok(nonce)
+    }
+
+    fn export(&self, exporter_context: &[u8], out: &mut [u8]) -> Result<()> {
+        if out.len() > 255 * NH {
+            return Err(Error::InvalidLength);
+        }
+        self.suite.labeled_expand(&self.exporter_secret, b"sec", exporter_context, out);
+        Ok(())
+    }
+}
+
+/// The sender's encryption context.
+#[derive(Clone)]
+pub struct SenderContext(Context);
+
+/// The recipient's encryption context.
+#[derive(Clone)]
+pub struct RecipientContext(Context);
+
+impl SenderContext {
+    /// Seal the plaintext into out, returns the ciphertext length, plaintext.len() + 16.
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8], out: &mut [u8]) -> Result<usize> {
+        let ctx = &mut self.0;
+        let aes = ctx.aes.as_ref().ok_or(Error::ExportOnly)?;
+        let len = plaintext.len() + gcm::TAG_SIZE;
+        let out = out.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        let nonce = ctx.nonce()?;
+        let (text, tag) = out.split_at_mut(plaintext.len());
+        text.copy_from_slice(plaintext);
+        tag.copy_from_slice(&gcm::seal(aes, &nonce, aad, text));
+        ctx.seq += 1;
+        Ok(len)
+    }
+
+    /// Export a secret of out.len() bytes, at most 255 * 32.
+    pub fn export(&self, exporter_context: &[u8], out: &mut [u8]) -> Result<()> {
+        self.0.export(exporter_context, out)
+    }
+}
+
+impl RecipientContext {
+    /// Open the ciphertext into out, returns the plaintext length, ciphertext.len() - 16.
+    /// The sequence number is not advanced if the decryption fails.
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8], out: &mut [u8]) -> Result<usize> {
+        let ctx = &mut self.0;
+        let aes = ctx.aes.as_ref().ok_or(Error::ExportOnly)?;
+        let len = ciphertext.len().checked_sub(gcm::TAG_SIZE).ok_or(Error::Open)?;
+        let out = out.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        let nonce = ctx.nonce()?;
+        let (text, tag) = ciphertext.split_at(len);
+        out.copy_from_slice(text);
+        gcm::open(aes, &nonce, aad, out, tag.try_into().unwrap()).map_err(|e| match e {
+            aes::Error::Tag => Error::Open,
+            _ => Error::InvalidLength,
+        })?;
+        ctx.seq += 1;
+        Ok(len)
+    }
+
+    /// Export a secret of out.len() bytes, at most 255 * 32.
+    pub fn export(&self, exporter_context: &[u8], out: &mut [u8]) -> Result<()> {
+        self.0.export(exporter_context, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_vectors() {
+        // RFC 9180 A.1.1 and A.1.2, DHKEM(X25519, HKDF-SHA256), HKDF-SHA256 and AES-128-GCM in the
+        // base and PSK modes.
+        let suite = Suite::new(Kem::X25519Sha256, Kdf::HkdfSha256, Aead::Aes128Gcm);
+        let info = b"Ode on a Grecian Urn";
+        for v in &VECTORS {
+            assert_eq!(suite.kem.derive_key_pair(&v.ikm_r), (v.sk_r, v.pk_r));
+            let mut pk = [0; 32];
+            assert_eq!(suite.kem.public_key(&v.sk_r, &mut pk), Ok(32));
+            assert_eq!(pk, v.pk_r);
+
+            let psk = v.psk.as_ref().map(|(psk, psk_id)| (&psk[..], *psk_id));
+            let mut enc = [0; 32];
+            let (_, mut s) = suite.setup_s_internal(&pk, info, psk, &v.ikm_e, &mut enc).unwrap();
+            assert_eq!(enc, v.enc);
+            let mut r = match psk {
+                Some((psk, psk_id)) => suite.setup_psk_r(&enc, &v.sk_r, info, psk, psk_id),
+                None => suite.setup_base_r(&enc, &v.sk_r, info),
+            }
+            .unwrap();
+
+            let mut ct = [0; 45];
+            let mut pt = [0; 29];
+            for (i, expected) in v.ct.iter().enumerate() {
+                let aad = [b'C', b'o', b'u', b'n', b't', b'-', b'0' + i as u8];
+                assert_eq!(s.seal(&aad, b"Beauty is truth, truth beauty", &mut ct), Ok(45));
+                assert_eq!(ct, *expected);
+                assert_eq!(r.open(&aad, &ct, &mut pt), Ok(29));
+                assert_eq!(&pt, b"Beauty is truth, truth beauty");
+            }
+            let mut out = [0; 32];
+            for (exporter_context, exported) in [&b""[..], &[0], b"TestContext"].iter().zip(&v.exported) {
+                s.export(exporter_context, &mut out).unwrap();
+                assert_eq!(out, *exported);
+                r.export(exporter_context, &mut out).unwrap();
+                assert_eq!(out, *exported);
+            }
+        }
+    }
+
+    struct Vector {
+        ikm_e: [u8; 32],
+        ikm_r: [u8; 32],
+        sk_r: [u8; 32],
+        pk_r: [u8; 32],
+        psk: Option<([u8; 32], &'static [u8])>,
+        enc: [u8; 32],
+        // the ciphertexts of the sequence numbers 0 and 1
+        ct: [[u8; 45]; 2],
+        // the exporter contexts "", 0x00 and "TestContext"
+        exported: [[u8; 32]; 3],
+    }
+
+    const VECTORS: [Vector; 2] = [
+        Vector {
+            ikm_e: hex!("7268600d403fce431561aef583ee1613527cff655c1343f29812e66706df3234"),
+            ikm_r: hex!("6db9df30aa07dd42ee5e8181afdb977e538f5e1fec8a06223f33f7013e525037"),
+            sk_r: hex!("4612c550263fc8ad58375df3f557aac531d26850903e55a9f23f21d8534e8ac8"),
+            pk_r: hex!("3948cfe0ad1ddb695d780e59077195da6c56506b027329794ab02bca80815c4d"),
+            psk: None,
+            enc: hex!("37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431"),
+            ct: [
+                hex!("f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a"),
+                hex!("af2d7e9ac9ae7e270f46ba1f975be53c09f8d875bdc8535458c2494e8a6eab251c03d0c22a56b8ca42c2063b84"),
+            ],
+            exported: [
+                hex!("3853fe2b4035195a573ffc53856e77058e15d9ea064de3e59f4961d0095250ee"),
+                hex!("2e8f0b54673c7029649d4eb9d5e33bf1872cf76d623ff164ac185da9e88c21a5"),
+                hex!("e9e43065102c3836401bed8c3c3c75ae46be1639869391d62c61f1ec7af54931"),
+            ],
+        },
+        Vector {
+            ikm_e: hex!("78628c354e46f3e169bd231be7b2ff1c77aa302460a26dbfa15515684c00130b"),
+            ikm_r: hex!("d4a09d09f575fef425905d2ab396c1449141463f698f8efdb7accfaff8995098"),
+            sk_r: hex!("c5eb01eb457fe6c6f57577c5413b931550a162c71a03ac8d196babbd4e5ce0fd"),
+            pk_r: hex!("9fed7e8c17387560e92cc6462a68049657246a09bfa8ade7aefe589672016366"),
+            psk: Some((
+                hex!("0247fd33b913760fa1fa51e1892d9f307fbe65eb171e8132c2af18555a738b82"),
+                b"Ennyn Durin aran Moria",
+            )),
+            enc: hex!("0ad0950d9fb9588e59690b74f1237ecdf1d775cd60be2eca57af5a4b0471c91b"),
+            ct: [
+                hex!("e52c6fed7f758d0cf7145689f21bc1be6ec9ea097fef4e959440012f4feb73fb611b946199e681f4cfc34db8ea"),
+                hex!("49f3b19b28a9ea9f43e8c71204c00d4a490ee7f61387b6719db765e948123b45b61633ef059ba22cd62437c8ba"),
+            ],
+            exported: [
+                hex!("dff17af354c8b41673567db6259fd6029967b4e1aad13023c2ae5df8f4f43bf6"),
+                hex!("6a847261d8207fe596befb52928463881ab493da345b10e1dcc645e3b94e2d95"),
+                hex!("8aff52b45a1be3a734bc7a41e20b4e055ad4c4d22104b0c20285a7c4302401cd"),
+            ],
+        },
+    ];
+
+    #[test]
+    fn test_openssl() {
+        // Sealed by the single-shot HPKE of OpenSSL 4.0 with HKDF-SHA256 and AES-128-GCM, enc || ct
+        // without aad. The private key seeds are the bytes 0, 1, ...
+        let sk: [u8; 64] = core::array::from_fn(|i| i as u8);
+        for (kem, sealed) in SEALED {
+            let suite = Suite::new(kem, Kdf::HkdfSha256, Aead::Aes128Gcm);
+            let (enc, ct) = sealed.split_at(kem.enc_len());
+            let mut r = suite.setup_base_r(enc, &sk[..kem.private_key_len()], b"Ode on a Grecian Urn").unwrap();
+            let mut pt = [0; 29];
+            assert_eq!(r.open(b"", ct, &mut pt), Ok(29));
+            assert_eq!(&pt, b"Beauty is truth, truth beauty");
+        }
+    }
+
+    const SEALED: [(Kem, &[u8]); 4] = [
+        (
+            Kem::MlKem768,
+            &hex!(
+                "0a6015c218aceed999f52d6fa47e0a0e00c13313aea176c5aacbba2e7cfb1970003aafe74ff325c33884e686b0a9936d"
+                "ef9b1cc114f4dcf3c8ee7c9d6a6167b66bd81787ee56ef0a22d081d7d659f37a9b1904b1153b36781e20553892fb457d"
+                "6d19a3cea0ad4c884141833e6d5e62295f50860f0f91bb98bdcf30c6032e60abeb9edcc55ba5b56842df2d82af09adff"
+                "5e90d4df64690b007a5269f9d6bcc00ea39d7684601521884db526b84dc2ce95ea86a74c002ee1bbeb5078c1a20f70dd"
+                "aa3f52be54fbed603917432b36aa825a4ff6792501553d0a79615b1960b652baec407054b3f8a40ae97a25e287e25bb8"
+                "5e3b8288c1304cee5555d2b82b91d455b8bf321ab100f124ee944ed938ee683da2d8abaf51f906950be56435096489dc"
+                "234f5eb56cbb00a1d408d7b0b207be11993ab08450a3100253bfd17c6cecaf76e4d72f41076e7214f3e22ac46b671041"
+                "32d7ecaa4c81d678f3c005e5d34a82cfb49580f92ce31beda62796e728fc6559a78e22d43c55d209959539c4b91d2e7e"
+                "348805e87b91817212303840565fb1b0c62739f700992629feecd29c3093d458f0e676ff121f54b82c166f2e215a5a33"
+                "fcea63c1fef94c2005a63244058c56d6e8a5bd59ee9971eb5f8d92c17d85a36a35efee13fac24d72cebf5d64e068a952"
+                "bd19316a7bd2a117490b864f0db0881320e5f4d1efaae34292ad1ecc28e26acf5fdb34828ae0e29f13e4ce9122b8036b"
+                "edcb6983e02e3c17ab5e4943561b0cbec7c1446e3beacbb36e38a6406c9a533ae77c314cb74215daeef85c71eaef231a"
+                "2c4265a29648b3d32a5c10e89904b42a22bda189723aa2f1499b171ad9955f51131d88245155840c68999492adcd390f"
+                "c5216bba611e7793039c3ddcf14f5e34f7f6aa6b359ed263e3e3a7231ce5a51e7881b0d091a3a76f10cb5225fdc21ba1"
+                "d8aad630ca2e6742cfc6dc498e9a4faac1c5ed898ededf3b6077b7dd92e444366470658f992b950619b6f2da76d0366b"
+                "9875312d7bd3348710ccfc6e3da2edce1588392d8e8040e607505cae95ab48b6a53dd04cce9edc1fee2b07e2ae795148"
+                "597c142b68f8b85e4b92db40ead244342a29f6d4b1c2c25917136527ccee2fe76a59d8ce381e9f67822def169a31afb1"
+                "83f4c9a4148033b4e63216a1c027c87a4f94a3fc0f0936766f936c050b1f9791c86edba1340cfb5583e58c5aaa208daa"
+                "20b4e6acad9c59ec8298535abf6d4da033e9ac9c045df9a665e1e9c511ed7a6f4b41ccd0d9a7448ff70fd8bfe694c90f"
+                "a4d8c1cf87969fcce10d620b7da5402ac6bba1e53ed4b69534ee3365c58ccbbdf2dd7942021b785f8f1d02ad0e7c621d"
+                "c19b83de5b0e2a9e8052846aac9241edb58ceef6a95c3b446e2d9e690cb422ce2fafcf0c6fe2b3d97fe6619cdc5f6035"
+                "15a19c6b7cc0ecb5c1165bda9c9a3e76bf933c7e26ada4e93ade7d78a715e4bc970a28faa6ff9924dc6e1082837ea232"
+                "b4529ff16b88fe7fa65154da1d380c902ac806e121878a193478cd07e44fae6db9f17298d2e71f282061076721734eff"
+                "c27e7da4308441800a35e0efbf653a0ddcddea3eb1a00614781f014015"
+            ),
+        ),
+        (
+            Kem::MlKem1024,
+            &hex!(
+                "d220fed0f4853d8f83b2263ffaf8bdabd732ae1eb005152402be22015b8f03cfadb8f1184be0c5127ea2c07030f4bcfa"
+                "579c2f07687aa978e4c0c1214831c8353693c46e603261eecd6a182603135f37e9857ff844c1d2e77396fd15da0a5358"
+                "e6570aafc5f3c6824be35924168afd81f2af3c0bceb739959a0189e85f40708847b1cc6815c43dc2f58fce958a7eaefc"
+                "f9951c29217ebd01637705f834a735d0c6d7eb00858a3736f9b09b9499d2f7c83a0fd7d0aea60d84fe563fb579c88567"
+                "9c108ca3b947333c940152268da9d7067b0b18976323fe7409be451f18e3f1bf43112793871aa3a0594f44a6aa4a04e6"
+                "029809b8029e0d0fb9e68a8f15fe26c0c414671a849c5e8228d5b56bd9e2ce687dc8cac910c0a7a9618591bd67a93d8c"
+                "d773a838c2c59bf4c1136e1dc33158ac20483624a5495762bba88dfe9c15f2bf3f5739a0984c93fc5b6452b9a022e450"
+                "4ed9a7628e024e6896f279bd7bcec10334828ee0e1011cd6ba143f5b828f92ea02b6bb1b3ca619d5ec6ba06007d0630c"
+                "8fe3fcf8a7751f92104797cf5aaaa0beb6bab96bb0a3ee81462f412f75154adab09e5bff474d0fe563072fb7d09dc497"
+                "724a8f358991a6290aecd724b8deafa1ce9e7998a16b4503f7a8bb9c213f0ef3a4a84f6600d06f6458f75f9be3d4a5d7"
+                "62d8c56aa5c1713721281004f1148b4bdb5aa823f39f3cf746cd8a58de9390cb9003a0f125a0dc2799729e57074c6df6"
+                "c6d6d0fef2cbc2e00fdbafd55a5993b0f70a7b9f3d7c48a14ad4bb4164b2fc23945e7c53c176fae47a3a33ae6a1abd19"
+                "dfb23786ef7a902cd9f670934d59be7ce1b0dbba0b9e1a97c7264737e166247aac8b4b7964987a98e908567afde7bab1"
+                "e47c40c4250b3b0cf7e612dd456c5e8eb4d6f6a5ced6f03be66418fcfff0a1a048a6af6f776b11b4deb1f67dd7e02a80"
+                "308825cc8815eeaecc1743c78002987e25c924b259db89d68a69cf2722cc6288f872f17e351160ff6fd596fb065bdf76"
+                "a69848cc27bc50ea95edbf7132dc8e7aeba5b267f96ea28f9f7331e29eda3b60d2c3cef81eebf4d2070c3b8f9d642ab6"
+                "e86876e8af99692bc402896eec8b3dbfcc1e15e43b99ae45b1b3b500ec7821919e81f8240c8c414970d8438e493221de"
+                "6490d964cc4a30ebebcec9fb2bed3da7d01521bd4334acd150a43baa644eab454355d38da597645334f6057a84d703db"
+                "d053fc94872b093d993c195ca460779971e8c9e9fb2a8a40b181b8303c8ebef6da0a511e718cf779dcf8fd86a9a26b4e"
+                "848ed3113d1f95b1517dbc91d0af18dec7a2352c9913c9cff1589cc1b95df5f3a7203d38a6d138a88e369aa01f2df29b"
+                "ea1891f8dd9566a26de176bc32215787be7b1bf209fc330bed34392db8e71046823b715fb0739490d6596048f5f25ab0"
+                "2a45b0cb166e7949395e7f88b85f225f77216607ca56cddf9d4423adc0168681ebd1790fb16cf93a3fa866dd366ac9b3"
+                "02720a264f7f54acc31200f4b026121efd177be6657ba7c50de0224391e40651a2ab8ef23edf8b7886aa452382e39af3"
+                "f11316dad324d484d9f6b2b7c6fa51c3163a4c82d4977b0b0839ccc9804bc58c33a3bb9e9d940c5f4230136993049fea"
+                "c3cff77eed70e3966c2ca5e9d17ccf2803735b6f279df0c5fcd7b89782451a8d1779f7b05698158d4109ce901204d9b7"
+                "b626b1508c458943adc57c9971b12a10104a9e3b97400b336984c72d1e573d19c7c00374c25ab0110e7195f3fc2860b3"
+                "6b0309f898720c98dee2c3cb15ecced19171ddacee1ba417405c8e3a18e677760bda4fbfa8d0d42eccb29c88f7f82611"
+                "216786934ea6432a900349499400d4a22ad69e205ea91d585c73200969d88fed7b62af771127dd53d189816107ceeac9"
+                "8ae18bbbad79ac476771878bbbd5e8c8a1d26755fa42176760f94415e1a54f37fc977fb03c23201cc3e3c4c8136c97d5"
+                "8f438330d1cea5196706d60893dbad4e3a8b31aab581de8d2557f0d4cff51a90d28d4345600ebecec179c02d180ad25f"
+                "7eb8f8cf9f645483f766017a57fd544bafb7793490ef5c2e1510a2be4af7b869475e7a0011fdd80e43179a358f8c4f43"
+                "49a4af84394536f6d9e915d93b0d5e53e974ef23ec72e25cb11cc67951b9cd965d82bde577d9d8c1034dc91ab3864380"
+                "0d5cded01382caf0a6f447e25fdd7848075aa378066b08b935258ad8e96f6813453fd3b2840684f0988b02918aa04672"
+                "37c7c152c156c8563237425e9d660cbe7dba0e799cb6296d7eabcaacf4"
+            ),
+        ),
+        (
+            Kem::XWing,
+            &hex!(
+                "c1888961d9f8e5c626d23cc4938719bd1951e17114a2514082b0d5a5e6384c4eb6ba40941746113ab79a57733523dad4"
+                "669316cdf040108b6e4d695325d94e58f4c879259958ee8d2e1cc37e0dea555f03910379be4ee890f55b63cb01e4e450"
+                "e51874882b3eefabde25e983f70bd8018fa491b299f237a266bf2d228631c07eea380a0dd0400010bfaceab5248d3a6b"
+                "8b11b6979a548941dbbe3b128b044fd178d553afb189826d68dd9d48d63e1175bba6be16ff0557f369f5cbd88a74891a"
+                "ed357b4f374316c5bd9eef269bc4e61d974b0e4c9ade5293c85c272ad7c92f6ea4c563d433865e3999e5242daa927995"
+                "38ad473e0833adec7110eba932c25ad8592f4daf983820ba191f45876b81d501f4e3bfe05b1c4922f2eb3e4245160094"
+                "4d0ae6f8ba11fd7daeeddab27f788f4b463cab5aa9fd6db0d679fc6598d0e824ddd4c5154271c3de8cf4d87608c421e6"
+                "fb81cba797154efbb864d9efde2407f8d1dd5de56274e796e21f68795a306b17da7118b8be5b4caf7342176c481ef7bd"
+                "ac849f68167d3109e5a367a6cf252c3a03b5c3c7460925bd6ebb92912e39f7787bd26151a89314e199d57805e6e33780"
+                "22ee410813f843512804baeb0d9e4635f600c751fec973a437340d731485129600b20cd6e101a1b42bec313b8b4bf429"
+                "79b8b8d33d84aa5d3dc3472bfd6a25fcde90f33763bff3385ee85b536c44c74d97612b81119c5740f9f15516cf285d18"
+                "c385249b7ba75883a4b777863c25e0c4e610fe6995ab93449a0462753072a9f246aadfa9ce48188e9e546571c0b3cfd1"
+                "d0c81565e3d7f6d8bb072281c4db869ba95f04afe083132fe4f755e740c90857c7b49aa3892717ae65c24dfed72e122c"
+                "dc770cfcdecaf3976c8c2a6d92a3b68a8047cf40c839126711e7e21276f088385781b7cb5bc317682c7fd37ce92ed203"
+                "2a70204961b66e5f996662fe686728090df5abe6d97c8b8f3530e0cc55b5ccf7a7602b9e6205afb671e9813fe8ecad1a"
+                "114dcf041ae4c9ee4376ab62382bfbc914287243fa5e12aa218e5d3eef38cdbb119d835f82588c516851b30227297ef9"
+                "b508ca55cca059094ebd6896a3eef5e66cfd7c51afa02a53b101956522a7394f278f21e98625dc918581c49a6c455bdb"
+                "72e53fb220c6a56e7eec582f0a102742898920ca94739de998abf733974efcb3576c2cefd568e4ff0f20e9653fa6c944"
+                "0c238b889db007b2e42aab719d60a9b18f28c2a1a075ed8fefd5b1f5eac575450d4acfa568a23adccf344eef2ad9c488"
+                "775b90d5cb6ff0b3d45bfee73b012b56d5907539a35a499aef65f085c1e2cbaf0beed0f0dd866d62fafc035c757558b1"
+                "d70339436d607b67adde1fb484c10091ef30830b4a82304d1e209aca2ca961bceb9d8d4ddb7938edaad352320f979f40"
+                "aa53f000d0613873793327ffd0cee0d917e2a89b64a06f720bc8109cc91f827a9eb4c48fa3cef8acad32406c945e7ca8"
+                "d10b09d467cea1c6337a9dd1de8c9c547d47a3c691b05c26039b17ba9a5570d3caca5357463b804a3532c8b8826eb88f"
+                "cdeabe2dae8f5e1aec72c14c8f3fe6674f3e44a1c8c789f87efb27a9d6cdebff2a76ff0f3c11e353ca61e89b0df4cd26"
+                "ca6c53dda845d855237096c51d"
+            ),
+        ),
+        (
+            Kem::X25519Sha256,
+            &hex!(
+                "1a7932ccfa6e8fc301737ae3c76342124b21c26247293c3dc2e2bcd3d932ff2044c9b2641822baa97d8d287cc52dee3f"
+                "f90dea9c6f091b91badade9e5c8ed0b670a13b60e315c3915c7146f43f"
+            ),
+        ),
+    ];
+
+    #[test]
+    fn test_hpke() {
+        let mut rng = rand::rng();
+        let mut pk = [0; 1568];
+        let mut enc = [0; 1568];
+        let mut ct = [0; 64];
+        let mut pt = [0; 48];
+        for kem in Kem::ALL {
+            assert_eq!(Kem::from_id(kem.id()), Some(kem));
+            for aead in [Aead::Aes128Gcm, Aead::Aes256Gcm] {
+                let suite = Suite::new(kem, Kdf::HkdfSha256, aead);
+                let sk = [7; 64];
+                let sk = &sk[..kem.private_key_len()];
+                let n = kem.public_key(sk, &mut pk).unwrap();
+                assert_eq!(n, kem.public_key_len());
+
+                let (e, mut s) = suite.setup_base_s(&pk[..n], b"info", &mut rng, &mut enc).unwrap();
+                assert_eq!(e, kem.enc_len());
+                let mut r = suite.setup_base_r(&enc[..e], sk, b"info").unwrap();
+                for i in 0..3 {
+                    let c = s.seal(&[i], &[i; 48], &mut ct).unwrap();
+                    assert_eq!(r.open(&[i], &ct[..c], &mut pt), Ok(48));
+                    assert_eq!(pt, [i; 48]);
+                }
+
+                // a failed open does not advance the sequence number
+                let c = s.seal(b"", b"x", &mut ct).unwrap();
+                ct[0] ^= 1;
+                assert_eq!(r.open(b"", &ct[..c], &mut pt), Err(Error::Open));
+                ct[0] ^= 1;
+                assert_eq!(r.open(b"", &ct[..c], &mut pt), Ok(1));
+
+                // a different info or PSK gives a different key
+                let mut r = suite.setup_base_r(&enc[..e], sk, b"other").unwrap();
+                let c = s.seal(b"", b"x", &mut ct).unwrap();
+                assert_eq!(r.open(b"", &ct[..c], &mut pt), Err(Error::Open));
+
+                let (e, mut s) = suite.setup_psk_s(&pk[..n], b"", &[1; 32], b"id", &mut rng, &mut enc).unwrap();
+                let mut r = suite.setup_psk_r(&enc[..e], sk, b"", &[1; 32], b"id").unwrap();
+                let c = s.seal(b"", b"psk", &mut ct).unwrap();
+                assert_eq!(r.open(b"", &ct[..c], &mut pt), Ok(3));
+                let mut r = suite.setup_psk_r(&enc[..e], sk, b"", &[2; 32], b"id").unwrap();
+                assert_eq!(r.open(b"", &ct[..c], &mut pt), Err(Error::Open));
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut rng = rand::rng();
+        let suite = Suite::new(Kem::MlKem512, Kdf::HkdfSha256, Aead::ExportOnly);
+        let sk = [1; 64];
+        let mut pk = [0; 800];
+        let mut enc = [0; 768];
+        suite.kem.public_key(&sk, &mut pk).unwrap();
+        assert_eq!(suite.kem.public_key(&sk, &mut [0; 799]), Err(Error::BufferTooSmall));
+        assert_eq!(suite.kem.public_key(&sk[1..], &mut pk), Err(Error::InvalidLength));
+
+        // export-only contexts export the same secret and do not encrypt
+        let (_, mut s) = suite.setup_base_s(&pk, b"", &mut rng, &mut enc).unwrap();
+        let mut r = suite.setup_base_r(&enc, &sk, b"").unwrap();
+        let (mut a, mut b) = ([0; 100], [0; 100]);
+        s.export(b"ctx", &mut a).unwrap();
+        r.export(b"ctx", &mut b).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(s.export(b"ctx", &mut [0; 255 * 32 + 1]), Err(Error::InvalidLength));
+        assert_eq!(s.seal(b"", b"", &mut [0; 16]), Err(Error::ExportOnly));
+        assert_eq!(r.open(b"", &[0; 16], &mut []), Err(Error::ExportOnly));
+
+        // the modulus check of the encapsulation key and the input lengths
+        let mut bad = pk;
+        bad[..2].copy_from_slice(&[0xff, 0xff]);
+        assert_eq!(suite.setup_base_s(&bad, b"", &mut rng, &mut enc).err(), Some(Error::InvalidPublicKey));
+        assert_eq!(suite.setup_base_s(&pk[1..], b"", &mut rng, &mut enc).err(), Some(Error::InvalidLength));
+        assert_eq!(suite.setup_base_s(&pk, b"", &mut rng, &mut enc[1..]).err(), Some(Error::BufferTooSmall));
+        assert_eq!(suite.setup_base_r(&enc[1..], &sk, b"").err(), Some(Error::InvalidLength));
+        assert_eq!(suite.setup_s_internal(&pk, b"", None, &[0; 31], &mut enc).err(), Some(Error::InvalidLength));
+
+        // the PSK and its id are both given in the PSK mode
+        assert_eq!(suite.setup_psk_s(&pk, b"", &[], b"id", &mut rng, &mut enc).err(), Some(Error::InconsistentPsk));
+        assert_eq!(suite.setup_psk_s(&pk, b"", &[1; 32], b"", &mut rng, &mut enc).err(), Some(Error::InconsistentPsk));
+        assert_eq!(suite.setup_psk_r(&enc, &sk, b"", &[], b"").err(), Some(Error::InconsistentPsk));
+
+        // DHKEM rejects the all-zero shared secret of a low order point
+        let suite = Suite::new(Kem::X25519Sha256, Kdf::HkdfSha256, Aead::ExportOnly);
+        assert_eq!(suite.setup_base_s(&[0; 32], b"", &mut rng, &mut enc).err(), Some(Error::InvalidPublicKey));
+        assert_eq!(suite.setup_base_r(&[0; 32], &sk[..32], b"").err(), Some(Error::InvalidPublicKey));
+
+        let suite = Suite::new(Kem::MlKem512, Kdf::HkdfSha256, Aead::Aes128Gcm);
+        let (_, mut s) = suite.setup_base_s(&pk, b"", &mut rng, &mut enc).unwrap();
+        let mut r = suite.setup_base_r(&enc, &sk, b"").unwrap();
+        assert_eq!(s.seal(b"", b"abc", &mut [0; 18]), Err(Error::BufferTooSmall));
+        assert_eq!(r.open(b"", &[0; 15], &mut [0; 16]), Err(Error::Open));
+        s.0.seq = u64::MAX;
+        assert_eq!(s.seal(b"", b"abc", &mut [0; 19]), Err(Error::MessageLimitReached));
+        assert_eq!(Aead::from_id(0xffff), Some(Aead::ExportOnly));
+        assert_eq!(Kdf::from_id(0x0002), None);
+    }
+}
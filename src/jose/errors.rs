@@ -0,0 +1,43 @@
+use thiserror;
+
+use crate::{alg, json, pkcs};
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error(transparent)]
+    Json(#[from] json::Error),
+
+    #[error(transparent)]
+    Pkcs(#[from] pkcs::Error),
+
+    #[error(transparent)]
+    Alg(#[from] alg::Error),
+
+    #[error("malformed JOSE object")]
+    Malformed,
+
+    #[error("unsupported key type")]
+    UnsupportedKeyType,
+
+    #[error("unsupported algorithm")]
+    UnsupportedAlgorithm,
+
+    #[error("algorithm does not match the key")]
+    AlgorithmMismatch,
+
+    #[error("invalid key length")]
+    InvalidLength,
+
+    #[error("public and private key mismatch")]
+    KeyMismatch,
+
+    #[error("no private key in the JWK")]
+    NoPrivateKey,
+
+    #[error("unsupported critical header parameter")]
+    UnsupportedCritical,
+
+    #[error("signature verification failed")]
+    Signature,
+}
+pub type Result<T> = core::result::Result<T, Error>;
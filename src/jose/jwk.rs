@@ -0,0 +1,110 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::alg::Algorithm;
+use crate::json::Value;
+use crate::pkcs::pem::{base64url_decode, base64url_encode};
+
+use super::{Error, Result, algorithm_from_name, parse_object};
+
+/// The `kty` of the ML-DSA keys.
+pub const KEY_TYPE: &str = "AKP";
+
+/// An ML-DSA JSON Web Key, the private key is the seed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Jwk {
+    pub alg: Algorithm,
+    pub public: Vec<u8>,
+    pub seed: Option<[u8; 32]>,
+    pub kid: Option<String>,
+}
+
+impl Jwk {
+    /// The public key must be of the algorithm, the seed is not checked against it.
+    pub fn new(alg: Algorithm, public: &[u8], seed: Option<&[u8; 32]>) -> Result<Self> {
+        if !alg.is_signature() {
+            return Err(Error::UnsupportedAlgorithm);
+        }
+        if public.len() != alg.public_key_len() {
+            return Err(Error::InvalidLength);
+        }
+        Ok(Jwk { alg, public: public.to_vec(), seed: seed.copied(), kid: None })
+    }
+
+    pub fn with_kid(mut self, kid: &str) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    /// The key without the seed.
+    pub fn to_public(&self) -> Self {
+        Jwk { seed: None, ..self.clone() }
+    }
+
+    /// Parse the JSON object, unknown members are ignored and duplicate members rejected.
+    pub fn from_json(s: &str) -> Result<Self> {
+        let v = parse_object(s)?;
+        let member = |name| v.get(name).map(|v| v.as_str().ok_or(Error::Malformed)).transpose();
+        if member("kty")?.ok_or(Error::Malformed)? != KEY_TYPE {
+            return Err(Error::UnsupportedKeyType);
+        }
+        let alg = algorithm_from_name(member("alg")?.ok_or(Error::Malformed)?)?;
+        let public = base64url_decode(member("pub")?.ok_or(Error::Malformed)?)?;
+        let seed = member("priv")?
+            .map(|s| base64url_decode(s)?.try_into().map_err(|_| Error::InvalidLength))
+            .transpose()?;
+        let mut jwk = Jwk::new(alg, &public, seed.as_ref())?;
+        jwk.kid = member("kid")?.map(String::from);
+        Ok(jwk)
+    }
+
+    /// The compact JSON object, members in the order kty, alg, kid, pub, priv.
+    pub fn to_json(&self) -> String {
+        let mut o = vec![("kty".into(), KEY_TYPE.into()), ("alg".into(), self.alg.name().into())];
+        if let Some(kid) = &self.kid {
+            o.push(("kid".into(), kid.as_str().into()));
+        }
+        o.push(("pub".into(), base64url_encode(&self.public).into()));
+        if let Some(seed) = &self.seed {
+            o.push(("priv".into(), base64url_encode(seed).into()));
+        }
+        format!("{}", Value::Object(o))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jwk() {
+        let public = [7; 1312];
+        let jwk = Jwk::new(Algorithm::MlDsa44, &public, Some(&[1; 32])).unwrap().with_kid("k1");
+        let s = jwk.to_json();
+        assert!(s.starts_with(r#"{"kty":"AKP","alg":"ML-DSA-44","kid":"k1","pub":"BwcH"#));
+        assert!(s.ends_with(r#""priv":"AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE"}"#));
+        assert_eq!(Jwk::from_json(&s), Ok(jwk.clone()));
+        let public_jwk = jwk.to_public();
+        assert!(!public_jwk.to_json().contains("priv"));
+        assert_eq!(Jwk::from_json(&public_jwk.to_json()), Ok(public_jwk));
+
+        let pub_b64 = base64url_encode(&public);
+        let ok = format!(r#"{{"pub":"{pub_b64}","use":"sig","alg":"ML-DSA-44","kty":"AKP"}}"#);
+        assert_eq!(Jwk::from_json(&ok).unwrap().public, public);
+        for (s, err) in [
+            (format!(r#"{{"kty":"OKP","alg":"ML-DSA-44","pub":"{pub_b64}"}}"#), Error::UnsupportedKeyType),
+            (format!(r#"{{"kty":"AKP","alg":"ML-DSA-65","pub":"{pub_b64}"}}"#), Error::InvalidLength),
+            (format!(r#"{{"kty":"AKP","alg":"ML-KEM-512","pub":"{pub_b64}"}}"#), Error::UnsupportedAlgorithm),
+            (format!(r#"{{"kty":"AKP","alg":"ML-DSA-44","pub":"{pub_b64}","priv":"AQE"}}"#), Error::InvalidLength),
+            (format!(r#"{{"kty":"AKP","alg":"ML-DSA-44","pub":"{pub_b64}","kid":1}}"#), Error::Malformed),
+            (format!(r#"{{"kty":"AKP","pub":"{pub_b64}"}}"#), Error::Malformed),
+            (r#"{"kty":"AKP","alg":"ML-DSA-44","pub":"*"}"#.into(), Error::Pkcs(crate::pkcs::Error::Base64)),
+            (r#"["AKP"]"#.into(), Error::Malformed),
+            (format!(r#"{{"kty":"AKP","alg":"ML-DSA-44","pub":"{pub_b64}","pub":"{pub_b64}"}}"#), Error::Malformed),
+            (format!(r#"{{"kty":"AKP","alg":"ML-DSA-44","alg":"ML-DSA-65","pub":"{pub_b64}"}}"#), Error::Malformed),
+            (format!(r#"{{"kty":"AKP","alg":"ML-DSA-44","pub":"{pub_b64}","priv":"","priv":""}}"#), Error::Malformed),
+        ] {
+            assert_eq!(Jwk::from_json(&s), Err(err), "{s}");
+        }
+        assert!(matches!(Jwk::from_json("{"), Err(Error::Json(_))));
+    }
+}
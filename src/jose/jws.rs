@@ -0,0 +1,173 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::alg::{Algorithm, Signer, Verifier};
+use crate::json::Value;
+use crate::pkcs::pem::{base64url_decode, base64url_encode};
+
+use super::{Error, Result, algorithm_from_name, parse_object};
+
+/// The protected header of a JWS.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub alg: Algorithm,
+    pub kid: Option<String>,
+    pub typ: Option<String>,
+}
+
+impl Header {
+    pub fn new(alg: Algorithm) -> Self {
+        Header { alg, kid: None, typ: None }
+    }
+
+    pub fn with_kid(mut self, kid: &str) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    pub fn with_typ(mut self, typ: &str) -> Self {
+        self.typ = Some(typ.into());
+        self
+    }
+
+    fn to_json(&self) -> String {
+        let mut o = vec![("alg".into(), self.alg.name().into())];
+        if let Some(kid) = &self.kid {
+            o.push(("kid".into(), kid.as_str().into()));
+        }
+        if let Some(typ) = &self.typ {
+            o.push(("typ".into(), typ.as_str().into()));
+        }
+        format!("{}", Value::Object(o))
+    }
+
+    // Critical extensions are not understood, so crit (and with it b64 of RFC 7797) is rejected.
+    fn from_json(s: &str) -> Result<Self> {
+        let v = parse_object(s)?;
+        if v.get("crit").is_some() {
+            return Err(Error::UnsupportedCritical);
+        }
+        let member = |name| v.get(name).map(|v| v.as_str().ok_or(Error::Malformed)).transpose();
+        Ok(Header {
+            alg: algorithm_from_name(member("alg")?.ok_or(Error::Malformed)?)?,
+            kid: member("kid")?.map(String::from),
+            typ: member("typ")?.map(String::from),
+        })
+    }
+}
+
+fn split(token: &str) -> Result<[&str; 3]> {
+    let mut parts = token.split('.');
+    let parts = [parts.next(), parts.next(), parts.next(), parts.next()];
+    match parts {
+        [Some(h), Some(p), Some(s), None] => Ok([h, p, s]),
+        _ => Err(Error::Malformed),
+    }
+}
+
+fn parse_header(b64: &str) -> Result<Header> {
+    let json = base64url_decode(b64)?;
+    Header::from_json(core::str::from_utf8(&json).map_err(|_| Error::Malformed)?)
+}
+
+/// Sign the payload to a compact JWS, the header algorithm must be the one of the key.
+/// rnd is all zero for the deterministic variant of ML-DSA.
+pub fn sign(key: &dyn Signer, header: &Header, payload: &[u8], rnd: &[u8; 32]) -> Result<String> {
+    if header.alg != key.algorithm() {
+        return Err(Error::AlgorithmMismatch);
+    }
+    let mut token = base64url_encode(header.to_json().as_bytes());
+    token.push('.');
+    token.push_str(&base64url_encode(payload));
+    let mut sig = vec![0; key.algorithm().signature_len()];
+    key.sign(token.as_bytes(), b"", rnd, &mut sig)?;
+    token.push('.');
+    token.push_str(&base64url_encode(&sig));
+    Ok(token)
+}
+
+/// Decode the protected header without verifying the signature, to select the key by its kid.
+pub fn decode_header(token: &str) -> Result<Header> {
+    parse_header(split(token)?[0])
+}
+
+/// Verify a compact JWS, returns the header and the payload.
+pub fn verify(token: &str, key: &dyn Verifier) -> Result<(Header, Vec<u8>)> {
+    let [h, p, s] = split(token)?;
+    let header = parse_header(h)?;
+    if header.alg != key.algorithm() {
+        return Err(Error::AlgorithmMismatch);
+    }
+    let payload = base64url_decode(p)?;
+    let sig = base64url_decode(s)?;
+    key.verify(&token.as_bytes()[..h.len() + 1 + p.len()], b"", &sig).map_err(|_| Error::Signature)?;
+    Ok((header, payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mldsa::{mldsa44, mldsa65};
+    use rand::Rng;
+
+    #[test]
+    fn test_jws() {
+        let mut rng = rand::rng();
+        let sk = mldsa44::PrivateKey::from_seed(&rng.random());
+        let pk = sk.public_key();
+        let header = Header::new(Algorithm::MlDsa44).with_kid("k1").with_typ("JWT");
+
+        let token = sign(&sk, &header, b"{\"iss\":\"opengm\"}", &rng.random()).unwrap();
+        assert!(token.starts_with("eyJhbGciOiJNTC1EU0EtNDQiLCJraWQiOiJrMSIsInR5cCI6IkpXVCJ9.eyJpc3MiOiJvcGVuZ20ifQ."));
+        assert_eq!(decode_header(&token), Ok(header.clone()));
+        assert_eq!(verify(&token, &pk), Ok((header.clone(), b"{\"iss\":\"opengm\"}".to_vec())));
+
+        // the signature is over the signing input, with an empty context
+        let [h, p, s] = split(&token).unwrap();
+        let input = format!("{h}.{p}");
+        let sig = mldsa44::Signature::sig_decode(&base64url_decode(s).unwrap().try_into().unwrap()).unwrap();
+        assert!(pk.verify(input.as_bytes(), b"", &sig));
+
+        let header = Header::new(Algorithm::MlDsa44);
+        let token = sign(&sk, &header, b"", &[0; 32]).unwrap();
+        assert_eq!(token, sign(&sk, &header, b"", &[0; 32]).unwrap());
+        assert!(token.starts_with("eyJhbGciOiJNTC1EU0EtNDQifQ.."));
+        assert_eq!(verify(&token, &pk), Ok((header, vec![])));
+
+        let other = mldsa65::PrivateKey::from_seed(&rng.random());
+        assert_eq!(sign(&other, &Header::new(Algorithm::MlDsa44), b"", &[0; 32]), Err(Error::AlgorithmMismatch));
+        assert_eq!(verify(&token, &other.public_key()), Err(Error::AlgorithmMismatch));
+        let pk2 = mldsa44::PrivateKey::from_seed(&rng.random()).public_key();
+        assert_eq!(verify(&token, &pk2), Err(Error::Signature));
+    }
+
+    #[test]
+    fn test_jws_invalid() {
+        let sk = mldsa44::PrivateKey::from_seed(&[1; 32]);
+        let pk = sk.public_key();
+        let token = sign(&sk, &Header::new(Algorithm::MlDsa44), b"payload", &[0; 32]).unwrap();
+        let [h, p, s] = split(&token).unwrap();
+
+        let tampered = format!("{h}.{}.{s}", base64url_encode(b"payloae"));
+        assert_eq!(verify(&tampered, &pk), Err(Error::Signature));
+        assert_eq!(verify(&format!("{h}.{p}.{}", &s[4..]), &pk), Err(Error::Signature));
+        assert_eq!(verify(&format!("{h}.{p}"), &pk), Err(Error::Malformed));
+        assert_eq!(verify(&format!("{token}."), &pk), Err(Error::Malformed));
+        assert!(matches!(verify(&format!("{h}.{p}=.{s}"), &pk), Err(Error::Pkcs(_))));
+
+        let with_header = |json: &str| format!("{}.{p}.{s}", base64url_encode(json.as_bytes()));
+        for (json, err) in [
+            (r#"{"alg":"none"}"#, Error::UnsupportedAlgorithm),
+            (r#"{"alg":"ML-DSA-44","crit":["b64"],"b64":false}"#, Error::UnsupportedCritical),
+            (r#"{"kid":"k1"}"#, Error::Malformed),
+            (r#"{"alg":"ML-DSA-44","kid":1}"#, Error::Malformed),
+            (r#""ML-DSA-44""#, Error::Malformed),
+            (r#"{"alg":"ML-DSA-44","alg":"ML-DSA-87"}"#, Error::Malformed),
+            (r#"{"alg":"ML-DSA-44","kid":"k1","kid":"k2"}"#, Error::Malformed),
+        ] {
+            assert_eq!(verify(&with_header(json), &pk), Err(err), "{json}");
+        }
+        assert!(matches!(verify(&with_header("{"), &pk), Err(Error::Json(_))));
+        // a header with the same algorithm but other bytes is not covered by the signature
+        assert_eq!(verify(&with_header(r#"{"alg": "ML-DSA-44"}"#), &pk), Err(Error::Signature));
+    }
+}
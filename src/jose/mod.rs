@@ -0,0 +1,53 @@
+//! JOSE with ML-DSA, after the IETF COSE/JOSE ML-DSA draft.
+//!
+//! [`Jwk`] is the `AKP` (Algorithm Key Pair) JSON Web Key: the `alg` is `ML-DSA-44`, `ML-DSA-65`
+//! or `ML-DSA-87`, `pub` is the encoded public key and `priv` the 32 bytes seed, both base64url.
+//! The parameter modules wrap it for their key types, as they do for the pkcs encodings.
+//!
+//! [`sign`] and [`verify`] create and verify compact JWS (RFC 7515) with the pure ML-DSA algorithm
+//! and an empty context, over the ASCII signing input `BASE64URL(header) '.' BASE64URL(payload)`.
+
+use crate::alg::Algorithm;
+use crate::json::Value;
+
+mod errors;
+mod jwk;
+mod jws;
+
+pub use errors::{Error, Result};
+pub use jwk::{Jwk, KEY_TYPE};
+pub use jws::{Header, decode_header, sign, verify};
+
+/// The signature algorithm of the JOSE name.
+pub fn algorithm_from_name(name: &str) -> Result<Algorithm> {
+    Algorithm::ALL
+        .into_iter()
+        .find(|alg| alg.is_signature() && alg.name() == name)
+        .ok_or(Error::UnsupportedAlgorithm)
+}
+
+// A JSON object of a header or a key. A duplicate member name is rejected, RFC 7515 leaves the choice
+// between that and taking the last one, and JSON parsers differ in the one they take.
+fn parse_object(s: &str) -> Result<Value> {
+    let v = Value::parse(s)?;
+    let o = v.as_object().ok_or(Error::Malformed)?;
+    if o.iter().enumerate().any(|(i, (name, _))| o[..i].iter().any(|(n, _)| n == name)) {
+        return Err(Error::Malformed);
+    }
+    Ok(v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algorithm_from_name() {
+        for alg in [Algorithm::MlDsa44, Algorithm::MlDsa65, Algorithm::MlDsa87] {
+            assert_eq!(algorithm_from_name(alg.name()), Ok(alg));
+        }
+        for name in ["ML-KEM-768", "none", "EdDSA", "ml-dsa-44", ""] {
+            assert_eq!(algorithm_from_name(name), Err(Error::UnsupportedAlgorithm));
+        }
+    }
+}
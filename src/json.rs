@@ -0,0 +1,401 @@
+//! A minimal JSON (RFC 8259) value with a parser and a compact serializer, for JOSE and the test
+//! vector files.
+//!
+//! Objects keep the order of their members, numbers are f64.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Write};
+
+use thiserror;
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("JSON syntax error at byte {0}")]
+    Syntax(usize),
+
+    #[error("JSON nesting too deep")]
+    Depth,
+}
+pub type Result<T> = core::result::Result<T, Error>;
+
+const MAX_DEPTH: usize = 128;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Parse a JSON text, whitespace around the value is allowed.
+    pub fn parse(s: &str) -> Result<Value> {
+        let mut p = Parser { s: s.as_bytes(), pos: 0 };
+        let v = p.value(0)?;
+        p.ws();
+        if p.pos != p.s.len() {
+            return Err(Error::Syntax(p.pos));
+        }
+        Ok(v)
+    }
+
+    /// The member of an object, the first one if the name is duplicated.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.as_object()?.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// A non-negative integer up to 2^53.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(n) if *n >= 0.0 && *n <= (1u64 << 53) as f64 && *n as u64 as f64 == *n => Some(*n as u64),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Number(n as f64)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// The compact serialization, without whitespace.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Number(n) if n.is_finite() => write!(f, "{n}"),
+            Value::Number(_) => f.write_str("null"),
+            Value::String(s) => write_string(f, s),
+            Value::Array(a) => {
+                f.write_char('[')?;
+                for (i, v) in a.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                f.write_char(']')
+            }
+            Value::Object(o) => {
+                f.write_char('{')?;
+                for (i, (n, v)) in o.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, n)?;
+                    write!(f, ":{v}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn err<T>(&self) -> Result<T> {
+        Err(Error::Syntax(self.pos))
+    }
+
+    fn ws(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.s.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, lit: &[u8]) -> Result<()> {
+        if !self.s[self.pos..].starts_with(lit) {
+            return self.err();
+        }
+        self.pos += lit.len();
+        Ok(())
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(Error::Depth);
+        }
+        self.ws();
+        match self.peek() {
+            Some(b'n') => self.expect(b"null").map(|_| Value::Null),
+            Some(b't') => self.expect(b"true").map(|_| Value::Bool(true)),
+            Some(b'f') => self.expect(b"false").map(|_| Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut a = Vec::new();
+                self.ws();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(Value::Array(a));
+                }
+                loop {
+                    a.push(self.value(depth + 1)?);
+                    self.ws();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Value::Array(a));
+                        }
+                        _ => return self.err(),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut o = Vec::new();
+                self.ws();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(Value::Object(o));
+                }
+                loop {
+                    self.ws();
+                    if self.peek() != Some(b'"') {
+                        return self.err();
+                    }
+                    let name = self.string()?;
+                    self.ws();
+                    self.expect(b":")?;
+                    o.push((name, self.value(depth + 1)?));
+                    self.ws();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Value::Object(o));
+                        }
+                        _ => return self.err(),
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => self.err(),
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<Value> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        let int = self.pos;
+        if self.digits() == 0 || (self.s[int] == b'0' && self.pos - int > 1) {
+            return self.err();
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if self.digits() == 0 {
+                return self.err();
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if self.digits() == 0 {
+                return self.err();
+            }
+        }
+        // the text is ASCII digits and signs
+        let text = core::str::from_utf8(&self.s[start..self.pos]).unwrap();
+        text.parse().map(Value::Number).or_else(|_| self.err())
+    }
+
+    fn hex4(&mut self) -> Result<u32> {
+        let h = self.s.get(self.pos..self.pos + 4).ok_or(Error::Syntax(self.pos))?;
+        let v = h.iter().try_fold(0, |v, c| (*c as char).to_digit(16).map(|d| v * 16 + d));
+        self.pos += 4;
+        v.ok_or(Error::Syntax(self.pos - 4))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(c) = self.peek()
+                && c != b'"'
+                && c != b'\\'
+                && c >= 0x20
+            {
+                self.pos += 1;
+            }
+            // the input is a str, the run stops at ASCII bytes
+            out.push_str(core::str::from_utf8(&self.s[start..self.pos]).unwrap());
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let c = self.peek().ok_or(Error::Syntax(self.pos))?;
+                    self.pos += 1;
+                    let c = match c {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let mut u = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&u) {
+                                self.expect(b"\\u")?;
+                                let low = self.hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return self.err();
+                                }
+                                u = 0x10000 + ((u - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            char::from_u32(u).ok_or(Error::Syntax(self.pos))?
+                        }
+                        _ => return self.err(),
+                    };
+                    out.push(c);
+                }
+                _ => return self.err(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let v = Value::parse(r#" {"a": [1, -2.5e1, true, false, null], "b": "x\"\\\/\n\u00e9\ud83d\ude00", "c": {}} "#).unwrap();
+        assert_eq!(
+            v.get("a"),
+            Some(&Value::Array(vec![
+                Value::Number(1.0),
+                Value::Number(-25.0),
+                Value::Bool(true),
+                Value::Bool(false),
+                Value::Null
+            ]))
+        );
+        assert_eq!(v.get("b").and_then(Value::as_str), Some("x\"\\/\né😀"));
+        assert_eq!(v.get("c"), Some(&Value::Object(vec![])));
+        assert_eq!(v.get("d"), None);
+        assert_eq!(v.get("a").unwrap().as_array().unwrap()[0].as_u64(), Some(1));
+        assert_eq!(v.get("a").unwrap().as_array().unwrap()[1].as_u64(), None);
+        assert_eq!(Value::parse("\"中文\""), Ok(Value::from("中文")));
+
+        for bad in ["", "{", "[1,]", "{\"a\" 1}", "01", "1.", "-", "\"\\x\"", "\"\\ud800\"", "\"a\nb\"", "tru", "1 2", "{1:2}"] {
+            assert!(Value::parse(bad).is_err(), "{bad}");
+        }
+        let deep = "[".repeat(200);
+        assert_eq!(Value::parse(&deep), Err(Error::Depth));
+    }
+
+    #[test]
+    fn test_display() {
+        let v = Value::Object(vec![
+            ("alg".into(), "ML-DSA-44".into()),
+            ("n".into(), 42u64.into()),
+            ("x".into(), Value::Array(vec![Value::Null, true.into(), Value::Number(0.5)])),
+            ("s".into(), "a\"b\\c\n\u{1}".into()),
+        ]);
+        let s = format!("{v}");
+        assert_eq!(s, r#"{"alg":"ML-DSA-44","n":42,"x":[null,true,0.5],"s":"a\"b\\c\n\u0001"}"#);
+        assert_eq!(Value::parse(&s), Ok(v));
+    }
+}
@@ -5,13 +5,15 @@
 
 #![feature(
     test,
-    bigint_helper_methods,
+    widening_mul,
+    signed_bigint_helpers,
     generic_const_exprs
 )]
 
 #![no_std]
 #![warn(clippy::std_instead_of_alloc, clippy::std_instead_of_core)]
 
+#[cfg(feature = "alloc")]
 #[allow(unused_imports)]
 #[macro_use]
 extern crate alloc;
@@ -23,7 +25,7 @@ extern crate std;
 
 
 /// build static C lib
-#[cfg(all(not(feature = "std"), feature = "build-lib"))]
+#[cfg(all(not(feature = "std"), any(feature = "build-lib", feature = "build-lib-noalloc")))]
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
     loop {}
@@ -38,13 +40,39 @@ use libc_alloc::LibcAlloc;
 #[global_allocator]
 static ALLOCATOR: LibcAlloc = LibcAlloc;
 
+#[cfg(feature = "alloc")]
+pub mod acvp;
+pub mod aes;
+pub mod alg;
+pub mod capi;
+pub mod cbor;
+pub mod combiner;
+pub mod cose;
+pub mod hkdf;
+pub mod hmac;
+pub mod hpke;
+pub mod p256;
+pub mod pkcs;
+pub mod x509;
+#[cfg(feature = "alloc")]
+pub mod cms;
+#[cfg(feature = "alloc")]
+pub mod jose;
+#[cfg(feature = "alloc")]
+pub mod json;
+pub mod sha2;
 pub mod sha3;
+pub mod sm3;
 pub mod tick;
+pub mod tls;
+pub mod x25519;
+pub mod xwing;
 
 pub mod mlkem;
 pub mod mldsa;
 
 pub mod mldsa_tpc;
+pub mod mlkem_tpc;
 
 
 #[cfg(test)]
@@ -22,10 +22,12 @@ pub(crate) fn power2_round(r: i32) -> (i32, i32) {
 /// or
 /// -gamma2 < a0 <= 0 and a1 = 0,
 ///
+/// ```text
 ///           Q-1-2gamma2       Q-gamma2-1   Q-gamma2      ...    Q-2      Q-1           0         
 ///           -----+----------------+-----------+------------------+--------+------------+------->
 ///         (m-1)*(2gamma2)                                              m*(2gamma2)                                  
 /// (a0,a1):   (0, m-1)       (gamma2, m-1)  (-gamma2, 0)  ...   (-2,0)    (-1, 0)     (0, 0)
+/// ```
 fn decompose<const gamma2: usize>(a: i32) -> (i32, i32) {
     // fixme
     let a = mod_q(a);
@@ -129,6 +131,8 @@ pub(crate) fn use_hint<const gamma2: usize>(h: u8, r: i32) -> i32 {
 }
 
 pub(crate) fn hint_bit_pack<const k: usize, const omega: usize>(y: &mut [u8; omega + k], h: &[[u8; N]; k]) {
+    // y may be a reused buffer, the unused hint positions must be zero.
+    y.fill(0);
     let mut index = 0;
     for i in 0..k {
         for j in 0..N {
@@ -1,83 +1,81 @@
 use crate::{
-    mldsa::{auxiliary::bit_pack::bit_unpack, hash::new_h, rq::Rq, util::bitlen},
-    sha3::XOF,
+    mldsa::{auxiliary::bit_pack::bit_unpack, rq::Rq, util::bitlen},
+    sha3::shake_x4,
 };
 
-use super::sample::{rej_bounded_poly, rej_ntt_poly};
+use super::sample::{rej_bounded_block, rej_ntt_block};
+
+// The polynomials are expanded from independent streams, four at a time with the multi-lane Keccak.
 
 pub(crate) fn expand_a<const k: usize, const l: usize>(a: &mut [[Rq; l]; k], rho: &[u8; 32]) {
-    for r in 0..k {
-        for s in 0..l {
-            rej_ntt_poly(&mut a[r][s], rho, s as u8, r as u8);
-        }
-    }
+    // the stream n = r * l + s is the one of rej_ntt_poly(a[r][s], rho, s, r)
+    let mut j = [0usize; 64];
+    shake_x4::<16>(
+        k * l,
+        |n, m| {
+            m[..32].copy_from_slice(rho);
+            m[32..34].copy_from_slice(&[(n % l) as u8, (n / l) as u8]);
+            34
+        },
+        |n, block| {
+            rej_ntt_block(&mut a[n / l][n % l], &mut j[n], block);
+            j[n] < 256
+        },
+    );
 }
 
 pub(crate) fn expand_s<const k: usize, const l: usize, const eta:usize>(s1: &mut [Rq; l], s2: &mut [Rq; k], rho: &[u8; 64]) {
-    for r in 0..l {
-        rej_bounded_poly::<eta>(&mut s1[r], rho, r as u16);
-    }
+    // the stream r is the one of rej_bounded_poly(s1[r], rho, r), then of s2[r - l]
+    let mut j = [0usize; 16];
+    shake_x4::<32>(
+        l + k,
+        |r, m| {
+            m[..64].copy_from_slice(rho);
+            m[64..66].copy_from_slice(&(r as u16).to_le_bytes());
+            66
+        },
+        |r, block| {
+            let a = if r < l { &mut s1[r] } else { &mut s2[r - l] };
+            rej_bounded_block::<eta>(a, &mut j[r], block);
+            j[r] < 256
+        },
+    );
+}
 
-    for r in 0..k {
-        rej_bounded_poly::<eta>(&mut s2[r], rho, (r + l) as u16);
+// y[r] is unpacked from the first 32 * c bytes of the stream of rho || mu + r
+fn expand_mask_unpack<const l: usize>(y: &mut [Rq; l], rho: &[u8], mu: u16, c: usize, unpack: fn(&mut Rq, &[u8])) {
+    let mut v = [[0u8; 32 * 20]; l];
+    let mut n = [0usize; l];
+    shake_x4::<32>(
+        l,
+        |r, m| {
+            m[..rho.len()].copy_from_slice(rho);
+            m[rho.len()..rho.len() + 2].copy_from_slice(&(mu + r as u16).to_le_bytes());
+            rho.len() + 2
+        },
+        |r, block| {
+            let len = block.len().min(32 * c - n[r]);
+            v[r][n[r]..n[r] + len].copy_from_slice(&block[..len]);
+            n[r] += len;
+            n[r] < 32 * c
+        },
+    );
+    for (y, v) in y.iter_mut().zip(&v) {
+        unpack(y, &v[..32 * c]);
     }
 }
 
 pub(crate) fn expand_mask<const k: usize, const l: usize, const gamma1:usize>(y: &mut [Rq; l], rho: &[u8], mu: u16) {
     match gamma1 {
-        131072 => {
-            // gamma1 = 2^17
-            const c: usize = bitlen(1 << 17);
-            let mut v = [0; 32 * c];
-            let mut ctx = new_h();
-            ctx.absorb(rho);
-            for r in 0..l {
-                ctx.clone()
-                    .absorb(&[(mu + r as u16) as u8, ((mu + r as u16) >> 8) as u8])
-                    .squeeze(&mut v);
-                bit_unpack::<131071, 131072>(&mut y[r], &v);
-            }
-        }
-        524288 => {
-            // gamma1 = 2^19
-            const c: usize = bitlen(1 << 19);
-            let mut v = [0; 32 * c];
-            let mut ctx = new_h();
-            ctx.absorb(rho);
-            for r in 0..l {
-                ctx.clone()
-                    .absorb(&[(mu + r as u16) as u8, ((mu + r as u16) >> 8) as u8])
-                    .squeeze(&mut v);
-                bit_unpack::<524287, 524288>(&mut y[r], &v);
-            }
-        }
+        // gamma1 = 2^17
+        131072 => expand_mask_unpack(y, rho, mu, bitlen(1 << 17), bit_unpack::<131071, 131072>),
+        // gamma1 = 2^19
+        524288 => expand_mask_unpack(y, rho, mu, bitlen(1 << 19), bit_unpack::<524287, 524288>),
         // FIXME: for tpc
-        65536 => {
-            // gamma1 = 2^16
-            const c: usize = bitlen(1 << 16);
-            let mut v = [0; 32 * c];
-            let mut ctx = new_h();
-            ctx.absorb(rho);
-            for r in 0..l {
-                ctx.clone()
-                    .absorb(&[(mu + r as u16) as u8, ((mu + r as u16) >> 8) as u8])
-                    .squeeze(&mut v);
-                bit_unpack::<65535, 65536>(&mut y[r], &v);
-            }
-        }
-        262144 => {
-            // gamma1 = 2^19
-            const c: usize = bitlen(1 << 18);
-            let mut v = [0; 32 * c];
-            let mut ctx = new_h();
-            ctx.absorb(rho);
-            for r in 0..l {
-                ctx.clone()
-                    .absorb(&[(mu + r as u16) as u8, ((mu + r as u16) >> 8) as u8])
-                    .squeeze(&mut v);
-                bit_unpack::<262143, 262144>(&mut y[r], &v);
-            }
-        }
+        // gamma1 = 2^16
+        65536 => expand_mask_unpack(y, rho, mu, bitlen(1 << 16), bit_unpack::<65535, 65536>),
+        // gamma1 = 2^18
+        262144 => expand_mask_unpack(y, rho, mu, bitlen(1 << 18), bit_unpack::<262143, 262144>),
 
         _ => panic!("wrone gamma1"),
     };
@@ -42,27 +42,23 @@ pub(crate) fn rej_ntt_poly(a: &mut Rq, rho: &[u8], k: u8, l: u8) {
     let mut ctx = sha3::new_shake128();
     ctx.absorb(rho).absorb(&[k, l]);
 
-    let mut s = [0; 3 * 8];
+    let mut s = [0; 168];
     let mut j = 0;
-    while j < 256 - 8 {
+    while j < 256 {
         ctx.squeeze(&mut s);
-        for s in s.chunks_exact(3) {
-            let (c, res) = coeff_from_three_bytes(s[0], s[1], s[2]);
-            a.coeffs[j] = c;
-            j = j + res
-        }
+        rej_ntt_block(a, &mut j, &s);
     }
+}
 
-    while j < 256 {
-        ctx.squeeze(&mut s);
-        for s in s.chunks_exact(3) {
-            let (c, res) = coeff_from_three_bytes(s[0], s[1], s[2]);
-            a.coeffs[j] = c;
-            j = j + res;
-            if j == 256 {
-                break;
-            }
+// continue rej_ntt_poly with the next block of the stream, j coefficients are already sampled
+pub(crate) fn rej_ntt_block(a: &mut Rq, j: &mut usize, block: &[u8]) {
+    for s in block.chunks_exact(3) {
+        if *j == 256 {
+            return;
         }
+        let (c, res) = coeff_from_three_bytes(s[0], s[1], s[2]);
+        a.coeffs[*j] = c;
+        *j += res;
     }
 }
 
@@ -83,39 +79,26 @@ pub(crate) fn coeff_from_half_byte<const eta: usize>(b: u8) -> (i32, usize) {
 pub(crate) fn rej_bounded_poly<const eta: usize>(a: &mut Rq, rho: &[u8], r: u16) {
     let mut ctx = new_h();
     ctx.absorb(rho).absorb(&[r as u8, (r >> 8) as u8]);
-    let coeffs = &mut a.coeffs;
 
-    let mut z = [0; 8];
+    let mut z = [0; 136];
     let mut j = 0;
-    while j < 256-16{
+    while j < 256 {
         ctx.squeeze(&mut z);
-        for z in z{
-    		let (z0, ok0) = coeff_from_half_byte::<eta>(z&15);
-    		coeffs[j] = z0;
-    		j += ok0;
-
-    		let (z1, ok1) = coeff_from_half_byte::<eta>(z>>4);
-    		coeffs[j] = z1;
-    		j += ok1
-    	}
+        rej_bounded_block::<eta>(a, &mut j, &z);
     }
+}
 
-    while j < 256{
-        ctx.squeeze(&mut z);
-        for z in z{
-    		let (z0, ok0) = coeff_from_half_byte::<eta>(z&15);
-    		coeffs[j] = z0;
-    		j += ok0;
-            if j == 256{
-                break
-            }
-
-    		let (z1, ok1) = coeff_from_half_byte::<eta>(z>>4);
-    		coeffs[j] = z1;
-    		j += ok1;
-            if j == 256{
-                break
+// continue rej_bounded_poly with the next block of the stream, j coefficients are already sampled
+pub(crate) fn rej_bounded_block<const eta: usize>(a: &mut Rq, j: &mut usize, block: &[u8]) {
+    let coeffs = &mut a.coeffs;
+    for &z in block {
+        for h in [z & 15, z >> 4] {
+            if *j == 256 {
+                return;
             }
-    	}
+            let (c, ok) = coeff_from_half_byte::<eta>(h);
+            coeffs[*j] = c;
+            *j += ok;
+        }
     }
 }
@@ -9,6 +9,9 @@ pub enum Error {
     DecapKeyDecodeError,
 
 
+    #[error("context string longer than 255 bytes")]
+    ContextTooLong,
+
     #[error("mldsa tpc: server check failed")]
     TPCServerCheckFailed,
 }
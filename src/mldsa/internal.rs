@@ -8,6 +8,7 @@ use super::reduce::mod_q;
 use super::util::{bitlen, vec_norm_less_than};
 use super::{N, Q, d};
 use super::{hash::new_h, rq::Rq};
+use super::prehash::PreHash;
 
 // note that d = 13 for all
 #[derive(Clone)]
@@ -115,25 +116,25 @@ impl<const k: usize, const l: usize> PrivateKey<k, l> {
         let eta_bitlen = bitlen(2 * eta);
 
         for s1 in &self.s1_ {
-            let mut s1 = s1.clone();
+            let mut s1 = *s1;
             s1.ntt_inverse_raw();
             s1.mods_q();
-            bit_pack::<eta, eta>(p, &s1);
+            bit_pack::<eta, eta>(&mut p[..eta_bitlen * 32], &s1);
             p = &mut p[eta_bitlen * 32..];
         }
 
         for s2 in &self.s2_ {
-            let mut s2 = s2.clone();
+            let mut s2 = *s2;
             s2.ntt_inverse_raw();
             s2.mods_q();
-            bit_pack::<eta, eta>(p, &s2);
+            bit_pack::<eta, eta>(&mut p[..eta_bitlen * 32], &s2);
             p = &mut p[eta_bitlen * 32..];
         }
 
         for t in &self.t0_ {
-            let mut t = t.clone();
+            let mut t = *t;
             t.ntt_inverse_raw();
-            bit_pack::<4095, 4096>(p, &t);
+            bit_pack::<4095, 4096>(&mut p[..13 * 32], &t);
             p = &mut p[13 * 32..];
         }
     }
@@ -158,10 +159,20 @@ impl<const k: usize, const l: usize> PrivateKey<k, l> {
             p = &p[eta_bitlen * 32..];
         }
 
-        for t in &mut sk.s2_ {
-            bit_unpack::<eta, eta>(t, p);
-            t.ntt();
-            t.mod_q();
+        for (i, s2) in sk.s2_.iter_mut().enumerate() {
+            bit_unpack::<eta, eta>(s2, p);
+
+            // t1 is not in the encoding, recompute it from t = A * s1 + s2.
+            let mut t = Rq::default();
+            t.dot_mul(&sk.pk.A[i], &sk.s1_);
+            t.ntt_inverse();
+            t.add_assign(s2);
+            for j in 0..N {
+                sk.pk.t1[i][j] = power2_round(mod_q(t[j])).1;
+            }
+
+            s2.ntt();
+            s2.mod_q();
             p = &p[eta_bitlen * 32..];
         }
 
@@ -303,6 +314,20 @@ where
     sk
 }
 
+// mu = H(tr || M', 64), where M' = pre || m.
+// pre is empty for the internal functions, 0 || |ctx| || ctx for ML-DSA.Sign/Verify, and
+// 1 || |ctx| || ctx || OID for HashML-DSA.Sign/Verify, with m = PH(M).
+fn message_representative(tr: &[u8; 64], pre: &[&[u8]], m: &[u8]) -> [u8; 64] {
+    let mut h = new_h();
+    h.absorb(tr);
+    for p in pre {
+        h.absorb(p);
+    }
+    let mut mu = [0; 64];
+    h.absorb(m).squeeze(&mut mu);
+    mu
+}
+
 impl<const k: usize, const l: usize> PrivateKey<k, l> {
     pub(crate) fn sign_internal_<
         const gamma1: usize,
@@ -320,12 +345,80 @@ impl<const k: usize, const l: usize> PrivateKey<k, l> {
         [(); 32 * k * bitlen((Q as usize - 1) / (2 * gamma2) - 1)]:,
         [(); lambda / 4]:,
     {
-        let mut mu = [0; 64];
-        let mut h = new_h();
-        h.absorb(&self.tr).absorb(m).squeeze(&mut mu);
+        let mu = message_representative(&self.tr, &[], m);
+        self.sign_mu_::<gamma1, gamma2, lambda, tau, beta, omega>(&mu, rnd)
+    }
+
+    // ML-DSA.Sign, returns None if ctx is longer than 255 bytes.
+    pub(crate) fn sign_<
+        const gamma1: usize,
+        const gamma2: usize,
+        const lambda: usize,
+        const tau: usize,
+        const beta: usize,
+        const omega: usize,
+    >(
+        &self,
+        m: &[u8],
+        ctx: &[u8],
+        rnd: &[u8; 32],
+    ) -> Option<Signature<k, l, lambda>>
+    where
+        [(); 32 * k * bitlen((Q as usize - 1) / (2 * gamma2) - 1)]:,
+        [(); lambda / 4]:,
+    {
+        if ctx.len() > 255 {
+            return None;
+        }
+        let mu = message_representative(&self.tr, &[&[0, ctx.len() as u8], ctx], m);
+        Some(self.sign_mu_::<gamma1, gamma2, lambda, tau, beta, omega>(&mu, rnd))
+    }
+
+    // HashML-DSA.Sign, returns None if ctx is longer than 255 bytes.
+    pub(crate) fn hash_sign_<
+        const gamma1: usize,
+        const gamma2: usize,
+        const lambda: usize,
+        const tau: usize,
+        const beta: usize,
+        const omega: usize,
+    >(
+        &self,
+        m: &[u8],
+        ctx: &[u8],
+        ph: PreHash,
+        rnd: &[u8; 32],
+    ) -> Option<Signature<k, l, lambda>>
+    where
+        [(); 32 * k * bitlen((Q as usize - 1) / (2 * gamma2) - 1)]:,
+        [(); lambda / 4]:,
+    {
+        if ctx.len() > 255 {
+            return None;
+        }
+        let mut digest = [0; 64];
+        let mu = message_representative(&self.tr, &[&[1, ctx.len() as u8], ctx, ph.oid()], ph.digest(m, &mut digest));
+        Some(self.sign_mu_::<gamma1, gamma2, lambda, tau, beta, omega>(&mu, rnd))
+    }
 
+    pub(crate) fn sign_mu_<
+        const gamma1: usize,
+        const gamma2: usize,
+        const lambda: usize,
+        const tau: usize,
+        const beta: usize,
+        const omega: usize,
+    >(
+        &self,
+        mu: &[u8; 64],
+        rnd: &[u8; 32],
+    ) -> Signature<k, l, lambda>
+    where
+        [(); 32 * k * bitlen((Q as usize - 1) / (2 * gamma2) - 1)]:,
+        [(); lambda / 4]:,
+    {
         let mut rho_pp = [0; 64];
-        h.init().absorb(&self.K).absorb(rnd).absorb(&mu).squeeze(&mut rho_pp);
+        new_h().absorb(&self.K).absorb(rnd).absorb(mu).squeeze(&mut rho_pp);
 
         let mut kappa: usize = 0;
         let mut y = [Rq::default(); l];
@@ -361,7 +454,7 @@ impl<const k: usize, const l: usize> PrivateKey<k, l> {
 
             w1_encode::<k, gamma2>(&mut b, &w1);
 
-            shake.absorb(&mu).absorb(&b).squeeze(&mut c_tilde);
+            shake.absorb(mu).absorb(&b).squeeze(&mut c_tilde);
             let mut c = Rq::default();
             sample_in_ball::<tau>(&mut c, &c_tilde);
             c.ntt();
@@ -450,13 +543,83 @@ impl<const k: usize, const l: usize> PublicKey<k, l> {
         [(); 32 + 320 * k]:,
         [(); 32 * k * bitlen((Q as usize - 1) / (2 * gamma2) - 1)]:,
     {
-        let mut A = [[Rq::default(); l]; k];
-        expand_a::<k, l>(&mut A, &self.rho);
+        let mu = message_representative(&self.tr_(), &[], m);
+        self.verify_mu_::<gamma1, gamma2, lambda, tau, beta>(&mu, sig)
+    }
 
+    // ML-DSA.Verify, returns false if ctx is longer than 255 bytes.
+    pub(crate) fn verify_<
+        const gamma1: usize,
+        const gamma2: usize,
+        const lambda: usize,
+        const tau: usize,
+        const beta: usize,
+    >(
+        &self,
+        m: &[u8],
+        ctx: &[u8],
+        sig: &Signature<k, l, lambda>,
+    ) -> bool
+    where
+        [(); lambda / 4]:,
+        [(); 32 + 320 * k]:,
+        [(); 32 * k * bitlen((Q as usize - 1) / (2 * gamma2) - 1)]:,
+    {
+        if ctx.len() > 255 {
+            return false;
+        }
+        let mu = message_representative(&self.tr_(), &[&[0, ctx.len() as u8], ctx], m);
+        self.verify_mu_::<gamma1, gamma2, lambda, tau, beta>(&mu, sig)
+    }
+
+    // HashML-DSA.Verify, returns false if ctx is longer than 255 bytes.
+    pub(crate) fn hash_verify_<
+        const gamma1: usize,
+        const gamma2: usize,
+        const lambda: usize,
+        const tau: usize,
+        const beta: usize,
+    >(
+        &self,
+        m: &[u8],
+        ctx: &[u8],
+        ph: PreHash,
+        sig: &Signature<k, l, lambda>,
+    ) -> bool
+    where
+        [(); lambda / 4]:,
+        [(); 32 + 320 * k]:,
+        [(); 32 * k * bitlen((Q as usize - 1) / (2 * gamma2) - 1)]:,
+    {
+        if ctx.len() > 255 {
+            return false;
+        }
+        let mut digest = [0; 64];
+        let mu = message_representative(&self.tr_(), &[&[1, ctx.len() as u8], ctx, ph.oid()], ph.digest(m, &mut digest));
+        self.verify_mu_::<gamma1, gamma2, lambda, tau, beta>(&mu, sig)
+    }
+
+    // tr = H(pk, 64)
+    fn tr_(&self) -> [u8; 64]
+    where
+        [(); 32 + 320 * k]:,
+    {
         let mut tr = [0; 64];
-        let mut mu = [0; 64];
         H(&mut tr, &self.pk_encode_());
-        new_h().absorb(&tr).absorb(m).squeeze(&mut mu);
+        tr
+    }
+
+    pub(crate) fn verify_mu_<const gamma1: usize, const gamma2: usize, const lambda: usize, const tau: usize, const beta: usize>(
+        &self,
+        mu: &[u8; 64],
+        sig: &Signature<k, l, lambda>,
+    ) -> bool
+    where
+        [(); lambda / 4]:,
+        [(); 32 * k * bitlen((Q as usize - 1) / (2 * gamma2) - 1)]:,
+    {
+        let mut A = [[Rq::default(); l]; k];
+        expand_a::<k, l>(&mut A, &self.rho);
 
         let mut c = Rq::default();
         sample_in_ball::<tau>(&mut c, &sig.c_wave);
@@ -499,7 +662,7 @@ impl<const k: usize, const l: usize> PublicKey<k, l> {
         let mut c_prime = [0; lambda / 4];
         let mut w1_encode_bytes = [0u8; 32 * k * bitlen((Q as usize - 1) / (2 * gamma2) - 1)];
         w1_encode::<k, gamma2>(&mut w1_encode_bytes, &w1);
-        new_h().absorb(&mu).absorb(&w1_encode_bytes).squeeze(&mut c_prime);
+        new_h().absorb(mu).absorb(&w1_encode_bytes).squeeze(&mut c_prime);
 
         for (a, b) in zip(sig.c_wave, c_prime) {
             if a != b {
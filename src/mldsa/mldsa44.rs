@@ -1,8 +1,21 @@
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
 use core::ffi::c_void;
-use alloc::boxed::Box;
-
+#[cfg(feature = "alloc")]
+use core::ptr::null_mut;
+
+use crate::alg::{self, Algorithm};
+use crate::capi;
+use crate::cose;
+#[cfg(feature = "alloc")]
+use crate::jose;
+use crate::pkcs;
+#[cfg(feature = "alloc")]
+use crate::pkcs::{PrivateKeyChoice, PrivateKeyFormat};
+use super::errors::{Error, Result};
 use super::{
     Q,
+    PreHash,
     internal,
 };
 
@@ -34,6 +47,23 @@ impl PrivateKey {
         self.sign_internal_::<gamma1, gamma2, lambda, tau, beta, omega>(m, rnd)
     }
 
+    // ML-DSA.Sign_internal on the message representative mu computed by the caller.
+    pub(crate) fn sign_mu(&self, mu: &[u8; 64], rnd: &[u8; 32]) -> Signature {
+        self.sign_mu_::<gamma1, gamma2, lambda, tau, beta, omega>(mu, rnd)
+    }
+
+    // ML-DSA.Sign, ctx is at most 255 bytes, rnd is all zero for the deterministic variant.
+    pub fn sign(&self, m: &[u8], ctx: &[u8], rnd: &[u8; 32]) -> Result<Signature> {
+        self.sign_::<gamma1, gamma2, lambda, tau, beta, omega>(m, ctx, rnd)
+            .ok_or(Error::ContextTooLong)
+    }
+
+    // HashML-DSA.Sign with the pre-hash ph, ctx is at most 255 bytes.
+    pub fn hash_sign(&self, m: &[u8], ctx: &[u8], ph: PreHash, rnd: &[u8; 32]) -> Result<Signature> {
+        self.hash_sign_::<gamma1, gamma2, lambda, tau, beta, omega>(m, ctx, ph, rnd)
+            .ok_or(Error::ContextTooLong)
+    }
+
     pub fn public_key(&self) -> PublicKey {
         self.public_key_()
     }
@@ -60,6 +90,21 @@ impl PublicKey {
         self.verify_internal_::<gamma1, gamma2, lambda, tau, beta>(m, &sig)
     }
 
+    // ML-DSA.Verify_internal on the message representative mu computed by the caller.
+    pub(crate) fn verify_mu(&self, mu: &[u8; 64], sig: &Signature) -> bool {
+        self.verify_mu_::<gamma1, gamma2, lambda, tau, beta>(mu, sig)
+    }
+
+    // ML-DSA.Verify, returns false if ctx is longer than 255 bytes.
+    pub fn verify(&self, m: &[u8], ctx: &[u8], sig: &Signature) -> bool {
+        self.verify_::<gamma1, gamma2, lambda, tau, beta>(m, ctx, sig)
+    }
+
+    // HashML-DSA.Verify with the pre-hash ph, returns false if ctx is longer than 255 bytes.
+    pub fn hash_verify(&self, m: &[u8], ctx: &[u8], ph: PreHash, sig: &Signature) -> bool {
+        self.hash_verify_::<gamma1, gamma2, lambda, tau, beta>(m, ctx, ph, sig)
+    }
+
     pub fn pk_encode_inplace(&self, b: &mut [u8; pklen]) {
         self.pk_encode_inplace_(b);
     }
@@ -87,109 +132,610 @@ impl Signature {
     }
 }
 
+/// ML-DSA-44 of the runtime dispatch layer.
+pub struct MlDsa44;
+
+impl alg::SignatureScheme for MlDsa44 {
+    const ALGORITHM: Algorithm = Algorithm::MlDsa44;
+    type PrivateKey = PrivateKey;
+    type PublicKey = PublicKey;
+
+    fn keygen_internal(xi: &[u8; 32]) -> PrivateKey {
+        keygen_internal(xi)
+    }
+
+    fn public_key(sk: &PrivateKey) -> PublicKey {
+        sk.public_key()
+    }
+
+    fn encode_public_key(pk: &PublicKey, b: &mut [u8]) -> alg::Result<()> {
+        pk.pk_encode_inplace(alg::array_mut::<pklen>(b)?);
+        Ok(())
+    }
+
+    fn decode_public_key(b: &[u8]) -> alg::Result<PublicKey> {
+        Ok(PublicKey::pk_decode(alg::array_ref::<pklen>(b)?))
+    }
+
+    fn encode_private_key(sk: &PrivateKey, b: &mut [u8]) -> alg::Result<()> {
+        sk.sk_encode_inplace(alg::array_mut::<sklen>(b)?);
+        Ok(())
+    }
+
+    fn decode_private_key(b: &[u8]) -> alg::Result<PrivateKey> {
+        Ok(PrivateKey::sk_decode(alg::array_ref::<sklen>(b)?))
+    }
+}
+
+impl alg::Signer for PrivateKey {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::MlDsa44
+    }
+
+    fn sign(&self, m: &[u8], ctx: &[u8], rnd: &[u8; 32], sig: &mut [u8]) -> alg::Result<()> {
+        let sig = alg::array_mut::<siglen>(sig)?;
+        let signature = PrivateKey::sign(self, m, ctx, rnd).map_err(|_| alg::Error::ContextTooLong)?;
+        signature.sig_encode_inplace(sig);
+        Ok(())
+    }
+}
+
+impl alg::Verifier for PublicKey {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::MlDsa44
+    }
+
+    fn verify(&self, m: &[u8], ctx: &[u8], sig: &[u8]) -> alg::Result<()> {
+        let sig = Signature::sig_decode(alg::array_ref::<siglen>(sig)?).ok_or(alg::Error::Verify)?;
+        if !PublicKey::verify(self, m, ctx, &sig) {
+            return Err(alg::Error::Verify);
+        }
+        Ok(())
+    }
+}
+
+impl PublicKey {
+    /// Decode from a DER SubjectPublicKeyInfo with the id-ml-dsa-44 OID.
+    pub fn from_public_key_der(der: &[u8]) -> pkcs::Result<Self> {
+        match pkcs::decode_spki(der)? {
+            (Algorithm::MlDsa44, pk) => Ok(PublicKey::pk_decode(pk.try_into().map_err(|_| pkcs::Error::InvalidLength)?)),
+            _ => Err(pkcs::Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Encode to a DER SubjectPublicKeyInfo.
+    #[cfg(feature = "alloc")]
+    pub fn to_public_key_der(&self) -> Vec<u8> {
+        pkcs::spki_der(pkcs::oid::ID_ML_DSA_44, &self.pk_encode())
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn from_public_key_pem(pem: &str) -> pkcs::Result<Self> {
+        Self::from_public_key_der(&pkcs::pem::decode_label(pem, pkcs::pem::PUBLIC_KEY)?)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_public_key_pem(&self) -> String {
+        pkcs::pem::encode(pkcs::pem::PUBLIC_KEY, &self.to_public_key_der())
+    }
+
+    /// Decode from an AKP JSON Web Key of ML-DSA-44, the priv member is ignored.
+    #[cfg(feature = "alloc")]
+    pub fn from_jwk(s: &str) -> jose::Result<Self> {
+        let jwk = jose::Jwk::from_json(s)?;
+        if jwk.alg != Algorithm::MlDsa44 {
+            return Err(jose::Error::AlgorithmMismatch);
+        }
+        Ok(PublicKey::pk_decode(jwk.public.as_slice().try_into().map_err(|_| jose::Error::InvalidLength)?))
+    }
+
+    /// Encode to an AKP JSON Web Key.
+    #[cfg(feature = "alloc")]
+    pub fn to_jwk(&self) -> String {
+        jose::Jwk { alg: Algorithm::MlDsa44, public: self.pk_encode().to_vec(), seed: None, kid: None }.to_json()
+    }
+
+    /// Decode from an AKP COSE_Key of ML-DSA-44, the priv parameter is ignored.
+    pub fn from_cose_key(data: &[u8]) -> cose::Result<Self> {
+        let key = cose::Key::decode(data)?;
+        if key.alg != Algorithm::MlDsa44 {
+            return Err(cose::Error::AlgorithmMismatch);
+        }
+        Ok(PublicKey::pk_decode(key.public.try_into().map_err(|_| cose::Error::InvalidLength)?))
+    }
+
+    /// Encode to an AKP COSE_Key into out, returns the length.
+    pub fn to_cose_key(&self, out: &mut [u8]) -> cose::Result<usize> {
+        cose::Key::new(Algorithm::MlDsa44, &self.pk_encode(), None)?.encode(out)
+    }
+}
+
+impl PrivateKey {
+    /// Generate the key from the 32 bytes seed xi.
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        keygen_internal(seed)
+    }
+
+    /// Decode from a DER PKCS#8 private key of the seed, expandedKey or both form,
+    /// the expanded key of the both form must match the seed.
+    pub fn from_pkcs8_der(der: &[u8]) -> pkcs::Result<Self> {
+        let key = match pkcs::decode_pkcs8(der)? {
+            (Algorithm::MlDsa44, key) => key,
+            _ => return Err(pkcs::Error::UnsupportedAlgorithm),
+        };
+        let seed = key.seed().map(|seed| seed.try_into().map_err(|_| pkcs::Error::InvalidLength)).transpose()?;
+        match (seed, key.expanded_key()) {
+            (Some(seed), None) => Ok(Self::from_seed(seed)),
+            (Some(seed), Some(expanded_key)) => {
+                let sk = Self::from_seed(seed);
+                pkcs::check_expanded_key(&sk.sk_encode(), expanded_key)?;
+                Ok(sk)
+            }
+            (None, Some(expanded_key)) => {
+                Ok(PrivateKey::sk_decode(expanded_key.try_into().map_err(|_| pkcs::Error::InvalidLength)?))
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    /// Encode to a DER PKCS#8 private key of the expandedKey form, as the key does not keep its seed.
+    /// Use seed_to_pkcs8_der for the seed and both forms.
+    #[cfg(feature = "alloc")]
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        pkcs::pkcs8_der(pkcs::oid::ID_ML_DSA_44, &PrivateKeyChoice::ExpandedKey(&self.sk_encode()))
+    }
+
+    /// Encode the key generated from the seed xi to a DER PKCS#8 private key of the format.
+    #[cfg(feature = "alloc")]
+    pub fn seed_to_pkcs8_der(seed: &[u8; 32], format: PrivateKeyFormat) -> Vec<u8> {
+        if format == PrivateKeyFormat::Seed {
+            return pkcs::pkcs8_der(pkcs::oid::ID_ML_DSA_44, &PrivateKeyChoice::Seed(seed));
+        }
+        let expanded_key = Self::from_seed(seed).sk_encode();
+        let key = match format {
+            PrivateKeyFormat::Both => PrivateKeyChoice::Both { seed, expanded_key: &expanded_key },
+            _ => PrivateKeyChoice::ExpandedKey(&expanded_key),
+        };
+        pkcs::pkcs8_der(pkcs::oid::ID_ML_DSA_44, &key)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn from_pkcs8_pem(pem: &str) -> pkcs::Result<Self> {
+        Self::from_pkcs8_der(&pkcs::pem::decode_label(pem, pkcs::pem::PRIVATE_KEY)?)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_pkcs8_pem(&self) -> String {
+        pkcs::pem::encode(pkcs::pem::PRIVATE_KEY, &self.to_pkcs8_der())
+    }
+
+    /// Decode from an AKP JSON Web Key of ML-DSA-44 with the seed, the public key must match it.
+    #[cfg(feature = "alloc")]
+    pub fn from_jwk(s: &str) -> jose::Result<Self> {
+        let jwk = jose::Jwk::from_json(s)?;
+        if jwk.alg != Algorithm::MlDsa44 {
+            return Err(jose::Error::AlgorithmMismatch);
+        }
+        let sk = Self::from_seed(&jwk.seed.ok_or(jose::Error::NoPrivateKey)?);
+        if sk.public_key().pk_encode()[..] != jwk.public[..] {
+            return Err(jose::Error::KeyMismatch);
+        }
+        Ok(sk)
+    }
+
+    /// Encode the key generated from the seed xi to an AKP JSON Web Key with the seed,
+    /// as the key does not keep its seed.
+    #[cfg(feature = "alloc")]
+    pub fn seed_to_jwk(seed: &[u8; 32]) -> String {
+        let public = Self::from_seed(seed).public_key().pk_encode().to_vec();
+        jose::Jwk { alg: Algorithm::MlDsa44, public, seed: Some(*seed), kid: None }.to_json()
+    }
+
+    /// Decode from an AKP COSE_Key of ML-DSA-44 with the seed, the public key must match it.
+    pub fn from_cose_key(data: &[u8]) -> cose::Result<Self> {
+        let key = cose::Key::decode(data)?;
+        if key.alg != Algorithm::MlDsa44 {
+            return Err(cose::Error::AlgorithmMismatch);
+        }
+        let sk = Self::from_seed(key.seed.ok_or(cose::Error::NoPrivateKey)?);
+        if sk.public_key().pk_encode()[..] != key.public[..] {
+            return Err(cose::Error::KeyMismatch);
+        }
+        Ok(sk)
+    }
+
+    /// Encode the key generated from the seed xi to an AKP COSE_Key with the seed into out,
+    /// returns the length.
+    pub fn seed_to_cose_key(seed: &[u8; 32], out: &mut [u8]) -> cose::Result<usize> {
+        let public = Self::from_seed(seed).public_key().pk_encode();
+        cose::Key::new(Algorithm::MlDsa44, &public, Some(seed))?.encode(out)
+    }
+}
+
 /////////////////////////////////////////////////////////////////////
-///  exports C api
+//  exports C api
 /////////////////////////////////////////////////////////////////////
 
+impl capi::Handle for PrivateKey {
+    const TAG: u32 = capi::tag(capi::MLDSA_PRIVATE_KEY, 44);
+}
+
+impl capi::Handle for PublicKey {
+    const TAG: u32 = capi::tag(capi::MLDSA_PUBLIC_KEY, 44);
+}
+
+/// opengm_mldsa44_generate_key_internal 由32字节的种子xi生成私钥, 成功时*sk_handle指向私钥.
+///
+/// # Safety
+/// xi必须为NULL或指向32字节, sk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_generate_key_internal(xi: *const u8, sk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let xi = unsafe { capi::array_ref::<32>(xi) }?;
+        let out = unsafe { capi::out_handle(sk_handle) }?;
+        *out = capi::into_handle(keygen_internal(xi));
+        Ok(())
+    })
+}
+
+/// opengm_mldsa44_public_key 由私钥得到公钥, 成功时*pk_handle指向公钥.
+///
+/// # Safety
+/// sk_handle必须为NULL或本库返回且未释放的handle, pk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa44_generate_key_internal(xi: *const u8) -> *mut c_void {
-    let xi = unsafe { core::slice::from_raw_parts(xi, 32) }.try_into().unwrap();
-    Box::leak(Box::new(keygen_internal(xi))) as *mut _ as *mut c_void
+pub unsafe extern "C" fn opengm_mldsa44_public_key(sk_handle: *const c_void, pk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let private_key = unsafe { capi::handle_ref::<PrivateKey>(sk_handle) }?;
+        let out = unsafe { capi::out_handle(pk_handle) }?;
+        *out = capi::into_handle(private_key.public_key());
+        Ok(())
+    })
 }
 
+/// opengm_mldsa44_private_key_encode 编码私钥, sk指向sklen字节.
+///
+/// # Safety
+/// sk必须为NULL或指向sklen字节的可写内存, sk_handle必须为NULL或本库返回且未释放的handle.
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa44_public_key(sk_handle:  *mut c_void) -> *mut c_void {
-    let private_key = unsafe { Box::from_raw(sk_handle as *mut PrivateKey) };
-    let public_key = Box::leak(Box::new(private_key.public_key())) as *mut _ as *mut c_void;
-    Box::leak(private_key);
-    public_key
+pub unsafe extern "C" fn opengm_mldsa44_private_key_encode(sk: *mut u8, sk_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let sk = unsafe { capi::array_mut::<sklen>(sk) }?;
+        let private_key = unsafe { capi::handle_ref::<PrivateKey>(sk_handle) }?;
+
+        private_key.sk_encode_inplace(sk);
+        Ok(())
+    })
 }
 
+/// opengm_mldsa44_public_key_encode 编码公钥, pk指向pklen字节.
+///
+/// # Safety
+/// pk必须为NULL或指向pklen字节的可写内存, pk_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_public_key_encode(pk: *mut u8, pk_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let pk = unsafe { capi::array_mut::<pklen>(pk) }?;
+        let public_key = unsafe { capi::handle_ref::<PublicKey>(pk_handle) }?;
+
+        public_key.pk_encode_inplace(pk);
+        Ok(())
+    })
+}
+
+/// opengm_mldsa44_import_private_key 导入私钥, sk指向sklen字节, 成功时*sk_handle指向私钥.
+///
+/// # Safety
+/// sk必须为NULL或指向sklen字节, sk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_import_private_key(sk: *const u8, sk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let sk = unsafe { capi::array_ref::<sklen>(sk) }?;
+        let out = unsafe { capi::out_handle(sk_handle) }?;
+        *out = capi::into_handle(PrivateKey::sk_decode(sk));
+        Ok(())
+    })
+}
 
+/// opengm_mldsa44_import_public_key 导入公钥, pk指向pklen字节, 成功时*pk_handle指向公钥.
+///
+/// # Safety
+/// pk必须为NULL或指向pklen字节, pk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa44_private_key_encode(sk: *mut u8, sk_handle: *mut c_void) {
-    // SAFTY: key_handle must be imported or generate key's returns.
-    let private_key: Box<internal::PrivateKey<4, 4>> = unsafe { Box::from_raw(sk_handle as *mut PrivateKey) };
+pub unsafe extern "C" fn opengm_mldsa44_import_public_key(pk: *const u8, pk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let pk = unsafe { capi::array_ref::<pklen>(pk) }?;
+        let out = unsafe { capi::out_handle(pk_handle) }?;
+        *out = capi::into_handle(PublicKey::pk_decode(pk));
+        Ok(())
+    })
+}
 
-    let sk = unsafe { core::slice::from_raw_parts_mut(sk, sklen) }
-        .try_into()
-        .unwrap();
+/// opengm_mldsa44_sign_internal 签名, sig指向siglen字节, rnd指向32字节, mlen为0时m可以为NULL.
+///
+/// # Safety
+/// sig必须为NULL或指向siglen字节的可写内存, rnd必须为NULL或指向32字节, m必须指向mlen字节,
+/// sk_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_sign_internal(
+    sig: *mut u8,
+    sk_handle: *const c_void,
+    m: *const u8,
+    mlen: usize,
+    rnd: *const u8,
+) -> i32 {
+    capi::status(|| {
+        let sig = unsafe { capi::array_mut::<siglen>(sig) }?;
+        let sk = unsafe { capi::handle_ref::<PrivateKey>(sk_handle) }?;
+        let m = unsafe { capi::slice_ref(m, mlen) }?;
+        let rnd = unsafe { capi::array_ref::<32>(rnd) }?;
+
+        let signature = sk.sign_internal(m, rnd);
+        signature.sig_encode_inplace(sig);
+        Ok(())
+    })
+}
 
-    private_key.sk_encode_inplace(sk);
+/// opengm_mldsa44_verify_internal 验证签名, 签名有效时返回OPENGM_OK, 无效时返回OPENGM_ERR_VERIFY.
+///
+/// # Safety
+/// sig必须为NULL或指向siglen字节, m必须指向mlen字节, pk_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_verify_internal(
+    sig: *const u8,
+    pk_handle: *const c_void,
+    m: *const u8,
+    mlen: usize,
+) -> i32 {
+    capi::status(|| {
+        let sig = unsafe { capi::array_ref::<siglen>(sig) }?;
+        let pk = unsafe { capi::handle_ref::<PublicKey>(pk_handle) }?;
+        let m = unsafe { capi::slice_ref(m, mlen) }?;
+
+        let sig = Signature::sig_decode(sig).ok_or(capi::OPENGM_ERR_VERIFY)?;
+        if !pk.verify_internal(m, &sig) {
+            return Err(capi::OPENGM_ERR_VERIFY);
+        }
+        Ok(())
+    })
+}
 
-    Box::leak(private_key);
+/// opengm_mldsa44_drop_private_key_handle 释放私钥, sk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+///
+/// # Safety
+/// sk_handle必须为NULL或本库返回的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_drop_private_key_handle(sk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::drop_handle::<PrivateKey>(sk_handle) })
 }
 
+/// opengm_mldsa44_drop_public_key_handle 释放公钥, pk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+///
+/// # Safety
+/// pk_handle必须为NULL或本库返回的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa44_public_key_encode(pk: *mut u8, pk_handle: *mut c_void) {
-    // SAFTY: key_handle must be imported or generate key's returns.
-    let public_key = unsafe { Box::from_raw(pk_handle as *mut PublicKey) };
+pub unsafe extern "C" fn opengm_mldsa44_drop_public_key_handle(pk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::drop_handle::<PublicKey>(pk_handle) })
+}
 
-    let pk = unsafe { core::slice::from_raw_parts_mut(pk, pklen) }
-        .try_into()
-        .unwrap();
-    public_key.pk_encode_inplace(pk);
+/////////////////////////////////////////////////////////////////////
+//  exports C api with caller provided storage, no allocation.
+//  The initialized storage is used as a handle of the functions above,
+//  and must be cleared by the *_clear_* functions instead of dropped.
+/////////////////////////////////////////////////////////////////////
 
-    Box::leak(public_key);
+/// opengm_mldsa44_private_key_size 返回私钥所需的存储空间字节数.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_mldsa44_private_key_size() -> usize {
+    capi::storage_size::<PrivateKey>()
 }
 
+/// opengm_mldsa44_public_key_size 返回公钥所需的存储空间字节数.
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa44_import_private_key(sk: *const u8) -> *mut c_void {
-    let sk = unsafe { core::slice::from_raw_parts(sk, sklen) }.try_into().unwrap();
-    Box::leak(Box::new(PrivateKey::sk_decode(&sk))) as *mut _ as *mut c_void
+pub extern "C" fn opengm_mldsa44_public_key_size() -> usize {
+    capi::storage_size::<PublicKey>()
 }
 
+/// opengm_mldsa44_generate_key_internal_inplace 在storage中由32字节的种子xi生成私钥, 成功时storage可作为sk_handle使用.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa44_private_key_size(), 否则返回OPENGM_ERR_STORAGE.
+///
+/// # Safety
+/// xi必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa44_import_public_key(pk: *const u8) -> *mut c_void {
-    let pk= unsafe { core::slice::from_raw_parts(pk, pklen) }.try_into().unwrap();
-    Box::leak(Box::new(PublicKey::pk_decode(&pk))) as *mut _ as *mut c_void
+pub unsafe extern "C" fn opengm_mldsa44_generate_key_internal_inplace(
+    xi: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let xi = unsafe { capi::array_ref::<32>(xi) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(keygen_internal(xi))) }
+    })
 }
 
+/// opengm_mldsa44_public_key_inplace 在storage中由私钥得到公钥, 成功时storage可作为pk_handle使用.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa44_public_key_size().
+///
+/// # Safety
+/// sk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa44_sign_internal(
+pub unsafe extern "C" fn opengm_mldsa44_public_key_inplace(
+    sk_handle: *const c_void,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let private_key = unsafe { capi::handle_ref::<PrivateKey>(sk_handle) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(private_key.public_key())) }
+    })
+}
+
+/// opengm_mldsa44_import_private_key_inplace 在storage中导入私钥, sk指向sklen字节.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa44_private_key_size().
+///
+/// # Safety
+/// sk必须为NULL或指向sklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_import_private_key_inplace(
+    sk: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let sk = unsafe { capi::array_ref::<sklen>(sk) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(PrivateKey::sk_decode(sk))) }
+    })
+}
+
+/// opengm_mldsa44_import_public_key_inplace 在storage中导入公钥, pk指向pklen字节.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa44_public_key_size().
+///
+/// # Safety
+/// pk必须为NULL或指向pklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_import_public_key_inplace(
+    pk: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let pk = unsafe { capi::array_ref::<pklen>(pk) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(PublicKey::pk_decode(pk))) }
+    })
+}
+
+/// opengm_mldsa44_clear_private_key 清除storage中的私钥, 私钥被清零, sk_handle为NULL时不做任何操作.
+///
+/// # Safety
+/// sk_handle必须为NULL或本库返回的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_clear_private_key(sk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::clear_handle::<PrivateKey>(sk_handle) })
+}
+
+/// opengm_mldsa44_clear_public_key 清除storage中的公钥, pk_handle为NULL时不做任何操作.
+///
+/// # Safety
+/// pk_handle必须为NULL或本库返回的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa44_clear_public_key(pk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::clear_handle::<PublicKey>(pk_handle) })
+}
+
+/////////////////////////////////////////////////////////////////////
+//  deprecated C api, shims of the opengm_mldsa44_* functions.
+//  The functions returning a handle return NULL on error, those returning
+//  or freeing a handle need the alloc feature.
+/////////////////////////////////////////////////////////////////////
+
+/// # Safety
+/// 同opengm_mldsa44_generate_key_internal.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa44_generate_key_internal")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa44_generate_key_internal(xi: *const u8) -> *mut c_void {
+    let mut sk_handle = null_mut();
+    unsafe { opengm_mldsa44_generate_key_internal(xi, &mut sk_handle) };
+    sk_handle
+}
+
+/// # Safety
+/// 同opengm_mldsa44_public_key.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa44_public_key")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa44_public_key(sk_handle: *mut c_void) -> *mut c_void {
+    let mut pk_handle = null_mut();
+    unsafe { opengm_mldsa44_public_key(sk_handle, &mut pk_handle) };
+    pk_handle
+}
+
+/// # Safety
+/// 同opengm_mldsa44_private_key_encode.
+#[deprecated(note = "use opengm_mldsa44_private_key_encode")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa44_private_key_encode(sk: *mut u8, sk_handle: *mut c_void) {
+    unsafe { opengm_mldsa44_private_key_encode(sk, sk_handle) };
+}
+
+/// # Safety
+/// 同opengm_mldsa44_public_key_encode.
+#[deprecated(note = "use opengm_mldsa44_public_key_encode")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa44_public_key_encode(pk: *mut u8, pk_handle: *mut c_void) {
+    unsafe { opengm_mldsa44_public_key_encode(pk, pk_handle) };
+}
+
+/// # Safety
+/// 同opengm_mldsa44_import_private_key.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa44_import_private_key")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa44_import_private_key(sk: *const u8) -> *mut c_void {
+    let mut sk_handle = null_mut();
+    unsafe { opengm_mldsa44_import_private_key(sk, &mut sk_handle) };
+    sk_handle
+}
+
+/// # Safety
+/// 同opengm_mldsa44_import_public_key.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa44_import_public_key")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa44_import_public_key(pk: *const u8) -> *mut c_void {
+    let mut pk_handle = null_mut();
+    unsafe { opengm_mldsa44_import_public_key(pk, &mut pk_handle) };
+    pk_handle
+}
+
+/// # Safety
+/// 同opengm_mldsa44_sign_internal.
+#[deprecated(note = "use opengm_mldsa44_sign_internal")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa44_sign_internal(
     sig: *mut u8,
     sk_handle: *mut c_void,
     m: *const u8,
     mlen: usize,
     rnd: *const u8,
 ) -> u32 {
-    let sig = unsafe { core::slice::from_raw_parts_mut(sig, siglen) }
-        .try_into()
-        .unwrap();
-    let sk = unsafe { Box::from_raw(sk_handle as *mut PrivateKey) };
-    let m = unsafe { core::slice::from_raw_parts(m, mlen) }.try_into().unwrap();
-    let rnd = unsafe { core::slice::from_raw_parts(rnd, 32) }.try_into().unwrap();
-    let signature = sk.sign_internal(m, rnd);
-    signature.sig_encode_inplace(sig);
-
-    Box::leak(sk);
-    // TODO: return error code.
-    0
+    unsafe { opengm_mldsa44_sign_internal(sig, sk_handle, m, mlen, rnd) as u32 }
 }
 
+/// # Safety
+/// 同opengm_mldsa44_verify_internal.
+#[deprecated(note = "use opengm_mldsa44_verify_internal")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa44_verify_internal(sig: *const u8, pk_handle: *mut c_void, m: *const u8, mlen: usize) -> bool {
-     let m = unsafe { core::slice::from_raw_parts(m, mlen)}.try_into().unwrap();
-    let pk = unsafe { Box::from_raw(pk_handle as *mut PublicKey) };
-    let sig = unsafe { core::slice::from_raw_parts(sig, siglen) }.try_into().unwrap();
-
-    let sig = Signature::sig_decode(sig);
-    if sig.is_none(){
-        return false;
-    }
-    let ok = pk.verify_internal(m, sig.as_ref().unwrap());
-    Box::leak(pk);
-    ok
+pub unsafe extern "C" fn mldsa44_verify_internal(
+    sig: *const u8,
+    pk_handle: *mut c_void,
+    m: *const u8,
+    mlen: usize,
+) -> bool {
+    unsafe { opengm_mldsa44_verify_internal(sig, pk_handle, m, mlen) == capi::OPENGM_OK }
 }
 
+/// # Safety
+/// 同opengm_mldsa44_drop_private_key_handle.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa44_drop_private_key_handle")]
 #[unsafe(no_mangle)]
-extern "C" fn mldsa44_drop_private_key_handle(sk_handle: *mut c_void) {
-    drop(unsafe { Box::from_raw(sk_handle as *mut PrivateKey) });
+pub unsafe extern "C" fn mldsa44_drop_private_key_handle(sk_handle: *mut c_void) {
+    unsafe { opengm_mldsa44_drop_private_key_handle(sk_handle) };
 }
 
+/// # Safety
+/// 同opengm_mldsa44_drop_public_key_handle.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa44_drop_public_key_handle")]
 #[unsafe(no_mangle)]
-extern "C" fn mldsa44_drop_public_key_handle(pk_handle: *mut c_void) {
-    drop(unsafe { Box::from_raw(pk_handle as *mut PublicKey) });
+pub unsafe extern "C" fn mldsa44_drop_public_key_handle(pk_handle: *mut c_void) {
+    unsafe { opengm_mldsa44_drop_public_key_handle(pk_handle) };
 }
 
 #[cfg(test)]
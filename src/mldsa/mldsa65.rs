@@ -1,7 +1,19 @@
-use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
 use core::ffi::c_void;
+#[cfg(feature = "alloc")]
+use core::ptr::null_mut;
 
-use super::{internal, Q};
+use crate::alg::{self, Algorithm};
+use crate::capi;
+use crate::cose;
+#[cfg(feature = "alloc")]
+use crate::jose;
+use crate::pkcs;
+#[cfg(feature = "alloc")]
+use crate::pkcs::{PrivateKeyChoice, PrivateKeyFormat};
+use super::errors::{Error, Result};
+use super::{internal, PreHash, Q};
 
 pub(crate) const d: usize = 13;
 pub(crate) const tau: usize = 49;
@@ -31,6 +43,23 @@ impl PrivateKey {
         self.sign_internal_::<gamma1, gamma2, lambda, tau, beta, omega>(m, rnd)
     }
 
+    // ML-DSA.Sign_internal on the message representative mu computed by the caller.
+    pub(crate) fn sign_mu(&self, mu: &[u8; 64], rnd: &[u8; 32]) -> Signature {
+        self.sign_mu_::<gamma1, gamma2, lambda, tau, beta, omega>(mu, rnd)
+    }
+
+    // ML-DSA.Sign, ctx is at most 255 bytes, rnd is all zero for the deterministic variant.
+    pub fn sign(&self, m: &[u8], ctx: &[u8], rnd: &[u8; 32]) -> Result<Signature> {
+        self.sign_::<gamma1, gamma2, lambda, tau, beta, omega>(m, ctx, rnd)
+            .ok_or(Error::ContextTooLong)
+    }
+
+    // HashML-DSA.Sign with the pre-hash ph, ctx is at most 255 bytes.
+    pub fn hash_sign(&self, m: &[u8], ctx: &[u8], ph: PreHash, rnd: &[u8; 32]) -> Result<Signature> {
+        self.hash_sign_::<gamma1, gamma2, lambda, tau, beta, omega>(m, ctx, ph, rnd)
+            .ok_or(Error::ContextTooLong)
+    }
+
     pub fn public_key(&self) -> PublicKey {
         self.public_key_()
     }
@@ -57,6 +86,21 @@ impl PublicKey {
         self.verify_internal_::<gamma1, gamma2, lambda, tau, beta>(m, &sig)
     }
 
+    // ML-DSA.Verify_internal on the message representative mu computed by the caller.
+    pub(crate) fn verify_mu(&self, mu: &[u8; 64], sig: &Signature) -> bool {
+        self.verify_mu_::<gamma1, gamma2, lambda, tau, beta>(mu, sig)
+    }
+
+    // ML-DSA.Verify, returns false if ctx is longer than 255 bytes.
+    pub fn verify(&self, m: &[u8], ctx: &[u8], sig: &Signature) -> bool {
+        self.verify_::<gamma1, gamma2, lambda, tau, beta>(m, ctx, sig)
+    }
+
+    // HashML-DSA.Verify with the pre-hash ph, returns false if ctx is longer than 255 bytes.
+    pub fn hash_verify(&self, m: &[u8], ctx: &[u8], ph: PreHash, sig: &Signature) -> bool {
+        self.hash_verify_::<gamma1, gamma2, lambda, tau, beta>(m, ctx, ph, sig)
+    }
+
     pub fn pk_encode_inplace(&self, b: &mut [u8; pklen]) {
         self.pk_encode_inplace_(b);
     }
@@ -84,108 +128,610 @@ impl Signature {
     }
 }
 
+/// ML-DSA-65 of the runtime dispatch layer.
+pub struct MlDsa65;
+
+impl alg::SignatureScheme for MlDsa65 {
+    const ALGORITHM: Algorithm = Algorithm::MlDsa65;
+    type PrivateKey = PrivateKey;
+    type PublicKey = PublicKey;
+
+    fn keygen_internal(xi: &[u8; 32]) -> PrivateKey {
+        keygen_internal(xi)
+    }
+
+    fn public_key(sk: &PrivateKey) -> PublicKey {
+        sk.public_key()
+    }
+
+    fn encode_public_key(pk: &PublicKey, b: &mut [u8]) -> alg::Result<()> {
+        pk.pk_encode_inplace(alg::array_mut::<pklen>(b)?);
+        Ok(())
+    }
+
+    fn decode_public_key(b: &[u8]) -> alg::Result<PublicKey> {
+        Ok(PublicKey::pk_decode(alg::array_ref::<pklen>(b)?))
+    }
+
+    fn encode_private_key(sk: &PrivateKey, b: &mut [u8]) -> alg::Result<()> {
+        sk.sk_encode_inplace(alg::array_mut::<sklen>(b)?);
+        Ok(())
+    }
+
+    fn decode_private_key(b: &[u8]) -> alg::Result<PrivateKey> {
+        Ok(PrivateKey::sk_decode(alg::array_ref::<sklen>(b)?))
+    }
+}
+
+impl alg::Signer for PrivateKey {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::MlDsa65
+    }
+
+    fn sign(&self, m: &[u8], ctx: &[u8], rnd: &[u8; 32], sig: &mut [u8]) -> alg::Result<()> {
+        let sig = alg::array_mut::<siglen>(sig)?;
+        let signature = PrivateKey::sign(self, m, ctx, rnd).map_err(|_| alg::Error::ContextTooLong)?;
+        signature.sig_encode_inplace(sig);
+        Ok(())
+    }
+}
+
+impl alg::Verifier for PublicKey {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::MlDsa65
+    }
+
+    fn verify(&self, m: &[u8], ctx: &[u8], sig: &[u8]) -> alg::Result<()> {
+        let sig = Signature::sig_decode(alg::array_ref::<siglen>(sig)?).ok_or(alg::Error::Verify)?;
+        if !PublicKey::verify(self, m, ctx, &sig) {
+            return Err(alg::Error::Verify);
+        }
+        Ok(())
+    }
+}
+
+impl PublicKey {
+    /// Decode from a DER SubjectPublicKeyInfo with the id-ml-dsa-65 OID.
+    pub fn from_public_key_der(der: &[u8]) -> pkcs::Result<Self> {
+        match pkcs::decode_spki(der)? {
+            (Algorithm::MlDsa65, pk) => Ok(PublicKey::pk_decode(pk.try_into().map_err(|_| pkcs::Error::InvalidLength)?)),
+            _ => Err(pkcs::Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Encode to a DER SubjectPublicKeyInfo.
+    #[cfg(feature = "alloc")]
+    pub fn to_public_key_der(&self) -> Vec<u8> {
+        pkcs::spki_der(pkcs::oid::ID_ML_DSA_65, &self.pk_encode())
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn from_public_key_pem(pem: &str) -> pkcs::Result<Self> {
+        Self::from_public_key_der(&pkcs::pem::decode_label(pem, pkcs::pem::PUBLIC_KEY)?)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_public_key_pem(&self) -> String {
+        pkcs::pem::encode(pkcs::pem::PUBLIC_KEY, &self.to_public_key_der())
+    }
+
+    /// Decode from an AKP JSON Web Key of ML-DSA-65, the priv member is ignored.
+    #[cfg(feature = "alloc")]
+    pub fn from_jwk(s: &str) -> jose::Result<Self> {
+        let jwk = jose::Jwk::from_json(s)?;
+        if jwk.alg != Algorithm::MlDsa65 {
+            return Err(jose::Error::AlgorithmMismatch);
+        }
+        Ok(PublicKey::pk_decode(jwk.public.as_slice().try_into().map_err(|_| jose::Error::InvalidLength)?))
+    }
+
+    /// Encode to an AKP JSON Web Key.
+    #[cfg(feature = "alloc")]
+    pub fn to_jwk(&self) -> String {
+        jose::Jwk { alg: Algorithm::MlDsa65, public: self.pk_encode().to_vec(), seed: None, kid: None }.to_json()
+    }
+
+    /// Decode from an AKP COSE_Key of ML-DSA-65, the priv parameter is ignored.
+    pub fn from_cose_key(data: &[u8]) -> cose::Result<Self> {
+        let key = cose::Key::decode(data)?;
+        if key.alg != Algorithm::MlDsa65 {
+            return Err(cose::Error::AlgorithmMismatch);
+        }
+        Ok(PublicKey::pk_decode(key.public.try_into().map_err(|_| cose::Error::InvalidLength)?))
+    }
+
+    /// Encode to an AKP COSE_Key into out, returns the length.
+    pub fn to_cose_key(&self, out: &mut [u8]) -> cose::Result<usize> {
+        cose::Key::new(Algorithm::MlDsa65, &self.pk_encode(), None)?.encode(out)
+    }
+}
+
+impl PrivateKey {
+    /// Generate the key from the 32 bytes seed xi.
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        keygen_internal(seed)
+    }
+
+    /// Decode from a DER PKCS#8 private key of the seed, expandedKey or both form,
+    /// the expanded key of the both form must match the seed.
+    pub fn from_pkcs8_der(der: &[u8]) -> pkcs::Result<Self> {
+        let key = match pkcs::decode_pkcs8(der)? {
+            (Algorithm::MlDsa65, key) => key,
+            _ => return Err(pkcs::Error::UnsupportedAlgorithm),
+        };
+        let seed = key.seed().map(|seed| seed.try_into().map_err(|_| pkcs::Error::InvalidLength)).transpose()?;
+        match (seed, key.expanded_key()) {
+            (Some(seed), None) => Ok(Self::from_seed(seed)),
+            (Some(seed), Some(expanded_key)) => {
+                let sk = Self::from_seed(seed);
+                pkcs::check_expanded_key(&sk.sk_encode(), expanded_key)?;
+                Ok(sk)
+            }
+            (None, Some(expanded_key)) => {
+                Ok(PrivateKey::sk_decode(expanded_key.try_into().map_err(|_| pkcs::Error::InvalidLength)?))
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    /// Encode to a DER PKCS#8 private key of the expandedKey form, as the key does not keep its seed.
+    /// Use seed_to_pkcs8_der for the seed and both forms.
+    #[cfg(feature = "alloc")]
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        pkcs::pkcs8_der(pkcs::oid::ID_ML_DSA_65, &PrivateKeyChoice::ExpandedKey(&self.sk_encode()))
+    }
+
+    /// Encode the key generated from the seed xi to a DER PKCS#8 private key of the format.
+    #[cfg(feature = "alloc")]
+    pub fn seed_to_pkcs8_der(seed: &[u8; 32], format: PrivateKeyFormat) -> Vec<u8> {
+        if format == PrivateKeyFormat::Seed {
+            return pkcs::pkcs8_der(pkcs::oid::ID_ML_DSA_65, &PrivateKeyChoice::Seed(seed));
+        }
+        let expanded_key = Self::from_seed(seed).sk_encode();
+        let key = match format {
+            PrivateKeyFormat::Both => PrivateKeyChoice::Both { seed, expanded_key: &expanded_key },
+            _ => PrivateKeyChoice::ExpandedKey(&expanded_key),
+        };
+        pkcs::pkcs8_der(pkcs::oid::ID_ML_DSA_65, &key)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn from_pkcs8_pem(pem: &str) -> pkcs::Result<Self> {
+        Self::from_pkcs8_der(&pkcs::pem::decode_label(pem, pkcs::pem::PRIVATE_KEY)?)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_pkcs8_pem(&self) -> String {
+        pkcs::pem::encode(pkcs::pem::PRIVATE_KEY, &self.to_pkcs8_der())
+    }
+
+    /// Decode from an AKP JSON Web Key of ML-DSA-65 with the seed, the public key must match it.
+    #[cfg(feature = "alloc")]
+    pub fn from_jwk(s: &str) -> jose::Result<Self> {
+        let jwk = jose::Jwk::from_json(s)?;
+        if jwk.alg != Algorithm::MlDsa65 {
+            return Err(jose::Error::AlgorithmMismatch);
+        }
+        let sk = Self::from_seed(&jwk.seed.ok_or(jose::Error::NoPrivateKey)?);
+        if sk.public_key().pk_encode()[..] != jwk.public[..] {
+            return Err(jose::Error::KeyMismatch);
+        }
+        Ok(sk)
+    }
+
+    /// Encode the key generated from the seed xi to an AKP JSON Web Key with the seed,
+    /// as the key does not keep its seed.
+    #[cfg(feature = "alloc")]
+    pub fn seed_to_jwk(seed: &[u8; 32]) -> String {
+        let public = Self::from_seed(seed).public_key().pk_encode().to_vec();
+        jose::Jwk { alg: Algorithm::MlDsa65, public, seed: Some(*seed), kid: None }.to_json()
+    }
+
+    /// Decode from an AKP COSE_Key of ML-DSA-65 with the seed, the public key must match it.
+    pub fn from_cose_key(data: &[u8]) -> cose::Result<Self> {
+        let key = cose::Key::decode(data)?;
+        if key.alg != Algorithm::MlDsa65 {
+            return Err(cose::Error::AlgorithmMismatch);
+        }
+        let sk = Self::from_seed(key.seed.ok_or(cose::Error::NoPrivateKey)?);
+        if sk.public_key().pk_encode()[..] != key.public[..] {
+            return Err(cose::Error::KeyMismatch);
+        }
+        Ok(sk)
+    }
+
+    /// Encode the key generated from the seed xi to an AKP COSE_Key with the seed into out,
+    /// returns the length.
+    pub fn seed_to_cose_key(seed: &[u8; 32], out: &mut [u8]) -> cose::Result<usize> {
+        let public = Self::from_seed(seed).public_key().pk_encode();
+        cose::Key::new(Algorithm::MlDsa65, &public, Some(seed))?.encode(out)
+    }
+}
+
 /////////////////////////////////////////////////////////////////////
-///  exports C api
+//  exports C api
 /////////////////////////////////////////////////////////////////////
 
+impl capi::Handle for PrivateKey {
+    const TAG: u32 = capi::tag(capi::MLDSA_PRIVATE_KEY, 65);
+}
+
+impl capi::Handle for PublicKey {
+    const TAG: u32 = capi::tag(capi::MLDSA_PUBLIC_KEY, 65);
+}
+
+/// opengm_mldsa65_generate_key_internal 由32字节的种子xi生成私钥, 成功时*sk_handle指向私钥.
+///
+/// # Safety
+/// xi必须为NULL或指向32字节, sk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa65_generate_key_internal(xi: *const u8) -> *mut c_void {
-    let xi = unsafe { core::slice::from_raw_parts(xi, 32) }.try_into().unwrap();
-    Box::leak(Box::new(keygen_internal(xi))) as *mut _ as *mut c_void
+pub unsafe extern "C" fn opengm_mldsa65_generate_key_internal(xi: *const u8, sk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let xi = unsafe { capi::array_ref::<32>(xi) }?;
+        let out = unsafe { capi::out_handle(sk_handle) }?;
+        *out = capi::into_handle(keygen_internal(xi));
+        Ok(())
+    })
 }
 
+/// opengm_mldsa65_public_key 由私钥得到公钥, 成功时*pk_handle指向公钥.
+///
+/// # Safety
+/// sk_handle必须为NULL或本库返回且未释放的handle, pk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa65_public_key(sk_handle: *mut c_void) -> *mut c_void {
-    let private_key = unsafe { Box::from_raw(sk_handle as *mut PrivateKey) };
-    let public_key = Box::leak(Box::new(private_key.public_key())) as *mut _ as *mut c_void;
-    Box::leak(private_key);
-    public_key
+pub unsafe extern "C" fn opengm_mldsa65_public_key(sk_handle: *const c_void, pk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let private_key = unsafe { capi::handle_ref::<PrivateKey>(sk_handle) }?;
+        let out = unsafe { capi::out_handle(pk_handle) }?;
+        *out = capi::into_handle(private_key.public_key());
+        Ok(())
+    })
 }
 
+/// opengm_mldsa65_private_key_encode 编码私钥, sk指向sklen字节.
+///
+/// # Safety
+/// sk必须为NULL或指向sklen字节的可写内存, sk_handle必须为NULL或本库返回且未释放的handle.
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa65_private_key_encode(sk: *mut u8, sk_handle: *mut c_void) {
-    // SAFTY: key_handle must be imported or generate key's returns.
-    let private_key = unsafe { Box::from_raw(sk_handle as *mut PrivateKey) };
+pub unsafe extern "C" fn opengm_mldsa65_private_key_encode(sk: *mut u8, sk_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let sk = unsafe { capi::array_mut::<sklen>(sk) }?;
+        let private_key = unsafe { capi::handle_ref::<PrivateKey>(sk_handle) }?;
 
-    let sk = unsafe { core::slice::from_raw_parts_mut(sk, sklen) }
-        .try_into()
-        .unwrap();
+        private_key.sk_encode_inplace(sk);
+        Ok(())
+    })
+}
 
-    private_key.sk_encode_inplace(sk);
+/// opengm_mldsa65_public_key_encode 编码公钥, pk指向pklen字节.
+///
+/// # Safety
+/// pk必须为NULL或指向pklen字节的可写内存, pk_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_public_key_encode(pk: *mut u8, pk_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let pk = unsafe { capi::array_mut::<pklen>(pk) }?;
+        let public_key = unsafe { capi::handle_ref::<PublicKey>(pk_handle) }?;
 
-    Box::leak(private_key);
+        public_key.pk_encode_inplace(pk);
+        Ok(())
+    })
 }
 
+/// opengm_mldsa65_import_private_key 导入私钥, sk指向sklen字节, 成功时*sk_handle指向私钥.
+///
+/// # Safety
+/// sk必须为NULL或指向sklen字节, sk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa65_public_key_encode(pk: *mut u8, pk_handle: *mut c_void) {
-    // SAFTY: key_handle must be imported or generate key's returns.
-    let public_key = unsafe { Box::from_raw(pk_handle as *mut PublicKey) };
+pub unsafe extern "C" fn opengm_mldsa65_import_private_key(sk: *const u8, sk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let sk = unsafe { capi::array_ref::<sklen>(sk) }?;
+        let out = unsafe { capi::out_handle(sk_handle) }?;
+        *out = capi::into_handle(PrivateKey::sk_decode(sk));
+        Ok(())
+    })
+}
 
-    let pk = unsafe { core::slice::from_raw_parts_mut(pk, pklen) }
-        .try_into()
-        .unwrap();
-    public_key.pk_encode_inplace(pk);
+/// opengm_mldsa65_import_public_key 导入公钥, pk指向pklen字节, 成功时*pk_handle指向公钥.
+///
+/// # Safety
+/// pk必须为NULL或指向pklen字节, pk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_import_public_key(pk: *const u8, pk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let pk = unsafe { capi::array_ref::<pklen>(pk) }?;
+        let out = unsafe { capi::out_handle(pk_handle) }?;
+        *out = capi::into_handle(PublicKey::pk_decode(pk));
+        Ok(())
+    })
+}
+
+/// opengm_mldsa65_sign_internal 签名, sig指向siglen字节, rnd指向32字节, mlen为0时m可以为NULL.
+///
+/// # Safety
+/// sig必须为NULL或指向siglen字节的可写内存, rnd必须为NULL或指向32字节, m必须指向mlen字节,
+/// sk_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_sign_internal(
+    sig: *mut u8,
+    sk_handle: *const c_void,
+    m: *const u8,
+    mlen: usize,
+    rnd: *const u8,
+) -> i32 {
+    capi::status(|| {
+        let sig = unsafe { capi::array_mut::<siglen>(sig) }?;
+        let sk = unsafe { capi::handle_ref::<PrivateKey>(sk_handle) }?;
+        let m = unsafe { capi::slice_ref(m, mlen) }?;
+        let rnd = unsafe { capi::array_ref::<32>(rnd) }?;
+
+        let signature = sk.sign_internal(m, rnd);
+        signature.sig_encode_inplace(sig);
+        Ok(())
+    })
+}
+
+/// opengm_mldsa65_verify_internal 验证签名, 签名有效时返回OPENGM_OK, 无效时返回OPENGM_ERR_VERIFY.
+///
+/// # Safety
+/// sig必须为NULL或指向siglen字节, m必须指向mlen字节, pk_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_verify_internal(
+    sig: *const u8,
+    pk_handle: *const c_void,
+    m: *const u8,
+    mlen: usize,
+) -> i32 {
+    capi::status(|| {
+        let sig = unsafe { capi::array_ref::<siglen>(sig) }?;
+        let pk = unsafe { capi::handle_ref::<PublicKey>(pk_handle) }?;
+        let m = unsafe { capi::slice_ref(m, mlen) }?;
 
-    Box::leak(public_key);
+        let sig = Signature::sig_decode(sig).ok_or(capi::OPENGM_ERR_VERIFY)?;
+        if !pk.verify_internal(m, &sig) {
+            return Err(capi::OPENGM_ERR_VERIFY);
+        }
+        Ok(())
+    })
 }
 
+/// opengm_mldsa65_drop_private_key_handle 释放私钥, sk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+///
+/// # Safety
+/// sk_handle必须为NULL或本库返回的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa65_import_private_key(sk: *const u8) -> *mut c_void {
-    let sk = unsafe { core::slice::from_raw_parts(sk, sklen) }.try_into().unwrap();
-    Box::leak(Box::new(PrivateKey::sk_decode(&sk))) as *mut _ as *mut c_void
+pub unsafe extern "C" fn opengm_mldsa65_drop_private_key_handle(sk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::drop_handle::<PrivateKey>(sk_handle) })
 }
 
+/// opengm_mldsa65_drop_public_key_handle 释放公钥, pk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+///
+/// # Safety
+/// pk_handle必须为NULL或本库返回的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa65_import_public_key(pk: *const u8) -> *mut c_void {
-    let pk = unsafe { core::slice::from_raw_parts(pk, pklen) }.try_into().unwrap();
-    Box::leak(Box::new(PublicKey::pk_decode(&pk))) as *mut _ as *mut c_void
+pub unsafe extern "C" fn opengm_mldsa65_drop_public_key_handle(pk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::drop_handle::<PublicKey>(pk_handle) })
 }
 
+/////////////////////////////////////////////////////////////////////
+//  exports C api with caller provided storage, no allocation.
+//  The initialized storage is used as a handle of the functions above,
+//  and must be cleared by the *_clear_* functions instead of dropped.
+/////////////////////////////////////////////////////////////////////
+
+/// opengm_mldsa65_private_key_size 返回私钥所需的存储空间字节数.
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa65_sign_internal(
+pub extern "C" fn opengm_mldsa65_private_key_size() -> usize {
+    capi::storage_size::<PrivateKey>()
+}
+
+/// opengm_mldsa65_public_key_size 返回公钥所需的存储空间字节数.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_mldsa65_public_key_size() -> usize {
+    capi::storage_size::<PublicKey>()
+}
+
+/// opengm_mldsa65_generate_key_internal_inplace 在storage中由32字节的种子xi生成私钥, 成功时storage可作为sk_handle使用.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa65_private_key_size(), 否则返回OPENGM_ERR_STORAGE.
+///
+/// # Safety
+/// xi必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_generate_key_internal_inplace(
+    xi: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let xi = unsafe { capi::array_ref::<32>(xi) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(keygen_internal(xi))) }
+    })
+}
+
+/// opengm_mldsa65_public_key_inplace 在storage中由私钥得到公钥, 成功时storage可作为pk_handle使用.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa65_public_key_size().
+///
+/// # Safety
+/// sk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_public_key_inplace(
+    sk_handle: *const c_void,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let private_key = unsafe { capi::handle_ref::<PrivateKey>(sk_handle) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(private_key.public_key())) }
+    })
+}
+
+/// opengm_mldsa65_import_private_key_inplace 在storage中导入私钥, sk指向sklen字节.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa65_private_key_size().
+///
+/// # Safety
+/// sk必须为NULL或指向sklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_import_private_key_inplace(
+    sk: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let sk = unsafe { capi::array_ref::<sklen>(sk) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(PrivateKey::sk_decode(sk))) }
+    })
+}
+
+/// opengm_mldsa65_import_public_key_inplace 在storage中导入公钥, pk指向pklen字节.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mldsa65_public_key_size().
+///
+/// # Safety
+/// pk必须为NULL或指向pklen字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_import_public_key_inplace(
+    pk: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let pk = unsafe { capi::array_ref::<pklen>(pk) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(PublicKey::pk_decode(pk))) }
+    })
+}
+
+/// opengm_mldsa65_clear_private_key 清除storage中的私钥, 私钥被清零, sk_handle为NULL时不做任何操作.
+///
+/// # Safety
+/// sk_handle必须为NULL或本库返回的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_clear_private_key(sk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::clear_handle::<PrivateKey>(sk_handle) })
+}
+
+/// opengm_mldsa65_clear_public_key 清除storage中的公钥, pk_handle为NULL时不做任何操作.
+///
+/// # Safety
+/// pk_handle必须为NULL或本库返回的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mldsa65_clear_public_key(pk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::clear_handle::<PublicKey>(pk_handle) })
+}
+
+/////////////////////////////////////////////////////////////////////
+//  deprecated C api, shims of the opengm_mldsa65_* functions.
+//  The functions returning a handle return NULL on error, those returning
+//  or freeing a handle need the alloc feature.
+/////////////////////////////////////////////////////////////////////
+
+/// # Safety
+/// 同opengm_mldsa65_generate_key_internal.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa65_generate_key_internal")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa65_generate_key_internal(xi: *const u8) -> *mut c_void {
+    let mut sk_handle = null_mut();
+    unsafe { opengm_mldsa65_generate_key_internal(xi, &mut sk_handle) };
+    sk_handle
+}
+
+/// # Safety
+/// 同opengm_mldsa65_public_key.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa65_public_key")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa65_public_key(sk_handle: *mut c_void) -> *mut c_void {
+    let mut pk_handle = null_mut();
+    unsafe { opengm_mldsa65_public_key(sk_handle, &mut pk_handle) };
+    pk_handle
+}
+
+/// # Safety
+/// 同opengm_mldsa65_private_key_encode.
+#[deprecated(note = "use opengm_mldsa65_private_key_encode")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa65_private_key_encode(sk: *mut u8, sk_handle: *mut c_void) {
+    unsafe { opengm_mldsa65_private_key_encode(sk, sk_handle) };
+}
+
+/// # Safety
+/// 同opengm_mldsa65_public_key_encode.
+#[deprecated(note = "use opengm_mldsa65_public_key_encode")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa65_public_key_encode(pk: *mut u8, pk_handle: *mut c_void) {
+    unsafe { opengm_mldsa65_public_key_encode(pk, pk_handle) };
+}
+
+/// # Safety
+/// 同opengm_mldsa65_import_private_key.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa65_import_private_key")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa65_import_private_key(sk: *const u8) -> *mut c_void {
+    let mut sk_handle = null_mut();
+    unsafe { opengm_mldsa65_import_private_key(sk, &mut sk_handle) };
+    sk_handle
+}
+
+/// # Safety
+/// 同opengm_mldsa65_import_public_key.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa65_import_public_key")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa65_import_public_key(pk: *const u8) -> *mut c_void {
+    let mut pk_handle = null_mut();
+    unsafe { opengm_mldsa65_import_public_key(pk, &mut pk_handle) };
+    pk_handle
+}
+
+/// # Safety
+/// 同opengm_mldsa65_sign_internal.
+#[deprecated(note = "use opengm_mldsa65_sign_internal")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mldsa65_sign_internal(
     sig: *mut u8,
     sk_handle: *mut c_void,
     m: *const u8,
     mlen: usize,
     rnd: *const u8,
 ) -> u32 {
-    let sig = unsafe { core::slice::from_raw_parts_mut(sig, siglen) }
-        .try_into()
-        .unwrap();
-    let sk = unsafe { Box::from_raw(sk_handle as *mut PrivateKey) };
-    let m = unsafe { core::slice::from_raw_parts(m, mlen) }.try_into().unwrap();
-    let rnd = unsafe { core::slice::from_raw_parts(rnd, 32) }.try_into().unwrap();
-    let signature = sk.sign_internal(m, rnd);
-    signature.sig_encode_inplace(sig);
-
-    Box::leak(sk);
-    // TODO: return error code.
-    0
+    unsafe { opengm_mldsa65_sign_internal(sig, sk_handle, m, mlen, rnd) as u32 }
 }
 
+/// # Safety
+/// 同opengm_mldsa65_verify_internal.
+#[deprecated(note = "use opengm_mldsa65_verify_internal")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mldsa65_verify_internal(sig: *const u8, pk_handle: *mut c_void, m: *const u8, mlen: usize) -> bool {
-    let m = unsafe { core::slice::from_raw_parts(m, mlen) }.try_into().unwrap();
-    let pk = unsafe { Box::from_raw(pk_handle as *mut PublicKey) };
-    let sig = unsafe { core::slice::from_raw_parts(sig, siglen) }.try_into().unwrap();
-
-    let sig = Signature::sig_decode(sig);
-    if sig.is_none() {
-        return false;
-    }
-    let ok = pk.verify_internal(m, sig.as_ref().unwrap());
-    Box::leak(pk);
-    ok
+pub unsafe extern "C" fn mldsa65_verify_internal(
+    sig: *const u8,
+    pk_handle: *mut c_void,
+    m: *const u8,
+    mlen: usize,
+) -> bool {
+    unsafe { opengm_mldsa65_verify_internal(sig, pk_handle, m, mlen) == capi::OPENGM_OK }
 }
 
+/// # Safety
+/// 同opengm_mldsa65_drop_private_key_handle.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa65_drop_private_key_handle")]
 #[unsafe(no_mangle)]
-extern "C" fn mldsa65_drop_private_key_handle(sk_handle: *mut c_void) {
-    drop(unsafe { Box::from_raw(sk_handle as *mut PrivateKey) });
+pub unsafe extern "C" fn mldsa65_drop_private_key_handle(sk_handle: *mut c_void) {
+    unsafe { opengm_mldsa65_drop_private_key_handle(sk_handle) };
 }
 
+/// # Safety
+/// 同opengm_mldsa65_drop_public_key_handle.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mldsa65_drop_public_key_handle")]
 #[unsafe(no_mangle)]
-extern "C" fn mldsa65_drop_public_key_handle(pk_handle: *mut c_void) {
-    drop(unsafe { Box::from_raw(pk_handle as *mut PublicKey) });
+pub unsafe extern "C" fn mldsa65_drop_public_key_handle(pk_handle: *mut c_void) {
+    unsafe { opengm_mldsa65_drop_public_key_handle(pk_handle) };
 }
 
 #[cfg(test)]
@@ -205,4 +751,295 @@ mod tests {
             assert!(result);
         }
     }
+
+    #[test]
+    fn test_sign_verify() {
+        let mut rng = rand::rng();
+        let sk = keygen_internal(&rng.random());
+        let pk = sk.public_key();
+        let m: [u8; 32] = rng.random();
+        let rnd = rng.random();
+
+        // M' = 0 || |ctx| || ctx || M
+        let ctx = b"opengm";
+        let mut m_prime = vec![0, ctx.len() as u8];
+        m_prime.extend_from_slice(ctx);
+        m_prime.extend_from_slice(&m);
+
+        let sig = sk.sign(&m, ctx, &rnd).unwrap();
+        assert_eq!(sig.sig_encode(), sk.sign_internal(&m_prime, &rnd).sig_encode());
+        assert!(pk.verify(&m, ctx, &sig));
+        assert!(pk.verify_internal(&m_prime, &sig));
+        assert!(!pk.verify(&m, b"", &sig));
+        assert!(!pk.verify_internal(&m, &sig));
+
+        assert!(sk.sign(&m, &[0; 255], &rnd).is_ok());
+        assert!(sk.sign(&m, &[0; 256], &rnd).is_err());
+        assert!(!pk.verify(&m, &[0; 256], &sig));
+    }
+
+    #[test]
+    fn test_hash_sign_verify() {
+        let mut rng = rand::rng();
+        let sk = keygen_internal(&rng.random());
+        let pk = sk.public_key();
+        let m: [u8; 32] = rng.random();
+        let rnd = rng.random();
+        let ctx = b"opengm";
+
+        for ph in PreHash::ALL {
+            // M' = 1 || |ctx| || ctx || OID || PH(M)
+            let mut digest = [0; 64];
+            let mut m_prime = vec![1, ctx.len() as u8];
+            m_prime.extend_from_slice(ctx);
+            m_prime.extend_from_slice(ph.oid());
+            m_prime.extend_from_slice(ph.digest(&m, &mut digest));
+
+            let sig = sk.hash_sign(&m, ctx, ph, &rnd).unwrap();
+            assert_eq!(sig.sig_encode(), sk.sign_internal(&m_prime, &rnd).sig_encode());
+            assert!(pk.hash_verify(&m, ctx, ph, &sig));
+            assert!(!pk.hash_verify(&m, b"", ph, &sig));
+            assert!(!pk.verify(&m, ctx, &sig));
+            let other = if ph == PreHash::Sm3 { PreHash::Sha256 } else { PreHash::Sm3 };
+            assert!(!pk.hash_verify(&m, ctx, other, &sig));
+        }
+
+        assert!(sk.hash_sign(&m, &[0; 256], PreHash::Sm3, &rnd).is_err());
+    }
+
+    #[test]
+    fn test_sk_decode_public_key() {
+        let sk = keygen_internal(&rand::rng().random());
+        let sk2 = PrivateKey::sk_decode(&sk.sk_encode());
+        assert_eq!(sk2.public_key().pk_encode(), sk.public_key().pk_encode());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_pkcs() {
+        let mut rng = rand::rng();
+        let seed: [u8; 32] = rng.random();
+        let sk = PrivateKey::from_seed(&seed);
+        let pk = sk.public_key();
+        let m: [u8; 32] = rng.random();
+
+        let der = pk.to_public_key_der();
+        assert_eq!(pkcs::decode_spki(&der).unwrap(), (Algorithm::MlDsa65, &pk.pk_encode()[..]));
+        let pk2 = PublicKey::from_public_key_pem(&pk.to_public_key_pem()).unwrap();
+        assert_eq!(pk2.pk_encode(), pk.pk_encode());
+
+        for format in [PrivateKeyFormat::Seed, PrivateKeyFormat::ExpandedKey, PrivateKeyFormat::Both] {
+            let der = PrivateKey::seed_to_pkcs8_der(&seed, format);
+            assert_eq!(pkcs::decode_pkcs8(&der).unwrap().1.format(), format);
+            let sk2 = PrivateKey::from_pkcs8_der(&der).unwrap();
+            assert_eq!(sk2.public_key().pk_encode(), pk.pk_encode());
+            let sig = sk2.sign(&m, b"", &[0; 32]).unwrap();
+            assert!(pk.verify(&m, b"", &sig));
+        }
+        assert_eq!(sk.to_pkcs8_der(), PrivateKey::seed_to_pkcs8_der(&seed, PrivateKeyFormat::ExpandedKey));
+        let sk2 = PrivateKey::from_pkcs8_pem(&sk.to_pkcs8_pem()).unwrap();
+        assert_eq!(sk2.sk_encode(), sk.sk_encode());
+
+        // the expanded key does not match the seed
+        let mut other = seed;
+        other[0] ^= 1;
+        let expanded_key = PrivateKey::from_seed(&other).sk_encode();
+        let der = pkcs::encode_pkcs8(
+            Algorithm::MlDsa65,
+            &PrivateKeyChoice::Both { seed: &seed, expanded_key: &expanded_key },
+        )
+        .unwrap();
+        assert_eq!(PrivateKey::from_pkcs8_der(&der).err(), Some(pkcs::Error::KeyMismatch));
+
+        // keys of the other parameter sets and algorithms
+        let der = super::super::mldsa44::PrivateKey::seed_to_pkcs8_der(&seed, PrivateKeyFormat::Seed);
+        assert_eq!(PrivateKey::from_pkcs8_der(&der).err(), Some(pkcs::Error::UnsupportedAlgorithm));
+        let der = super::super::mldsa87::PrivateKey::from_seed(&seed).public_key().to_public_key_der();
+        assert_eq!(PublicKey::from_public_key_der(&der).err(), Some(pkcs::Error::UnsupportedAlgorithm));
+        let der = crate::mlkem::mlkem768::DecapKey::from_seed(&[0; 64]).encapsulation_key().to_public_key_der();
+        assert_eq!(PublicKey::from_public_key_der(&der).err(), Some(pkcs::Error::UnsupportedAlgorithm));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_jwk() {
+        let seed: [u8; 32] = rand::rng().random();
+        let sk = PrivateKey::from_seed(&seed);
+        let pk = sk.public_key();
+
+        let s = pk.to_jwk();
+        assert!(s.starts_with(r#"{"kty":"AKP","alg":"ML-DSA-65","pub":""#));
+        assert_eq!(PublicKey::from_jwk(&s).unwrap().pk_encode(), pk.pk_encode());
+        let s = PrivateKey::seed_to_jwk(&seed);
+        assert_eq!(PrivateKey::from_jwk(&s).unwrap().sk_encode(), sk.sk_encode());
+        assert_eq!(PublicKey::from_jwk(&s).unwrap().pk_encode(), pk.pk_encode());
+        assert_eq!(PrivateKey::from_jwk(&pk.to_jwk()).err(), Some(jose::Error::NoPrivateKey));
+
+        // the public key does not match the seed
+        let mut other = seed;
+        other[0] ^= 1;
+        let jwk = jose::Jwk::new(Algorithm::MlDsa65, &pk.pk_encode(), Some(&other)).unwrap();
+        assert_eq!(PrivateKey::from_jwk(&jwk.to_json()).err(), Some(jose::Error::KeyMismatch));
+
+        let s = super::super::mldsa44::PrivateKey::seed_to_jwk(&seed);
+        assert_eq!(PrivateKey::from_jwk(&s).err(), Some(jose::Error::AlgorithmMismatch));
+        assert_eq!(PublicKey::from_jwk(&s).err(), Some(jose::Error::AlgorithmMismatch));
+    }
+
+    #[test]
+    fn test_cose_key() {
+        let seed: [u8; 32] = rand::rng().random();
+        let sk = PrivateKey::from_seed(&seed);
+        let pk = sk.public_key();
+        let mut buf = [0; 2048];
+
+        let n = pk.to_cose_key(&mut buf).unwrap();
+        assert_eq!(buf[..8], [0xa3, 0x01, 0x07, 0x03, 0x38, 0x30, 0x20, 0x59]);
+        assert_eq!(PublicKey::from_cose_key(&buf[..n]).unwrap().pk_encode(), pk.pk_encode());
+        assert_eq!(PrivateKey::from_cose_key(&buf[..n]).err(), Some(cose::Error::NoPrivateKey));
+        let n = PrivateKey::seed_to_cose_key(&seed, &mut buf).unwrap();
+        assert_eq!(PrivateKey::from_cose_key(&buf[..n]).unwrap().sk_encode(), sk.sk_encode());
+        assert_eq!(PublicKey::from_cose_key(&buf[..n]).unwrap().pk_encode(), pk.pk_encode());
+
+        // the public key does not match the seed
+        let mut other = seed;
+        other[0] ^= 1;
+        let n = cose::Key::new(Algorithm::MlDsa65, &pk.pk_encode(), Some(&other)).unwrap().encode(&mut buf).unwrap();
+        assert_eq!(PrivateKey::from_cose_key(&buf[..n]).err(), Some(cose::Error::KeyMismatch));
+
+        let n = super::super::mldsa44::PrivateKey::seed_to_cose_key(&seed, &mut buf).unwrap();
+        assert_eq!(PrivateKey::from_cose_key(&buf[..n]).err(), Some(cose::Error::AlgorithmMismatch));
+        assert_eq!(PublicKey::from_cose_key(&buf[..n]).err(), Some(cose::Error::AlgorithmMismatch));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[allow(deprecated)]
+    fn test_capi() {
+        use crate::capi::{OPENGM_ERR_HANDLE, OPENGM_ERR_NULL, OPENGM_ERR_VERIFY, OPENGM_OK};
+        use core::ptr::{null, null_mut};
+
+        let mut rng = rand::rng();
+        let xi: [u8; 32] = rng.random();
+        let rnd: [u8; 32] = rng.random();
+        let m: [u8; 32] = rng.random();
+
+        let mut sk = null_mut();
+        assert_eq!(unsafe { opengm_mldsa65_generate_key_internal(xi.as_ptr(), &mut sk) }, OPENGM_OK);
+        let mut pk = null_mut();
+        assert_eq!(unsafe { opengm_mldsa65_public_key(sk, &mut pk) }, OPENGM_OK);
+
+        let mut sig = [0u8; siglen];
+        assert_eq!(
+            unsafe { opengm_mldsa65_sign_internal(sig.as_mut_ptr(), sk, m.as_ptr(), m.len(), rnd.as_ptr()) },
+            OPENGM_OK
+        );
+        assert_eq!(unsafe { opengm_mldsa65_verify_internal(sig.as_ptr(), pk, m.as_ptr(), m.len()) }, OPENGM_OK);
+        assert!(unsafe { mldsa65_verify_internal(sig.as_ptr(), pk, m.as_ptr(), m.len()) });
+
+        // empty message with NULL pointer
+        assert_eq!(
+            unsafe { opengm_mldsa65_sign_internal(sig.as_mut_ptr(), sk, null(), 0, rnd.as_ptr()) },
+            OPENGM_OK
+        );
+        assert_eq!(unsafe { opengm_mldsa65_verify_internal(sig.as_ptr(), pk, null(), 0) }, OPENGM_OK);
+        assert_eq!(
+            unsafe { opengm_mldsa65_verify_internal(sig.as_ptr(), pk, m.as_ptr(), m.len()) },
+            OPENGM_ERR_VERIFY
+        );
+
+        // NULL arguments
+        assert_eq!(unsafe { opengm_mldsa65_verify_internal(null(), pk, m.as_ptr(), m.len()) }, OPENGM_ERR_NULL);
+        assert_eq!(unsafe { opengm_mldsa65_verify_internal(sig.as_ptr(), pk, null(), 1) }, OPENGM_ERR_NULL);
+        assert_eq!(unsafe { opengm_mldsa65_public_key(null(), &mut pk) }, OPENGM_ERR_NULL);
+        assert!(unsafe { mldsa65_public_key(null_mut()) }.is_null());
+
+        // handles of wrong type or parameter set
+        assert_eq!(
+            unsafe { opengm_mldsa65_sign_internal(sig.as_mut_ptr(), pk, m.as_ptr(), m.len(), rnd.as_ptr()) },
+            OPENGM_ERR_HANDLE
+        );
+        assert_eq!(
+            unsafe { opengm_mldsa65_verify_internal(sig.as_ptr(), sk, m.as_ptr(), m.len()) },
+            OPENGM_ERR_HANDLE
+        );
+        assert_eq!(unsafe { opengm_mldsa65_drop_private_key_handle(pk) }, OPENGM_ERR_HANDLE);
+        let mut sk44 = null_mut();
+        assert_eq!(
+            unsafe { crate::mldsa::mldsa44::opengm_mldsa44_generate_key_internal(xi.as_ptr(), &mut sk44) },
+            OPENGM_OK
+        );
+        assert_eq!(
+            unsafe { opengm_mldsa65_sign_internal(sig.as_mut_ptr(), sk44, m.as_ptr(), m.len(), rnd.as_ptr()) },
+            OPENGM_ERR_HANDLE
+        );
+        assert_eq!(unsafe { crate::mldsa::mldsa44::opengm_mldsa44_drop_private_key_handle(sk44) }, OPENGM_OK);
+
+        assert_eq!(unsafe { opengm_mldsa65_drop_public_key_handle(pk) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_mldsa65_drop_private_key_handle(sk) }, OPENGM_OK);
+    }
+
+    #[test]
+    fn test_capi_inplace() {
+        use crate::capi::{OPENGM_ERR_HANDLE, OPENGM_ERR_STORAGE, OPENGM_OK};
+        use core::ffi::c_void;
+
+        let mut rng = rand::rng();
+        let xi: [u8; 32] = rng.random();
+        let rnd: [u8; 32] = rng.random();
+        let m: [u8; 32] = rng.random();
+
+        // u64 for the alignment of storage.
+        let sk_size = opengm_mldsa65_private_key_size();
+        let pk_size = opengm_mldsa65_public_key_size();
+        let mut sk_storage = vec![0u64; sk_size.div_ceil(8)];
+        let mut pk_storage = vec![0u64; pk_size.div_ceil(8) + 1];
+        let sk = sk_storage.as_mut_ptr() as *mut c_void;
+        let pk = pk_storage.as_mut_ptr() as *mut c_void;
+
+        assert_eq!(
+            unsafe { opengm_mldsa65_generate_key_internal_inplace(xi.as_ptr(), sk, sk_size - 1) },
+            OPENGM_ERR_STORAGE
+        );
+        assert_eq!(unsafe { opengm_mldsa65_generate_key_internal_inplace(xi.as_ptr(), sk, sk_size) }, OPENGM_OK);
+        // misaligned storage
+        assert_eq!(
+            unsafe { opengm_mldsa65_public_key_inplace(sk, pk.wrapping_byte_add(4), pk_size) },
+            OPENGM_ERR_STORAGE
+        );
+        assert_eq!(unsafe { opengm_mldsa65_public_key_inplace(sk, pk, pk_size) }, OPENGM_OK);
+
+        let mut sig = [0u8; siglen];
+        assert_eq!(
+            unsafe { opengm_mldsa65_sign_internal(sig.as_mut_ptr(), sk, m.as_ptr(), m.len(), rnd.as_ptr()) },
+            OPENGM_OK
+        );
+        assert_eq!(unsafe { opengm_mldsa65_verify_internal(sig.as_ptr(), pk, m.as_ptr(), m.len()) }, OPENGM_OK);
+        assert_eq!(sig, keygen_internal(&xi).sign_internal(&m, &rnd).sig_encode());
+
+        // import into the same storage after clearing
+        let mut pk_encoded = [0u8; pklen];
+        assert_eq!(unsafe { opengm_mldsa65_public_key_encode(pk_encoded.as_mut_ptr(), pk) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_mldsa65_clear_public_key(pk) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_mldsa65_verify_internal(sig.as_ptr(), pk, m.as_ptr(), m.len()) }, OPENGM_ERR_HANDLE);
+        assert_eq!(unsafe { opengm_mldsa65_import_public_key_inplace(pk_encoded.as_ptr(), pk, pk_size) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_mldsa65_verify_internal(sig.as_ptr(), pk, m.as_ptr(), m.len()) }, OPENGM_OK);
+
+        let mut sk_encoded = [0u8; sklen];
+        assert_eq!(unsafe { opengm_mldsa65_private_key_encode(sk_encoded.as_mut_ptr(), sk) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_mldsa65_clear_private_key(sk) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_mldsa65_import_private_key_inplace(sk_encoded.as_ptr(), sk, sk_size) }, OPENGM_OK);
+        assert_eq!(
+            unsafe { opengm_mldsa65_sign_internal(sig.as_mut_ptr(), sk, m.as_ptr(), m.len(), rnd.as_ptr()) },
+            OPENGM_OK
+        );
+        assert_eq!(unsafe { opengm_mldsa65_verify_internal(sig.as_ptr(), pk, m.as_ptr(), m.len()) }, OPENGM_OK);
+
+        #[cfg(feature = "alloc")]
+        assert_eq!(unsafe { opengm_mldsa65_drop_private_key_handle(sk) }, OPENGM_ERR_HANDLE);
+        assert_eq!(unsafe { opengm_mldsa65_clear_private_key(pk) }, OPENGM_ERR_HANDLE);
+        assert_eq!(unsafe { opengm_mldsa65_clear_private_key(sk) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_mldsa65_clear_public_key(pk) }, OPENGM_OK);
+        assert!(sk_storage[1..].iter().all(|&x| x == 0));
+    }
 }
@@ -1,6 +1,9 @@
 pub mod mldsa44;
 pub mod mldsa65;
 pub mod mldsa87;
+pub mod prehash;
+
+pub use prehash::PreHash;
 
 pub(crate) const Q: i32 = 8380417; // 1<<23 - 1<<13 + 1
 pub(crate) const N: usize = 256;
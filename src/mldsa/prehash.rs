@@ -0,0 +1,158 @@
+//! The pre-hash functions of HashML-DSA (FIPS 204, section 5.4), and SM3 for the deployments bound
+//! to the national algorithms.
+
+use crate::sha2;
+use crate::sha3::{self, Hash};
+use crate::sm3;
+
+/// The hash function PH of HashML-DSA, identified in M' by its DER encoded OID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreHash {
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha512_224,
+    Sha512_256,
+    Sha3_224,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+    /// SHAKE128 with 256 bits of output.
+    Shake128,
+    /// SHAKE256 with 512 bits of output.
+    Shake256,
+    /// SM3 of GB/T 32905, OID 1.2.156.10197.1.401.
+    Sm3,
+}
+
+fn hash<const N: usize>(mut h: impl Hash<N>, m: &[u8], out: &mut [u8]) {
+    h.write(m);
+    h.sum_into(out);
+}
+
+impl PreHash {
+    pub const ALL: [PreHash; 13] = [
+        PreHash::Sha224,
+        PreHash::Sha256,
+        PreHash::Sha384,
+        PreHash::Sha512,
+        PreHash::Sha512_224,
+        PreHash::Sha512_256,
+        PreHash::Sha3_224,
+        PreHash::Sha3_256,
+        PreHash::Sha3_384,
+        PreHash::Sha3_512,
+        PreHash::Shake128,
+        PreHash::Shake256,
+        PreHash::Sm3,
+    ];
+
+    /// The DER encoding of the OID, tag and length included.
+    pub fn oid(self) -> &'static [u8] {
+        match self {
+            // 2.16.840.1.101.3.4.2.4
+            PreHash::Sha224 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x04],
+            // 2.16.840.1.101.3.4.2.1
+            PreHash::Sha256 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01],
+            // 2.16.840.1.101.3.4.2.2
+            PreHash::Sha384 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02],
+            // 2.16.840.1.101.3.4.2.3
+            PreHash::Sha512 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03],
+            // 2.16.840.1.101.3.4.2.5
+            PreHash::Sha512_224 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x05],
+            // 2.16.840.1.101.3.4.2.6
+            PreHash::Sha512_256 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x06],
+            // 2.16.840.1.101.3.4.2.7
+            PreHash::Sha3_224 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x07],
+            // 2.16.840.1.101.3.4.2.8
+            PreHash::Sha3_256 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x08],
+            // 2.16.840.1.101.3.4.2.9
+            PreHash::Sha3_384 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x09],
+            // 2.16.840.1.101.3.4.2.10
+            PreHash::Sha3_512 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0a],
+            // 2.16.840.1.101.3.4.2.11
+            PreHash::Shake128 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0b],
+            // 2.16.840.1.101.3.4.2.12
+            PreHash::Shake256 => &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0c],
+            // 1.2.156.10197.1.401
+            PreHash::Sm3 => &[0x06, 0x08, 0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x83, 0x11],
+        }
+    }
+
+    /// The length of PH(M) in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            PreHash::Sha224 | PreHash::Sha512_224 | PreHash::Sha3_224 => 28,
+            PreHash::Sha256 | PreHash::Sha512_256 | PreHash::Sha3_256 | PreHash::Shake128 | PreHash::Sm3 => 32,
+            PreHash::Sha384 | PreHash::Sha3_384 => 48,
+            PreHash::Sha512 | PreHash::Sha3_512 | PreHash::Shake256 => 64,
+        }
+    }
+
+    /// PH(m) written into out[..self.size()].
+    pub fn digest<'a>(self, m: &[u8], out: &'a mut [u8; 64]) -> &'a [u8] {
+        let out = &mut out[..self.size()];
+        match self {
+            PreHash::Sha224 => hash(sha2::new224(), m, out),
+            PreHash::Sha256 => hash(sha2::new256(), m, out),
+            PreHash::Sha384 => hash(sha2::new384(), m, out),
+            PreHash::Sha512 => hash(sha2::new512(), m, out),
+            PreHash::Sha512_224 => hash(sha2::new512_224(), m, out),
+            PreHash::Sha512_256 => hash(sha2::new512_256(), m, out),
+            PreHash::Sha3_224 => hash(sha3::new224(), m, out),
+            PreHash::Sha3_256 => hash(sha3::new256(), m, out),
+            PreHash::Sha3_384 => hash(sha3::new384(), m, out),
+            PreHash::Sha3_512 => hash(sha3::new512(), m, out),
+            PreHash::Shake128 => {
+                let mut h = sha3::new_shake128();
+                h.write(m);
+                h.read(out);
+            }
+            PreHash::Shake256 => {
+                let mut h = sha3::new_shake256();
+                h.write(m);
+                h.read(out);
+            }
+            PreHash::Sm3 => hash(sm3::new(), m, out),
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_digest() {
+        for ph in PreHash::ALL {
+            let oid = ph.oid();
+            assert_eq!((oid[0], oid[1] as usize), (0x06, oid.len() - 2));
+            assert_eq!(ph.digest(b"abc", &mut [0; 64]).len(), ph.size());
+        }
+
+        let mut out = [0; 64];
+        for (ph, expect) in [
+            (PreHash::Sha256, &hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")[..]),
+            (PreHash::Sha512_224, &hex!("4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa")),
+            (PreHash::Sha3_224, &hex!("e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf")),
+            (PreHash::Sm3, &hex!("66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0")),
+        ] {
+            assert_eq!(ph.digest(b"abc", &mut out), expect);
+        }
+        for (ph, expect) in [
+            (PreHash::Shake128, &hex!("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26")[..]),
+            (
+                PreHash::Shake256,
+                &hex!(
+                    "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f"
+                    "d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be"
+                ),
+            ),
+        ] {
+            assert_eq!(ph.digest(b"", &mut out), expect);
+        }
+    }
+}
@@ -13,7 +13,7 @@ pub(crate) fn compress_unsigned(x: u16, d: usize) -> u16 {
     let y = (x as u32) << d;
 
     // the higher 32 bits of BarrettParam * y
-    let (_, mut quo) = u32::widening_mul(BarrettParam, y);
+    let mut quo = (u32::widening_mul(BarrettParam, y) >> 32) as u32;
     let r = y - quo * Q as u32;
 
     let halfq = (Q / 2) as u32;
@@ -40,7 +40,7 @@ pub(crate) fn compress(x: i16, d: usize) -> u16 {
     let y = (x as i32) << d;
 
     // the higher 32 bits of BarrettParam * y
-    let (_, mut quo) = i32::widening_mul(BarrettParam, y);
+    let mut quo = (i32::widening_mul(BarrettParam, y) >> 32) as i32;
     let r = y - quo * Q as i32;
 
     const neg_halfq: i32 = -((Q - 1) / 2) as i32;
@@ -12,13 +12,13 @@ use super::{
 };
 
 #[derive(Clone, Debug)]
-pub(crate) struct DecapKey<const k: usize, const eta1: usize, const eta2: usize>
+pub struct DecapKey<const k: usize, const eta1: usize, const eta2: usize>
 where
     [(); ek_len!(k)]:,
 {
     // d: Option<[u8;32]>,
-    z: [u8; 32],
-    s: [Rq; k],
+    pub(crate) z: [u8; 32],
+    pub(crate) s: [Rq; k],
 
     pub(crate) ek: EncapKey<k, eta1, eta2>,
 }
@@ -36,16 +36,16 @@ where
 }
 
 #[derive(Clone, Debug)]
-pub(crate) struct EncapKey<const k: usize, const eta1: usize, const eta2: usize>
+pub struct EncapKey<const k: usize, const eta1: usize, const eta2: usize>
 where
     [(); ek_len!(k)]:,
 {
-    a: [[Rq; k]; k],
+    pub(crate) a: [[Rq; k]; k],
 
-    rho: [u8; 32],
-    t: [Rq; k],
+    pub(crate) rho: [u8; 32],
+    pub(crate) t: [Rq; k],
 
-    h: [u8; 32],
+    pub(crate) h: [u8; 32],
 }
 
 impl<const k: usize, const eta1: usize, const eta2: usize> Default for EncapKey<k, eta1, eta2>
@@ -74,16 +74,27 @@ where
         let mut m = [0; 32];
         pke::decrypt::<k, eta1, eta2, du, dv>(&mut m, &self.s, c);
 
+        self.reencrypt_check_::<du, dv>(&m, c)
+    }
+
+    // The Fujisaki-Okamoto transform of decapsulation:
+    // re-encrypt the decrypted m and return K if the ciphertext matches, else J(z, c).
+    // It only uses ek and z, thus can be shared by the two-party decapsulation.
+    pub(crate) fn reencrypt_check_<const du: usize, const dv: usize>(
+        &self,
+        m: &[u8; 32],
+        c: &[u8; cipher_len!(k, du, dv)],
+    ) -> [u8; 32] {
         // re-encrypt
         #[allow(non_snake_case)]
         let mut K = [0; 32];
         let mut r = [0; 32];
-        G(&mut K, &mut r, &m, &self.ek.h);
+        G(&mut K, &mut r, m, &self.ek.h);
 
         #[allow(non_snake_case)]
         let K_ = J(&self.z, c);
         let mut cp = [0; cipher_len!(k, du, dv)];
-        pke::encrypt::<k, eta1, eta2, du, dv>(&mut cp, &self.ek.a, &self.ek.t, &m, &r);
+        pke::encrypt::<k, eta1, eta2, du, dv>(&mut cp, &self.ek.a, &self.ek.t, m, &r);
 
         let mut equal = 0;
         for (a, b) in zip(c.chunks_exact(4), cp.chunks_exact(4)) {
@@ -120,7 +131,7 @@ where
         let b_h = &b[384 * k + ek_len!(k)..384 * k + ek_len!(k) + 32];
         let z = &b[384 * k + ek_len!(k) + 32..384 * k + ek_len!(k) + 64];
 
-        let h = H(&b_ek);
+        let h = H(b_ek);
         for (a, b) in zip(h, b_h) {
             if a != *b {
                 return Err(Error::DecapKeyDecodeError);
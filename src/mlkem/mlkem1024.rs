@@ -1,8 +1,17 @@
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
 use core::ffi::c_void;
-use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use core::ptr::null_mut;
+
+use crate::alg::{self, Algorithm};
+use crate::capi;
 use crate::mlkem::internal::{self, keygen_internal_};
+use crate::pkcs;
+#[cfg(feature = "alloc")]
+use crate::pkcs::{PrivateKeyChoice, PrivateKeyFormat};
 
-use rand::{CryptoRng, Rng};
+use rand::CryptoRng;
 
 pub(crate) const k: usize = 4;
 pub(crate) const eta1: usize = 2;
@@ -20,15 +29,14 @@ pub type DecapKey = internal::DecapKey<k, eta1, eta2>;
 
 
 pub fn keygen(rng: &mut dyn CryptoRng) -> DecapKey {
-    let d = rng.random();
-    let z = rng.random();
+    let (d, z) = alg::random_dz(rng);
     keygen_internal_(&d, &z)
 }
 
 impl EncapKey {
     
     pub fn encaps(&self, rng: &mut dyn CryptoRng) -> ([u8; 32], [u8; cipher_len]) {
-        let m = rng.random();
+        let m = alg::random_bytes(rng);
         self.encaps_internal_::<du, dv>(&m)
     }
 
@@ -51,108 +59,554 @@ impl DecapKey {
     }
 }
 
+/// ML-KEM-1024 of the runtime dispatch layer.
+pub struct MlKem1024;
+
+impl alg::Kem for MlKem1024 {
+    const ALGORITHM: Algorithm = Algorithm::MlKem1024;
+    type EncapKey = EncapKey;
+    type DecapKey = DecapKey;
+
+    fn keygen_internal(d: &[u8; 32], z: &[u8; 32]) -> DecapKey {
+        keygen_internal_(d, z)
+    }
+
+    fn encapsulation_key(dk: &DecapKey) -> EncapKey {
+        dk.encapsulation_key()
+    }
+
+    fn encaps_internal(ek: &EncapKey, m: &[u8; 32], c: &mut [u8]) -> alg::Result<[u8; 32]> {
+        let c = alg::array_mut::<cipher_len>(c)?;
+        let (key, cipher) = ek.encaps_internal(m);
+        *c = cipher;
+        Ok(key)
+    }
+
+    fn decaps(dk: &DecapKey, c: &[u8]) -> alg::Result<[u8; 32]> {
+        Ok(dk.decaps(alg::array_ref::<cipher_len>(c)?))
+    }
+
+    fn encode_encap_key(ek: &EncapKey, b: &mut [u8]) -> alg::Result<()> {
+        ek.byte_encode_inplace(alg::array_mut::<ek_len>(b)?);
+        Ok(())
+    }
+
+    fn decode_encap_key(b: &[u8]) -> alg::Result<EncapKey> {
+        EncapKey::byte_decode(alg::array_ref::<ek_len>(b)?).map_err(|_| alg::Error::Decode)
+    }
+
+    fn encode_decap_key(dk: &DecapKey, b: &mut [u8]) -> alg::Result<()> {
+        dk.byte_encode_inplace(alg::array_mut::<dk_len>(b)?);
+        Ok(())
+    }
+
+    fn decode_decap_key(b: &[u8]) -> alg::Result<DecapKey> {
+        DecapKey::byte_decode(alg::array_ref::<dk_len>(b)?).map_err(|_| alg::Error::Decode)
+    }
+}
+
+impl alg::Encapsulator for EncapKey {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::MlKem1024
+    }
+
+    fn encaps_internal(&self, m: &[u8; 32], c: &mut [u8]) -> alg::Result<[u8; 32]> {
+        <MlKem1024 as alg::Kem>::encaps_internal(self, m, c)
+    }
+}
+
+impl alg::Decapsulator for DecapKey {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::MlKem1024
+    }
+
+    fn decaps(&self, c: &[u8]) -> alg::Result<[u8; 32]> {
+        <MlKem1024 as alg::Kem>::decaps(self, c)
+    }
+}
+
+impl EncapKey {
+    /// Decode from a DER SubjectPublicKeyInfo with the id-alg-ml-kem-1024 OID.
+    pub fn from_public_key_der(der: &[u8]) -> pkcs::Result<Self> {
+        match pkcs::decode_spki(der)? {
+            (Algorithm::MlKem1024, ek) => {
+                let ek = ek.try_into().map_err(|_| pkcs::Error::InvalidLength)?;
+                EncapKey::byte_decode(ek).map_err(|_| pkcs::Error::InvalidKey)
+            }
+            _ => Err(pkcs::Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Encode to a DER SubjectPublicKeyInfo.
+    #[cfg(feature = "alloc")]
+    pub fn to_public_key_der(&self) -> Vec<u8> {
+        pkcs::spki_der(pkcs::oid::ID_ALG_ML_KEM_1024, &self.byte_encode())
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn from_public_key_pem(pem: &str) -> pkcs::Result<Self> {
+        Self::from_public_key_der(&pkcs::pem::decode_label(pem, pkcs::pem::PUBLIC_KEY)?)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_public_key_pem(&self) -> String {
+        pkcs::pem::encode(pkcs::pem::PUBLIC_KEY, &self.to_public_key_der())
+    }
+}
+
+impl DecapKey {
+    /// Generate the key from the 64 bytes seed d || z.
+    pub fn from_seed(seed: &[u8; 64]) -> Self {
+        keygen_internal_(seed.first_chunk().unwrap(), seed.last_chunk().unwrap())
+    }
+
+    /// Decode from a DER PKCS#8 private key of the seed, expandedKey or both form,
+    /// the expanded key of the both form must match the seed.
+    pub fn from_pkcs8_der(der: &[u8]) -> pkcs::Result<Self> {
+        let key = match pkcs::decode_pkcs8(der)? {
+            (Algorithm::MlKem1024, key) => key,
+            _ => return Err(pkcs::Error::UnsupportedAlgorithm),
+        };
+        let seed = key.seed().map(|seed| seed.try_into().map_err(|_| pkcs::Error::InvalidLength)).transpose()?;
+        match (seed, key.expanded_key()) {
+            (Some(seed), None) => Ok(Self::from_seed(seed)),
+            (Some(seed), Some(expanded_key)) => {
+                let dk = Self::from_seed(seed);
+                pkcs::check_expanded_key(&dk.byte_encode(), expanded_key)?;
+                Ok(dk)
+            }
+            (None, Some(expanded_key)) => {
+                let dk = expanded_key.try_into().map_err(|_| pkcs::Error::InvalidLength)?;
+                DecapKey::byte_decode(dk).map_err(|_| pkcs::Error::InvalidKey)
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    /// Encode to a DER PKCS#8 private key of the expandedKey form, as the key does not keep its seed.
+    /// Use seed_to_pkcs8_der for the seed and both forms.
+    #[cfg(feature = "alloc")]
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        pkcs::pkcs8_der(pkcs::oid::ID_ALG_ML_KEM_1024, &PrivateKeyChoice::ExpandedKey(&self.byte_encode()))
+    }
+
+    /// Encode the key generated from the seed d || z to a DER PKCS#8 private key of the format.
+    #[cfg(feature = "alloc")]
+    pub fn seed_to_pkcs8_der(seed: &[u8; 64], format: PrivateKeyFormat) -> Vec<u8> {
+        if format == PrivateKeyFormat::Seed {
+            return pkcs::pkcs8_der(pkcs::oid::ID_ALG_ML_KEM_1024, &PrivateKeyChoice::Seed(seed));
+        }
+        let expanded_key = Self::from_seed(seed).byte_encode();
+        let key = match format {
+            PrivateKeyFormat::Both => PrivateKeyChoice::Both { seed, expanded_key: &expanded_key },
+            _ => PrivateKeyChoice::ExpandedKey(&expanded_key),
+        };
+        pkcs::pkcs8_der(pkcs::oid::ID_ALG_ML_KEM_1024, &key)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn from_pkcs8_pem(pem: &str) -> pkcs::Result<Self> {
+        Self::from_pkcs8_der(&pkcs::pem::decode_label(pem, pkcs::pem::PRIVATE_KEY)?)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_pkcs8_pem(&self) -> String {
+        pkcs::pem::encode(pkcs::pem::PRIVATE_KEY, &self.to_pkcs8_der())
+    }
+}
+
 /////////////////////////////////////////////////////////////////////
-///  exports C api
+//  exports C api
 /////////////////////////////////////////////////////////////////////
 
-const dk_bytes_len:usize =  (2 * k + k * k) * 512 + 96;
-const ek_bytes_len:usize = (k + k * k) * 512 + 64;
+impl capi::Handle for DecapKey {
+    const TAG: u32 = capi::tag(capi::MLKEM_DECAPKEY, 1024);
+}
+
+impl capi::Handle for EncapKey {
+    const TAG: u32 = capi::tag(capi::MLKEM_ENCAPKEY, 1024);
+}
 
-/// mlkem768_keygen_internal 密钥生成,dk必须指向dk_len的缓冲区
+/// opengm_mlkem1024_keygen_internal 密钥生成, 成功时*dk_handle指向生成的解封装密钥.
 /// d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+///
+/// # Safety
+/// d, z必须为NULL或指向32字节, dk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mlkem1024_keygen_internal(d: *const u8, z: *const u8) -> *mut c_void {
-    let d = unsafe { core::slice::from_raw_parts(d, 32) }.try_into().unwrap();
-    let z = unsafe { core::slice::from_raw_parts(z, 32) }.try_into().unwrap();
-    
-    Box::leak(Box::new(internal::keygen_internal_::<k, eta1, eta2>(d, z))) as *mut _ as *mut c_void
+pub unsafe extern "C" fn opengm_mlkem1024_keygen_internal(
+    d: *const u8,
+    z: *const u8,
+    dk_handle: *mut *mut c_void,
+) -> i32 {
+    capi::status(|| {
+        let d = unsafe { capi::array_ref::<32>(d) }?;
+        let z = unsafe { capi::array_ref::<32>(z) }?;
+        let out = unsafe { capi::out_handle(dk_handle) }?;
+        *out = capi::into_handle(keygen_internal_::<k, eta1, eta2>(d, z));
+        Ok(())
+    })
 }
 
+/// opengm_mlkem1024_encapkey 由解封装密钥得到封装密钥, 成功时*ek_handle指向封装密钥.
+///
+/// # Safety
+/// dk_handle必须为NULL或本库返回且未释放的handle, ek_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mlkem1024_encapkey(dk_handle: *mut c_void) -> *mut c_void {
-    let dk = unsafe { Box::from_raw(dk_handle as *mut DecapKey) };
-    
-    let ek = Box::leak(Box::new(dk.encapsulation_key())) as *mut _ as *mut c_void;
-    Box::leak(dk);
-    ek
+pub unsafe extern "C" fn opengm_mlkem1024_encapkey(dk_handle: *const c_void, ek_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let dk = unsafe { capi::handle_ref::<DecapKey>(dk_handle) }?;
+        let out = unsafe { capi::out_handle(ek_handle) }?;
+        *out = capi::into_handle(dk.encapsulation_key());
+        Ok(())
+    })
+}
+
+/// opengm_mlkem1024_encap_internal 封装, key指向32字节, c指向cipher_len字节, m指向32字节的随机数.
+///
+/// # Safety
+/// key, c必须为NULL或指向32, cipher_len字节的可写内存, m必须为NULL或指向32字节,
+/// ek_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mlkem1024_encap_internal(
+    key: *mut u8,
+    c: *mut u8,
+    ek_handle: *const c_void,
+    m: *const u8,
+) -> i32 {
+    capi::status(|| {
+        let out_key = unsafe { capi::array_mut::<32>(key) }?;
+        let out_c = unsafe { capi::array_mut::<cipher_len>(c) }?;
+        let ek = unsafe { capi::handle_ref::<EncapKey>(ek_handle) }?;
+        let m = unsafe { capi::array_ref::<32>(m) }?;
+
+        let (key, c) = ek.encaps_internal(m);
+        out_key.copy_from_slice(&key);
+        out_c.copy_from_slice(&c);
+        Ok(())
+    })
 }
+
+/// opengm_mlkem1024_decap 解封装, key指向32字节, c指向cipher_len字节.
+///
+/// # Safety
+/// key必须为NULL或指向32字节的可写内存, c必须为NULL或指向cipher_len字节, dk_handle必须为NULL或本库返回且未释放的handle.
 #[unsafe(no_mangle)]
-pub extern "C" fn mlkem1024_encap_internal(key: *mut u8, c: *mut u8, ek_handle: *mut c_void, m: *const u8) -> i32 {
-    let ek = unsafe { Box::from_raw(ek_handle as *mut EncapKey) };
+pub unsafe extern "C" fn opengm_mlkem1024_decap(key: *mut u8, c: *const u8, dk_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let out_key = unsafe { capi::array_mut::<32>(key) }?;
+        let c = unsafe { capi::array_ref::<cipher_len>(c) }?;
+        let dk = unsafe { capi::handle_ref::<DecapKey>(dk_handle) }?;
 
-    let m = unsafe { core::slice::from_raw_parts(m, 32) }.try_into().unwrap();
-    let out_key = unsafe { core::slice::from_raw_parts_mut(key, 32) };
-    let out_c = unsafe { core::slice::from_raw_parts_mut(c, cipher_len) };
+        out_key.copy_from_slice(&dk.decaps(c));
+        Ok(())
+    })
+}
 
-    let (key, c) = ek.encaps_internal(m);
-    out_key.copy_from_slice(&key);
-    out_c.copy_from_slice(&c);
+/// opengm_mlkem1024_encapkey_encode 编码封装密钥, ek_encoded指向ek_len字节.
+///
+/// # Safety
+/// ek_encoded必须为NULL或指向ek_len字节的可写内存, ek_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mlkem1024_encapkey_encode(ek_encoded: *mut u8, ek_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let ek_encoded = unsafe { capi::array_mut::<ek_len>(ek_encoded) }?;
+        let ek = unsafe { capi::handle_ref::<EncapKey>(ek_handle) }?;
 
-    Box::leak(ek);
-    0
+        ek.byte_encode_inplace(ek_encoded);
+        Ok(())
+    })
 }
 
+/// opengm_mlkem1024_encapkey_decode 解码封装密钥, ek_encoded指向ek_len字节.
+/// 封装密钥未通过模数检查时返回OPENGM_ERR_DECODE.
+///
+/// # Safety
+/// ek_encoded必须为NULL或指向ek_len字节, ek_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mlkem1024_decap(key: *mut u8, c: *const u8, dk_handle: *mut c_void) -> i32 {
-    let dk = unsafe { Box::from_raw(dk_handle as *mut DecapKey) };
+pub unsafe extern "C" fn opengm_mlkem1024_encapkey_decode(ek_encoded: *const u8, ek_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let ek_encoded = unsafe { capi::array_ref::<ek_len>(ek_encoded) }?;
+        let out = unsafe { capi::out_handle(ek_handle) }?;
 
-    let out_key = unsafe { core::slice::from_raw_parts_mut(key, 32) };
-    let in_c = unsafe { core::slice::from_raw_parts(c, cipher_len) };
+        let ek = EncapKey::byte_decode(ek_encoded).map_err(|_| capi::OPENGM_ERR_DECODE)?;
+        *out = capi::into_handle(ek);
+        Ok(())
+    })
+}
 
-    let key = dk.decaps(in_c.try_into().unwrap());
-    out_key.copy_from_slice(&key);
+/// opengm_mlkem1024_decapkey_encode 编码解封装密钥, dk_encoded指向dk_len字节.
+///
+/// # Safety
+/// dk_encoded必须为NULL或指向dk_len字节的可写内存, dk_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mlkem1024_decapkey_encode(dk_encoded: *mut u8, dk_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let dk_encoded = unsafe { capi::array_mut::<dk_len>(dk_encoded) }?;
+        let dk = unsafe { capi::handle_ref::<DecapKey>(dk_handle) }?;
 
-    Box::leak(dk);
-    0
+        dk.byte_encode_inplace(dk_encoded);
+        Ok(())
+    })
 }
 
-// byte_encode encapkey
+/// opengm_mlkem1024_decapkey_decode 解码解封装密钥, dk_encoded指向dk_len字节.
+/// 解封装密钥未通过哈希检查或模数检查时返回OPENGM_ERR_DECODE.
+///
+/// # Safety
+/// dk_encoded必须为NULL或指向dk_len字节, dk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mlkem1024_encapkey_encode(ek_encoded: *mut u8, ek_handle: *mut c_void) {
-    let ek_encoded = unsafe { core::slice::from_raw_parts_mut(ek_encoded, ek_len) }
-    .try_into()
-    .unwrap();
-    let ek = unsafe { Box::from_raw(ek_handle as *mut EncapKey) };
-    
-    ek.byte_encode_inplace(ek_encoded);
-    Box::leak(ek);
+pub unsafe extern "C" fn opengm_mlkem1024_decapkey_decode(dk_encoded: *const u8, dk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let dk_encoded = unsafe { capi::array_ref::<dk_len>(dk_encoded) }?;
+        let out = unsafe { capi::out_handle(dk_handle) }?;
+
+        let dk = DecapKey::byte_decode(dk_encoded).map_err(|_| capi::OPENGM_ERR_DECODE)?;
+        *out = capi::into_handle(dk);
+        Ok(())
+    })
 }
 
+/// opengm_mlkem1024_drop_encapkey_handle 释放封装密钥, ek_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+///
+/// # Safety
+/// ek_handle必须为NULL或本库返回的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mlkem1024_encapkey_decode(ek_encoded: *const u8) -> *mut c_void {
-    let ek_encoded = unsafe { core::slice::from_raw_parts(ek_encoded, ek_len) }
-    .try_into()
-    .unwrap();
-    Box::leak(Box::new(EncapKey::byte_decode(ek_encoded).unwrap())) as *mut _ as *mut c_void
+pub unsafe extern "C" fn opengm_mlkem1024_drop_encapkey_handle(ek_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::drop_handle::<EncapKey>(ek_handle) })
 }
 
-// byte_encode decapkey
+/// opengm_mlkem1024_drop_decapkey_handle 释放解封装密钥, dk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+///
+/// # Safety
+/// dk_handle必须为NULL或本库返回的handle.
+#[cfg(feature = "alloc")]
 #[unsafe(no_mangle)]
-pub extern "C" fn mlkem1024_decapkey_encode(dk_encoded: *mut u8, dk_handle: *mut c_void) {
-    let dk = unsafe { Box::from_raw(dk_handle as *mut DecapKey) };
-    let dk_encoded = unsafe { core::slice::from_raw_parts_mut(dk_encoded, dk_len) }
-    .try_into()
-    .unwrap();
-    
-    dk.byte_encode_inplace(dk_encoded);
+pub unsafe extern "C" fn opengm_mlkem1024_drop_decapkey_handle(dk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::drop_handle::<DecapKey>(dk_handle) })
+}
+
+/////////////////////////////////////////////////////////////////////
+//  exports C api with caller provided storage, no allocation.
+//  The initialized storage is used as a handle of the functions above,
+//  and must be cleared by the *_clear_* functions instead of dropped.
+/////////////////////////////////////////////////////////////////////
 
-    Box::leak(dk);
+/// opengm_mlkem1024_decapkey_size 返回解封装密钥所需的存储空间字节数.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_mlkem1024_decapkey_size() -> usize {
+    capi::storage_size::<DecapKey>()
 }
 
+/// opengm_mlkem1024_encapkey_size 返回封装密钥所需的存储空间字节数.
 #[unsafe(no_mangle)]
-pub extern "C" fn mlkem1024_decapkey_decode(dk_encoded: *const u8) -> *mut c_void {
-    let dk_encoded = unsafe { core::slice::from_raw_parts(dk_encoded, dk_len) }
-    .try_into()
-    .unwrap();
-    Box::leak(Box::new(DecapKey::byte_decode(dk_encoded).unwrap())) as *mut _ as *mut c_void
+pub extern "C" fn opengm_mlkem1024_encapkey_size() -> usize {
+    capi::storage_size::<EncapKey>()
 }
 
+/// opengm_mlkem1024_keygen_internal_inplace 在storage中生成解封装密钥, 成功时storage可作为dk_handle使用.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem1024_decapkey_size(), 否则返回OPENGM_ERR_STORAGE.
+///
+/// # Safety
+/// d, z必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
 #[unsafe(no_mangle)]
-pub extern "C" fn  mlkem1024_drop_encapkey_handle(ek_handle: *mut c_void) {
-    drop(unsafe { Box::from_raw(ek_handle as *mut EncapKey) });
+pub unsafe extern "C" fn opengm_mlkem1024_keygen_internal_inplace(
+    d: *const u8,
+    z: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let d = unsafe { capi::array_ref::<32>(d) }?;
+        let z = unsafe { capi::array_ref::<32>(z) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(keygen_internal_::<k, eta1, eta2>(d, z))) }
+    })
 }
 
+/// opengm_mlkem1024_encapkey_inplace 在storage中由解封装密钥得到封装密钥, 成功时storage可作为ek_handle使用.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem1024_encapkey_size().
+///
+/// # Safety
+/// dk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
 #[unsafe(no_mangle)]
-pub extern "C" fn mlkem1024_drop_decapkey_handle(dk_handle: *mut c_void) {
-    drop(unsafe { Box::from_raw(dk_handle as *mut DecapKey) });
-}
\ No newline at end of file
+pub unsafe extern "C" fn opengm_mlkem1024_encapkey_inplace(
+    dk_handle: *const c_void,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let dk = unsafe { capi::handle_ref::<DecapKey>(dk_handle) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(dk.encapsulation_key())) }
+    })
+}
+
+/// opengm_mlkem1024_encapkey_decode_inplace 在storage中解码封装密钥, ek_encoded指向ek_len字节.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem1024_encapkey_size().
+///
+/// # Safety
+/// ek_encoded必须为NULL或指向ek_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mlkem1024_encapkey_decode_inplace(
+    ek_encoded: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let ek_encoded = unsafe { capi::array_ref::<ek_len>(ek_encoded) }?;
+        unsafe {
+            capi::init_handle(storage, storage_size, || {
+                EncapKey::byte_decode(ek_encoded).map_err(|_| capi::OPENGM_ERR_DECODE)
+            })
+        }
+    })
+}
+
+/// opengm_mlkem1024_decapkey_decode_inplace 在storage中解码解封装密钥, dk_encoded指向dk_len字节.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_mlkem1024_decapkey_size().
+///
+/// # Safety
+/// dk_encoded必须为NULL或指向dk_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mlkem1024_decapkey_decode_inplace(
+    dk_encoded: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let dk_encoded = unsafe { capi::array_ref::<dk_len>(dk_encoded) }?;
+        unsafe {
+            capi::init_handle(storage, storage_size, || {
+                DecapKey::byte_decode(dk_encoded).map_err(|_| capi::OPENGM_ERR_DECODE)
+            })
+        }
+    })
+}
+
+/// opengm_mlkem1024_clear_encapkey 清除storage中的封装密钥, ek_handle为NULL时不做任何操作.
+///
+/// # Safety
+/// ek_handle必须为NULL或本库返回的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mlkem1024_clear_encapkey(ek_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::clear_handle::<EncapKey>(ek_handle) })
+}
+
+/// opengm_mlkem1024_clear_decapkey 清除storage中的解封装密钥, 密钥被清零, dk_handle为NULL时不做任何操作.
+///
+/// # Safety
+/// dk_handle必须为NULL或本库返回的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_mlkem1024_clear_decapkey(dk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::clear_handle::<DecapKey>(dk_handle) })
+}
+
+/////////////////////////////////////////////////////////////////////
+//  deprecated C api, shims of the opengm_mlkem1024_* functions.
+//  The functions returning a handle return NULL on error, those returning
+//  or freeing a handle need the alloc feature.
+/////////////////////////////////////////////////////////////////////
+
+/// mlkem1024_keygen_internal 密钥生成, d,z必须指向32字节, d,z由调用者使用随机数发生器生成.
+///
+/// # Safety
+/// 同opengm_mlkem1024_keygen_internal.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mlkem1024_keygen_internal")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_keygen_internal(d: *const u8, z: *const u8) -> *mut c_void {
+    let mut dk_handle = null_mut();
+    unsafe { opengm_mlkem1024_keygen_internal(d, z, &mut dk_handle) };
+    dk_handle
+}
+
+/// # Safety
+/// 同opengm_mlkem1024_encapkey.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mlkem1024_encapkey")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_encapkey(dk_handle: *mut c_void) -> *mut c_void {
+    let mut ek_handle = null_mut();
+    unsafe { opengm_mlkem1024_encapkey(dk_handle, &mut ek_handle) };
+    ek_handle
+}
+
+/// # Safety
+/// 同opengm_mlkem1024_encap_internal.
+#[deprecated(note = "use opengm_mlkem1024_encap_internal")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_encap_internal(
+    key: *mut u8,
+    c: *mut u8,
+    ek_handle: *mut c_void,
+    m: *const u8,
+) -> i32 {
+    unsafe { opengm_mlkem1024_encap_internal(key, c, ek_handle, m) }
+}
+
+/// # Safety
+/// 同opengm_mlkem1024_decap.
+#[deprecated(note = "use opengm_mlkem1024_decap")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_decap(key: *mut u8, c: *const u8, dk_handle: *mut c_void) -> i32 {
+    unsafe { opengm_mlkem1024_decap(key, c, dk_handle) }
+}
+
+/// # Safety
+/// 同opengm_mlkem1024_encapkey_encode.
+#[deprecated(note = "use opengm_mlkem1024_encapkey_encode")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_encapkey_encode(ek_encoded: *mut u8, ek_handle: *mut c_void) {
+    unsafe { opengm_mlkem1024_encapkey_encode(ek_encoded, ek_handle) };
+}
+
+/// # Safety
+/// 同opengm_mlkem1024_encapkey_decode.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mlkem1024_encapkey_decode")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_encapkey_decode(ek_encoded: *const u8) -> *mut c_void {
+    let mut ek_handle = null_mut();
+    unsafe { opengm_mlkem1024_encapkey_decode(ek_encoded, &mut ek_handle) };
+    ek_handle
+}
+
+/// # Safety
+/// 同opengm_mlkem1024_decapkey_encode.
+#[deprecated(note = "use opengm_mlkem1024_decapkey_encode")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_decapkey_encode(dk_encoded: *mut u8, dk_handle: *mut c_void) {
+    unsafe { opengm_mlkem1024_decapkey_encode(dk_encoded, dk_handle) };
+}
+
+/// # Safety
+/// 同opengm_mlkem1024_decapkey_decode.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mlkem1024_decapkey_decode")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_decapkey_decode(dk_encoded: *const u8) -> *mut c_void {
+    let mut dk_handle = null_mut();
+    unsafe { opengm_mlkem1024_decapkey_decode(dk_encoded, &mut dk_handle) };
+    dk_handle
+}
+
+/// # Safety
+/// 同opengm_mlkem1024_drop_encapkey_handle.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mlkem1024_drop_encapkey_handle")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_drop_encapkey_handle(ek_handle: *mut c_void) {
+    unsafe { opengm_mlkem1024_drop_encapkey_handle(ek_handle) };
+}
+
+/// # Safety
+/// 同opengm_mlkem1024_drop_decapkey_handle.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use opengm_mlkem1024_drop_decapkey_handle")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mlkem1024_drop_decapkey_handle(dk_handle: *mut c_void) {
+    unsafe { opengm_mlkem1024_drop_decapkey_handle(dk_handle) };
+}
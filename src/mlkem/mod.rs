@@ -1,17 +1,17 @@
-mod compress_decode;
-mod errors;
-mod hash;
-mod internal;
+pub(crate) mod compress_decode;
+pub(crate) mod errors;
+pub(crate) mod hash;
+pub(crate) mod internal;
 mod kat;
-mod ntt;
-mod pke;
+pub(crate) mod ntt;
+pub(crate) mod pke;
 mod reduce;
-mod rq;
-mod sample;
+pub(crate) mod rq;
+pub(crate) mod sample;
 
-mod mlkem1024;
-mod mlkem512;
-mod mlkem768;
+pub(crate) mod mlkem1024;
+pub(crate) mod mlkem512;
+pub(crate) mod mlkem768;
 
 pub(crate) const N: usize = 256;
 pub(crate) const Q: i16 = 3329;
@@ -75,18 +75,13 @@ pub(crate) fn decrypt<const k: usize, const eta1: usize, const eta2: usize, cons
     s: &[Rq;k],
     c: &[u8],
 ) {
-    let c1 = &c[..k * 32 * du];
     let c2 = &c[k * 32 * du..];
 
     let mut u = [Rq::default(); k];
     let mut v = Rq::default();
-    decode_and_decompress_vecs(&mut u, c1, du);
+    decode_u_ntt::<k, du>(&mut u, c);
     decode_and_decompress(&mut v.coeffs, c2, dv);
 
-    for ui in &mut u {
-        ui.ntt();
-    }
-
     // w = v-su
     let mut w = Rq::default();
     for i in 0..k {
@@ -99,6 +94,15 @@ pub(crate) fn decrypt<const k: usize, const eta1: usize, const eta2: usize, cons
     compress_and_encode(m, &v.coeffs, 1);
 }
 
+// NTT(u) of the ciphertext, the only part of decryption multiplied by s.
+// s^T * NTT(u) is linear in s, thus for s = s1 + s2 it is the sum of the two products.
+pub(crate) fn decode_u_ntt<const k: usize, const du: usize>(u: &mut [Rq; k], c: &[u8]) {
+    decode_and_decompress_vecs(u, &c[..k * 32 * du], du);
+    for ui in u {
+        ui.ntt();
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 
@@ -1,6 +1,6 @@
 use core::iter::zip;
 
-use crate::sha3::{self, XOF, new_shake256};
+use crate::sha3::{self, XOF, new_shake256, shake_x4};
 
 use super::{Q, rq::Rq};
 
@@ -11,67 +11,79 @@ pub(crate) fn sample_ntt(a: &mut [i16; 256], rho: &[u8], ii: u8, jj: u8) {
     let mut b = sha3::new_shake128();
     b.absorb(rho).absorb(&[jj, ii]);
 
-    // SampleNTT essentially draws 12 bits at a time from r, interprets them in
-    // little-endian, and rejects values higher than q, until it drew 256
-    // values. (The rejection rate is approximately 19%.)
-    //
-    // To do this from a bytes stream, it draws three bytes at a time, and
-    // splits them into two uint16 appropriately masked.
-    //
-    //               r₀              r₁              r₂
-    //       |- - - - - - - -|- - - - - - - -|- - - - - - - -|
-    //
-    //               Uint16(r₀ || r₁)
-    //       |- - - - - - - - - - - - - - - -|
-    //       |- - - - - - - - - - - -|
-    //                   d₁
-    //
-    //                                Uint16(r₁ || r₂)
-    //                       |- - - - - - - - - - - - - - - -|
-    //                               |- - - - - - - - - - - -|
-    //                                           d₂
-    //
-    // Note that in little-endian, the rightmost bits are the most significant
-    // bits (dropped with a mask) and the leftmost bits are the least
-    // significant bits (dropped with a right shift).
-
     let mut j = 0; // index into a
-    let buf = &mut [0u8; 24]; // buffered reads from B
-    let mut off = buf.len(); // index into buf, starts in a "buffer fully consumed" state
-    loop {
-        if off >= buf.len() {
-            b.squeeze(buf);
-            off = 0
-        }
+    let mut block = [0u8; 168]; // one block of B at a time
+    while j < a.len() {
+        b.squeeze(&mut block);
+        sample_ntt_block(a, &mut j, &block);
+    }
+}
 
-        let d1 = u16::from_le_bytes(buf[off..off + 2].try_into().unwrap()) & 0b1111_1111_1111;
-        let d2 = u16::from_le_bytes(buf[off + 1..off + 3].try_into().unwrap()) >> 4;
-        off += 3;
+// sample_ntt_block continues sampleNTT with the next block of the XOF stream,
+// j coefficients of a are already drawn. The length of the block is a multiple
+// of 3.
+//
+// SampleNTT essentially draws 12 bits at a time from r, interprets them in
+// little-endian, and rejects values higher than q, until it drew 256
+// values. (The rejection rate is approximately 19%.)
+//
+// To do this from a bytes stream, it draws three bytes at a time, and
+// splits them into two uint16 appropriately masked.
+//
+//               r₀              r₁              r₂
+//       |- - - - - - - -|- - - - - - - -|- - - - - - - -|
+//
+//               Uint16(r₀ || r₁)
+//       |- - - - - - - - - - - - - - - -|
+//       |- - - - - - - - - - - -|
+//                   d₁
+//
+//                                Uint16(r₁ || r₂)
+//                       |- - - - - - - - - - - - - - - -|
+//                               |- - - - - - - - - - - -|
+//                                           d₂
+//
+// Note that in little-endian, the rightmost bits are the most significant
+// bits (dropped with a mask) and the leftmost bits are the least
+// significant bits (dropped with a right shift).
+fn sample_ntt_block(a: &mut [i16; 256], j: &mut usize, block: &[u8]) {
+    for r in block.chunks_exact(3) {
+        if *j >= a.len() {
+            return;
+        }
+        let d1 = u16::from_le_bytes([r[0], r[1]]) & 0b1111_1111_1111;
+        let d2 = u16::from_le_bytes([r[1], r[2]]) >> 4;
         if d1 < Q as u16 {
-            a[j] = d1 as i16;
-            j += 1;
+            a[*j] = d1 as i16;
+            *j += 1;
         }
-        if j >= a.len() {
-            break;
+        if *j >= a.len() {
+            return;
         }
         if d2 < Q as u16 {
-            a[j] = d2 as i16;
-            j += 1;
-        }
-        if j >= a.len() {
-            break;
+            a[*j] = d2 as i16;
+            *j += 1;
         }
     }
 }
 
+// The k * k streams of the matrix are independent, they are expanded four at
+// a time with the multi-lane Keccak.
 #[inline]
 pub(crate) fn sample_matrix_ntt<const k: usize>(a: &mut [[Rq; k]; k], rho: &[u8; 32]) {
-    for i in 0..k {
-        for j in 0..k {
-            // FIXME: repeat sha3
-            sample_ntt(&mut a[i][j].coeffs, rho, i as u8, j as u8);
-        }
-    }
+    let mut j = [0usize; 16];
+    shake_x4::<16>(
+        k * k,
+        |n, m| {
+            m[..32].copy_from_slice(rho);
+            m[32..34].copy_from_slice(&[(n % k) as u8, (n / k) as u8]);
+            34
+        },
+        |n, block| {
+            sample_ntt_block(&mut a[n / k][n % k].coeffs, &mut j[n], block);
+            j[n] < 256
+        },
+    );
 }
 
 // samplePolyCBD draws a ringElement from the special Dη distribution given a
@@ -79,12 +91,33 @@ pub(crate) fn sample_matrix_ntt<const k: usize>(a: &mut [[Rq; k]; k], rho: &[u8;
 // Algorithm 8 and Definition 4.3.
 #[inline]
 pub(crate) fn sample_poly_cbd_prf<const eta: usize>(f: &mut [i16; 256], s: &[u8], b: u8) {
-    if eta == 2 {
-        return sample_poly_cbd_prf2(f, s, b);
+    if eta == 1 {
+        sample_poly_cbd_prf1(f, s, b)
+    } else if eta == 2 {
+        sample_poly_cbd_prf2(f, s, b)
     } else if eta == 3 {
-        return sample_poly_cbd_prf3(f, s, b);
+        sample_poly_cbd_prf3(f, s, b)
     } else {
-        panic!("eta can only be 2 or 3")
+        panic!("eta can only be 1, 2 or 3")
+    }
+}
+
+// eta = 1, only used by the two-party key shares (mlkem_tpc),
+// D_1 + D_1 is D_2 and D_1 + D_2 is D_3.
+pub(crate) fn sample_poly_cbd_prf1(f: &mut [i16; 256], s: &[u8], b: u8) {
+    let mut prf = new_shake256();
+    prf.absorb(s).absorb(&[b]);
+    #[allow(non_snake_case)]
+    let mut B = [0u8; 64];
+    prf.squeeze(&mut B);
+
+    for (f, chunk) in zip(f.chunks_exact_mut(32), B.chunks_exact(8)) {
+        let a = u64::from_le_bytes(chunk.try_into().unwrap());
+        let x = a & 0x5555555555555555;
+        let y = (a >> 1) & 0x5555555555555555;
+        for (i, fi) in f.iter_mut().enumerate() {
+            *fi = ((x >> (2 * i)) & 1) as i16 - ((y >> (2 * i)) & 1) as i16;
+        }
     }
 }
 
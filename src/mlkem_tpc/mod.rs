@@ -0,0 +1,614 @@
+use core::iter::zip;
+
+use crate::cipher_len;
+use crate::ek_len;
+use crate::mlkem::RQ_ELEMENT_LEN;
+use crate::mlkem::compress_decode::{compress_and_encode, decode_and_decompress};
+use crate::mlkem::errors::Result;
+use crate::mlkem::hash::{G, H, J};
+use crate::mlkem::internal::{DecapKey, EncapKey};
+use crate::mlkem::ntt::ntt_add_mul;
+use crate::mlkem::pke::decode_u_ntt;
+use crate::mlkem::rq::Rq;
+use crate::mlkem::sample::{sample_matrix_ntt, sample_poly_cbd_prf, sample_poly_cbd_prf1, sample_poly_cbd_prf2};
+
+// MLKEM 客户端私钥: 私钥分片 s_client 和掩码种子.
+pub struct ClientKey<const k: usize, const eta1: usize, const eta2: usize>
+where
+    [(); ek_len!(k)]:,
+{
+    ek: EncapKey<k, eta1, eta2>,
+
+    // in ntt form, drawn from D_{eta1/2}.
+    s: [Rq; k],
+
+    // the seed of the masks of the partial decryptions.
+    sigma: [u8; 32],
+}
+
+// MLKEM 服务端私钥: 私钥分片 s_server 和 z.
+pub struct ServerKey<const k: usize, const eta1: usize, const eta2: usize>
+where
+    [(); ek_len!(k)]:,
+{
+    // s is the server's share, drawn from D_{eta1 - eta1/2}.
+    k: DecapKey<k, eta1, eta2>,
+}
+
+// 客户端密钥生成的中间状态, 在提交和打开 t_client 之间保存.
+pub struct ClientKeyGenCtx<const k: usize, const eta1: usize, const eta2: usize>
+where
+    [(); ek_len!(k)]:,
+{
+    share: KeyShare<k, eta1, eta2>,
+    s: [Rq; k],
+    sigma: [u8; 32],
+}
+
+// 服务端密钥生成的中间状态, 保存客户端的提交.
+pub struct ServerKeyGenCtx<const k: usize, const eta1: usize, const eta2: usize>
+where
+    [(); ek_len!(k)]:,
+{
+    share: KeyShare<k, eta1, eta2>,
+    s: [Rq; k],
+    z: [u8; 32],
+    commitment: [u8; 32],
+}
+
+// A party's share t_i = A * s_i + e_i of t, in NTT form, encoded as an encapsulation key.
+#[derive(Clone)]
+pub struct KeyShare<const k: usize, const eta1: usize, const eta2: usize>
+where
+    [(); ek_len!(k)]:,
+{
+    ek: EncapKey<k, eta1, eta2>,
+}
+
+// The client's masked partial decryption NTT^-1(s_client^T * NTT(u)) + e, sent to the server.
+#[derive(Clone, Copy, Default)]
+pub struct PartialDecryption {
+    w: Rq,
+}
+
+// D_eta of a key share, eta is 1 or 2.
+fn sample_share(f: &mut [i16; 256], r: &[u8], n: u8, eta: usize) {
+    if eta == 1 {
+        sample_poly_cbd_prf1(f, r, n);
+    } else {
+        sample_poly_cbd_prf2(f, r, n);
+    }
+}
+
+// A is expanded from the common d as in ML-KEM.KeyGen_internal, s_i and e_i are drawn from D_eta with the
+// party's own r instead of sigma, and (sigma_mask, _) = G(r || k) seeds the masks of the client.
+// The client draws eta1/2 and the server eta1 - eta1/2, D_a + D_b is D_{a+b}, thus s = s_client + s_server
+// and e = e_client + e_server have exactly the distribution of FIPS 203.
+fn partial_keygen<const k: usize, const eta1: usize, const eta2: usize>(
+    d: &[u8; 32],
+    r: &[u8; 32],
+    eta: usize,
+) -> (KeyShare<k, eta1, eta2>, [Rq; k], [u8; 32])
+where
+    [(); ek_len!(k)]:,
+{
+    let mut ek = EncapKey::<k, eta1, eta2>::default();
+    let mut sigma = [0; 32];
+    G(&mut ek.rho, &mut sigma, d, &[k as u8]);
+    sample_matrix_ntt(&mut ek.a, &ek.rho);
+
+    let mut s = [Rq::default(); k];
+    let mut n = 0;
+    for s in &mut s {
+        sample_share(&mut s.coeffs, r, n, eta);
+        s.ntt();
+        s.reduce();
+        n += 1;
+    }
+
+    for e in &mut ek.t {
+        sample_share(&mut e.coeffs, r, n, eta);
+        e.ntt();
+        n += 1;
+    }
+
+    // t = As + e, |t| < q
+    for (t, a) in zip(&mut ek.t, &ek.a) {
+        for (a, s) in zip(a, &s) {
+            ntt_add_mul(&mut t.coeffs, &a.coeffs, &s.coeffs);
+        }
+        t.reduce_to_positive();
+    }
+    ek.h = H(&ek.byte_encode());
+
+    let mut mask_seed = [0; 32];
+    G(&mut mask_seed, &mut sigma, r, &[k as u8]);
+    (KeyShare { ek }, s, mask_seed)
+}
+
+// ek = (t_client + t_server, rho)
+fn joint_encap_key<const k: usize, const eta1: usize, const eta2: usize>(
+    a: &KeyShare<k, eta1, eta2>,
+    b: &KeyShare<k, eta1, eta2>,
+) -> EncapKey<k, eta1, eta2>
+where
+    [(); ek_len!(k)]:,
+{
+    let mut ek = a.ek.clone();
+    for (t, b) in zip(&mut ek.t, &b.ek.t) {
+        t.add(b);
+        // |t| < 2q
+        t.reduce();
+    }
+    ek.h = H(&ek.byte_encode());
+    ek
+}
+
+// d is shared by client and server, r is the client's own randomness.
+// Returns the client's state and the commitment H(t_client || rho), sent to the server first.
+// The client opens t_client only after the server sent t_server, thus neither party chooses its share
+// after seeing the other's one.
+pub fn ClientKeyGen0<const k: usize, const eta1: usize, const eta2: usize>(
+    d: &[u8; 32],
+    r: &[u8; 32],
+) -> (ClientKeyGenCtx<k, eta1, eta2>, [u8; 32])
+where
+    [(); ek_len!(k)]:,
+{
+    let (share, s, sigma) = partial_keygen::<k, eta1, eta2>(d, r, eta1 / 2);
+    let commitment = share.ek.h;
+    (ClientKeyGenCtx { share, s, sigma }, commitment)
+}
+
+// Returns the server's state and t_server sent to the client. z is the server's own implicit rejection seed.
+pub fn ServerKeyGen0<const k: usize, const eta1: usize, const eta2: usize>(
+    d: &[u8; 32],
+    r: &[u8; 32],
+    z: &[u8; 32],
+    commitment: &[u8; 32],
+) -> (ServerKeyGenCtx<k, eta1, eta2>, KeyShare<k, eta1, eta2>)
+where
+    [(); ek_len!(k)]:,
+{
+    let (share, s, _) = partial_keygen::<k, eta1, eta2>(d, r, eta1 - eta1 / 2);
+    let ctx = ServerKeyGenCtx {
+        share: share.clone(),
+        s,
+        z: *z,
+        commitment: *commitment,
+    };
+    (ctx, share)
+}
+
+// Returns the client's key and t_client sent to the server, or None if t_server is not of the same A.
+pub fn ClientKeyGen1<const k: usize, const eta1: usize, const eta2: usize>(
+    ctx: ClientKeyGenCtx<k, eta1, eta2>,
+    server_share: &KeyShare<k, eta1, eta2>,
+) -> Option<(ClientKey<k, eta1, eta2>, KeyShare<k, eta1, eta2>)>
+where
+    [(); ek_len!(k)]:,
+{
+    if server_share.ek.rho != ctx.share.ek.rho {
+        return None;
+    }
+    let client_key = ClientKey {
+        ek: joint_encap_key(&ctx.share, server_share),
+        s: ctx.s,
+        sigma: ctx.sigma,
+    };
+    Some((client_key, ctx.share))
+}
+
+// Returns the server's key, or None if t_client does not open the commitment or is not of the same A.
+pub fn ServerKeyGen1<const k: usize, const eta1: usize, const eta2: usize>(
+    ctx: ServerKeyGenCtx<k, eta1, eta2>,
+    client_share: &KeyShare<k, eta1, eta2>,
+) -> Option<ServerKey<k, eta1, eta2>>
+where
+    [(); ek_len!(k)]:,
+{
+    if client_share.ek.h != ctx.commitment || client_share.ek.rho != ctx.share.ek.rho {
+        return None;
+    }
+    Some(ServerKey {
+        k: DecapKey {
+            z: ctx.z,
+            s: ctx.s,
+            ek: joint_encap_key(client_share, &ctx.share),
+        },
+    })
+}
+
+impl<const k: usize, const eta1: usize, const eta2: usize> KeyShare<k, eta1, eta2>
+where
+    [(); ek_len!(k)]:,
+{
+    // ByteEncode12(t_i) || rho
+    pub fn byte_encode(&self) -> [u8; ek_len!(k)] {
+        self.ek.byte_encode()
+    }
+
+    pub fn byte_decode(b: &[u8; ek_len!(k)]) -> Result<Self> {
+        Ok(KeyShare {
+            ek: EncapKey::byte_decode(b)?,
+        })
+    }
+}
+
+impl PartialDecryption {
+    pub fn byte_encode(&self) -> [u8; RQ_ELEMENT_LEN] {
+        let mut b = [0; RQ_ELEMENT_LEN];
+        self.w.byte_encode(&mut b);
+        b
+    }
+
+    pub fn byte_decode(b: &[u8; RQ_ELEMENT_LEN]) -> Result<Self> {
+        let mut partial = PartialDecryption::default();
+        partial.w.byte_decode(b)?;
+        Ok(partial)
+    }
+}
+
+impl<const k: usize, const eta1: usize, const eta2: usize> ClientKey<k, eta1, eta2>
+where
+    [(); ek_len!(k)]:,
+{
+    pub fn encapsulation_key_ref(&self) -> &EncapKey<k, eta1, eta2> {
+        &self.ek
+    }
+
+    // w = NTT^-1(s_client^T * NTT(u)) + e with e drawn from D_eta2 by J(sigma, encoded u), sent to server.
+    // Without e the server solves s_client from the partial decryptions of k ciphertexts, with e each one
+    // is an MLWE sample of s_client. e depends on u only, not on v, so every ciphertext with the same u
+    // gets the same w: a server resending a ciphertext, or resending its u with other v bytes, gets no
+    // fresh mask to average out. This protects s_client against a server decapsulating ciphertexts of
+    // encapsulators, whose u is pseudorandom; a server crafting u itself is not protected against.
+    pub fn client_decaps<const du: usize, const dv: usize>(&self, c: &[u8; cipher_len!(k, du, dv)]) -> PartialDecryption {
+        let mut u = [Rq::default(); k];
+        decode_u_ntt::<k, du>(&mut u, c);
+
+        let mut partial = PartialDecryption::default();
+        for (s, u) in zip(&self.s, &u) {
+            ntt_add_mul(&mut partial.w.coeffs, &s.coeffs, &u.coeffs);
+        }
+        // |w| < q
+        partial.w.ntt_inverse();
+
+        let mut e = Rq::default();
+        sample_poly_cbd_prf::<eta2>(&mut e.coeffs, &J(&self.sigma, &c[..k * 32 * du]), 0);
+        partial.w.add(&e);
+        partial.w.reduce();
+        partial
+    }
+}
+
+impl<const k: usize, const eta1: usize, const eta2: usize> ServerKey<k, eta1, eta2>
+where
+    [(); ek_len!(k)]:,
+{
+    pub fn encapsulation_key_ref(&self) -> &EncapKey<k, eta1, eta2> {
+        &self.k.ek
+    }
+
+    // Add the server's partial decryption to the client's, decrypt m and perform the Fujisaki-Okamoto
+    // re-encryption check with ek and z.
+    // Returns the shared key, or J(z, c) if the re-encryption does not match, thus a client tampering with
+    // its partial decryption gets the implicit rejection key unless m is unchanged.
+    // The mask of the client adds at most eta2 to the noise of decryption, a message decrypted wrongly is
+    // rejected by the re-encryption check like a decryption failure of ML-KEM.
+    // The server learns m and K, the key is sent to the client over a secure channel.
+    pub fn server_decaps<const du: usize, const dv: usize>(
+        &self,
+        c: &[u8; cipher_len!(k, du, dv)],
+        client: &PartialDecryption,
+    ) -> [u8; 32] {
+        let mut u = [Rq::default(); k];
+        decode_u_ntt::<k, du>(&mut u, c);
+
+        let mut w = Rq::default();
+        for (s, u) in zip(&self.k.s, &u) {
+            ntt_add_mul(&mut w.coeffs, &s.coeffs, &u.coeffs);
+        }
+        // |w| < q
+        w.ntt_inverse();
+
+        let mut v = Rq::default();
+        decode_and_decompress(&mut v.coeffs, &c[k * 32 * du..], dv);
+        v.reduce();
+        v.sub(&w);
+
+        // the client's input is any i16, reduce to [0,q).
+        let mut client_w = client.w;
+        client_w.reduce();
+        v.sub(&client_w);
+
+        // |v| < 2q
+        v.reduce();
+        let mut m = [0; 32];
+        compress_and_encode(&mut m, &v.coeffs, 1);
+
+        self.k.reencrypt_check_::<du, dv>(&m, c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::iter::zip;
+
+    use rand::Rng;
+
+    use crate::mlkem::hash::J;
+    use crate::mlkem::internal::DecapKey;
+    use crate::mlkem::ntt::ntt_mul;
+    use crate::mlkem::pke::decode_u_ntt;
+    use crate::mlkem::rq::Rq;
+    use crate::mlkem::{N, Q};
+    use crate::{cipher_len, dk_len, ek_len};
+
+    use super::*;
+
+    // s = s_client + s_server
+    fn combine_key<const k: usize, const eta1: usize, const eta2: usize>(
+        client_key: &ClientKey<k, eta1, eta2>,
+        server_key: &ServerKey<k, eta1, eta2>,
+    ) -> DecapKey<k, eta1, eta2>
+    where
+        [(); ek_len!(k)]:,
+    {
+        let mut s = client_key.s;
+        for (s, server_s) in zip(&mut s, &server_key.k.s) {
+            s.add(server_s);
+            s.reduce();
+        }
+        DecapKey {
+            z: server_key.k.z,
+            s,
+            ek: client_key.ek.clone(),
+        }
+    }
+
+    // all the coefficients of NTT^-1(s) are in [-eta, eta]
+    fn is_small<const k: usize>(s: &[Rq; k], eta: usize) -> bool {
+        s.iter().all(|s| {
+            let mut s = *s;
+            s.ntt_inverse();
+            s.reduce();
+            s.coeffs.iter().all(|&x| x.min(Q - x) <= eta as i16)
+        })
+    }
+
+    fn keygen<const k: usize, const eta1: usize, const eta2: usize>() -> (
+        ClientKey<k, eta1, eta2>,
+        ServerKey<k, eta1, eta2>,
+        DecapKey<k, eta1, eta2>,
+    )
+    where
+        [(); ek_len!(k)]:,
+        [(); dk_len!(k)]:,
+    {
+        let mut rng = rand::rng();
+        let d = rng.random();
+        let (client_ctx, commitment) = ClientKeyGen0::<k, eta1, eta2>(&d, &rng.random());
+        let (server_ctx, server_share) = ServerKeyGen0::<k, eta1, eta2>(&d, &rng.random(), &rng.random(), &commitment);
+        let (client_key, client_share) = ClientKeyGen1(client_ctx, &server_share).unwrap();
+        let server_key = ServerKeyGen1(server_ctx, &client_share).unwrap();
+        assert_eq!(client_key.ek.byte_encode(), server_key.k.ek.byte_encode());
+
+        // the joint key is small, each share is smaller.
+        let dk = combine_key(&client_key, &server_key);
+        assert!(is_small(&dk.s, eta1));
+        assert!(is_small(&client_key.s, eta1 / 2));
+        assert!(is_small(&server_key.k.s, eta1 - eta1 / 2));
+
+        (client_key, server_key, dk)
+    }
+
+    fn test_decaps<const k: usize, const eta1: usize, const eta2: usize, const du: usize, const dv: usize>()
+    where
+        [(); ek_len!(k)]:,
+        [(); dk_len!(k)]:,
+        [(); cipher_len!(k, du, dv)]:,
+    {
+        let mut rng = rand::rng();
+        for _ in 0..10 {
+            let (client_key, server_key, dk) = keygen::<k, eta1, eta2>();
+
+            for _ in 0..10 {
+                let (key, mut c) = client_key
+                    .encapsulation_key_ref()
+                    .encaps_internal_::<du, dv>(&rng.random());
+
+                let partial = client_key.client_decaps::<du, dv>(&c);
+                let shared_key = server_key.server_decaps::<du, dv>(&c, &partial);
+                assert_eq!(shared_key, key);
+                assert_eq!(shared_key, dk.decaps_internal_::<du, dv>(&c));
+
+                // the partial decryption goes through its encoding
+                let partial = PartialDecryption::byte_decode(&partial.byte_encode()).unwrap();
+                assert_eq!(server_key.server_decaps::<du, dv>(&c, &partial), key);
+
+                // a tampered ciphertext gets the implicit rejection key of the joint key.
+                c[rng.random_range(0..c.len())] ^= 1;
+                let partial = client_key.client_decaps::<du, dv>(&c);
+                let rejected_key = server_key.server_decaps::<du, dv>(&c, &partial);
+                assert_ne!(rejected_key, key);
+                assert_eq!(rejected_key, J(&dk.z, &c));
+                assert_eq!(rejected_key, dk.decaps_internal_::<du, dv>(&c));
+            }
+        }
+    }
+
+    fn test_malicious_client<const k: usize, const eta1: usize, const eta2: usize, const du: usize, const dv: usize>()
+    where
+        [(); ek_len!(k)]:,
+        [(); dk_len!(k)]:,
+        [(); cipher_len!(k, du, dv)]:,
+    {
+        let mut rng = rand::rng();
+        let (client_key, server_key, dk) = keygen::<k, eta1, eta2>();
+
+        for _ in 0..10 {
+            let (key, c) = client_key
+                .encapsulation_key_ref()
+                .encaps_internal_::<du, dv>(&rng.random());
+
+            // a client adding delta to w to make a key mismatch gets J(z, c) instead of K.
+            let mut forged = client_key.client_decaps::<du, dv>(&c);
+            forged.w.coeffs[rng.random_range(0..N)] += Q / 2;
+            let rejected_key = server_key.server_decaps::<du, dv>(&c, &forged);
+            assert_ne!(rejected_key, key);
+            assert_eq!(rejected_key, J(&dk.z, &c));
+
+            // a client recovering s_server by chosen ciphertexts: m leaks s_server, but the server returns
+            // it only through the re-encryption check, thus the client gets J(z, c).
+            let mut chosen = [0; cipher_len!(k, du, dv)];
+            rng.fill(&mut chosen[..]);
+            let partial = client_key.client_decaps::<du, dv>(&chosen);
+            let rejected_key = server_key.server_decaps::<du, dv>(&chosen, &partial);
+            assert_eq!(rejected_key, J(&dk.z, &chosen));
+            assert_eq!(rejected_key, dk.decaps_internal_::<du, dv>(&chosen));
+        }
+
+        // a client opening another t_client than it committed to is rejected.
+        let d = rng.random();
+        let (client_ctx, commitment) = ClientKeyGen0::<k, eta1, eta2>(&d, &rng.random());
+        let (server_ctx, server_share) = ServerKeyGen0::<k, eta1, eta2>(&d, &rng.random(), &rng.random(), &commitment);
+        let (_, mut client_share) = ClientKeyGen1(client_ctx, &server_share).unwrap();
+        client_share.ek.t[0].coeffs[0] = (client_share.ek.t[0].coeffs[0] + 1) % Q;
+        client_share.ek.h = H(&client_share.ek.byte_encode());
+        assert!(ServerKeyGen1(server_ctx, &client_share).is_none());
+    }
+
+    // x^(q^2 - 2), the inverse of x in each slot GF(q^2) of the NTT form.
+    fn inverse(x: &Rq) -> Rq {
+        let mut y = Rq::default();
+        y.coeffs.iter_mut().step_by(2).for_each(|y| *y = 1);
+        let e = Q as u32 * Q as u32 - 2;
+        for i in (0..32 - e.leading_zeros()).rev() {
+            let y2 = y;
+            ntt_mul(&mut y.coeffs, &y2.coeffs, &y2.coeffs);
+            if e >> i & 1 == 1 {
+                let y2 = y;
+                ntt_mul(&mut y.coeffs, &y2.coeffs, &x.coeffs);
+            }
+        }
+        y
+    }
+
+    fn mul(a: &Rq, b: &Rq) -> Rq {
+        let mut c = Rq::default();
+        ntt_mul(&mut c.coeffs, &a.coeffs, &b.coeffs);
+        c
+    }
+
+    // Solves NTT(w_j) = s^T * NTT(u_j) for s from k partial decryptions, by Gaussian elimination.
+    fn solve<const k: usize>(mut u: [[Rq; k]; k], w: &[Rq; k]) -> [Rq; k] {
+        let mut w = *w;
+        for w in &mut w {
+            w.ntt();
+        }
+        for col in 0..k {
+            let inv = inverse(&u[col][col]);
+            u[col] = u[col].map(|x| mul(&x, &inv));
+            w[col] = mul(&w[col], &inv);
+            for row in 0..k {
+                if row == col {
+                    continue;
+                }
+                let (f, pivot) = (u[row][col], u[col]);
+                for (x, y) in zip(&mut u[row], &pivot) {
+                    x.sub(&mul(&f, y));
+                    x.reduce();
+                }
+                w[row].sub(&mul(&f, &w[col]));
+                w[row].reduce();
+            }
+        }
+        w
+    }
+
+    fn test_malicious_server<const k: usize, const eta1: usize, const eta2: usize, const du: usize, const dv: usize>()
+    where
+        [(); ek_len!(k)]:,
+        [(); dk_len!(k)]:,
+        [(); cipher_len!(k, du, dv)]:,
+    {
+        let mut rng = rand::rng();
+        let (client_key, server_key, _) = keygen::<k, eta1, eta2>();
+
+        // the transcript of k decapsulations.
+        let mut u = [[Rq::default(); k]; k];
+        let mut masked = [Rq::default(); k];
+        let mut unmasked = [Rq::default(); k];
+        for j in 0..k {
+            let (key, c) = client_key
+                .encapsulation_key_ref()
+                .encaps_internal_::<du, dv>(&rng.random());
+            let partial = client_key.client_decaps::<du, dv>(&c);
+            assert_eq!(server_key.server_decaps::<du, dv>(&c, &partial), key);
+
+            decode_u_ntt::<k, du>(&mut u[j], &c);
+            masked[j] = partial.w;
+            for (s, u) in zip(&client_key.s, &u[j]) {
+                ntt_add_mul(&mut unmasked[j].coeffs, &s.coeffs, &u.coeffs);
+            }
+            unmasked[j].ntt_inverse();
+
+            // a server sending a ciphertext again gets the same mask, it cannot average it out.
+            for _ in 0..3 {
+                assert_eq!(client_key.client_decaps::<du, dv>(&c).w.coeffs, partial.w.coeffs);
+            }
+
+            // nor by sending the same u with other v bytes.
+            for i in 0..3 {
+                let mut c = c;
+                c[k * 32 * du + i] ^= 1;
+                assert_eq!(client_key.client_decaps::<du, dv>(&c).w.coeffs, partial.w.coeffs);
+            }
+        }
+
+        let reduced = |s: &[Rq; k]| {
+            s.map(|mut s| {
+                s.reduce();
+                s.coeffs
+            })
+        };
+
+        // without the masks, the server would solve s_client from the transcript.
+        assert_eq!(reduced(&solve(u, &unmasked)), reduced(&client_key.s));
+
+        // with the masks, the solution is neither s_client nor any small share.
+        let s = solve(u, &masked);
+        assert_ne!(reduced(&s), reduced(&client_key.s));
+        assert!(!is_small(&s, eta1));
+    }
+
+    fn test_tpc<const k: usize, const eta1: usize, const eta2: usize, const du: usize, const dv: usize>()
+    where
+        [(); ek_len!(k)]:,
+        [(); dk_len!(k)]:,
+        [(); cipher_len!(k, du, dv)]:,
+    {
+        test_decaps::<k, eta1, eta2, du, dv>();
+        test_malicious_client::<k, eta1, eta2, du, dv>();
+        test_malicious_server::<k, eta1, eta2, du, dv>();
+    }
+
+    #[test]
+    fn test_mlkem512_tpc() {
+        use crate::mlkem::mlkem512::*;
+        test_tpc::<k, eta1, eta2, du, dv>();
+    }
+
+    #[test]
+    fn test_mlkem768_tpc() {
+        use crate::mlkem::mlkem768::*;
+        test_tpc::<k, eta1, eta2, du, dv>();
+    }
+
+    #[test]
+    fn test_mlkem1024_tpc() {
+        use crate::mlkem::mlkem1024::*;
+        test_tpc::<k, eta1, eta2, du, dv>();
+    }
+}
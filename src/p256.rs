@@ -0,0 +1,365 @@
+//! ECDH over NIST P-256 (SEC 1), for the hybrid TLS key shares.
+//!
+//! The field elements are 4 limbs of 64 bits in the Montgomery form, the points are projective
+//! and added by the complete formulas of Renes, Costello and Batina, and the scalar
+//! multiplication always adds and selects in constant time, so the timing does not depend on the
+//! scalar.
+
+use thiserror;
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("scalar is zero or not less than the order")]
+    InvalidScalar,
+
+    #[error("invalid P-256 point")]
+    InvalidPoint,
+}
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// The length of an uncompressed point, 0x04 || x || y.
+pub(crate) const POINT_LEN: usize = 65;
+
+type Fe = [u64; 4];
+
+const P: Fe = [0xffff_ffff_ffff_ffff, 0x0000_0000_ffff_ffff, 0x0000_0000_0000_0000, 0xffff_ffff_0000_0001];
+const N: Fe = [0xf3b9_cac2_fc63_2551, 0xbce6_faad_a717_9e84, 0xffff_ffff_ffff_ffff, 0xffff_ffff_0000_0000];
+// 2^512 mod p, 2^256 mod p and b, the last two in the Montgomery form.
+const R2: Fe = [0x0000_0000_0000_0003, 0xffff_fffb_ffff_ffff, 0xffff_ffff_ffff_fffe, 0x0000_0004_ffff_fffd];
+const ONE: Fe = [0x0000_0000_0000_0001, 0xffff_ffff_0000_0000, 0xffff_ffff_ffff_ffff, 0x0000_0000_ffff_fffe];
+const B: Fe = [0xd89c_df62_29c4_bddf, 0xacf0_05cd_7884_3090, 0xe5a2_20ab_f721_2ed6, 0xdc30_061d_0487_4834];
+
+// The coordinates of the generator.
+const GX: Fe = [0xf4a1_3945_d898_c296, 0x7703_7d81_2deb_33a0, 0xf8bc_e6e5_63a4_40f2, 0x6b17_d1f2_e12c_4247];
+const GY: Fe = [0xcbb6_4068_37bf_51f5, 0x2bce_3357_6b31_5ece, 0x8ee7_eb4a_7c0f_9e16, 0x4fe3_42e2_fe1a_7f9b];
+
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+// a - b and the borrow.
+fn sub_borrow(a: &Fe, b: &Fe) -> (Fe, u64) {
+    let mut r = [0; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        (r[i], borrow) = sbb(a[i], b[i], borrow);
+    }
+    (r, borrow)
+}
+
+// hi * 2^256 + a reduced once, a is less than 2p.
+fn reduce_once(a: &Fe, hi: u64) -> Fe {
+    let (r, borrow) = sub_borrow(a, &P);
+    let (_, borrow) = sbb(hi, 0, borrow);
+    let mask = 0u64.wrapping_sub(borrow);
+    core::array::from_fn(|i| (a[i] & mask) | (r[i] & !mask))
+}
+
+fn add(a: &Fe, b: &Fe) -> Fe {
+    let mut r = [0; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        (r[i], carry) = adc(a[i], b[i], carry);
+    }
+    reduce_once(&r, carry)
+}
+
+fn sub(a: &Fe, b: &Fe) -> Fe {
+    let (r, borrow) = sub_borrow(a, b);
+    let mask = 0u64.wrapping_sub(borrow);
+    let mut out = [0; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        (out[i], carry) = adc(r[i], P[i] & mask, carry);
+    }
+    out
+}
+
+// The Montgomery product a * b / 2^256, -p^-1 mod 2^64 is 1.
+fn mul(a: &Fe, b: &Fe) -> Fe {
+    let mut t = [0u64; 6];
+    for bi in b {
+        let mut carry = 0;
+        for j in 0..4 {
+            let v = t[j] as u128 + a[j] as u128 * *bi as u128 + carry as u128;
+            (t[j], carry) = (v as u64, (v >> 64) as u64);
+        }
+        (t[4], t[5]) = adc(t[4], carry, 0);
+
+        let m = t[0];
+        let v = t[0] as u128 + m as u128 * P[0] as u128;
+        let mut carry = (v >> 64) as u64;
+        for j in 1..4 {
+            let v = t[j] as u128 + m as u128 * P[j] as u128 + carry as u128;
+            (t[j - 1], carry) = (v as u64, (v >> 64) as u64);
+        }
+        let (lo, c) = adc(t[4], carry, 0);
+        t[3] = lo;
+        t[4] = t[5] + c;
+    }
+    reduce_once(&[t[0], t[1], t[2], t[3]], t[4])
+}
+
+fn square(a: &Fe) -> Fe {
+    mul(a, a)
+}
+
+// a^(p-2), the exponent is public.
+fn invert(a: &Fe) -> Fe {
+    let e = sub_borrow(&P, &[2, 0, 0, 0]).0;
+    let mut r = ONE;
+    for i in (0..256).rev() {
+        r = square(&r);
+        if (e[i / 64] >> (i % 64)) & 1 == 1 {
+            r = mul(&r, a);
+        }
+    }
+    r
+}
+
+fn is_zero(a: &Fe) -> bool {
+    a.iter().fold(0, |acc, x| acc | x) == 0
+}
+
+fn load(b: &[u8]) -> Fe {
+    core::array::from_fn(|i| u64::from_be_bytes(b[24 - 8 * i..32 - 8 * i].try_into().unwrap()))
+}
+
+// A field element from 32 big endian bytes, which must be less than p.
+fn from_bytes(b: &[u8]) -> Option<Fe> {
+    let a = load(b);
+    if sub_borrow(&a, &P).1 == 0 {
+        return None;
+    }
+    Some(mul(&a, &R2))
+}
+
+fn to_bytes(a: &Fe, out: &mut [u8]) {
+    let a = mul(a, &[1, 0, 0, 0]);
+    for i in 0..4 {
+        out[24 - 8 * i..32 - 8 * i].copy_from_slice(&a[i].to_be_bytes());
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+}
+
+const IDENTITY: Point = Point { x: [0; 4], y: ONE, z: [0; 4] };
+
+impl Point {
+    // An uncompressed point on the curve, the identity has no encoding.
+    fn decode(b: &[u8]) -> Result<Self> {
+        if b.len() != POINT_LEN || b[0] != 4 {
+            return Err(Error::InvalidPoint);
+        }
+        let x = from_bytes(&b[1..33]).ok_or(Error::InvalidPoint)?;
+        let y = from_bytes(&b[33..]).ok_or(Error::InvalidPoint)?;
+        // y^2 = x^3 - 3x + b
+        let x3 = mul(&square(&x), &x);
+        let rhs = add(&sub(&x3, &add(&add(&x, &x), &x)), &B);
+        if square(&y) != rhs {
+            return Err(Error::InvalidPoint);
+        }
+        Ok(Point { x, y, z: ONE })
+    }
+
+    fn to_affine(self) -> Result<(Fe, Fe)> {
+        if is_zero(&self.z) {
+            return Err(Error::InvalidPoint);
+        }
+        let zinv = invert(&self.z);
+        Ok((mul(&self.x, &zinv), mul(&self.y, &zinv)))
+    }
+
+    // Algorithm 4 of "Complete addition formulas for prime order elliptic curves", a = -3.
+    fn add(&self, q: &Point) -> Point {
+        let (x1, y1, z1) = (&self.x, &self.y, &self.z);
+        let (x2, y2, z2) = (&q.x, &q.y, &q.z);
+        let mut t0 = mul(x1, x2);
+        let mut t1 = mul(y1, y2);
+        let mut t2 = mul(z1, z2);
+        let mut t3 = add(x1, y1);
+        let mut t4 = add(x2, y2);
+        t3 = mul(&t3, &t4);
+        t4 = add(&t0, &t1);
+        t3 = sub(&t3, &t4);
+        t4 = add(y1, z1);
+        let mut x3 = add(y2, z2);
+        t4 = mul(&t4, &x3);
+        x3 = add(&t1, &t2);
+        t4 = sub(&t4, &x3);
+        x3 = add(x1, z1);
+        let mut y3 = add(x2, z2);
+        x3 = mul(&x3, &y3);
+        y3 = add(&t0, &t2);
+        y3 = sub(&x3, &y3);
+        let mut z3 = mul(&B, &t2);
+        x3 = sub(&y3, &z3);
+        z3 = add(&x3, &x3);
+        x3 = add(&x3, &z3);
+        z3 = sub(&t1, &x3);
+        x3 = add(&t1, &x3);
+        y3 = mul(&B, &y3);
+        t1 = add(&t2, &t2);
+        t2 = add(&t1, &t2);
+        y3 = sub(&y3, &t2);
+        y3 = sub(&y3, &t0);
+        t1 = add(&y3, &y3);
+        y3 = add(&t1, &y3);
+        t1 = add(&t0, &t0);
+        t0 = add(&t1, &t0);
+        t0 = sub(&t0, &t2);
+        t1 = mul(&t4, &y3);
+        t2 = mul(&t0, &y3);
+        y3 = mul(&x3, &z3);
+        y3 = add(&y3, &t2);
+        x3 = mul(&t3, &x3);
+        x3 = sub(&x3, &t1);
+        z3 = mul(&t4, &z3);
+        t1 = mul(&t3, &t0);
+        z3 = add(&z3, &t1);
+        Point { x: x3, y: y3, z: z3 }
+    }
+
+    // self if choice is 0, q if choice is 1.
+    fn select(&self, q: &Point, choice: u64) -> Point {
+        let mask = 0u64.wrapping_sub(choice);
+        let sel = |a: &Fe, b: &Fe| -> Fe { core::array::from_fn(|i| (a[i] & !mask) | (b[i] & mask)) };
+        Point { x: sel(&self.x, &q.x), y: sel(&self.y, &q.y), z: sel(&self.z, &q.z) }
+    }
+
+    // The scalar is big endian.
+    fn mul(&self, scalar: &[u8; 32]) -> Point {
+        let mut r = IDENTITY;
+        for byte in scalar {
+            for i in (0..8).rev() {
+                r = r.add(&r);
+                let t = r.add(self);
+                r = r.select(&t, ((byte >> i) & 1) as u64);
+            }
+        }
+        r
+    }
+}
+
+// 0 < scalar < n in constant time.
+fn check_scalar(scalar: &[u8; 32]) -> Result<()> {
+    let k = load(scalar);
+    let less = sub_borrow(&k, &N).1;
+    let nonzero = (k.iter().fold(0, |acc, x| acc | x) != 0) as u64;
+    if less & nonzero == 1 { Ok(()) } else { Err(Error::InvalidScalar) }
+}
+
+/// The uncompressed public key of the private scalar, the scalar is big endian in [1, n-1].
+pub fn public_key(scalar: &[u8; 32]) -> Result<[u8; POINT_LEN]> {
+    check_scalar(scalar)?;
+    let g = Point { x: mul(&GX, &R2), y: mul(&GY, &R2), z: ONE };
+    let (x, y) = g.mul(scalar).to_affine()?;
+    let mut out = [4; POINT_LEN];
+    to_bytes(&x, &mut out[1..33]);
+    to_bytes(&y, &mut out[33..]);
+    Ok(out)
+}
+
+/// The ECDH shared secret, the x-coordinate of the scalar times the peer's uncompressed public
+/// key. The public key must be on the curve.
+pub fn ecdh(scalar: &[u8; 32], peer: &[u8]) -> Result<[u8; 32]> {
+    check_scalar(scalar)?;
+    let q = Point::decode(peer)?;
+    let (x, _) = q.mul(scalar).to_affine()?;
+    let mut out = [0; 32];
+    to_bytes(&x, &mut out);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_field() {
+        let a = from_bytes(&hex!("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296")).unwrap();
+        assert_eq!(mul(&a, &invert(&a)), ONE);
+        assert_eq!(add(&a, &sub(&[0; 4], &a)), [0; 4]);
+        let mut b = [0; 32];
+        to_bytes(&sub(&[0; 4], &ONE), &mut b);
+        assert_eq!(b, hex!("ffffffff00000001000000000000000000000000fffffffffffffffffffffffe"));
+        assert_eq!(from_bytes(&hex!("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff")), None);
+    }
+
+    #[test]
+    fn test_public_key() {
+        let mut one = [0; 32];
+        one[31] = 1;
+        let g = public_key(&one).unwrap();
+        assert_eq!(
+            g,
+            hex!(
+                "046b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"
+                "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"
+            )
+        );
+        assert!(Point::decode(&g).is_ok());
+        // n - 1 gives -G
+        let n1 = hex!("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632550");
+        assert_eq!(
+            public_key(&n1).unwrap()[33..],
+            hex!("b01cbd1c01e58065711814b583f061e9d431cca994cea1313449bf97c840ae0a")
+        );
+        for bad in [[0; 32], hex!("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"), [0xff; 32]] {
+            assert_eq!(public_key(&bad), Err(Error::InvalidScalar));
+        }
+    }
+
+    #[test]
+    fn test_ecdh() {
+        // RFC 5903 section 8.1
+        let a = hex!("c88f01f510d9ac3f70a292daa2316de544e9aab8afe84049c62a9c57862d1433");
+        let b = hex!("c6ef9c5d78ae012a011164acb397ce2088685d8f06bf9be0b283ab46476bee53");
+        let pa = public_key(&a).unwrap();
+        let pb = public_key(&b).unwrap();
+        assert_eq!(
+            pa,
+            hex!(
+                "04dad0b65394221cf9b051e1feca5787d098dfe637fc90b9ef945d0c3772581180"
+                "5271a0461cdb8252d61f1c456fa3e59ab1f45b33accf5f58389e0577b8990bb3"
+            )
+        );
+        assert_eq!(
+            pb,
+            hex!(
+                "04d12dfb5289c8d4f81208b70270398c342296970a0bccb74c736fc7554494bf63"
+                "56fbf3ca366cc23e8157854c13c58d6aac23f046ada30f8353e74f33039872ab"
+            )
+        );
+        let shared = hex!("d6840f6b42f6edafd13116e0e12565202fef8e9ece7dce03812464d04b9442de");
+        assert_eq!(ecdh(&a, &pb), Ok(shared));
+        assert_eq!(ecdh(&b, &pa), Ok(shared));
+
+        // points off the curve, of other encodings or with coordinates not less than p
+        let mut bad = pb;
+        bad[64] ^= 1;
+        assert_eq!(ecdh(&a, &bad), Err(Error::InvalidPoint));
+        assert_eq!(ecdh(&a, &pb[..33]), Err(Error::InvalidPoint));
+        let mut compressed = [0; 33];
+        compressed.copy_from_slice(&pb[..33]);
+        compressed[0] = 2 + (pb[64] & 1);
+        assert_eq!(ecdh(&a, &compressed), Err(Error::InvalidPoint));
+        assert_eq!(ecdh(&a, &[0; POINT_LEN]), Err(Error::InvalidPoint));
+        let mut big = [0xff; POINT_LEN];
+        big[0] = 4;
+        assert_eq!(ecdh(&a, &big), Err(Error::InvalidPoint));
+        assert_eq!(ecdh(&[0; 32], &pb), Err(Error::InvalidScalar));
+    }
+}
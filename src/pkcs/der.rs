@@ -0,0 +1,332 @@
+//! A minimal DER reader and writer, enough for the key, certificate and message formats of this crate.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::errors::{Error, Result};
+
+pub(crate) const BOOLEAN: u8 = 0x01;
+pub(crate) const INTEGER: u8 = 0x02;
+pub(crate) const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+pub(crate) const NULL: u8 = 0x05;
+pub(crate) const OBJECT_IDENTIFIER: u8 = 0x06;
+pub(crate) const SEQUENCE: u8 = 0x30;
+pub(crate) const SET: u8 = 0x31;
+
+/// Context specific primitive tag [n] IMPLICIT.
+pub const fn context(n: u8) -> u8 {
+    0x80 | n
+}
+
+/// Context specific constructed tag [n] EXPLICIT, or IMPLICIT of a constructed type.
+pub const fn context_constructed(n: u8) -> u8 {
+    0xa0 | n
+}
+
+/// Reads the TLVs of a DER encoding one by one, the contents are borrowed from the input.
+#[derive(Clone, Copy, Debug)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The tag of the next TLV.
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Read the next TLV, returns its tag, content and the whole encoding.
+    pub fn read_any(&mut self) -> Result<(u8, &'a [u8], &'a [u8])> {
+        let data = self.data;
+        let (&tag, rest) = data.split_first().ok_or(Error::Der)?;
+        // multi-byte tags are not used by any format here
+        if tag & 0x1f == 0x1f {
+            return Err(Error::Der);
+        }
+        let (&first, mut rest) = rest.split_first().ok_or(Error::Der)?;
+        let len = if first < 0x80 {
+            first as usize
+        } else {
+            let n = (first & 0x7f) as usize;
+            if n == 0 || n > 4 || rest.len() < n || rest[0] == 0 {
+                return Err(Error::Der);
+            }
+            let len = rest[..n].iter().fold(0usize, |len, b| (len << 8) | *b as usize);
+            if len < 0x80 {
+                return Err(Error::Der);
+            }
+            rest = &rest[n..];
+            len
+        };
+        if rest.len() < len {
+            return Err(Error::Der);
+        }
+        let header = data.len() - rest.len();
+        self.data = &rest[len..];
+        Ok((tag, &rest[..len], &data[..header + len]))
+    }
+
+    /// Read the next TLV which must have the tag, returns its content.
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8]> {
+        match self.read_any()? {
+            (t, content, _) if t == tag => Ok(content),
+            _ => Err(Error::Der),
+        }
+    }
+
+    /// Read the next TLV if it has the tag.
+    pub fn read_optional(&mut self, tag: u8) -> Result<Option<&'a [u8]>> {
+        if self.peek_tag() == Some(tag) { self.read(tag).map(Some) } else { Ok(None) }
+    }
+
+    /// Read a constructed TLV, returns a reader of its content.
+    pub fn read_constructed(&mut self, tag: u8) -> Result<Reader<'a>> {
+        self.read(tag).map(Reader::new)
+    }
+
+    pub fn read_sequence(&mut self) -> Result<Reader<'a>> {
+        self.read_constructed(SEQUENCE)
+    }
+
+    pub fn read_octet_string(&mut self) -> Result<&'a [u8]> {
+        self.read(OCTET_STRING)
+    }
+
+    pub fn read_oid(&mut self) -> Result<&'a [u8]> {
+        self.read(OBJECT_IDENTIFIER)
+    }
+
+    /// Read a BIT STRING without unused bits.
+    pub fn read_bit_string(&mut self) -> Result<&'a [u8]> {
+        match self.read(BIT_STRING)? {
+            [0, bits @ ..] => Ok(bits),
+            _ => Err(Error::Der),
+        }
+    }
+
+    /// Read a small non-negative INTEGER.
+    pub fn read_u64(&mut self) -> Result<u64> {
+        let v = self.read(INTEGER)?;
+        let v = match v {
+            [] => return Err(Error::Der),
+            [0, rest @ ..] if !rest.is_empty() && rest[0] < 0x80 => return Err(Error::Der),
+            [0, rest @ ..] => rest,
+            [b, ..] if *b >= 0x80 => return Err(Error::Der),
+            v => v,
+        };
+        if v.len() > 8 {
+            return Err(Error::Der);
+        }
+        Ok(v.iter().fold(0, |n, b| (n << 8) | *b as u64))
+    }
+
+    /// Read a non-negative INTEGER of any size, returns its big-endian magnitude without the leading zero.
+    pub fn read_uint(&mut self) -> Result<&'a [u8]> {
+        match self.read(INTEGER)? {
+            [] => Err(Error::Der),
+            [0, rest @ ..] if !rest.is_empty() && rest[0] < 0x80 => Err(Error::Der),
+            [0, rest @ ..] if !rest.is_empty() => Ok(rest),
+            [b, ..] if *b >= 0x80 => Err(Error::Der),
+            v => Ok(v),
+        }
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool> {
+        match self.read(BOOLEAN)? {
+            [0] => Ok(false),
+            [0xff] => Ok(true),
+            _ => Err(Error::Der),
+        }
+    }
+
+    /// Fails if there is any trailing data.
+    pub fn finish(self) -> Result<()> {
+        if self.data.is_empty() { Ok(()) } else { Err(Error::Der) }
+    }
+}
+
+/// Builds a DER encoding, constructed values are written by closures.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct Writer {
+    buf: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl Writer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+
+    fn header(&mut self, tag: u8, len: usize) {
+        self.buf.push(tag);
+        if len < 0x80 {
+            self.buf.push(len as u8);
+        } else {
+            let n = (usize::BITS - len.leading_zeros()).div_ceil(8) as usize;
+            self.buf.push(0x80 | n as u8);
+            self.buf.extend_from_slice(&len.to_be_bytes()[size_of::<usize>() - n..]);
+        }
+    }
+
+    pub fn write(&mut self, tag: u8, content: &[u8]) -> &mut Self {
+        self.header(tag, content.len());
+        self.buf.extend_from_slice(content);
+        self
+    }
+
+    /// Append an already encoded TLV.
+    pub fn write_raw(&mut self, der: &[u8]) -> &mut Self {
+        self.buf.extend_from_slice(der);
+        self
+    }
+
+    pub fn write_constructed(&mut self, tag: u8, f: impl FnOnce(&mut Writer)) -> &mut Self {
+        let mut w = Writer::new();
+        f(&mut w);
+        self.write(tag, &w.buf)
+    }
+
+    pub fn write_sequence(&mut self, f: impl FnOnce(&mut Writer)) -> &mut Self {
+        self.write_constructed(SEQUENCE, f)
+    }
+
+    /// Write a SET OF, or an IMPLICIT tagged one, the encoded elements are sorted as DER requires.
+    pub fn write_set_of(&mut self, tag: u8, elements: &mut [Vec<u8>]) -> &mut Self {
+        elements.sort();
+        self.write(tag, &elements.concat())
+    }
+
+    pub fn write_octet_string(&mut self, v: &[u8]) -> &mut Self {
+        self.write(OCTET_STRING, v)
+    }
+
+    pub fn write_oid(&mut self, oid: &[u8]) -> &mut Self {
+        self.write(OBJECT_IDENTIFIER, oid)
+    }
+
+    /// Write a BIT STRING without unused bits.
+    pub fn write_bit_string(&mut self, bits: &[u8]) -> &mut Self {
+        self.header(BIT_STRING, bits.len() + 1);
+        self.buf.push(0);
+        self.buf.extend_from_slice(bits);
+        self
+    }
+
+    /// Write a non-negative INTEGER from its big-endian magnitude.
+    pub fn write_uint(&mut self, v: &[u8]) -> &mut Self {
+        let skip = v.iter().take_while(|b| **b == 0).count().min(v.len().saturating_sub(1));
+        let v = &v[skip..];
+        if v.is_empty() || v[0] >= 0x80 {
+            self.header(INTEGER, v.len() + 1);
+            self.buf.push(0);
+        } else {
+            self.header(INTEGER, v.len());
+        }
+        self.buf.extend_from_slice(v);
+        self
+    }
+
+    pub fn write_bool(&mut self, v: bool) -> &mut Self {
+        self.write(BOOLEAN, &[if v { 0xff } else { 0 }])
+    }
+
+    pub fn write_u64(&mut self, v: u64) -> &mut Self {
+        let b = v.to_be_bytes();
+        let skip = (v.leading_zeros() / 8).min(7) as usize;
+        if b[skip] >= 0x80 {
+            self.header(INTEGER, 9 - skip);
+            self.buf.push(0);
+        } else {
+            self.header(INTEGER, 8 - skip);
+        }
+        self.buf.extend_from_slice(&b[skip..]);
+        self
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length() {
+        for len in [0, 1, 0x7f, 0x80, 0xff, 0x100, 0xffff, 0x10000] {
+            let content = vec![0xa5; len];
+            let mut w = Writer::new();
+            w.write_octet_string(&content);
+            let der = w.finish();
+            let mut r = Reader::new(&der);
+            assert_eq!(r.read_octet_string().unwrap(), &content[..]);
+            r.finish().unwrap();
+        }
+        // non-minimal lengths
+        assert!(Reader::new(&[0x04, 0x81, 0x01, 0x00]).read_octet_string().is_err());
+        assert!(Reader::new(&[0x04, 0x82, 0x00, 0x80]).read_any().is_err());
+        // indefinite length and truncated content
+        assert!(Reader::new(&[0x30, 0x80, 0x00, 0x00]).read_any().is_err());
+        assert!(Reader::new(&[0x04, 0x02, 0x00]).read_any().is_err());
+    }
+
+    #[test]
+    fn test_integer() {
+        for (v, der) in [
+            (0u64, &[0x02, 0x01, 0x00][..]),
+            (0x7f, &[0x02, 0x01, 0x7f]),
+            (0x80, &[0x02, 0x02, 0x00, 0x80]),
+            (0x0100, &[0x02, 0x02, 0x01, 0x00]),
+            (u64::MAX, &[0x02, 0x09, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        ] {
+            let mut w = Writer::new();
+            w.write_u64(v);
+            assert_eq!(w.finish(), der);
+            assert_eq!(Reader::new(der).read_u64().unwrap(), v);
+        }
+        for (v, der) in [
+            (&[0][..], &[0x02, 0x01, 0x00][..]),
+            (&[0, 0], &[0x02, 0x01, 0x00]),
+            (&[0, 0x01, 0x02], &[0x02, 0x02, 0x01, 0x02]),
+            (&[0x80; 9], &[0x02, 0x0a, 0x00, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80]),
+        ] {
+            let mut w = Writer::new();
+            w.write_uint(v);
+            assert_eq!(w.finish(), der);
+            let magnitude = Reader::new(der).read_uint().unwrap();
+            assert_eq!(magnitude, &v[v.iter().take_while(|b| **b == 0).count().min(v.len().saturating_sub(1))..]);
+        }
+        assert!(Reader::new(&[0x02, 0x02, 0x00, 0x01]).read_uint().is_err());
+        assert!(Reader::new(&[0x02, 0x02, 0x00, 0x01]).read_u64().is_err());
+        assert!(Reader::new(&[0x02, 0x01, 0x80]).read_u64().is_err());
+    }
+
+    #[test]
+    fn test_nested() {
+        let mut w = Writer::new();
+        w.write_sequence(|w| {
+            w.write_oid(&[0x2b, 0x65, 0x6e]).write_bit_string(&[1, 2, 3]);
+        });
+        let der = w.finish();
+        assert_eq!(der, [0x30, 0x0b, 0x06, 0x03, 0x2b, 0x65, 0x6e, 0x03, 0x04, 0x00, 1, 2, 3]);
+
+        let mut r = Reader::new(&der);
+        let mut seq = r.read_sequence().unwrap();
+        assert_eq!(seq.read_oid().unwrap(), [0x2b, 0x65, 0x6e]);
+        assert_eq!(seq.read_optional(NULL).unwrap(), None);
+        assert_eq!(seq.read_bit_string().unwrap(), [1, 2, 3]);
+        seq.finish().unwrap();
+        r.finish().unwrap();
+    }
+}
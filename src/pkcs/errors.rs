@@ -0,0 +1,29 @@
+use thiserror;
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("invalid DER encoding")]
+    Der,
+
+    #[error("invalid base64 encoding")]
+    Base64,
+
+    #[error("invalid PEM encoding")]
+    Pem,
+
+    #[error("unsupported algorithm")]
+    UnsupportedAlgorithm,
+
+    #[error("invalid key length")]
+    InvalidLength,
+
+    #[error("seed and expanded key mismatch")]
+    KeyMismatch,
+
+    #[error("no seed in the private key")]
+    NoSeed,
+
+    #[error("invalid key")]
+    InvalidKey,
+}
+pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,179 @@
+//! PEM armoring (RFC 7468) and the base64 codec (RFC 4648) it is built on.
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "alloc")]
+use super::errors::{Error, Result};
+
+pub const PUBLIC_KEY: &str = "PUBLIC KEY";
+pub const PRIVATE_KEY: &str = "PRIVATE KEY";
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[cfg(feature = "alloc")]
+fn base64_encode_(alphabet: &[u8; 64], data: &[u8], pad: bool) -> String {
+    let mut s = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let mut b = [0u8; 3];
+        b[..chunk.len()].copy_from_slice(chunk);
+        let n = u32::from_be_bytes([0, b[0], b[1], b[2]]);
+        for i in 0..chunk.len() + 1 {
+            s.push(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+        if pad {
+            for _ in chunk.len()..3 {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+#[cfg(feature = "alloc")]
+fn base64_decode_(alphabet: &[u8; 64], s: &[u8], pad: bool) -> Result<Vec<u8>> {
+    let s = if pad {
+        if !s.len().is_multiple_of(4) {
+            return Err(Error::Base64);
+        }
+        let n = s.iter().rev().take(2).take_while(|c| **c == b'=').count();
+        &s[..s.len() - n]
+    } else {
+        s
+    };
+    if s.len() % 4 == 1 {
+        return Err(Error::Base64);
+    }
+    let mut out = Vec::with_capacity(s.len() / 4 * 3 + 2);
+    for chunk in s.chunks(4) {
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            let v = alphabet.iter().position(|a| a == c).ok_or(Error::Base64)? as u32;
+            n |= v << (18 - 6 * i);
+        }
+        let b = n.to_be_bytes();
+        let len = chunk.len() - 1;
+        // the unused bits must be zero
+        if b[1 + len..].iter().any(|x| *x != 0) {
+            return Err(Error::Base64);
+        }
+        out.extend_from_slice(&b[1..1 + len]);
+    }
+    Ok(out)
+}
+
+/// Standard base64 with padding.
+#[cfg(feature = "alloc")]
+pub fn base64_encode(data: &[u8]) -> String {
+    base64_encode_(STANDARD, data, true)
+}
+
+/// Standard base64 with padding.
+#[cfg(feature = "alloc")]
+pub fn base64_decode(s: &str) -> Result<Vec<u8>> {
+    base64_decode_(STANDARD, s.as_bytes(), true)
+}
+
+/// URL safe base64 without padding, as used by JOSE.
+#[cfg(feature = "alloc")]
+pub fn base64url_encode(data: &[u8]) -> String {
+    base64_encode_(URL_SAFE, data, false)
+}
+
+/// URL safe base64 without padding, as used by JOSE.
+#[cfg(feature = "alloc")]
+pub fn base64url_decode(s: &str) -> Result<Vec<u8>> {
+    base64_decode_(URL_SAFE, s.as_bytes(), false)
+}
+
+/// Armor der with the label, lines are 64 characters.
+#[cfg(feature = "alloc")]
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let b64 = base64_encode(der);
+    let mut s = String::with_capacity(b64.len() + b64.len() / 64 + 2 * label.len() + 36);
+    s.push_str("-----BEGIN ");
+    s.push_str(label);
+    s.push_str("-----\n");
+    for line in b64.as_bytes().chunks(64) {
+        // base64 is ascii
+        s.push_str(core::str::from_utf8(line).unwrap());
+        s.push('\n');
+    }
+    s.push_str("-----END ");
+    s.push_str(label);
+    s.push_str("-----\n");
+    s
+}
+
+/// Decode the first PEM block, returns its label and content. Text around the block is ignored.
+#[cfg(feature = "alloc")]
+pub fn decode(pem: &str) -> Result<(&str, Vec<u8>)> {
+    let (_, rest) = pem.split_once("-----BEGIN ").ok_or(Error::Pem)?;
+    let (label, rest) = rest.split_once("-----").ok_or(Error::Pem)?;
+    let (body, rest) = rest.split_once("-----END ").ok_or(Error::Pem)?;
+    let end = rest.strip_prefix(label).and_then(|rest| rest.strip_prefix("-----"));
+    if end.is_none() || label.contains('\n') {
+        return Err(Error::Pem);
+    }
+    let b64: Vec<u8> = body.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    let der = base64_decode_(STANDARD, &b64, true)?;
+    Ok((label, der))
+}
+
+/// Decode the first PEM block, which must have the label.
+#[cfg(feature = "alloc")]
+pub fn decode_label(pem: &str, label: &str) -> Result<Vec<u8>> {
+    match decode(pem)? {
+        (l, der) if l == label => Ok(der),
+        _ => Err(Error::Pem),
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        // RFC 4648 section 10
+        for (data, b64) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(base64_encode(data.as_bytes()), b64);
+            assert_eq!(base64_decode(b64).unwrap(), data.as_bytes());
+            let url = b64.trim_end_matches('=');
+            assert_eq!(base64url_encode(data.as_bytes()), url);
+            assert_eq!(base64url_decode(url).unwrap(), data.as_bytes());
+        }
+        assert_eq!(base64url_encode(&[0xfb, 0xff]), "-_8");
+        assert!(base64_decode("Zg=").is_err());
+        assert!(base64_decode("Zh==").is_err());
+        assert!(base64_decode("Z===").is_err());
+        assert!(base64_decode("Zm9v!A==").is_err());
+        assert!(base64url_decode("Zg==").is_err());
+    }
+
+    #[test]
+    fn test_pem() {
+        let der: Vec<u8> = (0..=255).collect();
+        let pem = encode(PUBLIC_KEY, &der);
+        assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----\n"));
+        assert!(pem.lines().all(|line| line.len() <= 64));
+        assert_eq!(decode(&pem).unwrap(), (PUBLIC_KEY, der.clone()));
+        assert_eq!(decode_label(&pem, PUBLIC_KEY).unwrap(), der);
+        assert!(decode_label(&pem, PRIVATE_KEY).is_err());
+
+        let crlf = format!("comment\r\n{}trailer", pem.replace('\n', "\r\n"));
+        assert_eq!(decode_label(&crlf, PUBLIC_KEY).unwrap(), der);
+
+        let mismatch = pem.replace("END PUBLIC", "END PRIVATE");
+        assert!(decode(&mismatch).is_err());
+    }
+}
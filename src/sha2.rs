@@ -0,0 +1,408 @@
+//! The SHA-2 functions of FIPS 180-4, with the [`Hash`] trait of the SHA-3 functions.
+
+use crate::sha3::Hash;
+
+const K256: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const K512: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+const IV224: [u32; 8] = [0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4];
+const IV256: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+#[rustfmt::skip]
+const IV384: [u64; 8] = [
+    0xcbbb9d5dc1059ed8, 0x629a292a367cd507, 0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511, 0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4,
+];
+#[rustfmt::skip]
+const IV512: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+#[rustfmt::skip]
+const IV512_224: [u64; 8] = [
+    0x8c3d37c819544da2, 0x73e1996689dcd4d6, 0x1dfab7ae32ff9c82, 0x679dd514582f9fcf,
+    0x0f6d2b697bd44da8, 0x77e36f7304c48942, 0x3f9d85a86a1d36c8, 0x1112e6ad91d692a1,
+];
+#[rustfmt::skip]
+const IV512_256: [u64; 8] = [
+    0x22312194fc2bf72c, 0x9f555fa3c84c64c2, 0x2393b86b6f53b151, 0x963877195940eabd,
+    0x96283ee2a88effe3, 0xbe5e1e2553863992, 0x2b0199fc2c85b8aa, 0x0eb72ddc81c52ca2,
+];
+
+/// SHA-224 and SHA-256, N is the digest size.
+#[derive(Debug, Clone)]
+pub struct Sha256<const N: usize> {
+    iv: [u32; 8],
+    h: [u32; 8],
+    block: [u8; 64],
+    n: usize,
+    len: u64,
+}
+
+/// SHA-384, SHA-512, SHA-512/224 and SHA-512/256, N is the digest size.
+#[derive(Debug, Clone)]
+pub struct Sha512<const N: usize> {
+    iv: [u64; 8],
+    h: [u64; 8],
+    block: [u8; 128],
+    n: usize,
+    len: u128,
+}
+
+pub fn new224() -> Sha256<28> {
+    Sha256 { iv: IV224, h: IV224, block: [0; 64], n: 0, len: 0 }
+}
+
+pub fn new256() -> Sha256<32> {
+    Sha256 { iv: IV256, h: IV256, block: [0; 64], n: 0, len: 0 }
+}
+
+pub fn new384() -> Sha512<48> {
+    Sha512 { iv: IV384, h: IV384, block: [0; 128], n: 0, len: 0 }
+}
+
+pub fn new512() -> Sha512<64> {
+    Sha512 { iv: IV512, h: IV512, block: [0; 128], n: 0, len: 0 }
+}
+
+pub fn new512_224() -> Sha512<28> {
+    Sha512 { iv: IV512_224, h: IV512_224, block: [0; 128], n: 0, len: 0 }
+}
+
+pub fn new512_256() -> Sha512<32> {
+    Sha512 { iv: IV512_256, h: IV512_256, block: [0; 128], n: 0, len: 0 }
+}
+
+// buffer the data into the block of n bytes, and compress the complete blocks
+pub(crate) fn update<const B: usize>(block: &mut [u8; B], n: &mut usize, data: &[u8], mut compress: impl FnMut(&[u8; B])) {
+    let mut data = data;
+    if *n > 0 {
+        let k = (B - *n).min(data.len());
+        block[*n..*n + k].copy_from_slice(&data[..k]);
+        *n += k;
+        data = &data[k..];
+        if *n < B {
+            return;
+        }
+        compress(block);
+        *n = 0;
+    }
+    let mut blocks = data.chunks_exact(B);
+    for b in &mut blocks {
+        compress(b.try_into().unwrap());
+    }
+    let rest = blocks.remainder();
+    block[..rest.len()].copy_from_slice(rest);
+    *n = rest.len();
+}
+
+fn compress256(h: &mut [u32; 8], block: &[u8; 64]) {
+    let mut w = [0u32; 64];
+    for (w, b) in w.iter_mut().zip(block.chunks_exact(4)) {
+        *w = u32::from_be_bytes(b.try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(K256[i]).wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        (hh, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+    }
+    for (h, x) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *h = h.wrapping_add(x);
+    }
+}
+
+fn compress512(h: &mut [u64; 8], block: &[u8; 128]) {
+    let mut w = [0u64; 80];
+    for (w, b) in w.iter_mut().zip(block.chunks_exact(8)) {
+        *w = u64::from_be_bytes(b.try_into().unwrap());
+    }
+    for i in 16..80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+    for i in 0..80 {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(K512[i]).wrapping_add(w[i]);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        (hh, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+    }
+    for (h, x) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *h = h.wrapping_add(x);
+    }
+}
+
+impl<const N: usize> Hash<N> for Sha256<N> {
+    fn reset(&mut self) {
+        self.h = self.iv;
+        self.n = 0;
+        self.len = 0;
+    }
+
+    fn write(&mut self, data: &[u8]) {
+        self.len += data.len() as u64;
+        update(&mut self.block, &mut self.n, data, |b| compress256(&mut self.h, b));
+    }
+
+    fn sum_into(&self, digest: &mut [u8]) {
+        let mut d = self.clone();
+        let bits = d.len * 8;
+        let pad = if d.n < 56 { 56 - d.n } else { 120 - d.n };
+        let mut padding = [0; 64 + 8];
+        padding[0] = 0x80;
+        padding[pad..pad + 8].copy_from_slice(&bits.to_be_bytes());
+        d.write(&padding[..pad + 8]);
+        for (out, h) in digest.chunks_mut(4).zip(d.h) {
+            out.copy_from_slice(&h.to_be_bytes()[..out.len()]);
+        }
+    }
+
+    fn block_size(&self) -> usize {
+        64
+    }
+
+    fn size(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Hash<N> for Sha512<N> {
+    fn reset(&mut self) {
+        self.h = self.iv;
+        self.n = 0;
+        self.len = 0;
+    }
+
+    fn write(&mut self, data: &[u8]) {
+        self.len += data.len() as u128;
+        update(&mut self.block, &mut self.n, data, |b| compress512(&mut self.h, b));
+    }
+
+    fn sum_into(&self, digest: &mut [u8]) {
+        let mut d = self.clone();
+        let bits = d.len * 8;
+        let pad = if d.n < 112 { 112 - d.n } else { 240 - d.n };
+        let mut padding = [0; 128 + 16];
+        padding[0] = 0x80;
+        padding[pad..pad + 16].copy_from_slice(&bits.to_be_bytes());
+        d.write(&padding[..pad + 16]);
+        for (out, h) in digest.chunks_mut(8).zip(d.h) {
+            out.copy_from_slice(&h.to_be_bytes()[..out.len()]);
+        }
+    }
+
+    fn block_size(&self) -> usize {
+        128
+    }
+
+    fn size(&self) -> usize {
+        N
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_sha256() {
+        for (data, expect) in [
+            (&b""[..], hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")),
+            (b"abc", hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                hex!("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"),
+            ),
+        ] {
+            let mut h = new256();
+            h.write(data);
+            assert_eq!(h.sum(), expect);
+        }
+
+        // one million 'a' written in pieces across the block boundaries
+        let mut h = new256();
+        let a = [b'a'; 1000];
+        for i in 0..1000 {
+            h.write(&a[..i % 100]);
+            h.write(&a[i % 100..]);
+        }
+        assert_eq!(h.sum(), hex!("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"));
+        h.reset();
+        assert_eq!(h.sum(), hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"));
+    }
+
+    // the digests of the NIST examples: "", "abc", the two block messages and one million 'a'
+    fn check<const N: usize>(mut h: impl Hash<N>, expect: [[u8; N]; 5]) {
+        let a = [b'a'; 1000];
+        for (data, expect) in [
+            &b""[..],
+            b"abc",
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+              hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+        ]
+        .iter()
+        .zip(&expect)
+        {
+            h.reset();
+            h.write(data);
+            assert_eq!(&h.sum(), expect);
+        }
+        h.reset();
+        for i in 0..1000 {
+            h.write(&a[..i % 100]);
+            h.write(&a[i % 100..]);
+        }
+        assert_eq!(h.sum(), expect[4]);
+    }
+
+    #[test]
+    fn test_sha224() {
+        check(
+            new224(),
+            [
+                hex!("d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f"),
+                hex!("23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"),
+                hex!("75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525"),
+                hex!("c97ca9a559850ce97a04a96def6d99a9e0e0e2ab14e6b8df265fc0b3"),
+                hex!("20794655980c91d8bbb4c1ea97618a4bf03f42581948b2ee4ee7ad67"),
+            ],
+        );
+        assert_eq!((new224().size(), new224().block_size()), (28, 64));
+    }
+
+    #[test]
+    fn test_sha384() {
+        check(
+            new384(),
+            [
+                hex!(
+                    "38b060a751ac96384cd9327eb1b1e36a21fdb71114be0743"
+                    "4c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b"
+                ),
+                hex!(
+                    "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded163"
+                    "1a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"
+                ),
+                hex!(
+                    "3391fdddfc8dc7393707a65b1b4709397cf8b1d162af05ab"
+                    "fe8f450de5f36bc6b0455a8520bc4e6f5fe95b1fe3c8452b"
+                ),
+                hex!(
+                    "09330c33f71147e83d192fc782cd1b4753111b173b3b05d2"
+                    "2fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039"
+                ),
+                hex!(
+                    "9d0e1809716474cb086e834e310a4a1ced149e9c00f24852"
+                    "7972cec5704c2a5b07b8b3dc38ecc4ebae97ddd87f3d8985"
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_sha512() {
+        check(
+            new512(),
+            [
+                hex!(
+                    "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce"
+                    "47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+                ),
+                hex!(
+                    "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a"
+                    "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+                ),
+                hex!(
+                    "204a8fc6dda82f0a0ced7beb8e08a41657c16ef468b228a8279be331a703c335"
+                    "96fd15c13b1b07f9aa1d3bea57789ca031ad85c7a71dd70354ec631238ca3445"
+                ),
+                hex!(
+                    "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018"
+                    "501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"
+                ),
+                hex!(
+                    "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb"
+                    "de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b"
+                ),
+            ],
+        );
+        assert_eq!((new512().size(), new512().block_size()), (64, 128));
+    }
+
+    #[test]
+    fn test_sha512_224() {
+        check(
+            new512_224(),
+            [
+                hex!("6ed0dd02806fa89e25de060c19d3ac86cabb87d6a0ddd05c333b84f4"),
+                hex!("4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa"),
+                hex!("e5302d6d54bb242275d1e7622d68df6eb02dedd13f564c13dbda2174"),
+                hex!("23fec5bb94d60b23308192640b0c453335d664734fe40e7268674af9"),
+                hex!("37ab331d76f0d36de422bd0edeb22a28accd487b7a8453ae965dd287"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_sha512_256() {
+        check(
+            new512_256(),
+            [
+                hex!("c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a"),
+                hex!("53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"),
+                hex!("bde8e1f9f19bb9fd3406c90ec6bc47bd36d8ada9f11880dbc8a22a7078b6a461"),
+                hex!("3928e184fb8690f840da3988121d31be65cb9d3ef83ee6146feac861e19b563a"),
+                hex!("9a59a052930187a97038cae692f30708aa6491923ef5194394dc68d56c74fb21"),
+            ],
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Runtime detection of the CPU features used by the Keccak backends, with cpuid on x86_64 so that
+//! it works without std.
+
+#[cfg(target_arch = "x86_64")]
+use core::sync::atomic::{AtomicU8, Ordering};
+
+// 0 before the detection, then 1 without AVX2 and 2 with it
+#[cfg(target_arch = "x86_64")]
+static AVX2: AtomicU8 = AtomicU8::new(0);
+
+/// Whether the CPU has AVX2 and the OS saves the YMM registers.
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn has_avx2() -> bool {
+    match AVX2.load(Ordering::Relaxed) {
+        0 => {
+            let avx2 = detect_avx2();
+            AVX2.store(if avx2 { 2 } else { 1 }, Ordering::Relaxed);
+            avx2
+        }
+        v => v == 2,
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub(crate) fn has_avx2() -> bool {
+    false
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(unused_unsafe)]
+fn detect_avx2() -> bool {
+    use core::arch::x86_64::{__cpuid, __cpuid_count};
+
+    if unsafe { __cpuid(0) }.eax < 7 {
+        return false;
+    }
+    // OSXSAVE and AVX
+    let ecx = unsafe { __cpuid(1) }.ecx;
+    if ecx & (1 << 27) == 0 || ecx & (1 << 28) == 0 {
+        return false;
+    }
+    // the XMM and YMM states are enabled in XCR0
+    if unsafe { xcr0() } & 0b110 != 0b110 {
+        return false;
+    }
+    unsafe { __cpuid_count(7, 0) }.ebx & (1 << 5) != 0
+}
+
+// 0 before the detection, then 1 without BMI1 and BMI2 and 2 with them
+#[cfg(target_arch = "x86_64")]
+static BMI2: AtomicU8 = AtomicU8::new(0);
+
+/// Whether the CPU has BMI1 and BMI2.
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn has_bmi2() -> bool {
+    match BMI2.load(Ordering::Relaxed) {
+        0 => {
+            let bmi2 = detect_bmi2();
+            BMI2.store(if bmi2 { 2 } else { 1 }, Ordering::Relaxed);
+            bmi2
+        }
+        v => v == 2,
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(unused_unsafe)]
+fn detect_bmi2() -> bool {
+    use core::arch::x86_64::{__cpuid, __cpuid_count};
+
+    if unsafe { __cpuid(0) }.eax < 7 {
+        return false;
+    }
+    let ebx = unsafe { __cpuid_count(7, 0) }.ebx;
+    ebx & (1 << 3) != 0 && ebx & (1 << 8) != 0
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "xsave")]
+unsafe fn xcr0() -> u64 {
+    unsafe { core::arch::x86_64::_xgetbv(0) }
+}
+
+/// Whether the CPU has the SHA3 extension. The std detection reads the auxiliary vector of the OS;
+/// without std, only the features enabled at compile time are known.
+#[cfg(all(target_arch = "aarch64", feature = "std"))]
+pub(crate) fn has_sha3() -> bool {
+    std::arch::is_aarch64_feature_detected!("sha3")
+}
+
+#[cfg(all(target_arch = "aarch64", not(feature = "std")))]
+pub(crate) fn has_sha3() -> bool {
+    cfg!(target_feature = "sha3")
+}
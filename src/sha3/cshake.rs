@@ -0,0 +1,188 @@
+//! cSHAKE128/256 of SP 800-185, and the encodings shared by KMAC, TupleHash and ParallelHash.
+
+use super::{Hash, SHAKE, XOF, dsbyteShake};
+
+// the domain separation bits of cSHAKE
+const DS_CSHAKE: u8 = 0b00000100;
+
+// left_encode(x) or right_encode(x) into buf, returns the encoding
+pub(super) fn encode(x: u64, left: bool, buf: &mut [u8; 9]) -> &[u8] {
+    let n = (8 - x.leading_zeros() as usize / 8).max(1);
+    let (len, bytes) = if left { (0, 1..n + 1) } else { (n, 0..n) };
+    buf[len] = n as u8;
+    buf[bytes].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+    &buf[..n + 1]
+}
+
+// absorb encode_string(s)
+pub(super) fn absorb_string<const N: usize>(h: &mut SHAKE<N>, s: &[u8]) {
+    let mut buf = [0u8; 9];
+    h.write(encode(8 * s.len() as u64, true, &mut buf));
+    h.write(s);
+}
+
+// absorb bytepad(encode_string(s[0]) || encode_string(s[1]) ..., rate)
+pub(super) fn absorb_bytepad<const N: usize>(h: &mut SHAKE<N>, strings: &[&[u8]]) {
+    let rate = h.block_size();
+    let mut buf = [0u8; 9];
+    let p = encode(rate as u64, true, &mut buf);
+    h.write(p);
+    let mut n = p.len();
+    for s in strings {
+        absorb_string(h, s);
+        n += encode(8 * s.len() as u64, true, &mut buf).len() + s.len();
+    }
+    let zeros = [0u8; 168];
+    h.write(&zeros[..(rate - n % rate) % rate]);
+}
+
+/// cSHAKE with the function name and customization strings absorbed, N is 16 for cSHAKE128 and
+/// 32 for cSHAKE256. Reset goes back to the state after the strings.
+#[derive(Clone)]
+pub struct CShake<const N: usize> {
+    h: SHAKE<N>,
+    init: SHAKE<N>,
+}
+
+impl<const N: usize> CShake<N> {
+    /// cSHAKE is SHAKE when both the function name n and the customization string s are empty.
+    pub fn new(n: &[u8], s: &[u8]) -> Self {
+        let h = if n.is_empty() && s.is_empty() {
+            SHAKE::new(dsbyteShake)
+        } else {
+            let mut h = SHAKE::new(DS_CSHAKE);
+            absorb_bytepad(&mut h, &[n, s]);
+            h
+        };
+        CShake { init: h.clone(), h }
+    }
+
+    pub fn read(&mut self, buf: &mut [u8]) {
+        self.h.read(buf);
+    }
+
+    // Keep the data absorbed so far, such as a key, when resetting.
+    pub(super) fn keep(&mut self) {
+        self.init = self.h.clone();
+    }
+
+    pub(super) fn absorb_bytepad(&mut self, strings: &[&[u8]]) {
+        absorb_bytepad(&mut self.h, strings);
+    }
+
+    pub(super) fn absorb_string(&mut self, s: &[u8]) {
+        absorb_string(&mut self.h, s);
+    }
+
+    // A copy with right_encode(bits) absorbed, ready to be read. The bits are the output length,
+    // or 0 for the XOF variants.
+    pub(super) fn finish(&self, bits: usize) -> SHAKE<N> {
+        let mut h = self.h.clone();
+        let mut buf = [0u8; 9];
+        h.write(encode(bits as u64, false, &mut buf));
+        h
+    }
+}
+
+impl<const N: usize> Hash<N> for CShake<N> {
+    fn reset(&mut self) {
+        self.h = self.init.clone();
+    }
+
+    fn write(&mut self, p: &[u8]) {
+        self.h.write(p);
+    }
+
+    fn sum_into(&self, digest: &mut [u8]) {
+        self.h.sum_into(digest);
+    }
+
+    fn block_size(&self) -> usize {
+        self.h.block_size()
+    }
+
+    fn size(&self) -> usize {
+        self.h.size()
+    }
+}
+
+impl<const N: usize> XOF for CShake<N> {
+    fn init(&mut self) -> &mut Self {
+        self.reset();
+        self
+    }
+
+    fn absorb(&mut self, str: &[u8]) -> &mut Self {
+        self.write(str);
+        self
+    }
+
+    fn squeeze(&mut self, z: &mut [u8]) -> &mut Self {
+        self.read(z);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{new_cshake128, new_cshake256, new_shake128};
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_encode() {
+        let mut buf = [0u8; 9];
+        assert_eq!(encode(0, true, &mut buf), [1, 0]);
+        assert_eq!(encode(136, true, &mut buf), [1, 136]);
+        assert_eq!(encode(256, false, &mut buf), [1, 0, 2]);
+        assert_eq!(encode(u64::MAX, true, &mut buf), [8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn test_cshake() {
+        // the cSHAKE samples of NIST
+        let data: [u8; 200] = core::array::from_fn(|i| i as u8);
+        let mut h = new_cshake128(b"", b"Email Signature");
+        h.write(&data[..4]);
+        let mut out = [0u8; 32];
+        h.read(&mut out);
+        assert_eq!(out, hex!("c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5"));
+        h.reset();
+        h.write(&data);
+        h.read(&mut out);
+        assert_eq!(out, hex!("c5221d50e4f822d96a2e8881a961420f294b7b24fe3d2094baed2c6524cc166b"));
+
+        let mut h = new_cshake256(b"", b"Email Signature");
+        h.write(&data[..4]);
+        let mut out = [0u8; 64];
+        h.read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd1"
+                "64020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c"
+            )
+        );
+        h.reset();
+        h.write(&data);
+        h.read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "07dc27b11e51fbac75bc7b3c1d983e8b4b85fb1defaf218912ac864302730917"
+                "27f42b17ed1df63e8ec118f04b23633c1dfb1574c8fb55cb45da8e25afb092bb"
+            )
+        );
+
+        // without the strings cSHAKE is SHAKE
+        let mut h = new_cshake128(b"", b"");
+        let mut s = new_shake128();
+        h.write(&data);
+        s.write(&data);
+        let mut expect = [0u8; 32];
+        s.read(&mut expect);
+        let mut out = [0u8; 32];
+        h.read(&mut out);
+        assert_eq!(out, expect);
+    }
+}
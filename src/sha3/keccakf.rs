@@ -1,7 +1,40 @@
 
+#[cfg(any(target_arch = "aarch64", test))]
+mod aarch64;
+#[cfg(target_arch = "x86_64")]
+mod x86_64;
+
 #[inline]
 pub(crate) fn keccak_f1600(a: &mut [u64; 25]) {
-    keccak_f1600_generic(a);
+    keccak_p1600(a, 24);
+}
+
+#[inline]
+pub(crate) fn keccak_p1600_12(a: &mut [u64; 25]) {
+    keccak_p1600(a, 12);
+}
+
+// keccak_p1600 runs the fastest backend the CPU supports.
+#[inline]
+fn keccak_p1600(a: &mut [u64; 25], rounds: usize) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if super::cpu::has_bmi2() {
+            // SAFETY: BMI1 and BMI2 are supported
+            return unsafe { x86_64::keccak_p1600_bmi2(a, rounds) };
+        }
+        x86_64::keccak_p1600_lc(a, rounds)
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        if super::cpu::has_sha3() {
+            // SAFETY: the SHA3 extension is supported
+            return unsafe { aarch64::keccak_p1600_sha3(a, rounds) };
+        }
+        keccak_p1600_generic(a, rounds)
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    keccak_p1600_generic(a, rounds)
 }
 
 
@@ -127,7 +160,7 @@ macro_rules! round {
 
 
 //The round constants in the l(iota) step.
-const rc: [u64; 24] = [
+pub(super) const rc: [u64; 24] = [
     0x0000000000000001, 0x0000000000008082, 0x800000000000808A, 0x8000000080008000,
     0x000000000000808B, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
     0x000000000000008A, 0x0000000000000088, 0x0000000080008009, 0x000000008000000A,
@@ -139,10 +172,18 @@ const rc: [u64; 24] = [
 
 
 // keccak_f1600_generic applies the Keccak permutation.
-#[rustfmt::skip]
 #[inline]
 pub(crate) fn keccak_f1600_generic(a: &mut [u64; 25]) {
-    let mut i = 0;
+    keccak_p1600_generic(a, 24);
+}
+
+// keccak_p1600_generic applies the last rounds of the Keccak permutation, Keccak-p[1600, rounds].
+// The rounds are a multiple of 4, 12 for TurboSHAKE.
+#[rustfmt::skip]
+#[inline]
+pub(crate) fn keccak_p1600_generic(a: &mut [u64; 25], rounds: usize) {
+    debug_assert!(rounds.is_multiple_of(4) && rounds <= 24);
+    let mut i = 24 - rounds;
 
     while i < 24 {
         round!(
@@ -213,4 +254,45 @@ mod tests {
 
         assert_eq!(a, expect);
     }
+
+    #[test]
+    fn test_backends() {
+        // every backend the CPU supports against the generic permutation, on pseudo-random states
+        let mut x = 0x9e3779b97f4a7c15u64;
+        for _ in 0..64 {
+            let a: [u64; 25] = core::array::from_fn(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                x
+            });
+            for rounds in [24, 12] {
+                let mut expect = a;
+                keccak_p1600_generic(&mut expect, rounds);
+
+                let mut b = a;
+                keccak_p1600(&mut b, rounds);
+                assert_eq!(b, expect);
+
+                #[cfg(target_arch = "x86_64")]
+                {
+                    let mut b = a;
+                    x86_64::keccak_p1600_lc(&mut b, rounds);
+                    assert_eq!(b, expect);
+                    if crate::sha3::cpu::has_bmi2() {
+                        let mut b = a;
+                        unsafe { x86_64::keccak_p1600_bmi2(&mut b, rounds) };
+                        assert_eq!(b, expect);
+                    }
+                }
+
+                #[cfg(target_arch = "aarch64")]
+                if crate::sha3::cpu::has_sha3() {
+                    let mut b = a;
+                    unsafe { aarch64::keccak_p1600_sha3(&mut b, rounds) };
+                    assert_eq!(b, expect);
+                }
+            }
+        }
+    }
 }
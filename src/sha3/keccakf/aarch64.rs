@@ -0,0 +1,152 @@
+//! Keccak-p[1600] with the AArch64 SHA3 extension: eor3 and rax1 for theta, xar for rho and
+//! bcax for chi. Each lane is held in the low half of a vector register.
+
+#[cfg(target_arch = "aarch64")]
+use super::rc;
+
+// One round, with the instructions passed in so that the tests can run it on any target.
+#[rustfmt::skip]
+macro_rules! round {
+    ($a:ident, $rc:expr, $eor:path, $eor3:path, $rax1:path, $xar:ident, $bcax:path) => {{
+        let c0 = $eor3($eor3($a[0], $a[5], $a[10]), $a[15], $a[20]);
+        let c1 = $eor3($eor3($a[1], $a[6], $a[11]), $a[16], $a[21]);
+        let c2 = $eor3($eor3($a[2], $a[7], $a[12]), $a[17], $a[22]);
+        let c3 = $eor3($eor3($a[3], $a[8], $a[13]), $a[18], $a[23]);
+        let c4 = $eor3($eor3($a[4], $a[9], $a[14]), $a[19], $a[24]);
+        let d0 = $rax1(c4, c1);
+        let d1 = $rax1(c0, c2);
+        let d2 = $rax1(c1, c3);
+        let d3 = $rax1(c2, c4);
+        let d4 = $rax1(c3, c0);
+        let b0 = $eor($a[0], d0);
+        let b1 = $xar!($a[6], d1, 20);
+        let b2 = $xar!($a[12], d2, 21);
+        let b3 = $xar!($a[18], d3, 43);
+        let b4 = $xar!($a[24], d4, 50);
+        let b5 = $xar!($a[3], d3, 36);
+        let b6 = $xar!($a[9], d4, 44);
+        let b7 = $xar!($a[10], d0, 61);
+        let b8 = $xar!($a[16], d1, 19);
+        let b9 = $xar!($a[22], d2, 3);
+        let b10 = $xar!($a[1], d1, 63);
+        let b11 = $xar!($a[7], d2, 58);
+        let b12 = $xar!($a[13], d3, 39);
+        let b13 = $xar!($a[19], d4, 56);
+        let b14 = $xar!($a[20], d0, 46);
+        let b15 = $xar!($a[4], d4, 37);
+        let b16 = $xar!($a[5], d0, 28);
+        let b17 = $xar!($a[11], d1, 54);
+        let b18 = $xar!($a[17], d2, 49);
+        let b19 = $xar!($a[23], d3, 8);
+        let b20 = $xar!($a[2], d2, 2);
+        let b21 = $xar!($a[8], d3, 9);
+        let b22 = $xar!($a[14], d4, 25);
+        let b23 = $xar!($a[15], d0, 23);
+        let b24 = $xar!($a[21], d1, 62);
+        $a[0] = $bcax(b0, b2, b1);
+        $a[1] = $bcax(b1, b3, b2);
+        $a[2] = $bcax(b2, b4, b3);
+        $a[3] = $bcax(b3, b0, b4);
+        $a[4] = $bcax(b4, b1, b0);
+        $a[5] = $bcax(b5, b7, b6);
+        $a[6] = $bcax(b6, b8, b7);
+        $a[7] = $bcax(b7, b9, b8);
+        $a[8] = $bcax(b8, b5, b9);
+        $a[9] = $bcax(b9, b6, b5);
+        $a[10] = $bcax(b10, b12, b11);
+        $a[11] = $bcax(b11, b13, b12);
+        $a[12] = $bcax(b12, b14, b13);
+        $a[13] = $bcax(b13, b10, b14);
+        $a[14] = $bcax(b14, b11, b10);
+        $a[15] = $bcax(b15, b17, b16);
+        $a[16] = $bcax(b16, b18, b17);
+        $a[17] = $bcax(b17, b19, b18);
+        $a[18] = $bcax(b18, b15, b19);
+        $a[19] = $bcax(b19, b16, b15);
+        $a[20] = $bcax(b20, b22, b21);
+        $a[21] = $bcax(b21, b23, b22);
+        $a[22] = $bcax(b22, b24, b23);
+        $a[23] = $bcax(b23, b20, b24);
+        $a[24] = $bcax(b24, b21, b20);
+        $a[0] = $eor($a[0], $rc);
+    }};
+}
+
+#[cfg(target_arch = "aarch64")]
+mod ce {
+    pub(super) use core::arch::aarch64::{
+        uint64x2_t, vbcaxq_u64 as bcax, vdupq_n_u64, veor3q_u64 as eor3, veorq_u64 as eor, vgetq_lane_u64,
+        vrax1q_u64 as rax1, vxarq_u64,
+    };
+
+    macro_rules! xar {
+        ($x:expr, $y:expr, $n:literal) => {
+            vxarq_u64::<$n>($x, $y)
+        };
+    }
+    pub(super) use xar;
+}
+
+/// Keccak-p[1600, rounds] with the SHA3 instructions.
+///
+/// # Safety
+/// The CPU must support the SHA3 extension.
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon,sha3")]
+pub(crate) unsafe fn keccak_p1600_sha3(a: &mut [u64; 25], rounds: usize) {
+    use ce::*;
+
+    debug_assert!(rounds.is_multiple_of(4) && rounds <= 24);
+    let mut s: [uint64x2_t; 25] = core::array::from_fn(|i| vdupq_n_u64(a[i]));
+    for &r in &rc[24 - rounds..] {
+        round!(s, vdupq_n_u64(r), eor, eor3, rax1, xar, bcax);
+    }
+    for (a, s) in a.iter_mut().zip(s) {
+        *a = vgetq_lane_u64::<0>(s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{keccak_f1600_generic, rc};
+
+    // the instructions on scalar lanes
+    fn eor(x: u64, y: u64) -> u64 {
+        x ^ y
+    }
+    fn eor3(x: u64, y: u64, z: u64) -> u64 {
+        x ^ y ^ z
+    }
+    fn rax1(x: u64, y: u64) -> u64 {
+        x ^ y.rotate_left(1)
+    }
+    fn bcax(x: u64, y: u64, z: u64) -> u64 {
+        x ^ (y & !z)
+    }
+    macro_rules! xar {
+        ($x:expr, $y:expr, $n:literal) => {
+            ($x ^ $y).rotate_right($n)
+        };
+    }
+
+    #[test]
+    fn test_round() {
+        // the data flow of the SHA3 extension round, on any target
+        let mut x = 0x0123456789abcdefu64;
+        for _ in 0..8 {
+            let a: [u64; 25] = core::array::from_fn(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                x
+            });
+            let mut s = a;
+            for &r in &rc {
+                round!(s, r, eor, eor3, rax1, xar, bcax);
+            }
+            let mut expect = a;
+            keccak_f1600_generic(&mut expect);
+            assert_eq!(s, expect);
+        }
+    }
+}
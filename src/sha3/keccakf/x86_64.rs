@@ -0,0 +1,105 @@
+//! Keccak-p[1600] for x86_64, with the lane complementing transform so that chi needs a single
+//! not in 5 of its outputs, and the rotations left to the compiler, rorx with BMI2.
+
+use super::rc;
+
+// The lanes kept complemented between the rounds; a round maps this pattern to itself.
+const COMPLEMENTED: [usize; 6] = [1, 2, 8, 12, 17, 20];
+
+// One fully unrolled round on the complemented state.
+#[rustfmt::skip]
+macro_rules! round {
+    ($a:ident, $rc:expr) => {{
+        let c0 = $a[0] ^ $a[5] ^ $a[10] ^ $a[15] ^ $a[20];
+        let c1 = $a[1] ^ $a[6] ^ $a[11] ^ $a[16] ^ $a[21];
+        let c2 = $a[2] ^ $a[7] ^ $a[12] ^ $a[17] ^ $a[22];
+        let c3 = $a[3] ^ $a[8] ^ $a[13] ^ $a[18] ^ $a[23];
+        let c4 = $a[4] ^ $a[9] ^ $a[14] ^ $a[19] ^ $a[24];
+        let d0 = c4 ^ c1.rotate_left(1);
+        let d1 = c0 ^ c2.rotate_left(1);
+        let d2 = c1 ^ c3.rotate_left(1);
+        let d3 = c2 ^ c4.rotate_left(1);
+        let d4 = c3 ^ c0.rotate_left(1);
+        let b0 = $a[0] ^ d0;
+        let b1 = ($a[6] ^ d1).rotate_left(44);
+        let b2 = ($a[12] ^ d2).rotate_left(43);
+        let b3 = ($a[18] ^ d3).rotate_left(21);
+        let b4 = ($a[24] ^ d4).rotate_left(14);
+        let b5 = ($a[3] ^ d3).rotate_left(28);
+        let b6 = ($a[9] ^ d4).rotate_left(20);
+        let b7 = ($a[10] ^ d0).rotate_left(3);
+        let b8 = ($a[16] ^ d1).rotate_left(45);
+        let b9 = ($a[22] ^ d2).rotate_left(61);
+        let b10 = ($a[1] ^ d1).rotate_left(1);
+        let b11 = ($a[7] ^ d2).rotate_left(6);
+        let b12 = ($a[13] ^ d3).rotate_left(25);
+        let b13 = ($a[19] ^ d4).rotate_left(8);
+        let b14 = ($a[20] ^ d0).rotate_left(18);
+        let b15 = ($a[4] ^ d4).rotate_left(27);
+        let b16 = ($a[5] ^ d0).rotate_left(36);
+        let b17 = ($a[11] ^ d1).rotate_left(10);
+        let b18 = ($a[17] ^ d2).rotate_left(15);
+        let b19 = ($a[23] ^ d3).rotate_left(56);
+        let b20 = ($a[2] ^ d2).rotate_left(62);
+        let b21 = ($a[8] ^ d3).rotate_left(55);
+        let b22 = ($a[14] ^ d4).rotate_left(39);
+        let b23 = ($a[15] ^ d0).rotate_left(41);
+        let b24 = ($a[21] ^ d1).rotate_left(2);
+        $a[0] = b0 ^ (b1 | b2);
+        $a[1] = !(b1 ^ (b2 & !b3));
+        $a[2] = b2 ^ (b3 & b4);
+        $a[3] = b3 ^ (b4 | b0);
+        $a[4] = b4 ^ (b0 & b1);
+        $a[5] = b5 ^ (b6 | b7);
+        $a[6] = b6 ^ (b7 & b8);
+        $a[7] = !(b7 ^ (!b8 & b9));
+        $a[8] = b8 ^ (b9 | b5);
+        $a[9] = b9 ^ (b5 & b6);
+        $a[10] = b10 ^ (b11 | b12);
+        $a[11] = b11 ^ (b12 & b13);
+        $a[12] = b12 ^ (!b13 & b14);
+        $a[13] = !(b13 ^ (b14 | b10));
+        $a[14] = b14 ^ (b10 & b11);
+        $a[15] = b15 ^ (b16 & b17);
+        $a[16] = b16 ^ (b17 | b18);
+        $a[17] = !(b17 ^ (b18 & !b19));
+        $a[18] = !(b18 ^ (b19 & b15));
+        $a[19] = b19 ^ (b15 | b16);
+        $a[20] = b20 ^ (!b21 & b22);
+        $a[21] = !(b21 ^ (b22 | b23));
+        $a[22] = b22 ^ (b23 & b24);
+        $a[23] = b23 ^ (b24 | b20);
+        $a[24] = b24 ^ (b20 & b21);
+        $a[0] ^= $rc;
+    }};
+}
+
+#[inline(always)]
+fn permute(a: &mut [u64; 25], rounds: usize) {
+    debug_assert!(rounds.is_multiple_of(4) && rounds <= 24);
+    let mut s = *a;
+    for i in COMPLEMENTED {
+        s[i] = !s[i];
+    }
+    for &r in &rc[24 - rounds..] {
+        round!(s, r);
+    }
+    for i in COMPLEMENTED {
+        s[i] = !s[i];
+    }
+    *a = s;
+}
+
+/// Keccak-p[1600, rounds] with the baseline instruction set.
+pub(crate) fn keccak_p1600_lc(a: &mut [u64; 25], rounds: usize) {
+    permute(a, rounds);
+}
+
+/// Keccak-p[1600, rounds] with BMI1 and BMI2 (andn and rorx).
+///
+/// # Safety
+/// The CPU must support BMI1 and BMI2.
+#[target_feature(enable = "bmi1,bmi2")]
+pub(crate) unsafe fn keccak_p1600_bmi2(a: &mut [u64; 25], rounds: usize) {
+    permute(a, rounds);
+}
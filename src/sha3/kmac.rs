@@ -0,0 +1,151 @@
+//! KMAC128/256 and KMACXOF128/256 of SP 800-185, on the cSHAKE sponge.
+
+use super::{CShake, Hash, SHAKE};
+
+/// KMAC with the key and the customization string absorbed, N is 16 for KMAC128 and 32 for
+/// KMAC256. Reset goes back to the state after the key.
+#[derive(Clone)]
+pub struct Kmac<const N: usize> {
+    h: CShake<N>,
+}
+
+impl<const N: usize> Kmac<N> {
+    pub fn new(key: &[u8], s: &[u8]) -> Self {
+        let mut h = CShake::new(b"KMAC", s);
+        h.absorb_bytepad(&[key]);
+        h.keep();
+        Kmac { h }
+    }
+
+    pub fn reset(&mut self) {
+        self.h.reset();
+    }
+
+    pub fn write(&mut self, data: &[u8]) {
+        self.h.write(data);
+    }
+
+    /// KMAC of the data written so far, the output length is out.len().
+    pub fn sum_into(&self, out: &mut [u8]) {
+        self.h.finish(8 * out.len()).read(out);
+    }
+
+    /// KMACXOF of the data written so far, to read as much output as needed.
+    pub fn xof(&self) -> SHAKE<N> {
+        self.h.finish(0)
+    }
+}
+
+/// KMAC128 of the data with the key and the customization string s, the output length is out.len().
+pub fn kmac128(key: &[u8], data: &[u8], s: &[u8], out: &mut [u8]) {
+    let mut h = Kmac::<16>::new(key, s);
+    h.write(data);
+    h.sum_into(out);
+}
+
+/// KMAC256 of the data with the key and the customization string s, the output length is out.len().
+pub fn kmac256(key: &[u8], data: &[u8], s: &[u8], out: &mut [u8]) {
+    let mut h = Kmac::<32>::new(key, s);
+    h.write(data);
+    h.sum_into(out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_kmac128() {
+        // the KMAC and KMACXOF samples of NIST
+        let key = hex!("404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f");
+        let data: [u8; 200] = core::array::from_fn(|i| i as u8);
+        for (data, s, expect, expect_xof) in [
+            (
+                &data[..4],
+                &b""[..],
+                hex!("e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e"),
+                hex!("cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35"),
+            ),
+            (
+                &data[..4],
+                b"My Tagged Application",
+                hex!("3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5"),
+                hex!("31a44527b4ed9f5c6101d11de6d26f0620aa5c341def41299657fe9df1a3b16c"),
+            ),
+            (
+                &data[..],
+                b"My Tagged Application",
+                hex!("1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230"),
+                hex!("47026c7cd793084aa0283c253ef658490c0db61438b8326fe9bddf281b83ae0f"),
+            ),
+        ] {
+            let mut out = [0u8; 32];
+            kmac128(&key, data, s, &mut out);
+            assert_eq!(out, expect);
+
+            let mut h = Kmac::<16>::new(&key, s);
+            h.write(b"discarded");
+            h.reset();
+            h.write(data);
+            h.xof().read(&mut out);
+            assert_eq!(out, expect_xof);
+        }
+    }
+
+    #[test]
+    fn test_kmac256() {
+        let key = hex!("404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f");
+        let data: [u8; 200] = core::array::from_fn(|i| i as u8);
+        for (data, s, expect, expect_xof) in [
+            (
+                &data[..4],
+                &b"My Tagged Application"[..],
+                hex!(
+                    "20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7"
+                    "f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd"
+                ),
+                hex!(
+                    "1755133f1534752aad0748f2c706fb5c784512cab835cd15676b16c0c6647fa9"
+                    "6faa7af634a0bf8ff6df39374fa00fad9a39e322a7c92065a64eb1fb0801eb2b"
+                ),
+            ),
+            (
+                &data[..],
+                b"",
+                hex!(
+                    "75358cf39e41494e949707927cee0af20a3ff553904c86b08f21cc414bcfd691"
+                    "589d27cf5e15369cbbff8b9a4c2eb17800855d0235ff635da82533ec6b759b69"
+                ),
+                hex!(
+                    "ff7b171f1e8a2b24683eed37830ee797538ba8dc563f6da1e667391a75edc02c"
+                    "a633079f81ce12a25f45615ec89972031d18337331d24ceb8f8ca8e6a19fd98b"
+                ),
+            ),
+            (
+                &data[..],
+                b"My Tagged Application",
+                hex!(
+                    "b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d9"
+                    "70fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965"
+                ),
+                hex!(
+                    "d5be731c954ed7732846bb59dbe3a8e30f83e77a4bff4459f2f1c2b4ecebb8ce"
+                    "67ba01c62e8ab8578d2d499bd1bb276768781190020a306a97de281dcc30305d"
+                ),
+            ),
+        ] {
+            let mut out = [0u8; 64];
+            kmac256(&key, data, s, &mut out);
+            assert_eq!(out, expect);
+
+            // the XOF output does not depend on how it is read
+            let mut h = Kmac::<32>::new(&key, s);
+            h.write(data);
+            let mut xof = h.xof();
+            xof.read(&mut out[..10]);
+            xof.read(&mut out[10..]);
+            assert_eq!(out, expect_xof);
+        }
+    }
+}
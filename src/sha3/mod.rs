@@ -1,9 +1,20 @@
 pub use sponge::Digest;
 pub use shake::SHAKE;
-
+pub use cshake::CShake;
+pub use kmac::{Kmac, kmac128, kmac256};
+pub use tuplehash::{ParallelHash, TupleHash};
+pub use turboshake::{KangarooTwelve, kt128, kt256};
+pub(crate) use multilane::shake_x4;
+
+mod cpu;
+mod cshake;
 mod keccakf;
+mod kmac;
+mod multilane;
 mod sponge;
 mod shake;
+mod tuplehash;
+mod turboshake;
 
 
 pub trait XOF {
@@ -45,7 +56,9 @@ pub fn new384() -> Digest<48> {
 pub fn new256() -> Digest<32> {
     Digest::new(dsbyteSHA3)
 }
-pub fn new224() -> Digest<24> {
+/// SHA3-224 of FIPS 202, a 28-byte digest with a 144-byte rate. It returned `Digest<24>` before,
+/// with a 152-byte rate, which is not SHA3-224; callers naming the type need `Digest<28>`.
+pub fn new224() -> Digest<28> {
     Digest::new(dsbyteSHA3)
 }
 
@@ -59,6 +72,28 @@ pub fn new_shake256() -> SHAKE<32> {
     SHAKE::new(dsbyteShake)
 }
 
+// TurboSHAKE128 with the domain separation byte d, from 0x01 to 0x7f.
+pub fn new_turboshake128(d: u8) -> SHAKE<16> {
+    assert!((0x01..=0x7f).contains(&d));
+    SHAKE::turbo(d)
+}
+
+// TurboSHAKE256 with the domain separation byte d, from 0x01 to 0x7f.
+pub fn new_turboshake256(d: u8) -> SHAKE<32> {
+    assert!((0x01..=0x7f).contains(&d));
+    SHAKE::turbo(d)
+}
+
+// cSHAKE128 with the function name n and the customization string s.
+pub fn new_cshake128(n: &[u8], s: &[u8]) -> CShake<16> {
+    CShake::new(n, s)
+}
+
+// cSHAKE256 with the function name n and the customization string s.
+pub fn new_cshake256(n: &[u8], s: &[u8]) -> CShake<32> {
+    CShake::new(n, s)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -87,6 +122,24 @@ mod tests {
         assert_eq!(out, expect);
     }
 
+    #[test]
+    fn test_sha3_224() {
+        // FIPS 202 examples, a 28 bytes digest with a 144 bytes rate.
+        let mut h = new224();
+        assert_eq!(h.block_size(), 144);
+        assert_eq!(h.sum(), hex!("6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7"));
+        h.write(b"abc");
+        assert_eq!(h.sum(), hex!("e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf"));
+
+        let data: [u8; 200] = core::array::from_fn(|i| i as u8);
+        let mut h = new224();
+        h.write(&data[..100]);
+        assert_eq!(h.sum(), hex!("21649ac9d0430d74d5f5261653da46c87ac1560540b4579101083461"));
+        // a message longer than the rate
+        h.write(&data[100..]);
+        assert_eq!(h.sum(), hex!("8bcd90dbc5379549b5e78a1fbe24ae120d92caef17750461262b1e97"));
+    }
+
     #[test]
     fn test_shake() {
         let mut data = [0; 100];
@@ -0,0 +1,370 @@
+//! Independent SHAKE sponges permuted in lockstep, 2 or 4 lanes, for the samplers that expand
+//! many polynomials from short seeds.
+//!
+//! The 4 lanes are permuted with AVX2 on x86_64 when the CPU has it. Otherwise, and for 2 lanes,
+//! the portable permutation runs the same rounds on `[u64; L]` vectors, u64x4 or u64x2, whose
+//! lane-wise operations the compiler vectorizes to the SIMD registers of the target.
+
+use super::cpu::has_avx2;
+use super::keccakf::rc;
+
+// One round of the Keccak permutation of the lane vectors, with every index and rotation constant so
+// that the lanes stay in registers. The backends define xor, andn (!a & b) and rol!(a, n, 64 - n).
+macro_rules! keccak_round {
+    ($a:ident, $rc:expr) => {{
+        let c0 = xor(xor(xor($a[0], $a[5]), xor($a[10], $a[15])), $a[20]);
+        let c1 = xor(xor(xor($a[1], $a[6]), xor($a[11], $a[16])), $a[21]);
+        let c2 = xor(xor(xor($a[2], $a[7]), xor($a[12], $a[17])), $a[22]);
+        let c3 = xor(xor(xor($a[3], $a[8]), xor($a[13], $a[18])), $a[23]);
+        let c4 = xor(xor(xor($a[4], $a[9]), xor($a[14], $a[19])), $a[24]);
+        let d0 = xor(c4, rol!(c1, 1, 63));
+        let d1 = xor(c0, rol!(c2, 1, 63));
+        let d2 = xor(c1, rol!(c3, 1, 63));
+        let d3 = xor(c2, rol!(c4, 1, 63));
+        let d4 = xor(c3, rol!(c0, 1, 63));
+        let b0 = xor($a[0], d0);
+        let b1 = rol!(xor($a[6], d1), 44, 20);
+        let b2 = rol!(xor($a[12], d2), 43, 21);
+        let b3 = rol!(xor($a[18], d3), 21, 43);
+        let b4 = rol!(xor($a[24], d4), 14, 50);
+        let b5 = rol!(xor($a[3], d3), 28, 36);
+        let b6 = rol!(xor($a[9], d4), 20, 44);
+        let b7 = rol!(xor($a[10], d0), 3, 61);
+        let b8 = rol!(xor($a[16], d1), 45, 19);
+        let b9 = rol!(xor($a[22], d2), 61, 3);
+        let b10 = rol!(xor($a[1], d1), 1, 63);
+        let b11 = rol!(xor($a[7], d2), 6, 58);
+        let b12 = rol!(xor($a[13], d3), 25, 39);
+        let b13 = rol!(xor($a[19], d4), 8, 56);
+        let b14 = rol!(xor($a[20], d0), 18, 46);
+        let b15 = rol!(xor($a[4], d4), 27, 37);
+        let b16 = rol!(xor($a[5], d0), 36, 28);
+        let b17 = rol!(xor($a[11], d1), 10, 54);
+        let b18 = rol!(xor($a[17], d2), 15, 49);
+        let b19 = rol!(xor($a[23], d3), 56, 8);
+        let b20 = rol!(xor($a[2], d2), 62, 2);
+        let b21 = rol!(xor($a[8], d3), 55, 9);
+        let b22 = rol!(xor($a[14], d4), 39, 25);
+        let b23 = rol!(xor($a[15], d0), 41, 23);
+        let b24 = rol!(xor($a[21], d1), 2, 62);
+        $a[0] = xor(b0, andn(b1, b2));
+        $a[1] = xor(b1, andn(b2, b3));
+        $a[2] = xor(b2, andn(b3, b4));
+        $a[3] = xor(b3, andn(b4, b0));
+        $a[4] = xor(b4, andn(b0, b1));
+        $a[5] = xor(b5, andn(b6, b7));
+        $a[6] = xor(b6, andn(b7, b8));
+        $a[7] = xor(b7, andn(b8, b9));
+        $a[8] = xor(b8, andn(b9, b5));
+        $a[9] = xor(b9, andn(b5, b6));
+        $a[10] = xor(b10, andn(b11, b12));
+        $a[11] = xor(b11, andn(b12, b13));
+        $a[12] = xor(b12, andn(b13, b14));
+        $a[13] = xor(b13, andn(b14, b10));
+        $a[14] = xor(b14, andn(b10, b11));
+        $a[15] = xor(b15, andn(b16, b17));
+        $a[16] = xor(b16, andn(b17, b18));
+        $a[17] = xor(b17, andn(b18, b19));
+        $a[18] = xor(b18, andn(b19, b15));
+        $a[19] = xor(b19, andn(b15, b16));
+        $a[20] = xor(b20, andn(b21, b22));
+        $a[21] = xor(b21, andn(b22, b23));
+        $a[22] = xor(b22, andn(b23, b24));
+        $a[23] = xor(b23, andn(b24, b20));
+        $a[24] = xor(b24, andn(b20, b21));
+        $a[0] = xor($a[0], $rc);
+    }};
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use core::arch::x86_64::*;
+
+    use super::rc;
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    fn xor(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_xor_si256(a, b)
+    }
+
+    // !a & b
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    fn andn(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_andnot_si256(a, b)
+    }
+
+    macro_rules! rol {
+        ($a:expr, $n:literal, $m:literal) => {{
+            let a = $a;
+            _mm256_or_si256(_mm256_slli_epi64::<$n>(a), _mm256_srli_epi64::<$m>(a))
+        }};
+    }
+
+    // the Keccak permutation of 4 lanes
+    #[target_feature(enable = "avx2")]
+    pub(super) fn keccak_f1600_x4(s: &mut [[u64; 4]; 25]) {
+        let mut a = [_mm256_setzero_si256(); 25];
+        for (a, s) in a.iter_mut().zip(s.iter()) {
+            *a = unsafe { _mm256_loadu_si256(s.as_ptr().cast()) };
+        }
+        for rci in rc {
+            keccak_round!(a, _mm256_set1_epi64x(rci as i64));
+        }
+        for (a, s) in a.iter().zip(s.iter_mut()) {
+            unsafe { _mm256_storeu_si256(s.as_mut_ptr().cast(), *a) };
+        }
+    }
+}
+
+mod portable {
+    use super::rc;
+
+    #[inline(always)]
+    fn xor<const L: usize>(a: [u64; L], b: [u64; L]) -> [u64; L] {
+        core::array::from_fn(|l| a[l] ^ b[l])
+    }
+
+    // !a & b
+    #[inline(always)]
+    fn andn<const L: usize>(a: [u64; L], b: [u64; L]) -> [u64; L] {
+        core::array::from_fn(|l| !a[l] & b[l])
+    }
+
+    macro_rules! rol {
+        ($a:expr, $n:literal, $m:literal) => {{
+            let a: [u64; _] = $a;
+            core::array::from_fn(|l| a[l].rotate_left($n))
+        }};
+    }
+
+    // the Keccak permutation of L lanes
+    pub(super) fn keccak_f1600_x<const L: usize>(s: &mut [[u64; L]; 25]) {
+        let mut a = *s;
+        for rci in rc {
+            keccak_round!(a, [rci; L]);
+        }
+        *s = a;
+    }
+}
+
+fn permute<const L: usize>(a: &mut [[u64; L]; 25]) {
+    #[cfg(target_arch = "x86_64")]
+    if L == 4 && has_avx2() {
+        // SAFETY: L is 4, and AVX2 is available
+        unsafe { avx2::keccak_f1600_x4(&mut *(a as *mut [[u64; L]; 25]).cast::<[[u64; 4]; 25]>()) };
+        return;
+    }
+    portable::keccak_f1600_x(a);
+}
+
+/// L SHAKE sponges of rate 200 - 2N, N is 16 for SHAKE128 and 32 for SHAKE256, each absorbing
+/// one short message and squeezed one block at a time.
+pub(crate) struct ShakeX<const N: usize, const L: usize> {
+    a: [[u64; L]; 25],
+    squeezed: bool,
+}
+
+impl<const N: usize, const L: usize> ShakeX<N, L> {
+    const RATE: usize = 200 - 2 * N;
+
+    /// Absorb and pad the messages, all of them shorter than the rate.
+    pub(crate) fn new(messages: [&[u8]; L]) -> Self {
+        let mut a = [[0u64; L]; 25];
+        for (lane, m) in messages.iter().enumerate() {
+            assert!(m.len() < Self::RATE);
+            let mut block = [0u8; 200];
+            block[..m.len()].copy_from_slice(m);
+            block[m.len()] ^= super::dsbyteShake;
+            block[Self::RATE - 1] ^= 0x80;
+            for (a, b) in a.iter_mut().zip(block.chunks_exact(8)) {
+                a[lane] = u64::from_le_bytes(b.try_into().unwrap());
+            }
+        }
+        permute(&mut a);
+        ShakeX { a, squeezed: false }
+    }
+
+    /// The next rate bytes of every lane, blocks[i].len() is the rate.
+    pub(crate) fn squeeze(&mut self, blocks: &mut [&mut [u8]; L]) {
+        if self.squeezed {
+            permute(&mut self.a);
+        }
+        self.squeezed = true;
+        for (lane, block) in blocks.iter_mut().enumerate() {
+            for (a, b) in self.a.iter().zip(block[..Self::RATE].chunks_exact_mut(8)) {
+                b.copy_from_slice(&a[lane].to_le_bytes());
+            }
+        }
+    }
+}
+
+/// Expand count SHAKE streams four at a time. input(i, buf) writes the i-th message into buf and
+/// returns its length, the same for all the messages and at most 135 bytes, then
+/// consume(i, block) takes the blocks of the i-th stream in order until it returns false.
+pub(crate) fn shake_x4<const N: usize>(
+    count: usize,
+    input: impl FnMut(usize, &mut [u8]) -> usize,
+    consume: impl FnMut(usize, &[u8]) -> bool,
+) {
+    // without AVX2 the 2-lane permutation is cheaper than the 4-lane one, for the odd counts
+    shake_x::<N>(count, !has_avx2(), input, consume);
+}
+
+// shake_x4, the last one or two streams with 2 lanes if x2_tail
+fn shake_x<const N: usize>(
+    count: usize,
+    x2_tail: bool,
+    mut input: impl FnMut(usize, &mut [u8]) -> usize,
+    mut consume: impl FnMut(usize, &[u8]) -> bool,
+) {
+    let mut first = 0;
+    while first < count {
+        if count - first > 2 || !x2_tail {
+            expand::<N, 4>(first, count, &mut input, &mut consume);
+            first += 4;
+        } else {
+            expand::<N, 2>(first, count, &mut input, &mut consume);
+            first += 2;
+        }
+    }
+}
+
+// Expand the streams first..first + L, those past count are not consumed.
+fn expand<const N: usize, const L: usize>(
+    first: usize,
+    count: usize,
+    input: &mut impl FnMut(usize, &mut [u8]) -> usize,
+    consume: &mut impl FnMut(usize, &[u8]) -> bool,
+) {
+    let rate = 200 - 2 * N;
+    // the missing lanes of the last group repeat its last message
+    let index: [usize; L] = core::array::from_fn(|l| (first + l).min(count - 1));
+    let mut messages = [[0u8; 136]; L];
+    let mut len = 0;
+    for (m, &i) in messages.iter_mut().zip(&index) {
+        len = input(i, m);
+    }
+    let mut x = ShakeX::<N, L>::new(messages.each_ref().map(|m| &m[..len]));
+
+    let mut more: [bool; L] = core::array::from_fn(|l| first + l < count);
+    let mut blocks = [[0u8; 168]; L];
+    while more.iter().any(|&m| m) {
+        x.squeeze(&mut blocks.each_mut().map(|b| &mut b[..rate]));
+        for l in 0..L {
+            if more[l] {
+                more[l] = consume(index[l], &blocks[l][..rate]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Hash, new_shake128, new_shake256};
+    use super::*;
+
+    #[test]
+    fn test_permute() {
+        let mut a = [[0u64; 4]; 25];
+        for (i, a) in a.iter_mut().enumerate() {
+            *a = core::array::from_fn(|l| (i as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15) ^ l as u64);
+        }
+        let mut expect: [[u64; 25]; 4] = core::array::from_fn(|l| core::array::from_fn(|i| a[i][l]));
+        for e in &mut expect {
+            super::super::keccakf::keccak_f1600_generic(e);
+        }
+
+        let mut x4 = a;
+        permute(&mut x4);
+        let mut x2: [[u64; 2]; 25] = core::array::from_fn(|i| [a[i][2], a[i][3]]);
+        permute(&mut x2);
+        for i in 0..25 {
+            assert_eq!(x4[i], core::array::from_fn(|l| expect[l][i]));
+            assert_eq!(x2[i], [expect[2][i], expect[3][i]]);
+        }
+
+        // the portable permutation, which permute does not run for 4 lanes on the CPUs with AVX2
+        let mut v4 = a;
+        portable::keccak_f1600_x(&mut v4);
+        assert_eq!(v4, x4);
+        let mut v2: [[u64; 2]; 25] = core::array::from_fn(|i| [a[i][2], a[i][3]]);
+        portable::keccak_f1600_x(&mut v2);
+        assert_eq!(v2, x2);
+
+        #[cfg(target_arch = "x86_64")]
+        if has_avx2() {
+            let mut v = a;
+            unsafe { avx2::keccak_f1600_x4(&mut v) };
+            assert_eq!(v, x4);
+        }
+    }
+
+    #[test]
+    fn test_shake_x() {
+        let data: [u8; 167] = core::array::from_fn(|i| i as u8);
+        let mut blocks = [[0u8; 168 * 3]; 4];
+
+        let messages: [&[u8]; 4] = [&data[..0], &data[..34], &data[1..35], &data[..135]];
+        let mut x = ShakeX::<32, 4>::new(messages);
+        for k in 0..3 {
+            x.squeeze(&mut blocks.each_mut().map(|b| &mut b[136 * k..136 * (k + 1)]));
+        }
+        for (m, b) in messages.iter().zip(&blocks) {
+            let mut h = new_shake256();
+            h.write(m);
+            let mut expect = [0u8; 136 * 3];
+            h.read(&mut expect);
+            assert_eq!(b[..136 * 3], expect);
+        }
+
+        let messages: [&[u8]; 2] = [&data[..167], &data[3..37]];
+        let mut x = ShakeX::<16, 2>::new(messages);
+        let mut b2 = [[0u8; 168 * 2]; 2];
+        for k in 0..2 {
+            x.squeeze(&mut b2.each_mut().map(|b| &mut b[168 * k..168 * (k + 1)]));
+        }
+        for (m, b) in messages.iter().zip(&b2) {
+            let mut h = new_shake128();
+            h.write(m);
+            let mut expect = [0u8; 168 * 2];
+            h.read(&mut expect);
+            assert_eq!(*b, expect);
+        }
+    }
+
+    #[test]
+    fn test_shake_x4() {
+        // 1 to 6 streams of 1 to 6 blocks, the last ones with 2 or 4 lanes
+        for (count, x2_tail) in (1..=6).flat_map(|count| [(count, false), (count, true)]) {
+            let mut out = [[0u8; 168 * 6]; 6];
+            let mut n = [0usize; 6];
+            shake_x::<16>(
+                count,
+                x2_tail,
+                |i, m| {
+                    m[..3].copy_from_slice(&[i as u8, 1, 2]);
+                    3
+                },
+                |i, block| {
+                    out[i][168 * n[i]..168 * (n[i] + 1)].copy_from_slice(block);
+                    n[i] += 1;
+                    n[i] <= i
+                },
+            );
+            for (i, out) in out.iter().enumerate() {
+                let mut expect = [0u8; 168 * 6];
+                if i < count {
+                    assert_eq!(n[i], i + 1);
+                    let mut h = new_shake128();
+                    h.write(&[i as u8, 1, 2]);
+                    h.read(&mut expect[..168 * (i + 1)]);
+                } else {
+                    assert_eq!(n[i], 0);
+                }
+                assert_eq!(*out, expect);
+            }
+        }
+    }
+}
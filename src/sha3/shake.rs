@@ -42,6 +42,15 @@ impl<const N: usize> SHAKE<N> {
     pub fn new(ds: u8)-> Self{
         SHAKE { d: Digest::new(ds) }
     }
+
+    // TurboSHAKE, the sponge of SHAKE with 12 rounds of the permutation.
+    pub(crate) fn turbo(ds: u8) -> Self {
+        SHAKE { d: Digest::with_rounds(ds, 12) }
+    }
+
+    pub(crate) fn set_ds(&mut self, ds: u8) {
+        self.d.set_ds(ds);
+    }
 }
 
 
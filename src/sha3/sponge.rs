@@ -3,7 +3,9 @@ use core::iter::zip;
 use core::mem::transmute;
 
 use super::Hash;
-use super::keccakf::keccak_f1600;
+use super::keccakf::{keccak_f1600, keccak_p1600_12};
+#[cfg(target_endian = "big")]
+use super::keccakf::keccak_p1600_generic;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 enum SpongeDirection {
@@ -20,6 +22,7 @@ pub struct Digest<const DIGEST_SIZE: usize> {
     a: [u8; 200], // main state of the hash
     n: usize,
     ds: u8,
+    rounds: usize, // 24 for SHA-3 and SHAKE, 12 for TurboSHAKE
     state: SpongeDirection, // whether the sponge is absorbing or squeezing
 }
 
@@ -76,14 +79,25 @@ impl<const DIGEST_SIZE: usize> Hash<DIGEST_SIZE> for Digest<DIGEST_SIZE> {
 
 impl<const DIGEST_SIZE: usize> Digest<DIGEST_SIZE> {
     pub fn new(ds: u8) -> Self {
+        Self::with_rounds(ds, 24)
+    }
+
+    pub(crate) fn with_rounds(ds: u8, rounds: usize) -> Self {
         Digest {
             a: [0; 200],
             n: 0,
             ds: ds,
+            rounds,
             state: SpongeDirection::Absorbing,
         }
     }
 
+    // Change the domain separation byte, before the sponge is squeezed.
+    pub(crate) fn set_ds(&mut self, ds: u8) {
+        assert_eq!(self.state, SpongeDirection::Absorbing);
+        self.ds = ds;
+    }
+
 
     pub(crate) fn read(&mut self, out: &mut [u8]) {
         let rate = 200 - 2 * DIGEST_SIZE;
@@ -107,7 +121,11 @@ impl<const DIGEST_SIZE: usize> Digest<DIGEST_SIZE> {
 
     fn permute(&mut self) {
         #[cfg(target_endian = "little")]
-        keccak_f1600(unsafe { transmute(&mut self.a) });
+        if self.rounds == 12 {
+            keccak_p1600_12(unsafe { transmute::<&mut [u8; 200], &mut [u64; 25]>(&mut self.a) });
+        } else {
+            keccak_f1600(unsafe { transmute::<&mut [u8; 200], &mut [u64; 25]>(&mut self.a) });
+        }
 
         #[cfg(target_endian = "big")]
         {
@@ -117,7 +135,7 @@ impl<const DIGEST_SIZE: usize> Digest<DIGEST_SIZE> {
                 *ai = u64::from_le_bytes(self.a[8 * i..8 * i + 8].try_into().unwrap());
             }
 
-            keccak_f1600_generic(&mut a);
+            keccak_p1600_generic(&mut a, self.rounds);
 
             for (i, ai) in a.iter_mut().enumerate() {
                 self.a[8 * i..8 * i + 8].copy_from_slice(&u64::to_le_bytes(*ai));
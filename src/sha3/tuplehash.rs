@@ -0,0 +1,282 @@
+//! TupleHash and ParallelHash of SP 800-185, with their XOF variants.
+
+use super::cshake::encode;
+use super::{CShake, Hash, SHAKE, dsbyteShake};
+
+/// TupleHash with the customization string absorbed, N is 16 for TupleHash128 and 32 for
+/// TupleHash256.
+#[derive(Clone)]
+pub struct TupleHash<const N: usize> {
+    h: CShake<N>,
+}
+
+impl<const N: usize> TupleHash<N> {
+    pub fn new(s: &[u8]) -> Self {
+        TupleHash { h: CShake::new(b"TupleHash", s) }
+    }
+
+    pub fn reset(&mut self) {
+        self.h.reset();
+    }
+
+    /// Append one string to the tuple, so ("ab", "c") and ("a", "bc") hash differently.
+    pub fn push(&mut self, s: &[u8]) {
+        self.h.absorb_string(s);
+    }
+
+    /// TupleHash of the strings pushed so far, the output length is out.len().
+    pub fn sum_into(&self, out: &mut [u8]) {
+        self.h.finish(8 * out.len()).read(out);
+    }
+
+    /// TupleHashXOF of the strings pushed so far.
+    pub fn xof(&self) -> SHAKE<N> {
+        self.h.finish(0)
+    }
+}
+
+/// ParallelHash with the block size and the customization string absorbed, N is 16 for
+/// ParallelHash128 and 32 for ParallelHash256.
+///
+/// The blocks are hashed one after the other as the data is written, so no block is buffered.
+#[derive(Clone)]
+pub struct ParallelHash<const N: usize> {
+    h: CShake<N>,
+    block: SHAKE<N>,
+    block_size: usize,
+    // the bytes written into the current block
+    n: usize,
+    // the complete blocks
+    blocks: u64,
+}
+
+impl<const N: usize> ParallelHash<N> {
+    /// Panics if block_size is 0.
+    pub fn new(block_size: usize, s: &[u8]) -> Self {
+        assert!(block_size > 0);
+        let mut h = CShake::new(b"ParallelHash", s);
+        let mut buf = [0u8; 9];
+        h.write(encode(block_size as u64, true, &mut buf));
+        h.keep();
+        ParallelHash { h, block: SHAKE::new(dsbyteShake), block_size, n: 0, blocks: 0 }
+    }
+
+    pub fn reset(&mut self) {
+        self.h.reset();
+        self.block.reset();
+        self.n = 0;
+        self.blocks = 0;
+    }
+
+    pub fn write(&mut self, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            let k = (self.block_size - self.n).min(data.len());
+            self.block.write(&data[..k]);
+            self.n += k;
+            data = &data[k..];
+            if self.n == self.block_size {
+                self.end_block();
+            }
+        }
+    }
+
+    // absorb the 2N bytes SHAKE output of the current block
+    fn end_block(&mut self) {
+        let mut z = [0u8; 64];
+        self.block.read(&mut z[..2 * N]);
+        self.h.write(&z[..2 * N]);
+        self.block.reset();
+        self.n = 0;
+        self.blocks += 1;
+    }
+
+    fn finish(&self, bits: usize) -> SHAKE<N> {
+        let mut p = self.clone();
+        if p.n > 0 {
+            p.end_block();
+        }
+        let mut buf = [0u8; 9];
+        p.h.write(encode(p.blocks, false, &mut buf));
+        p.h.finish(bits)
+    }
+
+    /// ParallelHash of the data written so far, the output length is out.len().
+    pub fn sum_into(&self, out: &mut [u8]) {
+        self.finish(8 * out.len()).read(out);
+    }
+
+    /// ParallelHashXOF of the data written so far.
+    pub fn xof(&self) -> SHAKE<N> {
+        self.finish(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_tuple_hash() {
+        // the TupleHash and TupleHashXOF samples of NIST
+        let tuple = [&hex!("000102")[..], &hex!("101112131415"), &hex!("202122232425262728")];
+        for (n, s, expect, expect_xof) in [
+            (
+                2,
+                &b""[..],
+                hex!("c5d8786c1afb9b82111ab34b65b2c0048fa64e6d48e263264ce1707d3ffc8ed1"),
+                hex!("2f103cd7c32320353495c68de1a8129245c6325f6f2a3d608d92179c96e68488"),
+            ),
+            (
+                2,
+                b"My Tuple App",
+                hex!("75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb"),
+                hex!("3fc8ad69453128292859a18b6c67d7ad85f01b32815e22ce839c49ec374e9b9a"),
+            ),
+            (
+                3,
+                b"My Tuple App",
+                hex!("e60f202c89a2631eda8d4c588ca5fd07f39e5151998deccf973adb3804bb6e84"),
+                hex!("900fe16cad098d28e74d632ed852f99daab7f7df4d99e775657885b4bf76d6f8"),
+            ),
+        ] {
+            let mut h = TupleHash::<16>::new(s);
+            for x in &tuple[..n] {
+                h.push(x);
+            }
+            let mut out = [0u8; 32];
+            h.sum_into(&mut out);
+            assert_eq!(out, expect);
+            h.xof().read(&mut out);
+            assert_eq!(out, expect_xof);
+        }
+
+        for (n, s, expect, expect_xof) in [
+            (
+                2,
+                &b""[..],
+                hex!(
+                    "cfb7058caca5e668f81a12a20a2195ce97a925f1dba3e7449a56f82201ec6073"
+                    "11ac2696b1ab5ea2352df1423bde7bd4bb78c9aed1a853c78672f9eb23bbe194"
+                ),
+                hex!(
+                    "03ded4610ed6450a1e3f8bc44951d14fbc384ab0efe57b000df6b6df5aae7cd5"
+                    "68e77377daf13f37ec75cf5fc598b6841d51dd207c991cd45d210ba60ac52eb9"
+                ),
+            ),
+            (
+                2,
+                b"My Tuple App",
+                hex!(
+                    "147c2191d5ed7efd98dbd96d7ab5a11692576f5fe2a5065f3e33de6bba9f3aa1"
+                    "c4e9a068a289c61c95aab30aee1e410b0b607de3620e24a4e3bf9852a1d4367e"
+                ),
+                hex!(
+                    "6483cb3c9952eb20e830af4785851fc597ee3bf93bb7602c0ef6a65d741aeca7"
+                    "e63c3b128981aa05c6d27438c79d2754bb1b7191f125d6620fca12ce658b2442"
+                ),
+            ),
+            (
+                3,
+                b"My Tuple App",
+                hex!(
+                    "45000be63f9b6bfd89f54717670f69a9bc763591a4f05c50d68891a744bcc6e7"
+                    "d6d5b5e82c018da999ed35b0bb49c9678e526abd8e85c13ed254021db9e790ce"
+                ),
+                hex!(
+                    "0c59b11464f2336c34663ed51b2b950bec743610856f36c28d1d088d8a244628"
+                    "4dd09830a6a178dc752376199fae935d86cfdee5913d4922dfd369b66a53c897"
+                ),
+            ),
+        ] {
+            let mut h = TupleHash::<32>::new(s);
+            h.push(b"discarded");
+            h.reset();
+            for x in &tuple[..n] {
+                h.push(x);
+            }
+            let mut out = [0u8; 64];
+            h.sum_into(&mut out);
+            assert_eq!(out, expect);
+            h.xof().read(&mut out);
+            assert_eq!(out, expect_xof);
+        }
+    }
+
+    #[test]
+    fn test_parallel_hash() {
+        // the ParallelHash and ParallelHashXOF samples of NIST
+        let data = hex!("000102030405060710111213141516172021222324252627");
+        for (s, expect, expect_xof) in [
+            (
+                &b""[..],
+                hex!("ba8dc1d1d979331d3f813603c67f72609ab5e44b94a0b8f9af46514454a2b4f5"),
+                hex!("fe47d661e49ffe5b7d999922c062356750caf552985b8e8ce6667f2727c3c8d3"),
+            ),
+            (
+                b"Parallel Data",
+                hex!("fc484dcb3f84dceedc353438151bee58157d6efed0445a81f165e495795b7206"),
+                hex!("ea2a793140820f7a128b8eb70a9439f93257c6e6e79b4a540d291d6dae7098d7"),
+            ),
+        ] {
+            let mut h = ParallelHash::<16>::new(8, s);
+            h.write(&data);
+            let mut out = [0u8; 32];
+            h.sum_into(&mut out);
+            assert_eq!(out, expect);
+            h.xof().read(&mut out);
+            assert_eq!(out, expect_xof);
+        }
+
+        // a partial last block, and no block at all
+        for (data, expect) in [
+            (&data[..20], hex!("a725ed02aa4acb2041624b6222cd487942e03e63836b0face5dec556b9121297")),
+            (&[], hex!("96427c30224408859f95e89e4fa84e1c7a1478dbf2008ac982ce61a77f37a272")),
+        ] {
+            let mut h = ParallelHash::<16>::new(8, b"");
+            h.write(data);
+            let mut out = [0u8; 32];
+            h.sum_into(&mut out);
+            assert_eq!(out, expect);
+        }
+
+        for (s, expect, expect_xof) in [
+            (
+                &b""[..],
+                hex!(
+                    "bc1ef124da34495e948ead207dd9842235da432d2bbc54b4c110e64c45110553"
+                    "1b7f2a3e0ce055c02805e7c2de1fb746af97a1dd01f43b824e31b87612410429"
+                ),
+                hex!(
+                    "c10a052722614684144d28474850b410757e3cba87651ba167a5cbddff7f4666"
+                    "75fbf84bcae7378ac444be681d729499afca667fb879348bfdda427863c82f1c"
+                ),
+            ),
+            (
+                b"Parallel Data",
+                hex!(
+                    "cdf15289b54f6212b4bc270528b49526006dd9b54e2b6add1ef6900dda3963bb"
+                    "33a72491f236969ca8afaea29c682d47a393c065b38e29fae651a2091c833110"
+                ),
+                hex!(
+                    "538e105f1a22f44ed2f5cc1674fbd40be803d9c99bf5f8d90a2c8193f3fe6ea7"
+                    "68e5c1a20987e2c9c65febed03887a51d35624ed12377594b5585541dc377efc"
+                ),
+            ),
+        ] {
+            // written in pieces across the block boundaries
+            let mut h = ParallelHash::<32>::new(8, s);
+            h.write(b"discarded");
+            h.reset();
+            for chunk in data.chunks(5) {
+                h.write(chunk);
+            }
+            let mut out = [0u8; 64];
+            h.sum_into(&mut out);
+            assert_eq!(out, expect);
+            h.xof().read(&mut out);
+            assert_eq!(out, expect_xof);
+        }
+    }
+}
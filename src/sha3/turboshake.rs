@@ -0,0 +1,483 @@
+//! KangarooTwelve KT128 and KT256 of RFC 9861, tree hashing on TurboSHAKE128/256.
+
+use super::{Hash, SHAKE};
+
+const CHUNK_SIZE: usize = 8192;
+
+// the domain separation bytes of the single node, the leaves and the final node
+const DS_SINGLE: u8 = 0x07;
+const DS_LEAF: u8 = 0x0b;
+const DS_FINAL: u8 = 0x06;
+
+// length_encode(x), the big endian bytes without leading zeros then their number
+fn length_encode(x: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = 8 - x.leading_zeros() as usize / 8;
+    buf[..n].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+    buf[n] = n as u8;
+    &buf[..n + 1]
+}
+
+/// KangarooTwelve, N is 16 for KT128 and 32 for KT256.
+///
+/// The first chunk goes straight into the final node and every other chunk is hashed into its
+/// chaining value once complete, so nothing is buffered. Whether the input fits a single node is
+/// only decided by the domain byte when finishing.
+#[derive(Clone)]
+pub struct KangarooTwelve<const N: usize> {
+    node: SHAKE<N>,
+    leaf: SHAKE<N>,
+    // the bytes written, with the customization string when finishing
+    len: u64,
+    // the complete leaves
+    leaves: u64,
+}
+
+impl<const N: usize> Default for KangarooTwelve<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> KangarooTwelve<N> {
+    pub fn new() -> Self {
+        KangarooTwelve { node: SHAKE::turbo(DS_FINAL), leaf: SHAKE::turbo(DS_LEAF), len: 0, leaves: 0 }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn write(&mut self, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            if self.len < CHUNK_SIZE as u64 {
+                let k = (CHUNK_SIZE - self.len as usize).min(data.len());
+                self.node.write(&data[..k]);
+                self.len += k as u64;
+                data = &data[k..];
+                continue;
+            }
+            if self.len == CHUNK_SIZE as u64 {
+                self.node.write(&[0x03, 0, 0, 0, 0, 0, 0, 0]);
+            }
+            let n = ((self.len - CHUNK_SIZE as u64) % CHUNK_SIZE as u64) as usize;
+            let k = (CHUNK_SIZE - n).min(data.len());
+            self.leaf.write(&data[..k]);
+            self.len += k as u64;
+            data = &data[k..];
+            if n + k == CHUNK_SIZE {
+                self.end_leaf();
+            }
+        }
+    }
+
+    // absorb the 2N bytes chaining value of the current leaf
+    fn end_leaf(&mut self) {
+        let mut cv = [0u8; 64];
+        self.leaf.read(&mut cv[..2 * N]);
+        self.node.write(&cv[..2 * N]);
+        self.leaf.reset();
+        self.leaves += 1;
+    }
+
+    /// The output of the data written so far with the customization string c, to read as much
+    /// as needed.
+    pub fn xof(&self, c: &[u8]) -> SHAKE<N> {
+        let mut k = self.clone();
+        let mut buf = [0u8; 9];
+        k.write(c);
+        k.write(length_encode(c.len() as u64, &mut buf));
+        if k.len <= CHUNK_SIZE as u64 {
+            k.node.set_ds(DS_SINGLE);
+            return k.node;
+        }
+        if !(k.len - CHUNK_SIZE as u64).is_multiple_of(CHUNK_SIZE as u64) {
+            k.end_leaf();
+        }
+        k.node.write(length_encode(k.leaves, &mut buf));
+        k.node.write(&[0xff, 0xff]);
+        k.node
+    }
+
+    /// The output of the data written so far with the customization string c, the output length
+    /// is out.len().
+    pub fn sum_into(&self, c: &[u8], out: &mut [u8]) {
+        self.xof(c).read(out);
+    }
+}
+
+/// KT128 of the message m with the customization string c, the output length is out.len().
+pub fn kt128(m: &[u8], c: &[u8], out: &mut [u8]) {
+    let mut h = KangarooTwelve::<16>::new();
+    h.write(m);
+    h.sum_into(c, out);
+}
+
+/// KT256 of the message m with the customization string c, the output length is out.len().
+pub fn kt256(m: &[u8], c: &[u8], out: &mut [u8]) {
+    let mut h = KangarooTwelve::<32>::new();
+    h.write(m);
+    h.sum_into(c, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{new_turboshake128, new_turboshake256};
+    use super::*;
+    use hex_literal::hex;
+
+    // write ptn(n) of RFC 9861, the bytes 0 to 250 repeated, in pieces of different sizes
+    fn ptn(n: usize, mut write: impl FnMut(&[u8])) {
+        let pattern: [u8; 251 * 8] = core::array::from_fn(|i| (i % 251) as u8);
+        let mut n = n;
+        let mut piece = 251;
+        while n > 0 {
+            let k = piece.min(n);
+            write(&pattern[..k]);
+            n -= k;
+            piece = if piece == pattern.len() { 251 } else { piece + 251 };
+        }
+    }
+
+    #[test]
+    fn test_length_encode() {
+        let mut buf = [0u8; 9];
+        assert_eq!(length_encode(0, &mut buf), [0]);
+        assert_eq!(length_encode(12, &mut buf), [12, 1]);
+        assert_eq!(length_encode(65538, &mut buf), [1, 0, 2, 3]);
+    }
+
+    #[test]
+    fn test_turboshake128() {
+        // the TurboSHAKE128 vectors of RFC 9861
+        let mut h = new_turboshake128(0x1f);
+        let mut out = [0u8; 32];
+        h.sum_into(&mut out);
+        assert_eq!(out, hex!("1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c"));
+        let mut skip = [0u8; 10000];
+        h.read(&mut skip);
+        h.read(&mut out);
+        assert_eq!(out, hex!("a3b9b0385900ce761f22aed548e754da10a5242d62e8c658e3f3a923a7555607"));
+
+        for (n, expect) in [
+            (17, hex!("9c97d036a3bac819db70ede0ca554ec6e4c2a1a4ffbfd9ec269ca6a111161233")),
+            (17 * 17, hex!("96c77c279e0126f7fc07c9b07f5cdae1e0be60bdbe10620040e75d7223a624d2")),
+            (17 * 17 * 17, hex!("d4976eb56bcf118520582b709f73e1d6853e001fdaf80e1b13e0d0599d5fb372")),
+        ] {
+            h.reset();
+            ptn(n, |p| h.write(p));
+            h.sum_into(&mut out);
+            assert_eq!(out, expect);
+        }
+
+        for (m, d, expect) in [
+            (&hex!("ffffff")[..], 0x01, hex!("bf323f940494e88ee1c540fe660be8a0c93f43d15ec006998462fa994eed5dab")),
+            (&hex!("ff"), 0x06, hex!("8ec9c66465ed0d4a6c35d13506718d687a25cb05c74cca1e42501abd83874a67")),
+            (
+                &hex!("ffffffffffffff"),
+                0x0b,
+                hex!("8deeaa1aec47ccee569f659c21dfa8e112db3cee37b18178b2acd805b799cc37"),
+            ),
+        ] {
+            let mut h = new_turboshake128(d);
+            h.write(m);
+            h.read(&mut out);
+            assert_eq!(out, expect);
+        }
+    }
+
+    #[test]
+    fn test_turboshake256() {
+        let mut h = new_turboshake256(0x1f);
+        let mut out = [0u8; 64];
+        h.sum_into(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "367a329dafea871c7802ec67f905ae13c57695dc2c6663c61035f59a18f8e7db"
+                "11edc0e12e91ea60eb6b32df06dd7f002fbafabb6e13ec1cc20d995547600db0"
+            )
+        );
+        let mut skip = [0u8; 10000];
+        h.read(&mut skip);
+        h.read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "abefa11630c661269249742685ec082f207265dccf2f43534e9c61ba0c9d1d75"
+                "34ced68cfc7bb16b5db206e4583b904ccd2f0608616a58104d6f127c7bceed93"
+            )
+        );
+
+        for (n, expect) in [
+            (
+                17,
+                hex!(
+                    "b3bab0300e6a191fbe6137939835923578794ea54843f5011090fa2f3780a9e5"
+                    "cb22c59d78b40a0fbff9e672c0fbe0970bd2c845091c6044d687054da5d8e9c7"
+                ),
+            ),
+            (
+                17 * 17,
+                hex!(
+                    "66b810db8e90780424c0847372fdc95710882fde31c6df75beb9d4cd9305cfca"
+                    "e35e7b83e8b7e6eb4b78605880116316fe2c078a09b94ad7b8213c0a738b65c0"
+                ),
+            ),
+            (
+                17 * 17 * 17,
+                hex!(
+                    "c74ebc919a5b3b0dd1228185ba02d29ef442d69d3d4276a93efe0bf9a16a7dc0"
+                    "cd4eabadab8cd7a5edd96695f5d360abe09e2c6511a3ec397da3b76b9e1674fb"
+                ),
+            ),
+        ] {
+            h.reset();
+            ptn(n, |p| h.write(p));
+            h.sum_into(&mut out);
+            assert_eq!(out, expect);
+        }
+
+        for (m, d, expect) in [
+            (
+                &hex!("ffffff")[..],
+                0x01,
+                hex!(
+                    "d21c6fbbf587fa2282f29aea620175fb0257413af78a0b1b2a87419ce031d933"
+                    "ae7a4d383327a8a17641a34f8a1d1003ad7da6b72dba84bb62fef28f62f12424"
+                ),
+            ),
+            (
+                &hex!("ff"),
+                0x06,
+                hex!(
+                    "738d7b4e37d18b7f22ad1b5313e357e3dd7d07056a26a303c433fa3533455280"
+                    "f4f5a7d4f700efb437fe6d281405e07be32a0a972e22e63adc1b090daefe004b"
+                ),
+            ),
+            (
+                &hex!("ffffffffffffff"),
+                0x0b,
+                hex!(
+                    "bb36764951ec97e9d85f7ee9a67a7718fc005cf42556be79ce12c0bde50e5736"
+                    "d6632b0d0dfb202d1bbb8ffe3dd74cb00834fa756cb03471bab13a1e2c16b3c0"
+                ),
+            ),
+        ] {
+            let mut h = new_turboshake256(d);
+            h.write(m);
+            h.read(&mut out);
+            assert_eq!(out, expect);
+        }
+    }
+
+    #[test]
+    fn test_kt128() {
+        // the KT128 vectors of RFC 9861, the sizes around the chunk boundary and the
+        // customization string completing the first chunk
+        let mut out = [0u8; 32];
+        kt128(&[], &[], &mut out);
+        assert_eq!(out, hex!("1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"));
+        let mut xof = KangarooTwelve::<16>::new().xof(&[]);
+        let mut skip = [0u8; 10000];
+        xof.read(&mut skip);
+        xof.read(&mut out);
+        assert_eq!(out, hex!("e8dc563642f7228c84684c898405d3a834799158c079b12880277a1d28e2ff6d"));
+
+        let mut h = KangarooTwelve::<16>::new();
+        for (n, expect) in [
+            (17, hex!("6bf75fa2239198db4772e36478f8e19b0f371205f6a9a93a273f51df37122888")),
+            (17 * 17, hex!("0c315ebcdedbf61426de7dcf8fb725d1e74675d7f5327a5067f367b108ecb67c")),
+            (17 * 17 * 17, hex!("cb552e2ec77d9910701d578b457ddf772c12e322e4ee7fe417f92c758f0d59d0")),
+            (17 * 17 * 17 * 17, hex!("8701045e22205345ff4dda05555cbb5c3af1a771c2b89baef37db43d9998b9fe")),
+            (17 * 17 * 17 * 17 * 17, hex!("844d610933b1b9963cbdeb5ae3b6b05cc7cbd67ceedf883eb678a0a8e0371682")),
+        ] {
+            h.reset();
+            ptn(n, |p| h.write(p));
+            h.sum_into(&[], &mut out);
+            assert_eq!(out, expect);
+        }
+
+        // ptn(n) is the beginning of ptn(m) for n < m
+        let mut c = [0u8; 41 * 41 * 41];
+        let mut i = 0;
+        ptn(c.len(), |p| {
+            c[i..i + p.len()].copy_from_slice(p);
+            i += p.len();
+        });
+        for (m, n, expect) in [
+            (0, 41, hex!("76f06e60fba37414e0dc56d9d1e5d03b2d38c672b70c8c51d2e00a4fa959f1aa")),
+            (1, 41, hex!("d848c5068ced736f4462159b9867fd4c20b808acc3d5bc48e0b06ba0a3762ec4")),
+            (3, 41 * 41, hex!("c389e5009ae57120854c2e8c64670ac01358cf4c1baf89447a724234dc7ced74")),
+            (7, 41 * 41 * 41, hex!("75d2f86a2e644566726b4fbcfc5657b9dbcf070c7b0dca06450ab291d7443bcf")),
+        ] {
+            kt128(&[0xff; 7][..m], &c[..n], &mut out);
+            assert_eq!(out, expect);
+        }
+
+        for (m, n, expect) in [
+            (8191, 0, hex!("1b577636f723643e990cc7d6a659837436fd6a103626600eb8301cd1dbe553d6")),
+            (8192, 0, hex!("48f256f6772f9edfb6a8b661ec92dc93b95ebd05a08a17b39ae3490870c926c3")),
+            (8192, 8189, hex!("3ed12f70fb05ddb58689510ab3e4d23c6c6033849aa01e1d8c220a297fedcd0b")),
+            (8192, 8190, hex!("6a7c1b6a5cd0d8c9ca943a4a216cc64604559a2ea45f78570a15253d67ba00ae")),
+        ] {
+            h.reset();
+            ptn(m, |p| h.write(p));
+            h.sum_into(&c[..n], &mut out);
+            assert_eq!(out, expect);
+        }
+    }
+
+    #[test]
+    fn test_kt256() {
+        // the KT256 vectors of RFC 9861
+        let mut out = [0u8; 64];
+        kt256(&[], &[], &mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "b23d2e9cea9f4904e02bec06817fc10ce38ce8e93ef4c89e6537076af8646404"
+                "e3e8b68107b8833a5d30490aa33482353fd4adc7148ecb782855003aaebde4a9"
+            )
+        );
+        let mut xof = KangarooTwelve::<32>::new().xof(&[]);
+        let mut skip = [0u8; 10000];
+        xof.read(&mut skip);
+        xof.read(&mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "ad4a1d718cf950506709a4c33396139b4449041fc79a05d68da35f1e453522e0"
+                "56c64fe94958e7085f2964888259b9932752f3ccd855288efee5fcbb8b563069"
+            )
+        );
+
+        let mut h = KangarooTwelve::<32>::new();
+        for (n, expect) in [
+            (
+                17,
+                hex!(
+                    "1ba3c02b1fc514474f06c8979978a9056c8483f4a1b63d0dccefe3a28a2f323e"
+                    "1cdcca40ebf006ac76ef0397152346837b1277d3e7faa9c9653b19075098527b"
+                ),
+            ),
+            (
+                17 * 17,
+                hex!(
+                    "de8ccbc63e0f133ebb4416814d4c66f691bbf8b6a61ec0a7700f836b086cb029"
+                    "d54f12ac7159472c72db118c35b4e6aa213c6562caaa9dcc518959e69b10f3ba"
+                ),
+            ),
+            (
+                17 * 17 * 17,
+                hex!(
+                    "647efb49fe9d717500171b41e7f11bd491544443209997ce1c2530d15eb1ffbb"
+                    "598935ef954528ffc152b1e4d731ee2683680674365cd191d562bae753b84aa5"
+                ),
+            ),
+            (
+                17 * 17 * 17 * 17,
+                hex!(
+                    "b06275d284cd1cf205bcbe57dccd3ec1ff6686e3ed15776383e1f2fa3c6ac8f0"
+                    "8bf8a162829db1a44b2a43ff83dd89c3cf1ceb61ede659766d5ccf817a62ba8d"
+                ),
+            ),
+            (
+                17 * 17 * 17 * 17 * 17,
+                hex!(
+                    "9473831d76a4c7bf77ace45b59f1458b1673d64bcd877a7c66b2664aa6dd149e"
+                    "60eab71b5c2bab858c074ded81ddce2b4022b5215935c0d4d19bf511aeeb0772"
+                ),
+            ),
+        ] {
+            h.reset();
+            ptn(n, |p| h.write(p));
+            h.sum_into(&[], &mut out);
+            assert_eq!(out, expect);
+        }
+
+        let mut c = [0u8; 41 * 41 * 41];
+        let mut i = 0;
+        ptn(c.len(), |p| {
+            c[i..i + p.len()].copy_from_slice(p);
+            i += p.len();
+        });
+        for (m, n, expect) in [
+            (
+                0,
+                41,
+                hex!(
+                    "81ae09c22e6d0c37b49f2c514140f9e3b389844a3a5103f16f0baed145719b77"
+                    "d1d95821c266fb8ef1690a75c7457d245feafe6942cfd59307a77b20c517a5e9"
+                ),
+            ),
+            (
+                1,
+                41,
+                hex!(
+                    "47ef96dd616f200937aa7847e34ec2feae8087e3761dc0f8c1a154f51dc9ccf8"
+                    "45d7adbce57ff64b639722c6a1672e3bf5372d87e00aff89be97240756998853"
+                ),
+            ),
+            (
+                3,
+                41 * 41,
+                hex!(
+                    "3b48667a5051c5966c53c5d42b95de451e05584e7806e2fb765eda959074172c"
+                    "b438a9e91dde337c98e9c41bed94c4e0aef431d0b64ef2324f7932caa6f54969"
+                ),
+            ),
+            (
+                7,
+                41 * 41 * 41,
+                hex!(
+                    "e0911cc00025e1540831e266d94add9b98712142b80d2629e643aac4efaf5a3a"
+                    "30a88cbf4ac2a91a2432743054fbcc9897670e86ba8cec2fc2ace9c966369724"
+                ),
+            ),
+        ] {
+            kt256(&[0xff; 7][..m], &c[..n], &mut out);
+            assert_eq!(out, expect);
+        }
+
+        for (m, n, expect) in [
+            (
+                8191,
+                0,
+                hex!(
+                    "3081434d93a4108d8d8a3305b89682cebedc7ca4ea8a3ce869fbb73cbe4a58ee"
+                    "f6f24de38ffc170514c70e7ab2d01f03812616e863d769afb3753193ba045b20"
+                ),
+            ),
+            (
+                8192,
+                0,
+                hex!(
+                    "c6ee8e2ad3200c018ac87aaa031cdac22121b412d07dc6e0dccbb53423747e9a"
+                    "1c18834d99df596cf0cf4b8dfafb7bf02d139d0c9035725adc1a01b7230a41fa"
+                ),
+            ),
+            (
+                8192,
+                8189,
+                hex!(
+                    "74e47879f10a9c5d11bd2da7e194fe57e86378bf3c3f7448eff3c576a0f18c5c"
+                    "aae0999979512090a7f348af4260d4de3c37f1ecaf8d2c2c96c1d16c64b12496"
+                ),
+            ),
+            (
+                8192,
+                8190,
+                hex!(
+                    "f4b5908b929ffe01e0f79ec2f21243d41a396b2e7303a6af1d6399cd6c7a0a2d"
+                    "d7c4f607e8277f9c9b1cb4ab9ddc59d4b92d1fc7558441f1832c3279a4241b8b"
+                ),
+            ),
+        ] {
+            h.reset();
+            ptn(m, |p| h.write(p));
+            h.sum_into(&c[..n], &mut out);
+            assert_eq!(out, expect);
+        }
+    }
+}
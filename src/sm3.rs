@@ -0,0 +1,118 @@
+//! SM3 (GB/T 32905-2016), with the [`Hash`] trait of the SHA-3 functions.
+
+use crate::sha2::update;
+use crate::sha3::Hash;
+
+const IV: [u32; 8] = [0x7380166f, 0x4914b2b9, 0x172442d7, 0xda8a0600, 0xa96f30bc, 0x163138aa, 0xe38dee4d, 0xb0fb0e4e];
+
+#[derive(Debug, Clone)]
+pub struct Sm3 {
+    h: [u32; 8],
+    block: [u8; 64],
+    n: usize,
+    len: u64,
+}
+
+pub fn new() -> Sm3 {
+    Sm3 { h: IV, block: [0; 64], n: 0, len: 0 }
+}
+
+fn p0(x: u32) -> u32 {
+    x ^ x.rotate_left(9) ^ x.rotate_left(17)
+}
+
+fn p1(x: u32) -> u32 {
+    x ^ x.rotate_left(15) ^ x.rotate_left(23)
+}
+
+fn compress(v: &mut [u32; 8], block: &[u8; 64]) {
+    let mut w = [0u32; 68];
+    for (w, b) in w.iter_mut().zip(block.chunks_exact(4)) {
+        *w = u32::from_be_bytes(b.try_into().unwrap());
+    }
+    for j in 16..68 {
+        w[j] = p1(w[j - 16] ^ w[j - 9] ^ w[j - 3].rotate_left(15)) ^ w[j - 13].rotate_left(7) ^ w[j - 6];
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *v;
+    for j in 0..64 {
+        let t: u32 = if j < 16 { 0x79cc4519 } else { 0x7a879d8a };
+        let a12 = a.rotate_left(12);
+        let ss1 = a12.wrapping_add(e).wrapping_add(t.rotate_left(j as u32 % 32)).rotate_left(7);
+        let ss2 = ss1 ^ a12;
+        let (ff, gg) = if j < 16 { (a ^ b ^ c, e ^ f ^ g) } else { ((a & b) | (a & c) | (b & c), (e & f) | (!e & g)) };
+        let tt1 = ff.wrapping_add(d).wrapping_add(ss2).wrapping_add(w[j] ^ w[j + 4]);
+        let tt2 = gg.wrapping_add(h).wrapping_add(ss1).wrapping_add(w[j]);
+        (d, c, b, a) = (c, b.rotate_left(9), a, tt1);
+        (h, g, f, e) = (g, f.rotate_left(19), e, p0(tt2));
+    }
+    for (v, x) in v.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *v ^= x;
+    }
+}
+
+impl Hash<32> for Sm3 {
+    fn reset(&mut self) {
+        *self = new();
+    }
+
+    fn write(&mut self, data: &[u8]) {
+        self.len += data.len() as u64;
+        update(&mut self.block, &mut self.n, data, |b| compress(&mut self.h, b));
+    }
+
+    fn sum_into(&self, digest: &mut [u8]) {
+        let mut d = self.clone();
+        let bits = d.len * 8;
+        let pad = if d.n < 56 { 56 - d.n } else { 120 - d.n };
+        let mut padding = [0; 64 + 8];
+        padding[0] = 0x80;
+        padding[pad..pad + 8].copy_from_slice(&bits.to_be_bytes());
+        d.write(&padding[..pad + 8]);
+        for (out, h) in digest.chunks_mut(4).zip(d.h) {
+            out.copy_from_slice(&h.to_be_bytes()[..out.len()]);
+        }
+    }
+
+    fn block_size(&self) -> usize {
+        64
+    }
+
+    fn size(&self) -> usize {
+        32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_sm3() {
+        // the examples of GB/T 32905 and the empty message
+        for (data, expect) in [
+            (&b"abc"[..], hex!("66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0")),
+            (
+                b"abcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcd",
+                hex!("debe9ff92275b8a138604889c18e5a4d6fdb70e5387e5765293dcba39c0c5732"),
+            ),
+            (b"", hex!("1ab21d8355cfa17f8e61194831e81a8f22bec8c728fefb747ed035eb5082aa2b")),
+        ] {
+            let mut h = new();
+            h.write(data);
+            assert_eq!(h.sum(), expect);
+        }
+
+        // one million 'a' written in pieces across the block boundaries
+        let mut h = new();
+        let a = [b'a'; 1000];
+        for i in 0..1000 {
+            h.write(&a[..i % 100]);
+            h.write(&a[i % 100..]);
+        }
+        assert_eq!(h.sum(), hex!("c8aaf89429554029e231941a2acc0ad61ff2a5acd8fadd25847a3a732b3b02c3"));
+        h.reset();
+        assert_eq!(h.sum(), hex!("1ab21d8355cfa17f8e61194831e81a8f22bec8c728fefb747ed035eb5082aa2b"));
+    }
+}
@@ -0,0 +1,431 @@
+//! The TLS 1.3 key shares of the hybrid and pure ML-KEM groups, without allocation.
+//!
+//! The hybrid groups of draft-ietf-tls-ecdhe-mlkem and MLKEM1024 of draft-ietf-tls-mlkem, with
+//! their byte-level formats:
+//!
+//! | group             | client share       | server share       | shared secret      |
+//! |-------------------|--------------------|--------------------|--------------------|
+//! | SecP256r1MLKEM768 | P-256 point \|\| ek | P-256 point \|\| ct | ECDH \|\| ML-KEM    |
+//! | X25519MLKEM768    | ek \|\| X25519 key  | ct \|\| X25519 key  | ML-KEM \|\| ECDH    |
+//! | MLKEM1024         | ek                 | ct                 | ML-KEM             |
+//!
+//! The P-256 points are uncompressed. The client generates a [`ClientKeyShare`], sends
+//! [`ClientKeyShare::key_share`], the server answers with [`respond`] and the client derives the
+//! same secret by [`ClientKeyShare::finish`]. The shares are rejected when their length is wrong,
+//! the ML-KEM encapsulation key fails the modulus check, the P-256 point is not on the curve or
+//! the X25519 shared secret is all zero.
+
+use rand::{CryptoRng, Rng};
+use thiserror;
+
+use crate::mlkem::{mlkem1024, mlkem768};
+use crate::p256;
+use crate::x25519::{x25519, x25519_base};
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("key share of wrong length")]
+    InvalidLength,
+
+    #[error("ML-KEM encapsulation key check failed")]
+    InvalidEncapKey,
+
+    #[error("invalid ECDH key share")]
+    InvalidEcdhShare,
+
+    #[error("invalid ECDH private key")]
+    InvalidPrivateKey,
+
+    #[error("output buffer too small")]
+    BufferTooSmall,
+}
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// The TLS NamedGroup, the discriminant is the codepoint.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NamedGroup {
+    SecP256r1MlKem768 = 0x11eb,
+    X25519MlKem768 = 0x11ec,
+    MlKem1024 = 0x0202,
+}
+
+/// cbindgen:ignore
+impl NamedGroup {
+    pub const ALL: [NamedGroup; 3] = [NamedGroup::SecP256r1MlKem768, NamedGroup::X25519MlKem768, NamedGroup::MlKem1024];
+
+    pub fn codepoint(self) -> u16 {
+        self as u16
+    }
+
+    pub fn from_codepoint(codepoint: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|group| group.codepoint() == codepoint)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NamedGroup::SecP256r1MlKem768 => "SecP256r1MLKEM768",
+            NamedGroup::X25519MlKem768 => "X25519MLKEM768",
+            NamedGroup::MlKem1024 => "MLKEM1024",
+        }
+    }
+
+    pub fn client_share_len(self) -> usize {
+        match self {
+            NamedGroup::SecP256r1MlKem768 => p256::POINT_LEN + mlkem768::ek_len,
+            NamedGroup::X25519MlKem768 => mlkem768::ek_len + 32,
+            NamedGroup::MlKem1024 => mlkem1024::ek_len,
+        }
+    }
+
+    pub fn server_share_len(self) -> usize {
+        match self {
+            NamedGroup::SecP256r1MlKem768 => p256::POINT_LEN + mlkem768::cipher_len,
+            NamedGroup::X25519MlKem768 => mlkem768::cipher_len + 32,
+            NamedGroup::MlKem1024 => mlkem1024::cipher_len,
+        }
+    }
+
+    pub fn shared_secret_len(self) -> usize {
+        match self {
+            NamedGroup::SecP256r1MlKem768 | NamedGroup::X25519MlKem768 => 64,
+            NamedGroup::MlKem1024 => 32,
+        }
+    }
+}
+
+// The keys are not boxed so that the module works without alloc.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+enum Secret {
+    SecP256r1MlKem768(mlkem768::DecapKey, [u8; 32]),
+    X25519MlKem768(mlkem768::DecapKey, [u8; 32]),
+    MlKem1024(mlkem1024::DecapKey),
+}
+
+/// The client's private keys of a key share.
+#[derive(Clone, Debug)]
+pub struct ClientKeyShare(Secret);
+
+fn x25519_checked(scalar: &[u8; 32], u: &[u8; 32]) -> Result<[u8; 32]> {
+    let ss = x25519(scalar, u);
+    if ss.iter().fold(0, |acc, x| acc | x) == 0 {
+        return Err(Error::InvalidEcdhShare);
+    }
+    Ok(ss)
+}
+
+fn p256_public_key(scalar: &[u8; 32]) -> Result<[u8; p256::POINT_LEN]> {
+    p256::public_key(scalar).map_err(|_| Error::InvalidPrivateKey)
+}
+
+fn p256_ecdh(scalar: &[u8; 32], peer: &[u8]) -> Result<[u8; 32]> {
+    p256::ecdh(scalar, peer).map_err(|e| match e {
+        p256::Error::InvalidScalar => Error::InvalidPrivateKey,
+        p256::Error::InvalidPoint => Error::InvalidEcdhShare,
+    })
+}
+
+// Retry the P-256 scalars not in [1, n-1].
+fn retry<T>(rng: &mut dyn CryptoRng, mut f: impl FnMut(&[u8; 32]) -> Result<T>) -> Result<T> {
+    loop {
+        match f(&rng.random()) {
+            Err(Error::InvalidPrivateKey) => continue,
+            r => return r,
+        }
+    }
+}
+
+impl ClientKeyShare {
+    pub fn generate(group: NamedGroup, rng: &mut dyn CryptoRng) -> Self {
+        let mut seed = [0; 64];
+        rng.fill_bytes(&mut seed);
+        retry(rng, |ecdh_key| Self::generate_internal(group, &seed, ecdh_key)).unwrap()
+    }
+
+    /// The derandomized generation, seed is the ML-KEM seed d || z and ecdh_key the big endian
+    /// P-256 scalar or the X25519 private key, it is not used by MLKEM1024.
+    pub fn generate_internal(group: NamedGroup, seed: &[u8; 64], ecdh_key: &[u8; 32]) -> Result<Self> {
+        Ok(ClientKeyShare(match group {
+            NamedGroup::SecP256r1MlKem768 => {
+                p256_public_key(ecdh_key)?;
+                Secret::SecP256r1MlKem768(mlkem768::DecapKey::from_seed(seed), *ecdh_key)
+            }
+            NamedGroup::X25519MlKem768 => Secret::X25519MlKem768(mlkem768::DecapKey::from_seed(seed), *ecdh_key),
+            NamedGroup::MlKem1024 => Secret::MlKem1024(mlkem1024::DecapKey::from_seed(seed)),
+        }))
+    }
+
+    pub fn group(&self) -> NamedGroup {
+        match self.0 {
+            Secret::SecP256r1MlKem768(..) => NamedGroup::SecP256r1MlKem768,
+            Secret::X25519MlKem768(..) => NamedGroup::X25519MlKem768,
+            Secret::MlKem1024(..) => NamedGroup::MlKem1024,
+        }
+    }
+
+    /// Write the client share, returns its length.
+    pub fn key_share(&self, out: &mut [u8]) -> Result<usize> {
+        let len = self.group().client_share_len();
+        let out = out.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        match &self.0 {
+            Secret::SecP256r1MlKem768(dk, sk) => {
+                let (ecdh, ek) = out.split_at_mut(p256::POINT_LEN);
+                ecdh.copy_from_slice(&p256_public_key(sk)?);
+                dk.encapsulation_key_ref().byte_encode_inplace(ek.try_into().unwrap());
+            }
+            Secret::X25519MlKem768(dk, sk) => {
+                let (ek, ecdh) = out.split_at_mut(mlkem768::ek_len);
+                dk.encapsulation_key_ref().byte_encode_inplace(ek.try_into().unwrap());
+                ecdh.copy_from_slice(&x25519_base(sk));
+            }
+            Secret::MlKem1024(dk) => dk.encapsulation_key_ref().byte_encode_inplace(out.try_into().unwrap()),
+        }
+        Ok(len)
+    }
+
+    /// Derive the shared secret from the server share, returns its length.
+    pub fn finish(&self, server_share: &[u8], secret: &mut [u8]) -> Result<usize> {
+        let group = self.group();
+        if server_share.len() != group.server_share_len() {
+            return Err(Error::InvalidLength);
+        }
+        let len = group.shared_secret_len();
+        let out = secret.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        match &self.0 {
+            Secret::SecP256r1MlKem768(dk, sk) => {
+                let (ecdh, ct) = server_share.split_at(p256::POINT_LEN);
+                out[..32].copy_from_slice(&p256_ecdh(sk, ecdh)?);
+                out[32..].copy_from_slice(&dk.decaps(ct.try_into().unwrap()));
+            }
+            Secret::X25519MlKem768(dk, sk) => {
+                let (ct, ecdh) = server_share.split_at(mlkem768::cipher_len);
+                let ss_x = x25519_checked(sk, ecdh.try_into().unwrap())?;
+                out[..32].copy_from_slice(&dk.decaps(ct.try_into().unwrap()));
+                out[32..].copy_from_slice(&ss_x);
+            }
+            Secret::MlKem1024(dk) => out.copy_from_slice(&dk.decaps(server_share.try_into().unwrap())),
+        }
+        Ok(len)
+    }
+}
+
+/// Respond to the client share as the server, writes the server share and the shared secret and
+/// returns their lengths.
+pub fn respond(
+    group: NamedGroup,
+    client_share: &[u8],
+    rng: &mut dyn CryptoRng,
+    share: &mut [u8],
+    secret: &mut [u8],
+) -> Result<(usize, usize)> {
+    let m = rng.random();
+    retry(rng, |ecdh_key| respond_internal(group, client_share, &m, ecdh_key, share, secret))
+}
+
+/// The derandomized response, m is the ML-KEM randomness and ecdh_key the ephemeral big endian
+/// P-256 scalar or X25519 private key, it is not used by MLKEM1024.
+pub fn respond_internal(
+    group: NamedGroup,
+    client_share: &[u8],
+    m: &[u8; 32],
+    ecdh_key: &[u8; 32],
+    share: &mut [u8],
+    secret: &mut [u8],
+) -> Result<(usize, usize)> {
+    if client_share.len() != group.client_share_len() {
+        return Err(Error::InvalidLength);
+    }
+    let (share_len, secret_len) = (group.server_share_len(), group.shared_secret_len());
+    let share = share.get_mut(..share_len).ok_or(Error::BufferTooSmall)?;
+    let secret = secret.get_mut(..secret_len).ok_or(Error::BufferTooSmall)?;
+    match group {
+        NamedGroup::SecP256r1MlKem768 => {
+            let (ecdh, ek) = client_share.split_at(p256::POINT_LEN);
+            let ek = mlkem768::EncapKey::byte_decode(ek.try_into().unwrap()).map_err(|_| Error::InvalidEncapKey)?;
+            let ss_e = p256_ecdh(ecdh_key, ecdh)?;
+            let (ss_m, ct) = ek.encaps_internal(m);
+            share[..p256::POINT_LEN].copy_from_slice(&p256_public_key(ecdh_key)?);
+            share[p256::POINT_LEN..].copy_from_slice(&ct);
+            secret[..32].copy_from_slice(&ss_e);
+            secret[32..].copy_from_slice(&ss_m);
+        }
+        NamedGroup::X25519MlKem768 => {
+            let (ek, ecdh) = client_share.split_at(mlkem768::ek_len);
+            let ek = mlkem768::EncapKey::byte_decode(ek.try_into().unwrap()).map_err(|_| Error::InvalidEncapKey)?;
+            let ss_x = x25519_checked(ecdh_key, ecdh.try_into().unwrap())?;
+            let (ss_m, ct) = ek.encaps_internal(m);
+            share[..mlkem768::cipher_len].copy_from_slice(&ct);
+            share[mlkem768::cipher_len..].copy_from_slice(&x25519_base(ecdh_key));
+            secret[..32].copy_from_slice(&ss_m);
+            secret[32..].copy_from_slice(&ss_x);
+        }
+        NamedGroup::MlKem1024 => {
+            let ek = mlkem1024::EncapKey::byte_decode(client_share.try_into().unwrap())
+                .map_err(|_| Error::InvalidEncapKey)?;
+            let (ss, ct) = ek.encaps_internal(m);
+            share.copy_from_slice(&ct);
+            secret.copy_from_slice(&ss);
+        }
+    }
+    Ok((share_len, secret_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_SHARE: usize = 1600;
+
+    #[test]
+    fn test_named_group() {
+        for (group, codepoint, lens) in [
+            (NamedGroup::SecP256r1MlKem768, 0x11eb, (1249, 1153, 64)),
+            (NamedGroup::X25519MlKem768, 0x11ec, (1216, 1120, 64)),
+            (NamedGroup::MlKem1024, 0x0202, (1568, 1568, 32)),
+        ] {
+            assert_eq!(group.codepoint(), codepoint);
+            assert_eq!(NamedGroup::from_codepoint(codepoint), Some(group));
+            assert_eq!((group.client_share_len(), group.server_share_len(), group.shared_secret_len()), lens);
+        }
+        assert_eq!(NamedGroup::from_codepoint(0x001d), None);
+        assert_eq!(NamedGroup::from_codepoint(0x11ed), None);
+    }
+
+    #[test]
+    fn test_key_share() {
+        let mut rng = rand::rng();
+        for group in NamedGroup::ALL {
+            let client = ClientKeyShare::generate(group, &mut rng);
+            assert_eq!(client.group(), group);
+            let mut client_share = [0; MAX_SHARE];
+            let n = client.key_share(&mut client_share).unwrap();
+            assert_eq!(n, group.client_share_len());
+
+            let mut server_share = [0; MAX_SHARE];
+            let mut server_secret = [0; 64];
+            let (share_len, secret_len) =
+                respond(group, &client_share[..n], &mut rng, &mut server_share, &mut server_secret).unwrap();
+            let mut client_secret = [0; 64];
+            assert_eq!(client.finish(&server_share[..share_len], &mut client_secret), Ok(secret_len));
+            assert_eq!(client_secret[..secret_len], server_secret[..secret_len]);
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        // The shares and secrets are the concatenations of the component algorithms.
+        let mut rng = rand::rng();
+        let seed: [u8; 64] = rng.random();
+        let (m, client_key, server_key) = (rng.random(), [1; 32], [2; 32]);
+        let dk = mlkem768::DecapKey::from_seed(&seed);
+        let ek = dk.encapsulation_key().byte_encode();
+        let (ss_m, ct) = dk.encapsulation_key().encaps_internal(&m);
+        let (mut client_share, mut server_share, mut secret) = ([0; MAX_SHARE], [0; MAX_SHARE], [0; 64]);
+
+        let group = NamedGroup::SecP256r1MlKem768;
+        let client = ClientKeyShare::generate_internal(group, &seed, &client_key).unwrap();
+        let n = client.key_share(&mut client_share).unwrap();
+        let client_pub = p256::public_key(&client_key).unwrap();
+        assert_eq!(client_share[..65], client_pub);
+        assert_eq!(client_share[65..n], ek);
+        let (s, _) = respond_internal(group, &client_share[..n], &m, &server_key, &mut server_share, &mut secret).unwrap();
+        assert_eq!(server_share[..65], p256::public_key(&server_key).unwrap());
+        assert_eq!(server_share[65..s], ct);
+        assert_eq!(secret[..32], p256::ecdh(&server_key, &client_pub).unwrap());
+        assert_eq!(secret[32..], ss_m);
+        let mut client_secret = [0; 64];
+        client.finish(&server_share[..s], &mut client_secret).unwrap();
+        assert_eq!(client_secret, secret);
+
+        let group = NamedGroup::X25519MlKem768;
+        let client = ClientKeyShare::generate_internal(group, &seed, &client_key).unwrap();
+        let n = client.key_share(&mut client_share).unwrap();
+        let client_pub = x25519_base(&client_key);
+        assert_eq!(client_share[..1184], ek);
+        assert_eq!(client_share[1184..n], client_pub);
+        let (s, _) = respond_internal(group, &client_share[..n], &m, &server_key, &mut server_share, &mut secret).unwrap();
+        assert_eq!(server_share[..1088], ct);
+        assert_eq!(server_share[1088..s], x25519_base(&server_key));
+        assert_eq!(secret[..32], ss_m);
+        assert_eq!(secret[32..], x25519(&server_key, &client_pub));
+        client.finish(&server_share[..s], &mut client_secret).unwrap();
+        assert_eq!(client_secret, secret);
+
+        let group = NamedGroup::MlKem1024;
+        let dk = mlkem1024::DecapKey::from_seed(&seed);
+        let (ss, ct) = dk.encapsulation_key().encaps_internal(&m);
+        let client = ClientKeyShare::generate_internal(group, &seed, &client_key).unwrap();
+        let n = client.key_share(&mut client_share).unwrap();
+        assert_eq!(client_share[..n], dk.encapsulation_key().byte_encode());
+        let (s, k) = respond_internal(group, &client_share[..n], &m, &server_key, &mut server_share, &mut secret).unwrap();
+        assert_eq!(server_share[..s], ct);
+        assert_eq!(secret[..k], ss);
+    }
+
+    #[test]
+    fn test_malformed() {
+        let mut rng = rand::rng();
+        let (m, key) = ([3; 32], [4; 32]);
+        let (mut client_share, mut server_share, mut secret) = ([0; MAX_SHARE], [0; MAX_SHARE], [0; 64]);
+        for group in NamedGroup::ALL {
+            let client = ClientKeyShare::generate(group, &mut rng);
+            let n = client.key_share(&mut client_share).unwrap();
+            assert_eq!(client.key_share(&mut [0; 1000]), Err(Error::BufferTooSmall));
+
+            let mut answer = |client_share: &[u8], secret: &mut [u8]| {
+                respond_internal(group, client_share, &m, &key, &mut server_share, secret)
+            };
+            assert_eq!(answer(&client_share[..n - 1], &mut secret), Err(Error::InvalidLength));
+            assert_eq!(answer(&client_share[..n + 1], &mut secret), Err(Error::InvalidLength));
+            assert_eq!(answer(&client_share[..n], &mut secret[..31]), Err(Error::BufferTooSmall));
+
+            // the modulus check of the encapsulation key
+            let mut bad = client_share;
+            let ek_offset = if group == NamedGroup::SecP256r1MlKem768 { 65 } else { 0 };
+            bad[ek_offset..ek_offset + 2].copy_from_slice(&[0xff, 0xff]);
+            assert_eq!(answer(&bad[..n], &mut secret), Err(Error::InvalidEncapKey));
+
+            let (s, _) = answer(&client_share[..n], &mut secret).unwrap();
+            assert_eq!(client.finish(&server_share[..s - 1], &mut secret), Err(Error::InvalidLength));
+            assert_eq!(client.finish(&server_share[..s], &mut secret[..31]), Err(Error::BufferTooSmall));
+        }
+
+        // a P-256 point off the curve
+        let group = NamedGroup::SecP256r1MlKem768;
+        let client = ClientKeyShare::generate(group, &mut rng);
+        let n = client.key_share(&mut client_share).unwrap();
+        let mut bad = client_share;
+        bad[64] ^= 1;
+        assert_eq!(
+            respond_internal(group, &bad[..n], &m, &key, &mut server_share, &mut secret),
+            Err(Error::InvalidEcdhShare)
+        );
+        let (s, _) = respond_internal(group, &client_share[..n], &m, &key, &mut server_share, &mut secret).unwrap();
+        server_share[0] = 2;
+        assert_eq!(client.finish(&server_share[..s], &mut secret), Err(Error::InvalidEcdhShare));
+        assert_eq!(
+            ClientKeyShare::generate_internal(group, &[0; 64], &[0xff; 32]).unwrap_err(),
+            Error::InvalidPrivateKey
+        );
+        assert_eq!(
+            respond_internal(group, &client_share[..n], &m, &[0; 32], &mut server_share, &mut secret),
+            Err(Error::InvalidPrivateKey)
+        );
+
+        // a low order X25519 point
+        let group = NamedGroup::X25519MlKem768;
+        let client = ClientKeyShare::generate(group, &mut rng);
+        let n = client.key_share(&mut client_share).unwrap();
+        let mut bad = client_share;
+        bad[n - 32..n].fill(0);
+        assert_eq!(
+            respond_internal(group, &bad[..n], &m, &key, &mut server_share, &mut secret),
+            Err(Error::InvalidEcdhShare)
+        );
+        let (s, _) = respond_internal(group, &client_share[..n], &m, &key, &mut server_share, &mut secret).unwrap();
+        server_share[s - 32..s].fill(0);
+        server_share[s - 32] = 1;
+        assert_eq!(client.finish(&server_share[..s], &mut secret), Err(Error::InvalidEcdhShare));
+    }
+}
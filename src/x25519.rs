@@ -0,0 +1,263 @@
+//! X25519 (RFC 7748).
+//!
+//! The field elements are 5 limbs of 51 bits, the Montgomery ladder swaps in constant time and the
+//! inversion is a fixed exponentiation, so the timing does not depend on the scalar.
+
+type Fe = [u64; 5];
+
+const MASK: u64 = (1 << 51) - 1;
+
+/// The u-coordinate of the base point, 9.
+pub(crate) const BASE: [u8; 32] = {
+    let mut b = [0; 32];
+    b[0] = 9;
+    b
+};
+
+fn load64(b: &[u8]) -> u64 {
+    u64::from_le_bytes(b[..8].try_into().unwrap())
+}
+
+// The top bit is ignored.
+fn from_bytes(b: &[u8; 32]) -> Fe {
+    [
+        load64(&b[0..]) & MASK,
+        (load64(&b[6..]) >> 3) & MASK,
+        (load64(&b[12..]) >> 6) & MASK,
+        (load64(&b[19..]) >> 1) & MASK,
+        (load64(&b[24..]) >> 12) & MASK,
+    ]
+}
+
+fn carry(mut h: Fe) -> Fe {
+    for i in 0..4 {
+        h[i + 1] += h[i] >> 51;
+        h[i] &= MASK;
+    }
+    h[0] += 19 * (h[4] >> 51);
+    h[4] &= MASK;
+    h
+}
+
+// The canonical encoding, reduced modulo p.
+fn to_bytes(h: &Fe) -> [u8; 32] {
+    let mut h = carry(carry(*h));
+    // h < 2^255 + small, q is 1 iff h >= p
+    let mut q = (h[0] + 19) >> 51;
+    for x in &h[1..] {
+        q = (x + q) >> 51;
+    }
+    h[0] += 19 * q;
+    for i in 0..4 {
+        h[i + 1] += h[i] >> 51;
+        h[i] &= MASK;
+    }
+    h[4] &= MASK;
+
+    let mut out = [0; 32];
+    let mut acc: u128 = 0;
+    let mut bits = 0;
+    let mut j = 0;
+    for x in h {
+        acc |= (x as u128) << bits;
+        bits += 51;
+        while bits >= 8 && j < 32 {
+            out[j] = acc as u8;
+            acc >>= 8;
+            bits -= 8;
+            j += 1;
+        }
+    }
+    out[31] = acc as u8;
+    out
+}
+
+fn add(a: &Fe, b: &Fe) -> Fe {
+    core::array::from_fn(|i| a[i] + b[i])
+}
+
+// a + 4p - b, the limbs of b are below 2^53.
+fn sub(a: &Fe, b: &Fe) -> Fe {
+    const P4: Fe = [0x1f_ffff_ffff_ffb4, 0x1f_ffff_ffff_fffc, 0x1f_ffff_ffff_fffc, 0x1f_ffff_ffff_fffc, 0x1f_ffff_ffff_fffc];
+    carry(core::array::from_fn(|i| a[i] + P4[i] - b[i]))
+}
+
+fn reduce(r: [u128; 5]) -> Fe {
+    let mut r = r;
+    for i in 0..4 {
+        r[i + 1] += r[i] >> 51;
+        r[i] &= MASK as u128;
+    }
+    r[0] += 19 * (r[4] >> 51);
+    r[4] &= MASK as u128;
+    r[1] += r[0] >> 51;
+    r[0] &= MASK as u128;
+    core::array::from_fn(|i| r[i] as u64)
+}
+
+// The limbs of a and b are below 2^54.
+fn mul(a: &Fe, b: &Fe) -> Fe {
+    let m = |x: u64, y: u64| x as u128 * y as u128;
+    let [a0, a1, a2, a3, a4] = *a;
+    let [b0, b1, b2, b3, b4] = *b;
+    let (b1_19, b2_19, b3_19, b4_19) = (b1 * 19, b2 * 19, b3 * 19, b4 * 19);
+    reduce([
+        m(a0, b0) + m(a1, b4_19) + m(a2, b3_19) + m(a3, b2_19) + m(a4, b1_19),
+        m(a0, b1) + m(a1, b0) + m(a2, b4_19) + m(a3, b3_19) + m(a4, b2_19),
+        m(a0, b2) + m(a1, b1) + m(a2, b0) + m(a3, b4_19) + m(a4, b3_19),
+        m(a0, b3) + m(a1, b2) + m(a2, b1) + m(a3, b0) + m(a4, b4_19),
+        m(a0, b4) + m(a1, b3) + m(a2, b2) + m(a3, b1) + m(a4, b0),
+    ])
+}
+
+fn square(a: &Fe) -> Fe {
+    mul(a, a)
+}
+
+fn square_n(a: &Fe, n: usize) -> Fe {
+    let mut r = *a;
+    for _ in 0..n {
+        r = square(&r);
+    }
+    r
+}
+
+fn mul_small(a: &Fe, n: u64) -> Fe {
+    reduce(core::array::from_fn(|i| a[i] as u128 * n as u128))
+}
+
+// a^(p-2)
+fn invert(z: &Fe) -> Fe {
+    let z2 = square(z);
+    let z9 = mul(&square_n(&z2, 2), z);
+    let z11 = mul(&z9, &z2);
+    let z2_5_0 = mul(&square(&z11), &z9);
+    let z2_10_0 = mul(&square_n(&z2_5_0, 5), &z2_5_0);
+    let z2_20_0 = mul(&square_n(&z2_10_0, 10), &z2_10_0);
+    let z2_40_0 = mul(&square_n(&z2_20_0, 20), &z2_20_0);
+    let z2_50_0 = mul(&square_n(&z2_40_0, 10), &z2_10_0);
+    let z2_100_0 = mul(&square_n(&z2_50_0, 50), &z2_50_0);
+    let z2_200_0 = mul(&square_n(&z2_100_0, 100), &z2_100_0);
+    let z2_250_0 = mul(&square_n(&z2_200_0, 50), &z2_50_0);
+    mul(&square_n(&z2_250_0, 5), &z11)
+}
+
+fn cswap(swap: u64, a: &mut Fe, b: &mut Fe) {
+    let mask = 0u64.wrapping_sub(swap);
+    for i in 0..5 {
+        let t = mask & (a[i] ^ b[i]);
+        a[i] ^= t;
+        b[i] ^= t;
+    }
+}
+
+/// The X25519 function of the scalar and the u-coordinate, the scalar is clamped.
+/// The result is all zero for a low order u, callers that need contributory behavior check it.
+pub fn x25519(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut k = *scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+
+    let x1 = from_bytes(u);
+    let (mut x2, mut z2, mut x3, mut z3) = ([1, 0, 0, 0, 0], [0; 5], x1, [1, 0, 0, 0, 0]);
+    let mut swap = 0;
+    for t in (0..255).rev() {
+        let k_t = ((k[t / 8] >> (t % 8)) & 1) as u64;
+        swap ^= k_t;
+        cswap(swap, &mut x2, &mut x3);
+        cswap(swap, &mut z2, &mut z3);
+        swap = k_t;
+
+        let a = add(&x2, &z2);
+        let aa = square(&a);
+        let b = sub(&x2, &z2);
+        let bb = square(&b);
+        let e = sub(&aa, &bb);
+        let c = add(&x3, &z3);
+        let d = sub(&x3, &z3);
+        let da = mul(&d, &a);
+        let cb = mul(&c, &b);
+        x3 = square(&add(&da, &cb));
+        z3 = mul(&x1, &square(&sub(&da, &cb)));
+        x2 = mul(&aa, &bb);
+        z2 = mul(&e, &add(&aa, &mul_small(&e, 121665)));
+    }
+    cswap(swap, &mut x2, &mut x3);
+    cswap(swap, &mut z2, &mut z3);
+    to_bytes(&mul(&x2, &invert(&z2)))
+}
+
+/// The public key of the private key, X25519 of the base point.
+pub fn x25519_base(scalar: &[u8; 32]) -> [u8; 32] {
+    x25519(scalar, &BASE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_x25519() {
+        // RFC 7748 section 5.2
+        assert_eq!(
+            x25519(
+                &hex!("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4"),
+                &hex!("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c")
+            ),
+            hex!("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552")
+        );
+        assert_eq!(
+            x25519(
+                &hex!("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d"),
+                &hex!("e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493")
+            ),
+            hex!("95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957")
+        );
+
+        let (mut k, mut u) = (BASE, BASE);
+        for i in 1..=1000 {
+            (k, u) = (x25519(&k, &u), k);
+            if i == 1 {
+                assert_eq!(k, hex!("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079"));
+            }
+        }
+        assert_eq!(k, hex!("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51"));
+    }
+
+    #[test]
+    fn test_diffie_hellman() {
+        // RFC 7748 section 6.1
+        let a = hex!("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let b = hex!("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let pa = x25519_base(&a);
+        let pb = x25519_base(&b);
+        assert_eq!(pa, hex!("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"));
+        assert_eq!(pb, hex!("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"));
+        let shared = hex!("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(x25519(&a, &pb), shared);
+        assert_eq!(x25519(&b, &pa), shared);
+
+        // low order points give zero, the top bit of u is ignored
+        assert_eq!(x25519(&a, &[0; 32]), [0; 32]);
+        assert_eq!(x25519(&a, &hex!("0100000000000000000000000000000000000000000000000000000000000000")), [0; 32]);
+        let mut pb_high = pb;
+        pb_high[31] |= 0x80;
+        assert_eq!(x25519(&a, &pb_high), shared);
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        // p and p + 1 reduce to 0 and 1, 2^255 - 1 to 18
+        let p = hex!("edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f");
+        assert_eq!(to_bytes(&from_bytes(&p)), [0; 32]);
+        let mut p1 = p;
+        p1[0] += 1;
+        assert_eq!(to_bytes(&from_bytes(&p1))[..2], [1, 0]);
+        let max = from_bytes(&[0xff; 32]);
+        assert_eq!(to_bytes(&max)[0], 18);
+        let one = [1, 0, 0, 0, 0];
+        assert_eq!(to_bytes(&mul(&from_bytes(&p1), &invert(&from_bytes(&p1)))), to_bytes(&one));
+    }
+}
@@ -0,0 +1,162 @@
+use alloc::vec::Vec;
+
+use crate::alg::{Algorithm, Signer};
+use crate::pkcs::{self, der, der::Writer};
+use crate::sha3::{self, Hash};
+
+use super::errors::{Error, Result};
+use super::{BasicConstraints, Certificate, KeyUsage, Name, oid, time};
+
+/// Builds and signs a version 3 certificate.
+///
+/// The certificate is self-issued unless [`CertificateBuilder::issuer`] is set. The subjectKeyIdentifier
+/// is the leftmost 160 bits of the SHA3-256 of the public key, like method 1 of RFC 7093.
+#[derive(Clone, Debug)]
+pub struct CertificateBuilder {
+    serial: Vec<u8>,
+    issuer: Vec<u8>,
+    subject: Vec<u8>,
+    not_before: i64,
+    not_after: i64,
+    spki: Vec<u8>,
+    subject_key_id: [u8; 20],
+    authority_key_id: Option<Vec<u8>>,
+    basic_constraints: Option<BasicConstraints>,
+    key_usage: KeyUsage,
+}
+
+impl CertificateBuilder {
+    /// spki is the DER SubjectPublicKeyInfo of the subject, from to_public_key_der() of the key.
+    /// The validity is in seconds since the Unix epoch.
+    pub fn new(serial: &[u8], subject: &Name, spki: &[u8], not_before: i64, not_after: i64) -> Result<Self> {
+        time::check_time(not_before)?;
+        time::check_time(not_after)?;
+        if not_before > not_after {
+            return Err(Error::InvalidTime);
+        }
+        let (public_key_algorithm, public_key) = pkcs::decode_spki(spki)?;
+        let mut h = sha3::new256();
+        h.write(public_key);
+        let mut subject_key_id = [0; 20];
+        subject_key_id.copy_from_slice(&h.sum()[..20]);
+
+        let subject = subject.to_der();
+        // digitalSignature for the signature keys, keyEncipherment for the KEM keys
+        let key_usage = if public_key_algorithm.is_kem() { KeyUsage::KEY_ENCIPHERMENT } else { KeyUsage::DIGITAL_SIGNATURE };
+        Ok(CertificateBuilder {
+            serial: serial.into(),
+            issuer: subject.clone(),
+            subject,
+            not_before,
+            not_after,
+            spki: spki.into(),
+            authority_key_id: Some(subject_key_id.into()),
+            subject_key_id,
+            basic_constraints: None,
+            key_usage,
+        })
+    }
+
+    /// Issue the certificate by the issuer, which must be signed with the private key of issuer.
+    pub fn issuer(mut self, issuer: &Certificate) -> Self {
+        self.issuer = issuer.subject.into();
+        self.authority_key_id = issuer.subject_key_id.map(Into::into);
+        self
+    }
+
+    /// Make a CA certificate with keyCertSign and cRLSign, path_len limits the CA certificates below it.
+    pub fn ca(mut self, path_len: Option<u64>) -> Self {
+        self.basic_constraints = Some(BasicConstraints { ca: true, path_len });
+        self.key_usage = KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN;
+        self
+    }
+
+    pub fn key_usage(mut self, key_usage: KeyUsage) -> Self {
+        self.key_usage = key_usage;
+        self
+    }
+
+    fn write_extension(w: &mut Writer, id: &[u8], critical: bool, f: impl FnOnce(&mut Writer)) {
+        let mut v = Writer::new();
+        f(&mut v);
+        w.write_sequence(|w| {
+            w.write_oid(id);
+            if critical {
+                w.write_bool(true);
+            }
+            w.write_octet_string(&v.finish());
+        });
+    }
+
+    /// The DER TBSCertificate to be signed with the algorithm.
+    pub fn tbs_der(&self, signature_algorithm: Algorithm) -> Result<Vec<u8>> {
+        if !signature_algorithm.is_signature() {
+            return Err(Error::Pkcs(pkcs::Error::UnsupportedAlgorithm));
+        }
+        let mut w = Writer::new();
+        w.write_sequence(|w| {
+            w.write_constructed(der::context_constructed(0), |w| {
+                w.write_u64(2);
+            });
+            w.write_uint(&self.serial);
+            pkcs::write_algorithm_identifier(w, pkcs::algorithm_oid(signature_algorithm));
+            w.write_raw(&self.issuer);
+            w.write_sequence(|w| {
+                time::write_time(w, self.not_before);
+                time::write_time(w, self.not_after);
+            });
+            w.write_raw(&self.subject);
+            w.write_raw(&self.spki);
+            w.write_constructed(der::context_constructed(3), |w| {
+                w.write_sequence(|w| self.write_extensions(w));
+            });
+        });
+        Ok(w.finish())
+    }
+
+    fn write_extensions(&self, w: &mut Writer) {
+        if let Some(bc) = self.basic_constraints {
+            Self::write_extension(w, oid::BASIC_CONSTRAINTS, true, |w| {
+                w.write_sequence(|w| {
+                    if bc.ca {
+                        w.write_bool(true);
+                    }
+                    if let Some(n) = bc.path_len {
+                        w.write_u64(n);
+                    }
+                });
+            });
+        }
+        Self::write_extension(w, oid::KEY_USAGE, true, |w| {
+            let (b, n) = self.key_usage.encode();
+            w.write(der::BIT_STRING, &b[..n]);
+        });
+        Self::write_extension(w, oid::SUBJECT_KEY_IDENTIFIER, false, |w| {
+            w.write_octet_string(&self.subject_key_id);
+        });
+        if let Some(akid) = &self.authority_key_id {
+            Self::write_extension(w, oid::AUTHORITY_KEY_IDENTIFIER, false, |w| {
+                w.write_sequence(|w| {
+                    w.write(der::context(0), akid);
+                });
+            });
+        }
+    }
+
+    /// Sign the certificate with the private key of the issuer, returns the DER certificate.
+    /// rnd is all zero for the deterministic variant of ML-DSA.
+    pub fn sign(&self, key: &dyn Signer, rnd: &[u8; 32]) -> Result<Vec<u8>> {
+        let alg = key.algorithm();
+        let tbs = self.tbs_der(alg)?;
+        let mut signature = vec![0; alg.signature_len()];
+        key.sign(&tbs, b"", rnd, &mut signature)?;
+
+        let mut w = Writer::new();
+        w.write_sequence(|w| {
+            w.write_raw(&tbs);
+            pkcs::write_algorithm_identifier(w, pkcs::algorithm_oid(alg));
+            w.write_bit_string(&signature);
+        });
+        Ok(w.finish())
+    }
+}
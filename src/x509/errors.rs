@@ -0,0 +1,40 @@
+use thiserror;
+
+use crate::{alg, pkcs};
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error(transparent)]
+    Pkcs(#[from] pkcs::Error),
+
+    #[error(transparent)]
+    Alg(#[from] alg::Error),
+
+    #[error("malformed certificate")]
+    Malformed,
+
+    #[error("invalid time")]
+    InvalidTime,
+
+    #[error("unsupported critical extension")]
+    UnsupportedCriticalExtension,
+
+    #[error("certificate signature verification failed")]
+    Signature,
+
+    #[error("certificate is not valid at the time")]
+    Expired,
+
+    #[error("issuer does not match")]
+    IssuerMismatch,
+
+    #[error("issuer is not a CA")]
+    NotCA,
+
+    #[error("path length constraint exceeded")]
+    PathLength,
+
+    #[error("empty certificate chain")]
+    EmptyChain,
+}
+pub type Result<T> = core::result::Result<T, Error>;
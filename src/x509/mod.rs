@@ -0,0 +1,505 @@
+//! X.509 certificates (RFC 5280) signed with the pure ML-DSA algorithm identifiers (RFC 9881).
+//!
+//! [`CertificateBuilder`] issues certificates, [`Certificate`] parses one without copying, and
+//! [`verify_chain`] validates a chain up to a trusted root. Only the basicConstraints, keyUsage,
+//! subjectKeyIdentifier and authorityKeyIdentifier extensions are understood, any other critical
+//! extension is rejected.
+
+use crate::alg::Algorithm;
+use crate::pkcs::{self, der, der::Reader};
+
+#[cfg(feature = "alloc")]
+mod builder;
+mod errors;
+mod name;
+mod time;
+
+#[cfg(feature = "alloc")]
+pub use builder::CertificateBuilder;
+pub use errors::{Error, Result};
+#[cfg(feature = "alloc")]
+pub use name::Name;
+pub use name::{name_attribute, oid as attribute};
+pub use time::unix_time;
+#[cfg(feature = "alloc")]
+pub(crate) use time::{read_time, write_time};
+
+/// The DER contents of the extension OIDs.
+pub mod oid {
+    /// 2.5.29.14
+    pub const SUBJECT_KEY_IDENTIFIER: &[u8] = &[0x55, 0x1d, 0x0e];
+    /// 2.5.29.15
+    pub const KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x0f];
+    /// 2.5.29.19
+    pub const BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x13];
+    /// 2.5.29.35
+    pub const AUTHORITY_KEY_IDENTIFIER: &[u8] = &[0x55, 0x1d, 0x23];
+}
+
+/// The keyUsage bits, bit n is the named bit n of the KeyUsage BIT STRING.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct KeyUsage(pub u16);
+
+/// cbindgen:ignore
+impl KeyUsage {
+    pub const DIGITAL_SIGNATURE: KeyUsage = KeyUsage(1 << 0);
+    pub const NON_REPUDIATION: KeyUsage = KeyUsage(1 << 1);
+    pub const KEY_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 2);
+    pub const DATA_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 3);
+    pub const KEY_AGREEMENT: KeyUsage = KeyUsage(1 << 4);
+    pub const KEY_CERT_SIGN: KeyUsage = KeyUsage(1 << 5);
+    pub const CRL_SIGN: KeyUsage = KeyUsage(1 << 6);
+    pub const ENCIPHER_ONLY: KeyUsage = KeyUsage(1 << 7);
+    pub const DECIPHER_ONLY: KeyUsage = KeyUsage(1 << 8);
+
+    pub fn contains(self, other: KeyUsage) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    // the DER BIT STRING content, trailing zero bits are removed.
+    fn encode(self) -> ([u8; 3], usize) {
+        let mut b = [0u8; 3];
+        let bits = 16 - self.0.leading_zeros() as usize;
+        for i in 0..bits {
+            if self.0 >> i & 1 == 1 {
+                b[1 + i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        let n = bits.div_ceil(8);
+        b[0] = ((8 - bits % 8) % 8) as u8;
+        (b, n + 1)
+    }
+
+    fn decode(v: &[u8]) -> Result<Self> {
+        let (unused, bits) = v.split_first().ok_or(Error::Malformed)?;
+        if *unused > 7 || bits.len() > 2 || (bits.is_empty() && *unused != 0) {
+            return Err(Error::Malformed);
+        }
+        let mut ku = 0;
+        for (i, b) in bits.iter().enumerate() {
+            for j in 0..8 {
+                if b & (0x80 >> j) != 0 {
+                    ku |= 1 << (8 * i + j);
+                }
+            }
+        }
+        // the unused bits must be zero
+        if bits.last().is_some_and(|b| b & ((1u8 << unused) - 1) != 0) {
+            return Err(Error::Malformed);
+        }
+        Ok(KeyUsage(ku))
+    }
+}
+
+impl core::ops::BitOr for KeyUsage {
+    type Output = KeyUsage;
+
+    fn bitor(self, rhs: KeyUsage) -> KeyUsage {
+        KeyUsage(self.0 | rhs.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct BasicConstraints {
+    pub ca: bool,
+    pub path_len: Option<u64>,
+}
+
+/// A parsed certificate, the fields are borrowed from the DER.
+#[derive(Clone, Debug)]
+pub struct Certificate<'a> {
+    /// The whole certificate.
+    pub der: &'a [u8],
+    /// The TBSCertificate TLV, which is signed.
+    pub tbs: &'a [u8],
+    /// The serial number without the leading zero.
+    pub serial: &'a [u8],
+    pub signature_algorithm: Algorithm,
+    /// The DER Name of the issuer.
+    pub issuer: &'a [u8],
+    /// The DER Name of the subject.
+    pub subject: &'a [u8],
+    /// Seconds since the Unix epoch.
+    pub not_before: i64,
+    pub not_after: i64,
+    /// The SubjectPublicKeyInfo TLV.
+    pub spki: &'a [u8],
+    pub public_key_algorithm: Algorithm,
+    /// The encoded public key.
+    pub public_key: &'a [u8],
+    pub basic_constraints: Option<BasicConstraints>,
+    pub key_usage: Option<KeyUsage>,
+    pub subject_key_id: Option<&'a [u8]>,
+    pub authority_key_id: Option<&'a [u8]>,
+    pub signature: &'a [u8],
+}
+
+// The signature AlgorithmIdentifier, only the ML-DSA ones are accepted.
+fn read_signature_algorithm(r: &mut Reader) -> Result<Algorithm> {
+    let alg = pkcs::read_algorithm_identifier(r)?;
+    if !alg.is_signature() {
+        return Err(Error::Pkcs(pkcs::Error::UnsupportedAlgorithm));
+    }
+    Ok(alg)
+}
+
+impl<'a> Certificate<'a> {
+    /// Parse a DER certificate of version 3.
+    pub fn from_der(der: &'a [u8]) -> Result<Self> {
+        let mut r = Reader::new(der);
+        let mut cert = r.read_sequence()?;
+        r.finish()?;
+
+        let (tag, tbs_content, tbs) = cert.read_any()?;
+        if tag != der::SEQUENCE {
+            return Err(Error::Malformed);
+        }
+        let signature_algorithm = read_signature_algorithm(&mut cert)?;
+        let signature = cert.read_bit_string()?;
+        cert.finish()?;
+
+        let mut tbs_r = Reader::new(tbs_content);
+        let mut version = tbs_r.read_constructed(der::context_constructed(0))?;
+        if version.read_u64()? != 2 {
+            return Err(Error::Malformed);
+        }
+        version.finish()?;
+        let serial = tbs_r.read_uint()?;
+        if read_signature_algorithm(&mut tbs_r)? != signature_algorithm {
+            return Err(Error::Malformed);
+        }
+        let issuer = Self::read_name(&mut tbs_r)?;
+        let mut validity = tbs_r.read_sequence()?;
+        let not_before = time::read_time(&mut validity)?;
+        let not_after = time::read_time(&mut validity)?;
+        validity.finish()?;
+        let subject = Self::read_name(&mut tbs_r)?;
+        let (tag, _, spki) = tbs_r.read_any()?;
+        if tag != der::SEQUENCE {
+            return Err(Error::Malformed);
+        }
+        let (public_key_algorithm, public_key) = pkcs::decode_spki(spki)?;
+        // the unique identifiers are obsolete
+        tbs_r.read_optional(der::context(1))?;
+        tbs_r.read_optional(der::context(2))?;
+
+        let mut certificate = Certificate {
+            der,
+            tbs,
+            serial,
+            signature_algorithm,
+            issuer,
+            subject,
+            not_before,
+            not_after,
+            spki,
+            public_key_algorithm,
+            public_key,
+            basic_constraints: None,
+            key_usage: None,
+            subject_key_id: None,
+            authority_key_id: None,
+            signature,
+        };
+        if let Some(extensions) = tbs_r.read_optional(der::context_constructed(3))? {
+            let mut r = Reader::new(extensions);
+            certificate.read_extensions(r.read_sequence()?)?;
+            r.finish()?;
+        }
+        tbs_r.finish()?;
+        Ok(certificate)
+    }
+
+    fn read_name(r: &mut Reader<'a>) -> Result<&'a [u8]> {
+        match r.read_any()? {
+            (der::SEQUENCE, _, name) => Ok(name),
+            _ => Err(Error::Malformed),
+        }
+    }
+
+    fn read_extensions(&mut self, mut r: Reader<'a>) -> Result<()> {
+        let mut seen = 0u8;
+        while !r.is_empty() {
+            let mut ext = r.read_sequence()?;
+            let id = ext.read_oid()?;
+            let critical = if ext.peek_tag() == Some(der::BOOLEAN) { ext.read_bool()? } else { false };
+            let mut v = Reader::new(ext.read_octet_string()?);
+            ext.finish()?;
+
+            let index = [oid::BASIC_CONSTRAINTS, oid::KEY_USAGE, oid::SUBJECT_KEY_IDENTIFIER, oid::AUTHORITY_KEY_IDENTIFIER]
+                .iter()
+                .position(|o| *o == id);
+            match index {
+                Some(i) if seen & (1 << i) != 0 => return Err(Error::Malformed),
+                Some(i) => seen |= 1 << i,
+                None if critical => return Err(Error::UnsupportedCriticalExtension),
+                None => continue,
+            }
+            match id {
+                oid::BASIC_CONSTRAINTS => {
+                    let mut seq = v.read_sequence()?;
+                    let ca = if seq.peek_tag() == Some(der::BOOLEAN) { seq.read_bool()? } else { false };
+                    let path_len = if seq.is_empty() { None } else { Some(seq.read_u64()?) };
+                    seq.finish()?;
+                    self.basic_constraints = Some(BasicConstraints { ca, path_len });
+                }
+                oid::KEY_USAGE => self.key_usage = Some(KeyUsage::decode(v.read(der::BIT_STRING)?)?),
+                oid::SUBJECT_KEY_IDENTIFIER => self.subject_key_id = Some(v.read_octet_string()?),
+                _ => {
+                    // only the keyIdentifier [0] is used
+                    let mut seq = v.read_sequence()?;
+                    self.authority_key_id = seq.read_optional(der::context(0))?;
+                }
+            }
+            v.finish()?;
+        }
+        Ok(())
+    }
+
+    /// The common name of the subject.
+    pub fn subject_common_name(&self) -> Result<Option<&'a str>> {
+        name_attribute(self.subject, attribute::COMMON_NAME)
+    }
+
+    pub fn is_ca(&self) -> bool {
+        self.basic_constraints.is_some_and(|bc| bc.ca)
+    }
+
+    /// Whether the time in seconds since the Unix epoch is in the validity period.
+    pub fn is_valid_at(&self, time: i64) -> bool {
+        (self.not_before..=self.not_after).contains(&time)
+    }
+
+    /// Verify the signature of the certificate with the public key of the issuer.
+    pub fn verify_signature(&self, issuer: &Certificate) -> Result<()> {
+        if issuer.public_key_algorithm != self.signature_algorithm {
+            return Err(Error::Signature);
+        }
+        self.signature_algorithm
+            .sig_verify(issuer.public_key, self.tbs, b"", self.signature)
+            .map_err(|_| Error::Signature)
+    }
+
+    /// Check that issuer issued the certificate, with at most `below` CA certificates between them.
+    fn check_issued_by(&self, issuer: &Certificate, below: u64) -> Result<()> {
+        if self.issuer != issuer.subject {
+            return Err(Error::IssuerMismatch);
+        }
+        if let (Some(akid), Some(skid)) = (self.authority_key_id, issuer.subject_key_id)
+            && akid != skid
+        {
+            return Err(Error::IssuerMismatch);
+        }
+        let bc = issuer.basic_constraints.filter(|bc| bc.ca).ok_or(Error::NotCA)?;
+        if issuer.key_usage.is_some_and(|ku| !ku.contains(KeyUsage::KEY_CERT_SIGN)) {
+            return Err(Error::NotCA);
+        }
+        if bc.path_len.is_some_and(|n| below > n) {
+            return Err(Error::PathLength);
+        }
+        self.verify_signature(issuer)
+    }
+}
+
+/// Verify a chain of certificates up to the trusted root at the time in seconds since the Unix epoch.
+///
+/// chain[0] is the end entity certificate and chain[i + 1] issued chain[i]. The last one is issued by
+/// the root, or is the root itself. Every certificate, the root included, must be valid at the time.
+pub fn verify_chain(chain: &[Certificate], root: &Certificate, time: i64) -> Result<()> {
+    let chain = match chain {
+        [] => return Err(Error::EmptyChain),
+        [rest @ .., last] if !rest.is_empty() && last.der == root.der => rest,
+        chain => chain,
+    };
+    if !root.is_valid_at(time) {
+        return Err(Error::Expired);
+    }
+    for (i, cert) in chain.iter().enumerate() {
+        if !cert.is_valid_at(time) {
+            return Err(Error::Expired);
+        }
+        let issuer = chain.get(i + 1).unwrap_or(root);
+        // the CA certificates between the issuer and the end entity
+        cert.check_issued_by(issuer, i as u64)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::mldsa::{mldsa44, mldsa65, mldsa87};
+    use crate::mlkem::mlkem768;
+
+    #[test]
+    fn test_key_usage() {
+        for (ku, der) in [
+            (KeyUsage(0), &[0x00][..]),
+            (KeyUsage::DIGITAL_SIGNATURE, &[0x07, 0x80]),
+            (KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN, &[0x01, 0x06]),
+            (KeyUsage::DIGITAL_SIGNATURE | KeyUsage::DECIPHER_ONLY, &[0x07, 0x80, 0x80]),
+        ] {
+            let (b, n) = ku.encode();
+            assert_eq!(&b[..n], der);
+            assert_eq!(KeyUsage::decode(der), Ok(ku));
+        }
+        assert!(KeyUsage::decode(&[0x07, 0x81]).is_err());
+        assert!(KeyUsage::decode(&[0x01]).is_err());
+        assert!((KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN).contains(KeyUsage::KEY_CERT_SIGN));
+        assert!(!KeyUsage::DIGITAL_SIGNATURE.contains(KeyUsage::KEY_CERT_SIGN));
+    }
+
+    struct Pki {
+        root: Vec<u8>,
+        intermediate: Vec<u8>,
+        leaf: Vec<u8>,
+        kem_leaf: Vec<u8>,
+        intermediate_key: mldsa65::PrivateKey,
+        leaf_key: mldsa44::PrivateKey,
+    }
+
+    fn pki(not_before: i64, not_after: i64) -> Pki {
+        let root_key = mldsa87::PrivateKey::from_seed(&[1; 32]);
+        let intermediate_key = mldsa65::PrivateKey::from_seed(&[2; 32]);
+        let leaf_key = mldsa44::PrivateKey::from_seed(&[3; 32]);
+        let kem_key = mlkem768::DecapKey::from_seed(&[4; 64]);
+        let name = |cn| Name::new().country("CN").organization("OpenGM").common_name(cn);
+
+        let root = CertificateBuilder::new(&[1], &name("Root CA"), &root_key.public_key().to_public_key_der(), not_before, not_after)
+            .unwrap()
+            .ca(None)
+            .sign(&root_key, &[0; 32])
+            .unwrap();
+        let intermediate = CertificateBuilder::new(
+            &[0x80, 2],
+            &name("Intermediate CA"),
+            &intermediate_key.public_key().to_public_key_der(),
+            not_before,
+            not_after,
+        )
+        .unwrap()
+        .ca(Some(0))
+        .issuer(&Certificate::from_der(&root).unwrap())
+        .sign(&root_key, &[0; 32])
+        .unwrap();
+        let issuer = Certificate::from_der(&intermediate).unwrap();
+        let leaf = CertificateBuilder::new(&[3], &name("leaf"), &leaf_key.public_key().to_public_key_der(), not_before, not_after)
+            .unwrap()
+            .issuer(&issuer)
+            .sign(&intermediate_key, &[0; 32])
+            .unwrap();
+        let kem_leaf = CertificateBuilder::new(
+            &[4],
+            &name("kem"),
+            &kem_key.encapsulation_key().to_public_key_der(),
+            not_before,
+            not_after,
+        )
+        .unwrap()
+        .issuer(&issuer)
+        .sign(&intermediate_key, &[0; 32])
+        .unwrap();
+        Pki { root, intermediate, leaf, kem_leaf, intermediate_key, leaf_key }
+    }
+
+    #[test]
+    fn test_certificate() {
+        let not_before = unix_time(2025, 1, 1, 0, 0, 0).unwrap();
+        let not_after = unix_time(2055, 1, 1, 0, 0, 0).unwrap();
+        let pki = pki(not_before, not_after);
+        let root = Certificate::from_der(&pki.root).unwrap();
+        let intermediate = Certificate::from_der(&pki.intermediate).unwrap();
+        let leaf = Certificate::from_der(&pki.leaf).unwrap();
+        let kem_leaf = Certificate::from_der(&pki.kem_leaf).unwrap();
+
+        assert_eq!(root.issuer, root.subject);
+        assert_eq!(root.signature_algorithm, Algorithm::MlDsa87);
+        assert_eq!(root.basic_constraints, Some(BasicConstraints { ca: true, path_len: None }));
+        assert_eq!(root.subject_key_id, root.authority_key_id);
+        root.verify_signature(&root).unwrap();
+
+        assert_eq!(intermediate.serial, [0x80, 2]);
+        assert_eq!(intermediate.subject_common_name(), Ok(Some("Intermediate CA")));
+        assert_eq!(intermediate.issuer, root.subject);
+        assert_eq!(intermediate.authority_key_id, root.subject_key_id);
+        assert_eq!(intermediate.basic_constraints, Some(BasicConstraints { ca: true, path_len: Some(0) }));
+        assert_eq!(intermediate.key_usage, Some(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN));
+        assert_eq!((intermediate.not_before, intermediate.not_after), (not_before, not_after));
+
+        assert_eq!(leaf.signature_algorithm, Algorithm::MlDsa65);
+        assert_eq!(leaf.public_key_algorithm, Algorithm::MlDsa44);
+        assert_eq!(leaf.public_key, pki.leaf_key.public_key().pk_encode());
+        assert_eq!(leaf.basic_constraints, None);
+        assert_eq!(leaf.key_usage, Some(KeyUsage::DIGITAL_SIGNATURE));
+        assert_eq!(kem_leaf.public_key_algorithm, Algorithm::MlKem768);
+        assert_eq!(kem_leaf.key_usage, Some(KeyUsage::KEY_ENCIPHERMENT));
+
+        // the certificate of the leaf key can be loaded back into a key
+        let pk = mldsa44::PublicKey::from_public_key_der(leaf.spki).unwrap();
+        assert_eq!(pk.pk_encode(), pki.leaf_key.public_key().pk_encode());
+
+        let mut tampered = pki.leaf.clone();
+        let n = tampered.len();
+        tampered[n - 1] ^= 1;
+        let tampered = Certificate::from_der(&tampered).unwrap();
+        assert_eq!(tampered.verify_signature(&intermediate), Err(Error::Signature));
+        assert_eq!(leaf.verify_signature(&root), Err(Error::Signature));
+
+        assert!(Certificate::from_der(&pki.leaf[..pki.leaf.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let not_before = unix_time(2025, 1, 1, 0, 0, 0).unwrap();
+        let not_after = unix_time(2026, 1, 1, 0, 0, 0).unwrap();
+        let now = unix_time(2025, 6, 1, 0, 0, 0).unwrap();
+        let pki = pki(not_before, not_after);
+        let root = Certificate::from_der(&pki.root).unwrap();
+        let intermediate = Certificate::from_der(&pki.intermediate).unwrap();
+        let leaf = Certificate::from_der(&pki.leaf).unwrap();
+        let kem_leaf = Certificate::from_der(&pki.kem_leaf).unwrap();
+
+        verify_chain(&[leaf.clone(), intermediate.clone()], &root, now).unwrap();
+        verify_chain(&[leaf.clone(), intermediate.clone(), root.clone()], &root, now).unwrap();
+        verify_chain(&[kem_leaf.clone(), intermediate.clone()], &root, now).unwrap();
+        verify_chain(core::slice::from_ref(&intermediate), &root, now).unwrap();
+        verify_chain(core::slice::from_ref(&root), &root, now).unwrap();
+
+        assert_eq!(verify_chain(&[], &root, now), Err(Error::EmptyChain));
+        assert_eq!(verify_chain(&[leaf.clone(), intermediate.clone()], &root, not_after + 1), Err(Error::Expired));
+        assert_eq!(verify_chain(&[leaf.clone(), intermediate.clone()], &root, not_before - 1), Err(Error::Expired));
+        assert_eq!(verify_chain(core::slice::from_ref(&leaf), &root, now), Err(Error::IssuerMismatch));
+        assert_eq!(verify_chain(&[leaf.clone(), root.clone()], &intermediate, now), Err(Error::IssuerMismatch));
+
+        // the leaf is not a CA
+        let name = Name::new().common_name("by leaf");
+        let key = mldsa44::PrivateKey::from_seed(&[5; 32]);
+        let spki = key.public_key().to_public_key_der();
+        let by_leaf = CertificateBuilder::new(&[5], &name, &spki, not_before, not_after)
+            .unwrap()
+            .issuer(&leaf)
+            .sign(&pki.leaf_key, &[0; 32])
+            .unwrap();
+        let by_leaf = Certificate::from_der(&by_leaf).unwrap();
+        assert_eq!(verify_chain(&[by_leaf, leaf.clone(), intermediate.clone()], &root, now), Err(Error::NotCA));
+
+        // the intermediate CA has path length 0
+        let sub_ca = CertificateBuilder::new(&[6], &Name::new().common_name("sub CA"), &spki, not_before, not_after)
+            .unwrap()
+            .ca(None)
+            .issuer(&intermediate)
+            .sign(&pki.intermediate_key, &[0; 32])
+            .unwrap();
+        let sub_ca = Certificate::from_der(&sub_ca).unwrap();
+        let sub_leaf = CertificateBuilder::new(&[7], &Name::new().common_name("sub leaf"), &spki, not_before, not_after)
+            .unwrap()
+            .issuer(&sub_ca)
+            .sign(&key, &[0; 32])
+            .unwrap();
+        let sub_leaf = Certificate::from_der(&sub_leaf).unwrap();
+        verify_chain(&[sub_ca.clone(), intermediate.clone()], &root, now).unwrap();
+        assert_eq!(verify_chain(&[sub_leaf, sub_ca, intermediate], &root, now), Err(Error::PathLength));
+    }
+}
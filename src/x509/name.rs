@@ -0,0 +1,118 @@
+//! Distinguished names, an RDNSequence of single attribute RDNs.
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+use crate::pkcs::der::{self, Reader};
+#[cfg(feature = "alloc")]
+use crate::pkcs::der::Writer;
+
+use super::errors::{Error, Result};
+
+const UTF8_STRING: u8 = 0x0c;
+const PRINTABLE_STRING: u8 = 0x13;
+const IA5_STRING: u8 = 0x16;
+
+/// The DER contents of the attribute type OIDs.
+pub mod oid {
+    /// 2.5.4.3
+    pub const COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+    /// 2.5.4.6
+    pub const COUNTRY: &[u8] = &[0x55, 0x04, 0x06];
+    /// 2.5.4.10
+    pub const ORGANIZATION: &[u8] = &[0x55, 0x04, 0x0a];
+    /// 2.5.4.11
+    pub const ORGANIZATIONAL_UNIT: &[u8] = &[0x55, 0x04, 0x0b];
+}
+
+/// A name to encode, the attributes are written in the order they are added.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Name {
+    attributes: Vec<(&'static [u8], String)>,
+}
+
+#[cfg(feature = "alloc")]
+impl Name {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn country(mut self, v: &str) -> Self {
+        self.attributes.push((oid::COUNTRY, v.into()));
+        self
+    }
+
+    pub fn organization(mut self, v: &str) -> Self {
+        self.attributes.push((oid::ORGANIZATION, v.into()));
+        self
+    }
+
+    pub fn organizational_unit(mut self, v: &str) -> Self {
+        self.attributes.push((oid::ORGANIZATIONAL_UNIT, v.into()));
+        self
+    }
+
+    pub fn common_name(mut self, v: &str) -> Self {
+        self.attributes.push((oid::COMMON_NAME, v.into()));
+        self
+    }
+
+    /// The country is a PrintableString, the others are UTF8String.
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut w = Writer::new();
+        w.write_sequence(|w| {
+            for (oid, v) in &self.attributes {
+                w.write_constructed(der::SET, |w| {
+                    w.write_sequence(|w| {
+                        let tag = if *oid == oid::COUNTRY { PRINTABLE_STRING } else { UTF8_STRING };
+                        w.write_oid(oid).write(tag, v.as_bytes());
+                    });
+                });
+            }
+        });
+        w.finish()
+    }
+}
+
+/// Find the first attribute of the type in a DER Name, e.g. oid::COMMON_NAME.
+pub fn name_attribute<'a>(name: &'a [u8], attribute_type: &[u8]) -> Result<Option<&'a str>> {
+    let mut r = Reader::new(name);
+    let mut rdns = r.read_sequence()?;
+    r.finish()?;
+    let mut found = None;
+    while !rdns.is_empty() {
+        let mut rdn = rdns.read_constructed(der::SET)?;
+        while !rdn.is_empty() {
+            let mut atv = rdn.read_sequence()?;
+            let t = atv.read_oid()?;
+            let (tag, v, _) = atv.read_any()?;
+            atv.finish()?;
+            if found.is_none() && t == attribute_type {
+                if !matches!(tag, UTF8_STRING | PRINTABLE_STRING | IA5_STRING) {
+                    return Err(Error::Malformed);
+                }
+                found = Some(core::str::from_utf8(v).map_err(|_| Error::Malformed)?);
+            }
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name() {
+        let name = Name::new().country("CN").organization("OpenGM").common_name("测试 CA");
+        let der = name.to_der();
+        assert_eq!((der[0], der[1] as usize), (0x30, der.len() - 2));
+        assert_eq!(der[2..13], [0x31, 0x0b, 0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x06, 0x13, 0x02]);
+        assert_eq!(name_attribute(&der, oid::COMMON_NAME), Ok(Some("测试 CA")));
+        assert_eq!(name_attribute(&der, oid::COUNTRY), Ok(Some("CN")));
+        assert_eq!(name_attribute(&der, oid::ORGANIZATIONAL_UNIT), Ok(None));
+        assert_eq!(name_attribute(&Name::new().to_der(), oid::COMMON_NAME), Ok(None));
+        assert!(name_attribute(&der[..der.len() - 1], oid::COMMON_NAME).is_err());
+    }
+}
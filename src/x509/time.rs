@@ -0,0 +1,160 @@
+//! UTCTime and GeneralizedTime, as seconds since the Unix epoch.
+
+#[cfg(feature = "alloc")]
+use crate::pkcs::der::Writer;
+use crate::pkcs::der::Reader;
+
+use super::errors::{Error, Result};
+
+pub(crate) const UTC_TIME: u8 = 0x17;
+pub(crate) const GENERALIZED_TIME: u8 = 0x18;
+
+// days since 1970-01-01, the algorithm of http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + (month <= 2) as i64, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Seconds since the Unix epoch of the UTC date and time.
+pub fn unix_time(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Result<i64> {
+    if !(1..=12).contains(&month)
+        || day == 0
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return Err(Error::InvalidTime);
+    }
+    Ok(days_from_civil(year, month, day) * 86400 + (hour * 3600 + minute * 60 + second) as i64)
+}
+
+fn digits(s: &[u8]) -> Result<u32> {
+    s.iter().try_fold(0, |n, c| match c {
+        b'0'..=b'9' => Ok(n * 10 + (c - b'0') as u32),
+        _ => Err(Error::InvalidTime),
+    })
+}
+
+/// Read a UTCTime or GeneralizedTime in the DER form YYMMDDHHMMSSZ or YYYYMMDDHHMMSSZ.
+pub(crate) fn read_time(r: &mut Reader) -> Result<i64> {
+    let (tag, v, _) = r.read_any()?;
+    let (year, rest) = match (tag, v.len()) {
+        (UTC_TIME, 13) => {
+            let yy = digits(&v[..2])? as i64;
+            (if yy >= 50 { 1900 + yy } else { 2000 + yy }, &v[2..])
+        }
+        (GENERALIZED_TIME, 15) => (digits(&v[..4])? as i64, &v[4..]),
+        _ => return Err(Error::InvalidTime),
+    };
+    if rest[10] != b'Z' {
+        return Err(Error::InvalidTime);
+    }
+    let field = |i: usize| digits(&rest[2 * i..2 * i + 2]);
+    unix_time(year, field(0)?, field(1)?, field(2)?, field(3)?, field(4)?)
+}
+
+/// The time must be in the years 0000 to 9999 to be encoded.
+pub(crate) fn check_time(t: i64) -> Result<()> {
+    if (days_from_civil(0, 1, 1) * 86400..=days_from_civil(9999, 12, 31) * 86400 + 86399).contains(&t) {
+        Ok(())
+    } else {
+        Err(Error::InvalidTime)
+    }
+}
+
+/// Write a UTCTime for the years 1950 to 2049 and a GeneralizedTime otherwise, as RFC 5280 requires.
+#[cfg(feature = "alloc")]
+pub(crate) fn write_time(w: &mut Writer, t: i64) {
+    let (year, month, day) = civil_from_days(t.div_euclid(86400));
+    let secs = t.rem_euclid(86400) as u32;
+    let mut s = [0u8; 15];
+    let (tag, n) = if (1950..2050).contains(&year) { (UTC_TIME, 13) } else { (GENERALIZED_TIME, 15) };
+    let fields = [(year % 100) as u32, month, day, secs / 3600, secs / 60 % 60, secs % 60];
+    let mut p = &mut s[..];
+    if tag == GENERALIZED_TIME {
+        let century = (year / 100) as u32;
+        p[..2].copy_from_slice(&[b'0' + (century / 10 % 10) as u8, b'0' + (century % 10) as u8]);
+        p = &mut p[2..];
+    }
+    for v in fields {
+        p[..2].copy_from_slice(&[b'0' + (v / 10) as u8, b'0' + (v % 10) as u8]);
+        p = &mut p[2..];
+    }
+    p[0] = b'Z';
+    w.write(tag, &s[..n]);
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unix_time() {
+        assert_eq!(unix_time(1970, 1, 1, 0, 0, 0), Ok(0));
+        assert_eq!(unix_time(2000, 2, 29, 0, 0, 0), Ok(951782400));
+        assert_eq!(unix_time(2025, 1, 1, 0, 0, 0), Ok(1735689600));
+        assert_eq!(unix_time(1950, 1, 1, 0, 0, 0), Ok(-631152000));
+        assert_eq!(unix_time(9999, 12, 31, 23, 59, 59), Ok(253402300799));
+        assert_eq!(unix_time(2100, 2, 29, 0, 0, 0), Err(Error::InvalidTime));
+        assert_eq!(unix_time(2025, 13, 1, 0, 0, 0), Err(Error::InvalidTime));
+        assert_eq!(unix_time(2025, 4, 31, 0, 0, 0), Err(Error::InvalidTime));
+        assert_eq!(check_time(253402300799), Ok(()));
+        assert_eq!(check_time(253402300800), Err(Error::InvalidTime));
+        assert_eq!(check_time(unix_time(0, 1, 1, 0, 0, 0).unwrap() - 1), Err(Error::InvalidTime));
+        for days in [-719468, -1, 0, 1, 11016, 20089, 2932896] {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+    }
+
+    #[test]
+    fn test_time() {
+        for (t, der) in [
+            (unix_time(2025, 6, 30, 12, 34, 56).unwrap(), &b"\x17\x0d250630123456Z"[..]),
+            (unix_time(1950, 1, 1, 0, 0, 0).unwrap(), b"\x17\x0d500101000000Z"),
+            (unix_time(2050, 1, 1, 0, 0, 0).unwrap(), b"\x18\x0f20500101000000Z"),
+            (unix_time(9999, 12, 31, 23, 59, 59).unwrap(), b"\x18\x0f99991231235959Z"),
+        ] {
+            let mut w = Writer::new();
+            write_time(&mut w, t);
+            assert_eq!(w.finish(), der);
+            assert_eq!(read_time(&mut Reader::new(der)), Ok(t));
+        }
+        for der in [
+            &b"\x17\x0d2506301234560"[..],
+            b"\x17\x0f20250630123456Z",
+            b"\x17\x0d25063012345aZ",
+            b"\x18\x0d250630123456Z",
+            b"\x17\x0d250631123456Z",
+        ] {
+            assert!(read_time(&mut Reader::new(der)).is_err());
+        }
+    }
+}
@@ -0,0 +1,804 @@
+//! X-Wing, the hybrid KEM of ML-KEM-768 and X25519 (draft-connolly-cfrg-xwing-kem).
+//!
+//! The decapsulation key is the 32 bytes seed, expanded by SHAKE256 to the ML-KEM-768 seed d || z
+//! and the X25519 private key. The encapsulation key is the ML-KEM-768 encapsulation key followed
+//! by the X25519 public key, the ciphertext is the ML-KEM-768 ciphertext followed by the ephemeral
+//! X25519 public key, and the shared secret is
+//! SHA3-256(ss_M || ss_X || ct_X || pk_X || XWingLabel).
+
+use core::ffi::c_void;
+
+use crate::alg;
+use crate::capi;
+use crate::combiner::combine_sha3_256;
+use crate::mlkem::errors::Result;
+use crate::mlkem::mlkem768;
+use crate::sha3::{self, Hash};
+use crate::x25519::{x25519, x25519_base};
+
+use rand::CryptoRng;
+
+pub(crate) const ek_len: usize = mlkem768::ek_len + 32;
+pub(crate) const dk_len: usize = 32;
+pub(crate) const cipher_len: usize = mlkem768::cipher_len + 32;
+
+// "\.//^\"
+const LABEL: &[u8] = b"\\.//^\\";
+
+/// cbindgen:ignore
+#[derive(Clone, Debug)]
+pub struct EncapKey {
+    ek_m: mlkem768::EncapKey,
+    pk_x: [u8; 32],
+}
+
+/// cbindgen:ignore
+#[derive(Clone, Debug)]
+pub struct DecapKey {
+    seed: [u8; 32],
+    dk_m: mlkem768::DecapKey,
+    sk_x: [u8; 32],
+    ek: EncapKey,
+}
+
+pub fn keygen(rng: &mut dyn CryptoRng) -> DecapKey {
+    DecapKey::from_seed(&alg::random_bytes(rng))
+}
+
+fn combiner(ss_m: &[u8; 32], ss_x: &[u8; 32], ct_x: &[u8; 32], pk_x: &[u8; 32]) -> [u8; 32] {
+    combine_sha3_256(ss_m, ss_x, ct_x, pk_x, LABEL)
+}
+
+impl EncapKey {
+    pub fn encaps(&self, rng: &mut dyn CryptoRng) -> ([u8; 32], [u8; cipher_len]) {
+        let mut eseed = [0; 64];
+        rng.fill_bytes(&mut eseed);
+        self.encaps_internal(&eseed)
+    }
+
+    /// The derandomized encapsulation, eseed is the ML-KEM-768 randomness m followed by the
+    /// ephemeral X25519 private key.
+    pub fn encaps_internal(&self, eseed: &[u8; 64]) -> ([u8; 32], [u8; cipher_len]) {
+        let (m, ek_x) = (eseed.first_chunk().unwrap(), eseed.last_chunk().unwrap());
+        let ct_x = x25519_base(ek_x);
+        let ss_x = x25519(ek_x, &self.pk_x);
+        let (ss_m, ct_m) = self.ek_m.encaps_internal(m);
+
+        let mut c = [0; cipher_len];
+        c[..mlkem768::cipher_len].copy_from_slice(&ct_m);
+        c[mlkem768::cipher_len..].copy_from_slice(&ct_x);
+        (combiner(&ss_m, &ss_x, &ct_x, &self.pk_x), c)
+    }
+
+    pub fn byte_encode_inplace(&self, b: &mut [u8; ek_len]) {
+        let (ek_m, pk_x) = b.split_at_mut(mlkem768::ek_len);
+        self.ek_m.byte_encode_inplace(ek_m.try_into().unwrap());
+        pk_x.copy_from_slice(&self.pk_x);
+    }
+
+    pub fn byte_encode(&self) -> [u8; ek_len] {
+        let mut b = [0; ek_len];
+        self.byte_encode_inplace(&mut b);
+        b
+    }
+
+    /// The ML-KEM-768 encapsulation key must pass the modulus check.
+    pub fn byte_decode(b: &[u8; ek_len]) -> Result<Self> {
+        let ek_m = mlkem768::EncapKey::byte_decode(b.first_chunk().unwrap())?;
+        Ok(EncapKey { ek_m, pk_x: *b.last_chunk().unwrap() })
+    }
+}
+
+impl DecapKey {
+    /// Expand the 32 bytes seed to the key pair.
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let mut expanded = [0; 96];
+        let mut xof = sha3::new_shake256();
+        xof.write(seed);
+        xof.read(&mut expanded);
+
+        let dk_m = mlkem768::DecapKey::from_seed(expanded.first_chunk().unwrap());
+        let sk_x = *expanded.last_chunk().unwrap();
+        let ek = EncapKey { ek_m: dk_m.encapsulation_key(), pk_x: x25519_base(&sk_x) };
+        expanded.fill(0);
+        DecapKey { seed: *seed, dk_m, sk_x, ek }
+    }
+
+    pub fn encapsulation_key(&self) -> EncapKey {
+        self.ek.clone()
+    }
+
+    pub fn encapsulation_key_ref(&self) -> &EncapKey {
+        &self.ek
+    }
+
+    pub fn decaps(&self, c: &[u8; cipher_len]) -> [u8; 32] {
+        let (ct_m, ct_x) = (c.first_chunk().unwrap(), c.last_chunk().unwrap());
+        let ss_m = self.dk_m.decaps(ct_m);
+        let ss_x = x25519(&self.sk_x, ct_x);
+        combiner(&ss_m, &ss_x, ct_x, &self.ek.pk_x)
+    }
+
+    /// The encoded decapsulation key is the seed.
+    pub fn byte_encode_inplace(&self, b: &mut [u8; dk_len]) {
+        b.copy_from_slice(&self.seed);
+    }
+
+    pub fn byte_encode(&self) -> [u8; dk_len] {
+        self.seed
+    }
+
+    /// Any 32 bytes is a valid seed.
+    pub fn byte_decode(b: &[u8; dk_len]) -> Result<Self> {
+        Ok(Self::from_seed(b))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////
+//  exports C api
+/////////////////////////////////////////////////////////////////////
+
+impl capi::Handle for DecapKey {
+    const TAG: u32 = capi::tag(capi::XWING_DECAPKEY, 768);
+}
+
+impl capi::Handle for EncapKey {
+    const TAG: u32 = capi::tag(capi::XWING_ENCAPKEY, 768);
+}
+
+/// opengm_xwing_keygen_internal 密钥生成, 成功时*dk_handle指向生成的解封装密钥.
+/// seed必须指向32字节, seed由调用者使用随机数发生器生成.
+///
+/// # Safety
+/// seed必须为NULL或指向32字节, dk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_keygen_internal(seed: *const u8, dk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let seed = unsafe { capi::array_ref::<32>(seed) }?;
+        let out = unsafe { capi::out_handle(dk_handle) }?;
+        *out = capi::into_handle(DecapKey::from_seed(seed));
+        Ok(())
+    })
+}
+
+/// opengm_xwing_encapkey 由解封装密钥得到封装密钥, 成功时*ek_handle指向封装密钥.
+///
+/// # Safety
+/// dk_handle必须为NULL或本库返回且未释放的handle, ek_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_encapkey(dk_handle: *const c_void, ek_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let dk = unsafe { capi::handle_ref::<DecapKey>(dk_handle) }?;
+        let out = unsafe { capi::out_handle(ek_handle) }?;
+        *out = capi::into_handle(dk.encapsulation_key());
+        Ok(())
+    })
+}
+
+/// opengm_xwing_encap_internal 封装, key指向32字节, c指向cipher_len字节, eseed指向64字节的随机数.
+///
+/// # Safety
+/// key, c必须为NULL或指向32, cipher_len字节的可写内存, eseed必须为NULL或指向64字节,
+/// ek_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_encap_internal(
+    key: *mut u8,
+    c: *mut u8,
+    ek_handle: *const c_void,
+    eseed: *const u8,
+) -> i32 {
+    capi::status(|| {
+        let out_key = unsafe { capi::array_mut::<32>(key) }?;
+        let out_c = unsafe { capi::array_mut::<cipher_len>(c) }?;
+        let ek = unsafe { capi::handle_ref::<EncapKey>(ek_handle) }?;
+        let eseed = unsafe { capi::array_ref::<64>(eseed) }?;
+
+        let (key, c) = ek.encaps_internal(eseed);
+        out_key.copy_from_slice(&key);
+        out_c.copy_from_slice(&c);
+        Ok(())
+    })
+}
+
+/// opengm_xwing_decap 解封装, key指向32字节, c指向cipher_len字节.
+///
+/// # Safety
+/// key必须为NULL或指向32字节的可写内存, c必须为NULL或指向cipher_len字节,
+/// dk_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_decap(key: *mut u8, c: *const u8, dk_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let out_key = unsafe { capi::array_mut::<32>(key) }?;
+        let c = unsafe { capi::array_ref::<cipher_len>(c) }?;
+        let dk = unsafe { capi::handle_ref::<DecapKey>(dk_handle) }?;
+
+        out_key.copy_from_slice(&dk.decaps(c));
+        Ok(())
+    })
+}
+
+/// opengm_xwing_encapkey_encode 编码封装密钥, ek_encoded指向ek_len字节.
+///
+/// # Safety
+/// ek_encoded必须为NULL或指向ek_len字节的可写内存, ek_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_encapkey_encode(ek_encoded: *mut u8, ek_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let ek_encoded = unsafe { capi::array_mut::<ek_len>(ek_encoded) }?;
+        let ek = unsafe { capi::handle_ref::<EncapKey>(ek_handle) }?;
+
+        ek.byte_encode_inplace(ek_encoded);
+        Ok(())
+    })
+}
+
+/// opengm_xwing_encapkey_decode 解码封装密钥, ek_encoded指向ek_len字节.
+/// ML-KEM-768封装密钥未通过模数检查时返回OPENGM_ERR_DECODE.
+///
+/// # Safety
+/// ek_encoded必须为NULL或指向ek_len字节, ek_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_encapkey_decode(ek_encoded: *const u8, ek_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let ek_encoded = unsafe { capi::array_ref::<ek_len>(ek_encoded) }?;
+        let out = unsafe { capi::out_handle(ek_handle) }?;
+
+        let ek = EncapKey::byte_decode(ek_encoded).map_err(|_| capi::OPENGM_ERR_DECODE)?;
+        *out = capi::into_handle(ek);
+        Ok(())
+    })
+}
+
+/// opengm_xwing_decapkey_encode 编码解封装密钥, dk_encoded指向dk_len(32)字节的种子.
+///
+/// # Safety
+/// dk_encoded必须为NULL或指向dk_len字节的可写内存, dk_handle必须为NULL或本库返回且未释放的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_decapkey_encode(dk_encoded: *mut u8, dk_handle: *const c_void) -> i32 {
+    capi::status(|| {
+        let dk_encoded = unsafe { capi::array_mut::<dk_len>(dk_encoded) }?;
+        let dk = unsafe { capi::handle_ref::<DecapKey>(dk_handle) }?;
+
+        dk.byte_encode_inplace(dk_encoded);
+        Ok(())
+    })
+}
+
+/// opengm_xwing_decapkey_decode 解码解封装密钥, dk_encoded指向dk_len(32)字节的种子.
+///
+/// # Safety
+/// dk_encoded必须为NULL或指向dk_len字节, dk_handle必须为NULL或指向可写的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_decapkey_decode(dk_encoded: *const u8, dk_handle: *mut *mut c_void) -> i32 {
+    capi::status(|| {
+        let dk_encoded = unsafe { capi::array_ref::<dk_len>(dk_encoded) }?;
+        let out = unsafe { capi::out_handle(dk_handle) }?;
+
+        let dk = DecapKey::byte_decode(dk_encoded).map_err(|_| capi::OPENGM_ERR_DECODE)?;
+        *out = capi::into_handle(dk);
+        Ok(())
+    })
+}
+
+/// opengm_xwing_drop_encapkey_handle 释放封装密钥, ek_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+///
+/// # Safety
+/// ek_handle必须为NULL或本库返回的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_drop_encapkey_handle(ek_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::drop_handle::<EncapKey>(ek_handle) })
+}
+
+/// opengm_xwing_drop_decapkey_handle 释放解封装密钥, dk_handle为NULL时不做任何操作, 释放后handle不能再使用, 重复释放返回OPENGM_ERR_HANDLE.
+///
+/// # Safety
+/// dk_handle必须为NULL或本库返回的handle.
+#[cfg(feature = "alloc")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_drop_decapkey_handle(dk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::drop_handle::<DecapKey>(dk_handle) })
+}
+
+/////////////////////////////////////////////////////////////////////
+//  exports C api with caller provided storage, no allocation.
+//  The initialized storage is used as a handle of the functions above,
+//  and must be cleared by the *_clear_* functions instead of dropped.
+/////////////////////////////////////////////////////////////////////
+
+/// opengm_xwing_decapkey_size 返回解封装密钥所需的存储空间字节数.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_xwing_decapkey_size() -> usize {
+    capi::storage_size::<DecapKey>()
+}
+
+/// opengm_xwing_encapkey_size 返回封装密钥所需的存储空间字节数.
+#[unsafe(no_mangle)]
+pub extern "C" fn opengm_xwing_encapkey_size() -> usize {
+    capi::storage_size::<EncapKey>()
+}
+
+/// opengm_xwing_keygen_internal_inplace 在storage中生成解封装密钥, 成功时storage可作为dk_handle使用.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_xwing_decapkey_size(), 否则返回OPENGM_ERR_STORAGE.
+///
+/// # Safety
+/// seed必须为NULL或指向32字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_keygen_internal_inplace(
+    seed: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let seed = unsafe { capi::array_ref::<32>(seed) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(DecapKey::from_seed(seed))) }
+    })
+}
+
+/// opengm_xwing_encapkey_inplace 在storage中由解封装密钥得到封装密钥, 成功时storage可作为ek_handle使用.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_xwing_encapkey_size().
+///
+/// # Safety
+/// dk_handle必须为NULL或本库返回且未释放的handle, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_encapkey_inplace(
+    dk_handle: *const c_void,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let dk = unsafe { capi::handle_ref::<DecapKey>(dk_handle) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(dk.encapsulation_key())) }
+    })
+}
+
+/// opengm_xwing_encapkey_decode_inplace 在storage中解码封装密钥, ek_encoded指向ek_len字节.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_xwing_encapkey_size().
+///
+/// # Safety
+/// ek_encoded必须为NULL或指向ek_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_encapkey_decode_inplace(
+    ek_encoded: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let ek_encoded = unsafe { capi::array_ref::<ek_len>(ek_encoded) }?;
+        unsafe {
+            capi::init_handle(storage, storage_size, || {
+                EncapKey::byte_decode(ek_encoded).map_err(|_| capi::OPENGM_ERR_DECODE)
+            })
+        }
+    })
+}
+
+/// opengm_xwing_decapkey_decode_inplace 在storage中解码解封装密钥, dk_encoded指向dk_len(32)字节的种子.
+/// storage按OPENGM_STORAGE_ALIGN对齐, storage_size不小于opengm_xwing_decapkey_size().
+///
+/// # Safety
+/// dk_encoded必须为NULL或指向dk_len字节, storage必须为NULL或指向storage_size字节的可写内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_decapkey_decode_inplace(
+    dk_encoded: *const u8,
+    storage: *mut c_void,
+    storage_size: usize,
+) -> i32 {
+    capi::status(|| {
+        let dk_encoded = unsafe { capi::array_ref::<dk_len>(dk_encoded) }?;
+        unsafe { capi::init_handle(storage, storage_size, || Ok(DecapKey::from_seed(dk_encoded))) }
+    })
+}
+
+/// opengm_xwing_clear_encapkey 清除storage中的封装密钥, ek_handle为NULL时不做任何操作.
+///
+/// # Safety
+/// ek_handle必须为NULL或本库返回的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_clear_encapkey(ek_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::clear_handle::<EncapKey>(ek_handle) })
+}
+
+/// opengm_xwing_clear_decapkey 清除storage中的解封装密钥, 密钥被清零, dk_handle为NULL时不做任何操作.
+///
+/// # Safety
+/// dk_handle必须为NULL或本库返回的handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opengm_xwing_clear_decapkey(dk_handle: *mut c_void) -> i32 {
+    capi::status(|| unsafe { capi::clear_handle::<DecapKey>(dk_handle) })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr::null_mut;
+
+    use hex_literal::hex;
+    use rand::Rng;
+
+    use super::*;
+    use crate::capi::{OPENGM_ERR_DECODE, OPENGM_ERR_HANDLE, OPENGM_ERR_NULL, OPENGM_ERR_STORAGE, OPENGM_OK};
+
+    struct Vector {
+        seed: [u8; 32],
+        pk: [u8; ek_len],
+        eseed: [u8; 64],
+        ct: [u8; cipher_len],
+        ss: [u8; 32],
+    }
+
+    #[test]
+    fn test_vectors() {
+        // The test vectors of draft-connolly-cfrg-xwing-kem, seed and eseed are read in turn from
+        // SHAKE128 of the empty string. The encoded decapsulation key sk is the seed. The values were
+        // also reproduced by composing the ML-KEM-768, X25519 and SHA3-256 of OpenSSL 3.5.
+        let mut xof = sha3::new_shake128();
+        for v in &VECTORS {
+            let mut seed = [0; 32];
+            let mut eseed = [0; 64];
+            xof.read(&mut seed);
+            xof.read(&mut eseed);
+            assert_eq!(seed, v.seed);
+            assert_eq!(eseed, v.eseed);
+
+            let dk = DecapKey::from_seed(&v.seed);
+            assert_eq!(dk.byte_encode(), v.seed);
+            let ek = dk.encapsulation_key();
+            assert_eq!(ek.byte_encode(), v.pk);
+            let (key, c) = EncapKey::byte_decode(&v.pk).unwrap().encaps_internal(&v.eseed);
+            assert_eq!(c, v.ct);
+            assert_eq!(key, v.ss);
+            assert_eq!(dk.decaps(&v.ct), v.ss);
+        }
+    }
+
+    const VECTORS: [Vector; 3] = [
+        Vector {
+            seed: hex!("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"),
+            pk: hex!(
+                "e2236b35a8c24b39b10aa1323a96a919a2ced88400633a7b07131713fc14b2b5b19cfc3da5fa1a92c49f25513e0fd30d"
+                "6b1611c9ab9635d7086727a4b7d21d34244e66969cf15b3b2a785329f61b096b277ea037383479a6b556de7231fe4b7f"
+                "a9c9ac24c0699a0018a5253401bacfa905ca816573e56a2d2e067e9b7287533ba13a937dedb31fa44baced4076992361"
+                "0034ae31e619a170245199b3c5c39864859fe1b4c9717a07c30495bdfb98a0a002ccf56c1286cef5041dede3c44cf16b"
+                "f562c7448518026b3d8b9940680abd38a1575fd27b58da063bfac32c39c30869374c05c1aeb1898b6b303cc68be45534"
+                "6ee0af699636224a148ca2aea10463111c709f69b69c70ce8538746698c4c60a9aef0030c7924ceec42a5d36816f545e"
+                "ae13293460b3acb37ea0e13d70e4aa78686da398a8397c08eaf96882113fe4f7bad4da40b0501e1c753efe73053c8701"
+                "4e8661c33099afe8bede414a5b1aa27d8392b3e131e9a70c1055878240cad0f40d5fe3cdf85236ead97e2a97448363b2"
+                "808caafd516cd25052c5c362543c2517e4acd0e60ec07163009b6425fc32277acee71c24bab53ed9f29e74c66a0a3564"
+                "955998d76b96a9a8b50d1635a4d7a67eb42df5644d330457293a8042f53cc7a69288f17ed55827e82b28e82665a86a14"
+                "fbd96645eca8172c044f83bc0d8c0b4c8626985631ca87af829068f1358963cb333664ca482763ba3b3bb208577f9ba6"
+                "ac62c25f76592743b64be519317714cb4102cb7b2f9a25b2b4f0615de31decd9ca55026d6da0b65111b16fe52feed8a4"
+                "87e144462a6dba93728f500b6ffc49e515569ef25fed17aff520507368253525860f58be3be61c964604a6ac814e6935"
+                "596402a520a4670b3d284318866593d15a4bb01c35e3e587ee0c67d2880d6f2407fb7a70712b838deb96c5d7bf2b44bc"
+                "f6038ccbe33fbcf51a54a584fe90083c91c7a6d43d4fb15f48c60c2fd66e0a8aad4ad64e5c42bb8877c0ebec2b5e387c"
+                "8a988fdc23beb9e16c8757781e0a1499c61e138c21f216c29d076979871caa6942bafc090544bee99b54b16cb9a9a364"
+                "d6246d9f42cce53c66b59c45c8f9ae9299a75d15180c3c952151a91b7a10772429dc4cbae6fcc622fa8018c63439f890"
+                "630b9928db6bb7f9438ae4065ed34d73d486f3f52f90f0807dc88dfdd8c728e954f1ac35c06c000ce41a0582580e3bb5"
+                "7b672972890ac5e7988e7850657116f1b57d0809aaedec0bede1ae148148311c6f7e317346e5189fb8cd635b986f8c0b"
+                "dd27641c584b778b3a911a80be1c9692ab8e1bbb12839573cce19df183b45835bbb55052f9fc66a1678ef2a36dea7841"
+                "1e6c8d60501b4e60592d13698a943b509185db912e2ea10be06171236b327c71716094c964a68b03377f513a05bcd99c"
+                "1f346583bb052977a10a12adfc758034e5617da4c1276585e5774e1f3b9978b09d0e9c44d3bc86151c43aad185712717"
+                "340223ac381d21150a04294e97bb13bbda21b5a182b6da969e19a7fd072737fa8e880a53c2428e3d049b7d2197405296"
+                "ddb361912a7bcf4827ced611d0c7a7da104dde4322095339f64a61d5bb108ff0bf4d780cae509fb22c256914193ff734"
+                "9042581237d522828824ee3bdfd07fb03f1f942d2ea179fe722f06cc03de5b69859edb06eff389b27dce598445702162"
+                "23593d4ba32d9abac8cd049040ef6534"
+            ),
+            eseed: hex!(
+                "3cb1eea988004b93103cfb0aeefd2a686e01fa4a58e8a3639ca8a1e3f9ae57e235b8cc873c23dc62b8d260169afa2f75"
+                "ab916a58d974918835d25e6a435085b2"
+            ),
+            ct: hex!(
+                "b83aa828d4d62b9a83ceffe1d3d3bb1ef31264643c070c5798927e41fb07914a273f8f96e7826cd5375a283d7da88530"
+                "4c5de0516a0f0654243dc5b97f8bfeb831f68251219aabdd723bc6512041acbaef8af44265524942b902e68ffd23221c"
+                "da70b1b55d776a92d1143ea3a0c475f63ee6890157c7116dae3f62bf72f60acd2bb8cc31ce2ba0de364f52b8ed38c79d"
+                "719715963a5dd3842d8e8b43ab704e4759b5327bf027c63c8fa857c4908d5a8a7b88ac7f2be394d93c3706ddd4e698cc"
+                "6ce370101f4d0213254238b4a2e8821b6e414a1cf20f6c1244b699046f5a01caa0a1a55516300b40d2048c77cc73afba"
+                "79afeea9d2c0118bdf2adb8870dc328c5516cc45b1a2058141039e2c90a110a9e16b318dfb53bd49a126d6b73f215787"
+                "517b8917cc01cabd107d06859854ee8b4f9861c226d3764c87339ab16c3667d2f49384e55456dd40414b70a6af841585"
+                "f4c90c68725d57704ee8ee7ce6e2f9be582dbee985e038ffc346ebfb4e22158b6c84374a9ab4a44e1f91de5aac5197f8"
+                "9bc5e5442f51f9a5937b102ba3beaebf6e1c58380a4a5fedce4a4e5026f88f528f59ffd2db41752b3a3d90efabe46389"
+                "9b7d40870c530c8841e8712b733668ed033adbfafb2d49d37a44d4064e5863eb0af0a08d47b3cc888373bc05f7a33b84"
+                "1bc2587c57eb69554e8a3767b7506917b6b70498727f16eac1a36ec8d8cfaf751549f2277db277e8a55a9a5106b23a02"
+                "06b4721fa9b3048552c5bd5b594d6e247f38c18c591aea7f56249c72ce7b117afcc3a8621582f9cf71787e183dee0936"
+                "7976e98409ad9217a497df888042384d7707a6b78f5f7fb8409e3b535175373461b776002d799cbad62860be70573ecb"
+                "e13b246e0da7e93a52168e0fb6a9756b895ef7f0147a0dc81bfa644b088a9228160c0f9acf1379a2941cd28c06ebc80e"
+                "44e17aa2f8177010afd78a97ce0868d1629ebb294c5151812c583daeb88685220f4da9118112e07041fcc24d5564a99f"
+                "dbde28869fe0722387d7a9a4d16e1cc8555917e09944aa5ebaaaec2cf62693afad42a3f518fce67d273cc6c9fb5472b3"
+                "80e8573ec7de06a3ba2fd5f931d725b493026cb0acbd3fe62d00e4c790d965d7a03a3c0b4222ba8c2a9a16e2ac658f57"
+                "2ae0e746eafc4feba023576f08942278a041fb82a70a595d5bacbf297ce2029898a71e5c3b0d1c6228b485b1ade509b3"
+                "5fbca7eca97b2132e7cb6bc465375146b7dceac969308ac0c2ac89e7863eb8943015b24314cafb9c7c0e85fe543d5665"
+                "8c213632599efabfc1ec49dd8c88547bb2cc40c9d38cbd3099b4547840560531d0188cd1e9c23a0ebee0a03d5577d66b"
+                "1d2bcb4baaf21cc7fef1e03806ca96299df0dfbc56e1b2b43e4fc20c37f834c4af62127e7dae86c3c25a2f696ac8b589"
+                "dec71d595bfbe94b5ed4bc07d800b330796fda89edb77be0294136139354eb8cd37591578f9c600dd9be8ec6219fdd50"
+                "7adf3397ed4d68707b8d13b24ce4cd8fb22851bfe9d632407f31ed6f7cb1600de56f17576740ce2a32fc5145030145cf"
+                "b97e63e0e41d354274a079d3e6fb2e15"
+            ),
+            ss: hex!("d2df0522128f09dd8e2c92b1e905c793d8f57a54c3da25861f10bf4ca613e384"),
+        },
+        Vector {
+            seed: hex!("badfd6dfaac359a5efbb7bcc4b59d538df9a04302e10c8bc1cbf1a0b3a5120ea"),
+            pk: hex!(
+                "0333285fa253661508c9fb444852caa4061636cb060e69943b431400134ae1fbc02287247cb38068bbb89e6714af10a3"
+                "fcda6613acc4b5e4b0d6eb960c302a0253b1f507b596f0884d351da89b01c35543214c8e542390b2bc497967961ef102"
+                "86879c34316e6483b644fc27e8019d73024ba1d1cc83650bb068a5431b33d1221b3d122dc1239010a55cb13782140893"
+                "f30aca7c09380255a0c621602ffbb6a9db064c1406d12723ab3bbe2950a21fe521b160b30b16724cc359754b4c883426"
+                "51333ea9412d5137791cf75558ebc5c54c520dd6c622a059f6b332ccebb9f24103e59a297cd69e4a48a3bfe53a595855"
+                "9e840db5c023f66c10ce23081c2c8261d744799ba078285cfa71ac51f44708d0a6212c3993340724b3ac38f63e82a889"
+                "a4fc581f6b8353cc6233ac8f5394b6cca292f892360570a3031c90c4da3f02a895677390e60c24684a405f69ccf1a7b9"
+                "5312a47c844a4f9c2c4a37696dc10072a87bf41a2717d45b2a99ce09a4898d5a3f6b67085f9a626646bcf369982d4839"
+                "72b9cd7d244c4f49970f766a22507925eca7df99a491d80c27723e84c7b49b633a46b46785a16a41e02c538251622117"
+                "364615d9c2cdaa1687a860c18bfc9ce8690efb2a524cb97cdfd1a4ea661fa7d08817998af838679b07c9db8455e2167a"
+                "67c14d6a347522e89e8971270bec858364b1c1023b82c483cf8a8b76f040fe41c24dec2d49f6376170660605b8038339"
+                "1c4abad1136d874a77ef73b440758b6e7059add20873192e6e372e069c22c5425188e5c240cb3a6e29197ad17e87ec41"
+                "a813af68531f262a6db25bbdb8a15d2ed9c9f35b9f2063890bd26ef09426f225aa1e6008d31600a29bcdf3b10d0bc727"
+                "88d35e25f4976b3ca6ac7cbf0b442ae399b225d9714d0638a864bda7018d3b7c793bd2ace6ac68f4284d10977cc029cf"
+                "203c5698f15a06b162d6c8b4fd40c6af40824f9c6101bb94e9327869ab7efd835dfc805367160d6c8571e3643ac70cba"
+                "d5b96a1ad99352793f5af71705f95126cb4787392e94d808491a2245064ba5a7a30c066301392a6c315336e10dbc9c21"
+                "77c7af382765b6c88eeab51588d01d6a95747f3652dc5b5c401a23863c7a0343737c737c99287a40a90896d4594730b5"
+                "52b910d23244684206f0eb842fb9aa316ab182282a75fb72b6806cea4774b822169c386a58773c3edc8229d85905abb8"
+                "7ac228f0f7a2ce9a497bb5325e17a6a82777a997c036c3b862d29c14682ad325a9600872f3913029a1588648ba590a71"
+                "57809ff740b5138380015c40e9fb90f0311107946f28e5962e21666ad65092a3a60480cd16e61ff7fb5b44b70cf12201"
+                "878428ef8067fceb1e1dcb49d66c773d312c7e53238cb620e126187009472d41036b702032411dc96cb750631df9d994"
+                "52e495deb4300df660c8d35f32b424e98c7ed14b12d8ab11a289ac63c50a24d52925950e49ba6bf4c2c38953c92d60b6"
+                "cd034e575c711ac41bfa66951f62b9392828d7b45aed377ac69c35f1c6b80f388f34e0bb9ce8167eb2bc630382825c39"
+                "6a407e905108081b444ac8a07c2507376a750d18248ee0a81c4318d9a38fc44c3b41e8681f87c34138442659512c4127"
+                "6e1cc8fc4eb66e12727bcb5a9e0e405cdea21538d6ea885ab169050e6b91e1b69f7ed34bcbb48fd4c562a576549f85b5"
+                "28c953926d96ea8a160b8843f1c89c62"
+            ),
+            eseed: hex!(
+                "17cda7cfad765f5623474d368ccca8af0007cd9f5e4c849f167a580b14aabdefaee7eef47cb0fca9767be1fda69419df"
+                "b927e9df07348b196691abaeb580b32d"
+            ),
+            ct: hex!(
+                "c93beb22326705699bbc3d1d0aa6339be7a405debe61a7c337e1a91453c097a6f77c130639d1aaeb193175f1a987aa1f"
+                "d789a63c9cd487ebd6965f5d8389c8d7c8cfacbba4b44d2fbe0ae84de9e96fb11215d9b76acd51887b752329c1a3e046"
+                "8ccc49392c1e0f1aad61a73c10831e60a9798cb2e7ec07596b5803db3e243ecbb94166feade0c9197378700f8eb65a43"
+                "502bbac4605992e2de2b906ab30ba401d7e1ff3c98f42cfc4b30b974d3316f331461ac05f43e0db7b41d3da702a4f567"
+                "b6ee7295199c7be92f6b4a47e7307d34278e03c872fb48647c446a64a3937dccd7c6d8de4d34b9dea45a0b065ef15b9e"
+                "94d1b6df6dca7174d9bc9d14c6225e3a78a58785c3fe4e2fe6a0706f3365389e4258fbb61ecf1a1957715982b3f18444"
+                "24e03acd83da7eee50573f6cd3ff396841e9a00ad679da92274129da277833d0524674feea09a98d25b888616f338412"
+                "d8e65e151e65736c8c6fb448c9260fa20e7b2712148bcd3a0853865f50c1fc9e4f201aee3757120e034fd509d954b7a7"
+                "49ff776561382c4cb64cebcbb6aa82d04cd5c2b40395ecaf231bde8334ecfd955d09efa8c6e7935b1cb0298fb8b6740b"
+                "e4593360eed5f129d59d98822a6cea37c57674e919e84d6b90f695fca58e7d29092bd70f7c97c6dfb021b9f87216a627"
+                "1d8b144a364d03b6bf084f972dc59800b14a2c008bbd0992b5b82801020978f2bdddb3ca3367d876cffb3548dab695a2"
+                "9882cae2eb5ba7c847c3c71bd0150fa9c33aac8e6240e0c269b8e295ddb7b77e9c17bd310be65e28c0802136d086777b"
+                "e5652d6f1ac879d3263e9c712d1af736eac048fe848a577d6afaea1428dc71db8c430edd7b584ae6e6aeaf7257aff0fd"
+                "8fe25c30840e30ccfa1d95118ef0f6657367e9070f3d97a2e9a7bae19957bd707b00e31b6b0ebb9d7df4bd22e44c0608"
+                "30a194b5b8288353255b52954ff5905ab2b126d9aa049e44599368c27d6cb033eae5182c2e1504ee4e3745f51488997b"
+                "8f958f0209064f6f44a7e4de5226d5594d1ad9b42ac59a2d100a2f190df873a2e141552f33c923b4c927e8747c6f830c"
+                "441a8bd3c5b371f6b3ab8103ebcfb18543aefc1beb6f776bbfd5344779f4aa23daaf395f69ec31dc046b491f0e5cc9c6"
+                "51dfc306bd8f2105be7bc7a4f4e21957f87278c771528a8740a92e2daefa76a3525f1fae17ec4362a2700988001d8600"
+                "11d6ca3a95f79a0205bcf634cef373a8ea273ff0f4250eb8617d0fb92102a6aa09cf0c3ee2cad1ad96438c8e4dfd6ee0"
+                "fcc85833c3103dd6c1600cd305bc2df4cda89b55ca237a3f9c3f82390074ff30825fc750130ebaf13d0cf7556d2c52a9"
+                "8a4bad39ca5d44aaadeaef775c695e64d06e966acfcd552a14e2df6c63ae541f0fa88fc48263089685704506a21a0385"
+                "6ce65d4f06d54f3157eeabd62491cb4ac7bf029e79f9fbd4c77e2a3588790c710e611da8b2040c76a61507a8020758dc"
+                "c30894ad018fef98e401cc54106e20d94bd544a8f0e1fd0500342d123f618aa8c91bdf6e0e03200693c9651e469aee6f"
+                "91c98bea4127ae66312f4ae3ea155b67"
+            ),
+            ss: hex!("f2e86241c64d60f6649fbc6c5b7d17180b780a3f34355e64a85749949c45f150"),
+        },
+        Vector {
+            seed: hex!("ef58538b8d23f87732ea63b02b4fa0f4873360e2841928cd60dd4cee8cc0d4c9"),
+            pk: hex!(
+                "36244278824f77c621c660892c1c3886a9560caa52a97c461fd3958a598e749bbc8c7798ac8870bac7318ac2b863000c"
+                "a3b0bdcbbc1ccfcb1a30875df9a76976763247083e646ccb2499a4e4f0c9f4125378ba3da1999538b86f99f2328332c1"
+                "77d1192b849413e65510128973f679d23253850bb6c347ba7ca81b5e6ac4c574565c731740b3cd8c9756caac39fba7ac"
+                "422acc60c6c1a645b94e3b6d21485ebad9c4fe5bb4ea0853670c5246652bff65ce8381cb473c40c1a0cd06b54dcec118"
+                "72b351397c0eaf995bebdb6573000cbe2496600ba76c8cb023ec260f0571e3ec12a9c82d9db3c57b3a99e8701f78db4f"
+                "abc1cc58b1bae02745073a81fc8045439ba3b885581a283a1ba64e103610aabb4ddfe9959e7241011b2638b56ba6a982"
+                "ef610c514a57212555db9a98fb6bcf0e91660ec15dfa66a67408596e9ccb97489a09a073ffd1a0a7ebbe71aa5ff793cb"
+                "91964160703b4b6c9c5390842c2c905d4a9f88111fed57874ba9b03cf611e70486edf539767c7485189d5f1b08e32a27"
+                "4dc24a39c918fd2a4dfa946a8c897486f2c974031b2804aabc81749db430b85311372a3b8478868200b40e043f7bf4a1"
+                "c3a08b0771b431e342ee277410bca034a0c77086c8f702b3aed2b4108bbd3af471633373a1ac74b128b148d1b9412aa6"
+                "6948cac6dc6614681fda02ca86675d2a756003c49c50f06e13c63ce4bc9f321c860b202ee931834930011f485c9af86b"
+                "9f642f0c353ad305c66996b9a136b753973929495f0d8048db75529edcb4935904797ac66605490f66329c3bb36b8573"
+                "a3e00f817b3082162ff106674d11b261baae0506cde7e69fdce93c6c7b59b9d4c759758acf287c2e4c4bfab5170a9236"
+                "daf21bdb6005e92464ee8863f845cf37978ef19969264a516fe992c93b5f7ae7cb6718ac69257d630379e4aac6029cb9"
+                "06f98d91c92d118c36a6d16115d4c8f16066078badd161a65ba51e0252bc358c67cd2c4beab2537e42956e08a39cfccf"
+                "0cd875b5499ee952c83a162c68084f6d35cf92f71ec66baec74ab87e2243160b64df54afb5a07f78ec0f5c5759e5a432"
+                "2bca2643425748a1a97c62108510c44fd9089c5a7c14e57b1b77532800013027cff91922d7c935b4202bb507aa47598a"
+                "6a5a030117210d4c49c174700550ad6f82ad40e965598b86bc575448eb19d70380d465c1f870824c026d74a2522a799b"
+                "7b122d06c83aa64c0974635897261433914fdfb14106c230425a83dc8467ad8234f086c72a47418be9cfb582b1dcfa3d"
+                "9aa45299b79fff265356d8286a1ca2f3c2184b2a70d15289e5b202d03b64c735a867b1154c55533ff61d6c2962770118"
+                "48143bc85a4b823040ae025a29293ab77747d85310078682e0ba0ac236548d905a79494324574d417c7a3457bd5fb525"
+                "3c4876679034ae844d0d05010fec722db5621e3a67a2d58e2ff33b432269169b51f9dcc095b8406dc1864cf0aeb6a213"
+                "2661a38d641877594b3c51892b9364d25c63d637140a2018d10931b0daa5a2f2a405017688c991e586b522f94b1132bc"
+                "7e87a63246475816c8be9c62b731691ab912eb656ce2619225663364701a014b7d0337212caa2ecc731f34438289e0ca"
+                "4590a276802d980056b5d0d316cae2ecfea6d86696a9f161aa90ad47eaad8cadd31ae3cbc1c013747dfee80fb35b5299"
+                "f555dcc2b787ea4f6f16ffdf66952461"
+            ),
+            eseed: hex!(
+                "22a96188d032675c8ac850933c7aff1533b94c834adbb69c6115bad4692d8619f90b0cdf8a7b9c264029ac185b70b83f"
+                "2801f2f4b3f70c593ea3aeeb613a7f1b"
+            ),
+            ct: hex!(
+                "0d2e38cbf17a2e2e4e0c87a94ca1e7701ae1552e02509b3b00f9c82c39e3fd435b05b91275f47abc9f1021429a26a346"
+                "598cd6cd9efdc8adc1dbc35036d0290bf89733c835309202232f9bf652ea82f3d49280d6e8a3bd3135fb883445ab5b07"
+                "4d949c5350c7c7d6ac59905bdbfce6639da8a9d4b390ecc1dd05522d2956f2d37a05593996e5cb3fd8d5a9eb52417732"
+                "e1ebf545588713b4760227115aab7ada178dadbca583b26cfedba2888a0c95b950bf07f750d7aa8103798aa3470a042c"
+                "0105c6a037de2f9ebc396021b2ba2c16aba696fbac3454dc8e053b8fa55edd45215eeb57a1eab9106fb426b375a9b9e5"
+                "c3419efc7610977e72640f9fd1b2ec337de33c35e5a7581b2aae4d8ee86d2e0ebf82a1350714de50d2d788687878a196"
+                "44ae4e3175e8d59dc90171b3badeff65aeaf600e5e5483a3595fdeb40cbafcbd040c29a2f6900533ae999d24f54dfcef"
+                "748c30313ca447cdddfa57ad78eaa890e90f3f7bf8d116968a5713cc75fd0408f36364fa265c5617039304eaeac4cbee"
+                "6fc49b9fe2276768cdbec2d73a507b543cc028dc1b154b7c2b0412254c466a94a8d6ea3a47e1743469bd45c08f54cf96"
+                "5884be3696e961741ede16e3b1bc4feb93faaef31d911dc0cb3fa90bcda991959a9d2cbc817a5564c5c01177a59e9577"
+                "589ea344d60cf5b0aa39f31863febd54603ca87ad2363c766642a3f52557bcd9e4c05a87665842ba336b83156a677030"
+                "f0bad531a8387a1486a599caa748fcea7bdc1eb63f3cdb97173551ab7c1c36b69acbbdb2ff7a1e7bc70439632ddc67b9"
+                "7f3da1f59b3c1588515957cb8a2f86ab635ce0a78b7cdf24eac3445e8fc8b79ba04da9e903f49a7d912c197a84b4cfab"
+                "c779b97d24788419bcf58035db99717edb9fd1c1df8c4005f700eabba528ddfcbaeda6dd30754f795948a34c9319ab65"
+                "3524b19931c7900c4167988af52292fe902e746b524d20ceffb4339e8f5535f41cf35f0f8ea8b4a7b949c5d2381116b1"
+                "46e9b913a83a3fa1c65ff9468c835fe4114554a6c66a80e1c9a6bb064b380be3c95e5595ec979bf1c85aa938938e3f10"
+                "e72b0c87811969e8ab0d83de0b0604c4016ac3a015e19514089271bdc6ebf2ec56fab6018e44de749b4c36cc235e370d"
+                "a8466dbdc253542a2d704eb3316fd70d5d238cb7eaaf05966d973f62c7ef43b9a806f4ed213ac8099ea15d61a9024441"
+                "60883f6bf441a3e1469945c9b79489ea18390f1ebc83caca10bdb8f2429877b52bd44c94a228ef91c392ef5398c5c839"
+                "82701318ccedab92f7a279c4fddebaa7fe5e986c48b7d8135b3fe4cd15be2004ce73ff86b1e55f8ecd6ba5b8114315f8"
+                "e716ef3ab0a64564a4644651166ebd68b1f783e2e443dbccadfe189368647629f1a12215840b7f1d026de2f665c2eb02"
+                "3ff51a6df160912811ee03444ae4227fb941dc9ec4f31b445006fd384de5e60e0a5061b50cb1202f863090fc05eb814e"
+                "2d42a03586c0b56f533847ac7b8184ce9690bc8dece32a88ca934f541d4cc520fa64de6b6e1c3c8e03db5971a4459922"
+                "27c825590688d203523f527161137334"
+            ),
+            ss: hex!("953f7f4e8c5b5049bdc771d1dffada0dd961477d1a2ae0988baa7ea6898d893f"),
+        },
+    ];
+
+    #[test]
+    fn test_xwing() {
+        let mut rng = rand::rng();
+        let dk = keygen(&mut rng);
+        let ek = EncapKey::byte_decode(&dk.encapsulation_key_ref().byte_encode()).unwrap();
+        let (key, c) = ek.encaps(&mut rng);
+        assert_eq!(dk.decaps(&c), key);
+
+        let dk2 = DecapKey::byte_decode(&dk.byte_encode()).unwrap();
+        assert_eq!(dk2.decaps(&c), key);
+
+        // a modified X25519 or ML-KEM ciphertext changes the secret
+        let mut bad = c;
+        bad[cipher_len - 1] ^= 1;
+        assert_ne!(dk.decaps(&bad), key);
+        bad = c;
+        bad[0] ^= 1;
+        assert_ne!(dk.decaps(&bad), key);
+
+        let mut bad_ek = ek.byte_encode();
+        bad_ek[0] = 0xff;
+        bad_ek[1] = 0xff;
+        assert!(EncapKey::byte_decode(&bad_ek).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_capi() {
+        let mut rng = rand::rng();
+        let seed: [u8; 32] = rng.random();
+        let eseed: [u8; 64] = rng.random();
+
+        let mut dk = null_mut();
+        assert_eq!(unsafe { opengm_xwing_keygen_internal(seed.as_ptr(), &mut dk) }, OPENGM_OK);
+        let mut ek = null_mut();
+        assert_eq!(unsafe { opengm_xwing_encapkey(dk, &mut ek) }, OPENGM_OK);
+
+        let mut ek_encoded = [0u8; ek_len];
+        assert_eq!(unsafe { opengm_xwing_encapkey_encode(ek_encoded.as_mut_ptr(), ek) }, OPENGM_OK);
+        assert_eq!(ek_encoded, DecapKey::from_seed(&seed).encapsulation_key().byte_encode());
+
+        let mut key = [0u8; 32];
+        let mut c = [0u8; cipher_len];
+        assert_eq!(
+            unsafe { opengm_xwing_encap_internal(key.as_mut_ptr(), c.as_mut_ptr(), ek, eseed.as_ptr()) },
+            OPENGM_OK
+        );
+        let mut key2 = [0u8; 32];
+        assert_eq!(unsafe { opengm_xwing_decap(key2.as_mut_ptr(), c.as_ptr(), dk) }, OPENGM_OK);
+        assert_eq!(key, key2);
+
+        let mut dk_encoded = [0u8; dk_len];
+        assert_eq!(unsafe { opengm_xwing_decapkey_encode(dk_encoded.as_mut_ptr(), dk) }, OPENGM_OK);
+        assert_eq!(dk_encoded, seed);
+        let mut dk2 = null_mut();
+        assert_eq!(unsafe { opengm_xwing_decapkey_decode(dk_encoded.as_ptr(), &mut dk2) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_decap(key2.as_mut_ptr(), c.as_ptr(), dk2) }, OPENGM_OK);
+        assert_eq!(key, key2);
+        assert_eq!(unsafe { opengm_xwing_drop_decapkey_handle(dk2) }, OPENGM_OK);
+
+        // NULL arguments
+        let mut handle = null_mut();
+        assert_eq!(unsafe { opengm_xwing_keygen_internal(null_mut(), &mut handle) }, OPENGM_ERR_NULL);
+        assert!(handle.is_null());
+        assert_eq!(unsafe { opengm_xwing_decap(key2.as_mut_ptr(), c.as_ptr(), null_mut()) }, OPENGM_ERR_NULL);
+        assert_eq!(unsafe { opengm_xwing_encapkey_decode(ek_encoded.as_ptr(), null_mut()) }, OPENGM_ERR_NULL);
+
+        // the modulus check of the ML-KEM-768 encapsulation key
+        let mut bad_ek = ek_encoded;
+        bad_ek[0] = 0xff;
+        bad_ek[1] = 0xff;
+        assert_eq!(unsafe { opengm_xwing_encapkey_decode(bad_ek.as_ptr(), &mut handle) }, OPENGM_ERR_DECODE);
+        assert!(handle.is_null());
+
+        // handles of wrong type, ML-KEM-768 keys are not X-Wing keys
+        assert_eq!(unsafe { opengm_xwing_decap(key2.as_mut_ptr(), c.as_ptr(), ek) }, OPENGM_ERR_HANDLE);
+        assert_eq!(
+            unsafe { opengm_xwing_encap_internal(key.as_mut_ptr(), c.as_mut_ptr(), dk, eseed.as_ptr()) },
+            OPENGM_ERR_HANDLE
+        );
+        let mut dk768 = null_mut();
+        assert_eq!(
+            unsafe { mlkem768::opengm_mlkem768_keygen_internal(seed.as_ptr(), seed.as_ptr(), &mut dk768) },
+            OPENGM_OK
+        );
+        assert_eq!(unsafe { opengm_xwing_decap(key2.as_mut_ptr(), c.as_ptr(), dk768) }, OPENGM_ERR_HANDLE);
+        assert_eq!(unsafe { mlkem768::opengm_mlkem768_drop_decapkey_handle(dk768) }, OPENGM_OK);
+
+        assert_eq!(unsafe { opengm_xwing_drop_encapkey_handle(ek) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_drop_decapkey_handle(dk) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_drop_decapkey_handle(null_mut()) }, OPENGM_OK);
+    }
+
+    #[test]
+    fn test_capi_inplace() {
+        let mut rng = rand::rng();
+        let seed: [u8; 32] = rng.random();
+        let eseed: [u8; 64] = rng.random();
+
+        // u64 for the alignment of storage.
+        let dk_size = opengm_xwing_decapkey_size();
+        let ek_size = opengm_xwing_encapkey_size();
+        let mut dk_storage = vec![0u64; dk_size.div_ceil(8)];
+        let mut dk2_storage = vec![0u64; dk_size.div_ceil(8)];
+        let mut ek_storage = vec![0u64; ek_size.div_ceil(8)];
+        let dk = dk_storage.as_mut_ptr() as *mut c_void;
+        let dk2 = dk2_storage.as_mut_ptr() as *mut c_void;
+        let ek = ek_storage.as_mut_ptr() as *mut c_void;
+
+        assert_eq!(unsafe { opengm_xwing_keygen_internal_inplace(seed.as_ptr(), dk, dk_size - 1) }, OPENGM_ERR_STORAGE);
+        assert_eq!(
+            unsafe { opengm_xwing_keygen_internal_inplace(seed.as_ptr(), null_mut(), dk_size) },
+            OPENGM_ERR_NULL
+        );
+        assert_eq!(unsafe { opengm_xwing_keygen_internal_inplace(seed.as_ptr(), dk, dk_size) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_encapkey_inplace(dk, ek, ek_size) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_encapkey_inplace(ek, ek, ek_size) }, OPENGM_ERR_HANDLE);
+
+        let mut key = [0u8; 32];
+        let mut c = [0u8; cipher_len];
+        assert_eq!(
+            unsafe { opengm_xwing_encap_internal(key.as_mut_ptr(), c.as_mut_ptr(), ek, eseed.as_ptr()) },
+            OPENGM_OK
+        );
+
+        // decode into another storage
+        let mut dk_encoded = [0u8; dk_len];
+        assert_eq!(unsafe { opengm_xwing_decapkey_encode(dk_encoded.as_mut_ptr(), dk) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_decapkey_decode_inplace(dk_encoded.as_ptr(), dk2, dk_size) }, OPENGM_OK);
+        let mut key2 = [0u8; 32];
+        assert_eq!(unsafe { opengm_xwing_decap(key2.as_mut_ptr(), c.as_ptr(), dk2) }, OPENGM_OK);
+        assert_eq!(key, key2);
+
+        let mut ek_encoded = [0u8; ek_len];
+        assert_eq!(unsafe { opengm_xwing_encapkey_encode(ek_encoded.as_mut_ptr(), ek) }, OPENGM_OK);
+        let mut bad_ek = ek_encoded;
+        bad_ek[0] = 0xff;
+        bad_ek[1] = 0xff;
+        assert_eq!(unsafe { opengm_xwing_clear_encapkey(ek) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_encapkey_decode_inplace(bad_ek.as_ptr(), ek, ek_size) }, OPENGM_ERR_DECODE);
+        assert_eq!(unsafe { opengm_xwing_encapkey_decode_inplace(ek_encoded.as_ptr(), ek, ek_size) }, OPENGM_OK);
+        assert_eq!(
+            unsafe { opengm_xwing_encap_internal(key2.as_mut_ptr(), c.as_mut_ptr(), ek, eseed.as_ptr()) },
+            OPENGM_OK
+        );
+        assert_eq!(key, key2);
+
+        // in place handles are cleared, not dropped.
+        #[cfg(feature = "alloc")]
+        assert_eq!(unsafe { opengm_xwing_drop_decapkey_handle(dk) }, OPENGM_ERR_HANDLE);
+        assert_eq!(unsafe { opengm_xwing_clear_decapkey(ek) }, OPENGM_ERR_HANDLE);
+        assert_eq!(unsafe { opengm_xwing_clear_encapkey(ek) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_clear_decapkey(dk) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_clear_decapkey(dk2) }, OPENGM_OK);
+        assert_eq!(unsafe { opengm_xwing_decap(key2.as_mut_ptr(), c.as_ptr(), dk) }, OPENGM_ERR_HANDLE);
+        assert!(dk_storage[1..].iter().all(|&x| x == 0));
+    }
+}